@@ -1,6 +1,8 @@
 use anchor_lang::prelude::*;
 
-use crate::{constants::*, errors::AuctionHouseError, AuctionHouse, Auctioneer};
+use crate::{
+    constants::*, errors::AuctionHouseError, events::AuctioneerDelegated, AuctionHouse, Auctioneer,
+};
 
 #[derive(Accounts)]
 pub struct DelegateAuctioneer<'info> {
@@ -57,5 +59,11 @@ pub fn delegate_auctioneer<'info>(
         .get("auctioneer")
         .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
 
+    emit!(AuctioneerDelegated {
+        auction_house: auctioneer.auction_house,
+        auctioneer_authority: auctioneer.auctioneer_authority,
+        auctioneer: auctioneer.key(),
+    });
+
     Ok(())
 }
@@ -4,12 +4,174 @@ use anchor_lang::{
     solana_program::{program::invoke, system_instruction},
     AnchorDeserialize,
 };
-use anchor_spl::token::{Mint, Token, TokenAccount};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 use crate::{
-    constants::*, errors::AuctionHouseError, utils::*, AuctionHouse, Auctioneer, TRADE_STATE_SIZE,
+    constants::*, errors::AuctionHouseError, utils::*, AuctionHouse, Auctioneer, EscrowLedger,
+    TRADE_STATE_SIZE,
 };
 
+#[derive(Accounts)]
+#[instruction(
+    trade_state_bump: u8,
+    escrow_payment_bump: u8,
+    buyer_price: u64,
+    token_size: u64
+)]
+pub struct Buy<'info> {
+    #[account(mut)]
+    wallet: Signer<'info>,
+    #[account(mut)]
+    payment_account: UncheckedAccount<'info>,
+    transfer_authority: UncheckedAccount<'info>,
+    treasury_mint: Box<InterfaceAccount<'info, Mint>>,
+    token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    metadata: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            wallet.key().as_ref()
+        ],
+        bump
+    )]
+    escrow_payment_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            wallet.key().as_ref(),
+            ESCROW_LEDGER.as_bytes()
+        ],
+        bump
+    )]
+    escrow_ledger: UncheckedAccount<'info>,
+    authority: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump = auction_house.bump,
+        has_one = authority,
+        has_one = treasury_mint,
+        has_one = auction_house_fee_account
+    )]
+    auction_house: Box<Account<'info, AuctionHouse>>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            FEE_PAYER.as_bytes()
+        ],
+        bump = auction_house.fee_payer_bump
+    )]
+    auction_house_fee_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            wallet.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_account.key().as_ref(),
+            treasury_mint.key().as_ref(),
+            token_account.mint.as_ref(),
+            buyer_price.to_le_bytes().as_ref(),
+            token_size.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    buyer_trade_state: UncheckedAccount<'info>,
+
+    token_program: Interface<'info, TokenInterface>,
+    system_program: Program<'info, System>,
+    rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    trade_state_bump: u8,
+    escrow_payment_bump: u8,
+    buyer_price: u64,
+    token_size: u64
+)]
+pub struct PublicBuy<'info> {
+    #[account(mut)]
+    wallet: Signer<'info>,
+    #[account(mut)]
+    payment_account: UncheckedAccount<'info>,
+    transfer_authority: UncheckedAccount<'info>,
+    treasury_mint: Box<InterfaceAccount<'info, Mint>>,
+    token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    metadata: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            wallet.key().as_ref()
+        ],
+        bump
+    )]
+    escrow_payment_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            wallet.key().as_ref(),
+            ESCROW_LEDGER.as_bytes()
+        ],
+        bump
+    )]
+    escrow_ledger: UncheckedAccount<'info>,
+    authority: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump = auction_house.bump,
+        has_one = authority,
+        has_one = treasury_mint,
+        has_one = auction_house_fee_account
+    )]
+    auction_house: Box<Account<'info, AuctionHouse>>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            FEE_PAYER.as_bytes()
+        ],
+        bump = auction_house.fee_payer_bump
+    )]
+    auction_house_fee_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            wallet.key().as_ref(),
+            auction_house.key().as_ref(),
+            treasury_mint.key().as_ref(),
+            token_account.mint.as_ref(),
+            buyer_price.to_le_bytes().as_ref(),
+            token_size.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    buyer_trade_state: UncheckedAccount<'info>,
+
+    token_program: Interface<'info, TokenInterface>,
+    system_program: Program<'info, System>,
+    rent: Sysvar<'info, Rent>,
+}
+
 #[derive(Accounts)]
 #[instruction(
     trade_state_bump: u8,
@@ -22,8 +184,8 @@ pub struct AuctioneerBuy<'info> {
     #[account(mut)]
     payment_account: UncheckedAccount<'info>,
     transfer_authority: UncheckedAccount<'info>,
-    treasury_mint: Box<Account<'info, Mint>>,
-    token_account: Box<Account<'info, TokenAccount>>,
+    treasury_mint: Box<InterfaceAccount<'info, Mint>>,
+    token_account: Box<InterfaceAccount<'info, TokenAccount>>,
     metadata: UncheckedAccount<'info>,
     #[account(
         mut,
@@ -35,6 +197,17 @@ pub struct AuctioneerBuy<'info> {
         bump
     )]
     escrow_payment_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            wallet.key().as_ref(),
+            ESCROW_LEDGER.as_bytes()
+        ],
+        bump
+    )]
+    escrow_ledger: UncheckedAccount<'info>,
     authority: UncheckedAccount<'info>,
     auctioneer_authority: Signer<'info>,
     #[account(
@@ -84,11 +257,99 @@ pub struct AuctioneerBuy<'info> {
     )]
     pub auctioneer: Account<'info, Auctioneer>,
 
-    token_program: Program<'info, Token>,
+    token_program: Interface<'info, TokenInterface>,
     system_program: Program<'info, System>,
     rent: Sysvar<'info, Rent>,
 }
 
+pub fn private_bid<'info>(
+    ctx: Context<'_, '_, '_, 'info, Buy<'info>>,
+    trade_state_bump: u8,
+    escrow_payment_bump: u8,
+    buyer_price: u64,
+    token_size: u64,
+) -> Result<()> {
+    if ctx.accounts.auction_house.has_auctioneer {
+        return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
+    }
+
+    auctioneer_bid_logic(
+        ctx.accounts.wallet.to_owned(),
+        ctx.accounts.payment_account.to_owned(),
+        ctx.accounts.transfer_authority.to_owned(),
+        *ctx.accounts.treasury_mint.to_owned(),
+        *ctx.accounts.token_account.to_owned(),
+        ctx.accounts.metadata.to_owned(),
+        ctx.accounts.escrow_payment_account.to_owned(),
+        ctx.accounts.escrow_ledger.to_owned(),
+        &mut ctx.accounts.auction_house,
+        ctx.accounts.auction_house_fee_account.to_owned(),
+        ctx.accounts.buyer_trade_state.to_owned(),
+        ctx.accounts.authority.to_owned(),
+        ctx.accounts.token_program.to_owned(),
+        ctx.accounts.system_program.to_owned(),
+        ctx.accounts.rent.to_owned(),
+        trade_state_bump,
+        escrow_payment_bump,
+        buyer_price,
+        token_size,
+        false,
+        *ctx.bumps
+            .get("escrow_payment_account")
+            .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?,
+        *ctx.bumps
+            .get("buyer_trade_state")
+            .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?,
+        *ctx.bumps
+            .get("escrow_ledger")
+            .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?,
+    )
+}
+
+pub fn public_bid<'info>(
+    ctx: Context<'_, '_, '_, 'info, PublicBuy<'info>>,
+    trade_state_bump: u8,
+    escrow_payment_bump: u8,
+    buyer_price: u64,
+    token_size: u64,
+) -> Result<()> {
+    if ctx.accounts.auction_house.has_auctioneer {
+        return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
+    }
+
+    auctioneer_bid_logic(
+        ctx.accounts.wallet.to_owned(),
+        ctx.accounts.payment_account.to_owned(),
+        ctx.accounts.transfer_authority.to_owned(),
+        *ctx.accounts.treasury_mint.to_owned(),
+        *ctx.accounts.token_account.to_owned(),
+        ctx.accounts.metadata.to_owned(),
+        ctx.accounts.escrow_payment_account.to_owned(),
+        ctx.accounts.escrow_ledger.to_owned(),
+        &mut ctx.accounts.auction_house,
+        ctx.accounts.auction_house_fee_account.to_owned(),
+        ctx.accounts.buyer_trade_state.to_owned(),
+        ctx.accounts.authority.to_owned(),
+        ctx.accounts.token_program.to_owned(),
+        ctx.accounts.system_program.to_owned(),
+        ctx.accounts.rent.to_owned(),
+        trade_state_bump,
+        escrow_payment_bump,
+        buyer_price,
+        token_size,
+        true,
+        *ctx.bumps
+            .get("escrow_payment_account")
+            .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?,
+        *ctx.bumps
+            .get("buyer_trade_state")
+            .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?,
+        *ctx.bumps
+            .get("escrow_ledger")
+            .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?,
+    )
+}
+
 pub fn auctioneer_private_bid<'info>(
     ctx: Context<'_, '_, '_, 'info, AuctioneerBuy<'info>>,
     trade_state_bump: u8,
@@ -96,6 +357,10 @@ pub fn auctioneer_private_bid<'info>(
     buyer_price: u64,
     token_size: u64,
 ) -> Result<()> {
+    if !ctx.accounts.auction_house.has_auctioneer {
+        return Err(AuctionHouseError::NoAuctioneerProgramSet.into());
+    }
+
     auctioneer_bid_logic(
         ctx.accounts.wallet.to_owned(),
         ctx.accounts.payment_account.to_owned(),
@@ -104,6 +369,7 @@ pub fn auctioneer_private_bid<'info>(
         *ctx.accounts.token_account.to_owned(),
         ctx.accounts.metadata.to_owned(),
         ctx.accounts.escrow_payment_account.to_owned(),
+        ctx.accounts.escrow_ledger.to_owned(),
         &mut ctx.accounts.auction_house,
         ctx.accounts.auction_house_fee_account.to_owned(),
         ctx.accounts.buyer_trade_state.to_owned(),
@@ -122,6 +388,9 @@ pub fn auctioneer_private_bid<'info>(
         *ctx.bumps
             .get("buyer_trade_state")
             .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?,
+        *ctx.bumps
+            .get("escrow_ledger")
+            .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?,
     )
 }
 
@@ -130,15 +399,16 @@ pub fn auctioneer_bid_logic<'info>(
     wallet: Signer<'info>,
     payment_account: UncheckedAccount<'info>,
     transfer_authority: UncheckedAccount<'info>,
-    treasury_mint: Account<'info, Mint>,
-    token_account: Account<'info, TokenAccount>,
+    treasury_mint: InterfaceAccount<'info, Mint>,
+    token_account: InterfaceAccount<'info, TokenAccount>,
     metadata: UncheckedAccount<'info>,
     escrow_payment_account: UncheckedAccount<'info>,
+    escrow_ledger: UncheckedAccount<'info>,
     auction_house: &mut Box<Account<'info, AuctionHouse>>,
     auction_house_fee_account: UncheckedAccount<'info>,
     buyer_trade_state: UncheckedAccount<'info>,
     authority: UncheckedAccount<'info>,
-    token_program: Program<'info, Token>,
+    token_program: Interface<'info, TokenInterface>,
     system_program: Program<'info, System>,
     rent: Sysvar<'info, Rent>,
     trade_state_bump: u8,
@@ -148,11 +418,8 @@ pub fn auctioneer_bid_logic<'info>(
     public: bool,
     escrow_canonical_bump: u8,
     trade_state_canonical_bump: u8,
+    escrow_ledger_bump: u8,
 ) -> Result<()> {
-    if !auction_house.has_auctioneer {
-        return Err(AuctionHouseError::NoAuctioneerProgramSet.into());
-    }
-
     if (escrow_canonical_bump != escrow_payment_bump)
         || (trade_state_canonical_bump != trade_state_bump)
     {
@@ -183,6 +450,9 @@ pub fn auctioneer_bid_logic<'info>(
         &seeds,
     )?;
 
+    // The escrow only moves through the program of the treasury mint
+    assert_keys_equal(token_program.key(), *treasury_mint.to_account_info().owner)?;
+
     let is_native = treasury_mint.key() == spl_token::native_mint::id();
 
     let auction_house_key = auction_house.key();
@@ -205,15 +475,26 @@ pub fn auctioneer_bid_logic<'info>(
         fee_seeds,
         is_native,
     )?;
+    // The winner of an auctioneer sale also owes the buyer's premium, so the bid funds it upfront.
+    // Funds locked for bids on other listings can not back this one, only the rest of the escrow
+    // counts towards it
+    let required_escrow = buyer_price
+        .checked_add(get_buyer_premium(auction_house, buyer_price)?)
+        .ok_or(AuctionHouseError::NumericalOverflow)?
+        .checked_add(get_locked_escrow_requirement(
+            auction_house,
+            &escrow_ledger.to_account_info(),
+        )?)
+        .ok_or(AuctionHouseError::NumericalOverflow)?;
     if is_native {
         assert_keys_equal(wallet.key(), payment_account.key())?;
 
         if escrow_payment_account.lamports()
-            < buyer_price
+            < required_escrow
                 .checked_add(rent.minimum_balance(escrow_payment_account.data_len()))
                 .ok_or(AuctionHouseError::NumericalOverflow)?
         {
-            let diff = buyer_price
+            let diff = required_escrow
                 .checked_add(rent.minimum_balance(escrow_payment_account.data_len()))
                 .ok_or(AuctionHouseError::NumericalOverflow)?
                 .checked_sub(escrow_payment_account.lamports())
@@ -233,28 +514,21 @@ pub fn auctioneer_bid_logic<'info>(
             )?;
         }
     } else {
-        let escrow_payment_loaded: spl_token::state::Account =
-            assert_initialized(&escrow_payment_account)?;
+        let escrow_payment_loaded = unpack_token_account(&escrow_payment_account)?;
 
-        if escrow_payment_loaded.amount < buyer_price {
-            let diff = buyer_price
+        if escrow_payment_loaded.amount < required_escrow {
+            let diff = required_escrow
                 .checked_sub(escrow_payment_loaded.amount)
                 .ok_or(AuctionHouseError::NumericalOverflow)?;
-            invoke(
-                &spl_token::instruction::transfer(
-                    &token_program.key(),
-                    &payment_account.key(),
-                    &escrow_payment_account.key(),
-                    &transfer_authority.key(),
-                    &[],
-                    diff,
-                )?,
-                &[
-                    transfer_authority.to_account_info(),
-                    payment_account.to_account_info(),
-                    escrow_payment_account.to_account_info(),
-                    token_program.to_account_info(),
-                ],
+            // A transfer fee may leave the escrow slightly short, settlement tolerates it
+            token_transfer_checked(
+                &token_program.to_account_info(),
+                &payment_account.to_account_info(),
+                &treasury_mint.to_account_info(),
+                &escrow_payment_account.to_account_info(),
+                &transfer_authority.to_account_info(),
+                diff,
+                &[],
             )?;
         }
     }
@@ -271,7 +545,7 @@ pub fn auctioneer_bid_logic<'info>(
                 &rent.to_account_info(),
                 &system_program,
                 &fee_payer,
-                TRADE_STATE_SIZE,
+                BUYER_TRADE_STATE_SIZE,
                 fee_seeds,
                 &[
                     PREFIX.as_bytes(),
@@ -291,7 +565,7 @@ pub fn auctioneer_bid_logic<'info>(
                 &rent.to_account_info(),
                 &system_program,
                 &fee_payer,
-                TRADE_STATE_SIZE,
+                BUYER_TRADE_STATE_SIZE,
                 fee_seeds,
                 &[
                     PREFIX.as_bytes(),
@@ -312,6 +586,157 @@ pub fn auctioneer_bid_logic<'info>(
             trade_state_bump,
             TRADE_STATE_SIZE,
         );
+
+        // Every new buyer trade state counts as an open bid against the escrow
+        let mut ledger = load_or_create_escrow_ledger(
+            &escrow_ledger.to_account_info(),
+            &auction_house_key,
+            &wallet_key,
+            escrow_ledger_bump,
+            &fee_payer,
+            fee_seeds,
+            &system_program,
+            &rent.to_account_info(),
+        )?;
+        ledger.open_bids = ledger
+            .open_bids
+            .checked_add(1)
+            .ok_or(AuctionHouseError::NumericalOverflow)?;
+        ledger.try_serialize(&mut &mut escrow_ledger.data.borrow_mut()[..])?;
+        mark_open_bid_counted(&ts_info)?;
     }
     Ok(())
 }
+
+#[derive(Accounts)]
+#[instruction(trade_state_bump: u8, buyer_price: u64, token_size: u64)]
+pub struct AuctioneerBuyFromEscrow<'info> {
+    wallet: UncheckedAccount<'info>,
+    token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    metadata: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            wallet.key().as_ref(),
+            ESCROW_LEDGER.as_bytes()
+        ],
+        bump = escrow_ledger.bump
+    )]
+    escrow_ledger: Box<Account<'info, EscrowLedger>>,
+    auctioneer_authority: Signer<'info>,
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump = auction_house.bump
+    )]
+    auction_house: Box<Account<'info, AuctionHouse>>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            wallet.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_account.key().as_ref(),
+            auction_house.treasury_mint.as_ref(),
+            token_account.mint.as_ref(),
+            buyer_price.to_le_bytes().as_ref(),
+            token_size.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    buyer_trade_state: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            AUCTIONEER.as_bytes(),
+            auction_house.key().as_ref(),
+            auctioneer_authority.key().as_ref()
+        ],
+        bump = auctioneer.bump,
+    )]
+    pub auctioneer: Account<'info, Auctioneer>,
+    // Whoever settles the auction funds the trade state, the wallet does not sign
+    #[account(mut)]
+    payer: Signer<'info>,
+
+    system_program: Program<'info, System>,
+    rent: Sysvar<'info, Rent>,
+}
+
+// Places the bid of a wallet whose escrow the auctioneer already locked for it, so an auction
+// can be settled without the signature of the winner
+pub fn auctioneer_buy_from_escrow<'info>(
+    ctx: Context<'_, '_, '_, 'info, AuctioneerBuyFromEscrow<'info>>,
+    trade_state_bump: u8,
+    buyer_price: u64,
+    token_size: u64,
+) -> Result<()> {
+    let auction_house = &ctx.accounts.auction_house;
+    if !auction_house.has_auctioneer {
+        return Err(AuctionHouseError::NoAuctioneerProgramSet.into());
+    }
+    if *ctx
+        .bumps
+        .get("buyer_trade_state")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?
+        != trade_state_bump
+    {
+        return Err(AuctionHouseError::BumpSeedNotInHashMap.into());
+    }
+
+    // The lock keeps the wallet from withdrawing the funds of the bid since it was placed. It
+    // pools the bids of the wallet on every listing, so the auctioneer checks that the lock it
+    // recorded for this listing covers the bid
+    if ctx.accounts.escrow_ledger.locked_amount < buyer_price {
+        return Err(AuctionHouseError::EscrowNotLocked.into());
+    }
+    assert_metadata_valid(&ctx.accounts.metadata, &ctx.accounts.token_account)?;
+
+    let ts_info = ctx.accounts.buyer_trade_state.to_account_info();
+    if !ts_info.data_is_empty() {
+        return Ok(());
+    }
+
+    let wallet_key = ctx.accounts.wallet.key();
+    let auction_house_key = auction_house.key();
+    let token_account_key = ctx.accounts.token_account.key();
+    create_or_allocate_account_raw(
+        crate::id(),
+        &ts_info,
+        &ctx.accounts.rent.to_account_info(),
+        &ctx.accounts.system_program,
+        &ctx.accounts.payer,
+        BUYER_TRADE_STATE_SIZE,
+        &[],
+        &[
+            PREFIX.as_bytes(),
+            wallet_key.as_ref(),
+            auction_house_key.as_ref(),
+            token_account_key.as_ref(),
+            auction_house.treasury_mint.as_ref(),
+            ctx.accounts.token_account.mint.as_ref(),
+            &buyer_price.to_le_bytes(),
+            &token_size.to_le_bytes(),
+            &[trade_state_bump],
+        ],
+    )?;
+    #[allow(clippy::explicit_auto_deref)]
+    sol_memset(
+        *ts_info.try_borrow_mut_data()?,
+        trade_state_bump,
+        TRADE_STATE_SIZE,
+    );
+
+    let escrow_ledger = &mut ctx.accounts.escrow_ledger;
+    escrow_ledger.open_bids = escrow_ledger
+        .open_bids
+        .checked_add(1)
+        .ok_or(AuctionHouseError::NumericalOverflow)?;
+    mark_open_bid_counted(&ts_info)?;
+
+    Ok(())
+}
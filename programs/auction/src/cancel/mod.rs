@@ -1,7 +1,97 @@
-use crate::{constants::*, errors::*, utils::*, AuctionHouse, *};
+use crate::{
+    constants::*, errors::*, pda::find_program_as_signer_address, utils::*, AuctionHouse, *,
+};
 use anchor_lang::solana_program::program_memory::sol_memset;
 use anchor_lang::{prelude::*, solana_program::program::invoke, AnchorDeserialize};
-use spl_token::instruction::revoke;
+use mpl_token_metadata::instruction::{builders::RevokeBuilder, InstructionBuilder, RevokeArgs};
+use spl_token_2022::instruction::revoke;
+
+#[derive(Accounts, Clone)]
+#[instruction(buyer_price: u64, token_size: u64)]
+pub struct Cancel<'info> {
+    #[account(mut)]
+    pub wallet: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub authority: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=authority,
+        has_one=auction_house_fee_account
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            FEE_PAYER.as_bytes()
+        ],
+        bump=auction_house.fee_payer_bump
+    )]
+    pub auction_house_fee_account: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub trade_state: UncheckedAccount<'info>,
+    // Proceeds split and referral recorded for a listing close along with it, bids never have
+    // either
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            trade_state.key().as_ref(),
+            PROCEEDS_SPLIT.as_bytes()
+        ],
+        bump
+    )]
+    pub proceeds_split: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            trade_state.key().as_ref(),
+            REFERRAL.as_bytes()
+        ],
+        bump
+    )]
+    pub referral: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            wallet.key().as_ref(),
+            ESCROW_LEDGER.as_bytes()
+        ],
+        bump
+    )]
+    pub escrow_ledger: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> From<AuctioneerCancel<'info>> for Cancel<'info> {
+    fn from(a: AuctioneerCancel<'info>) -> Cancel<'info> {
+        Cancel {
+            wallet: a.wallet,
+            token_account: a.token_account,
+            token_mint: a.token_mint,
+            authority: a.authority,
+            auction_house: a.auction_house,
+            auction_house_fee_account: a.auction_house_fee_account,
+            trade_state: a.trade_state,
+            proceeds_split: a.proceeds_split,
+            referral: a.referral,
+            escrow_ledger: a.escrow_ledger,
+            token_program: a.token_program,
+        }
+    }
+}
 
 #[derive(Accounts, Clone)]
 #[instruction(buyer_price: u64, token_size: u64)]
@@ -9,8 +99,8 @@ pub struct AuctioneerCancel<'info> {
     #[account(mut)]
     pub wallet: UncheckedAccount<'info>,
     #[account(mut)]
-    pub token_account: Box<Account<'info, TokenAccount>>,
-    pub token_mint: Box<Account<'info, Mint>>,
+    pub token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
     pub authority: UncheckedAccount<'info>,
     pub auctioneer_authority: Signer<'info>,
     #[account(
@@ -36,6 +126,39 @@ pub struct AuctioneerCancel<'info> {
     pub auction_house_fee_account: UncheckedAccount<'info>,
     #[account(mut)]
     pub trade_state: UncheckedAccount<'info>,
+    // Proceeds split and referral recorded for a listing close along with it, bids never have
+    // either
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            trade_state.key().as_ref(),
+            PROCEEDS_SPLIT.as_bytes()
+        ],
+        bump
+    )]
+    pub proceeds_split: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            trade_state.key().as_ref(),
+            REFERRAL.as_bytes()
+        ],
+        bump
+    )]
+    pub referral: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            wallet.key().as_ref(),
+            ESCROW_LEDGER.as_bytes()
+        ],
+        bump
+    )]
+    pub escrow_ledger: UncheckedAccount<'info>,
     #[account(
         seeds = [
             AUCTIONEER.as_bytes(),
@@ -46,7 +169,24 @@ pub struct AuctioneerCancel<'info> {
     )]
     pub auctioneer: Account<'info, Auctioneer>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn cancel<'info>(
+    ctx: Context<'_, '_, '_, 'info, Cancel<'info>>,
+    buyer_price: u64,
+    token_size: u64,
+) -> Result<()> {
+    if ctx.accounts.auction_house.has_auctioneer {
+        return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
+    }
+
+    cancel_logic(
+        ctx.accounts,
+        ctx.remaining_accounts,
+        buyer_price,
+        token_size,
+    )
 }
 
 pub fn auctioneer_cancel<'info>(
@@ -60,15 +200,19 @@ pub fn auctioneer_cancel<'info>(
         return Err(AuctionHouseError::NoAuctioneerProgramSet.into());
     }
 
-    let mut accounts: AuctioneerCancel<'info> = (*ctx.accounts).clone();
+    let mut accounts: Cancel<'info> = (*ctx.accounts).clone().into();
 
-    cancel_logic(&mut accounts, buyer_price, token_size)
+    cancel_logic(
+        &mut accounts,
+        ctx.remaining_accounts,
+        buyer_price,
+        token_size,
+    )
 }
 
-#[allow(clippy::needless_lifetimes)]
-#[allow(clippy::extra_unused_lifetimes)]
 fn cancel_logic<'c, 'info>(
-    accounts: &mut AuctioneerCancel<'info>,
+    accounts: &mut Cancel<'info>,
+    remaining_accounts: &'c [AccountInfo<'info>],
     buyer_price: u64,
     token_size: u64,
 ) -> Result<()> {
@@ -79,6 +223,9 @@ fn cancel_logic<'c, 'info>(
     let auction_house = &accounts.auction_house;
     let auction_house_fee_account = &accounts.auction_house_fee_account;
     let trade_state = &accounts.trade_state;
+    let proceeds_split = &accounts.proceeds_split;
+    let referral = &accounts.referral;
+    let escrow_ledger = &accounts.escrow_ledger;
     let token_program = &accounts.token_program;
 
     let ts_bump = trade_state.try_borrow_data()?[0];
@@ -112,7 +259,76 @@ fn cancel_logic<'c, 'info>(
         &seeds,
     )?;
 
-    if token_account.owner == wallet.key() && wallet.is_signer {
+    // A programmable NFT listing passes its metadata and Token Metadata accounts so the sale
+    // delegate can be revoked, which also unlocks the token
+    let remaining_accounts = &mut remaining_accounts.iter();
+    let programmable_metadata = match remaining_accounts.next() {
+        Some(metadata) => {
+            assert_derivation(
+                &mpl_token_metadata::id(),
+                metadata,
+                &[
+                    mpl_token_metadata::state::PREFIX.as_bytes(),
+                    mpl_token_metadata::id().as_ref(),
+                    token_account.mint.as_ref(),
+                ],
+            )?;
+            is_programmable(metadata)?.then_some(metadata)
+        }
+        None => None,
+    };
+
+    let is_seller = token_account.owner == wallet.key() && wallet.is_signer;
+    // Only the owner can revoke the sale delegate of a programmable NFT, cancelling its listing
+    // without the seller would leave the token locked
+    if token_account.owner == wallet.key()
+        && !is_seller
+        && token_account.is_frozen()
+        && token_account.delegate == Some(find_program_as_signer_address().0).into()
+    {
+        return Err(AuctionHouseError::ProgrammableCancelRequiresSeller.into());
+    }
+    if let (true, Some(metadata)) = (is_seller, programmable_metadata) {
+        let pnft_accounts = next_programmable_accounts(remaining_accounts)?;
+        let program_as_signer = next_account_info(remaining_accounts)?;
+        let system_program = next_account_info(remaining_accounts)?;
+
+        let revoke_sale = RevokeBuilder::new()
+            .delegate(program_as_signer.key())
+            .metadata(metadata.key())
+            .master_edition(pnft_accounts.edition.key())
+            .token_record(pnft_accounts.token_record.key())
+            .mint(token_mint.key())
+            .token(token_account.key())
+            .authority(wallet.key())
+            .payer(wallet.key())
+            .system_program(system_program.key())
+            .sysvar_instructions(pnft_accounts.sysvar_instructions.key())
+            .spl_token_program(token_program.key())
+            .authorization_rules_program(pnft_accounts.authorization_rules_program.key())
+            .authorization_rules(pnft_accounts.authorization_rules.key())
+            .build(RevokeArgs::SaleV1)
+            .map_err(|_| AuctionHouseError::InvalidProgrammableAccounts)?;
+
+        invoke(
+            &revoke_sale.instruction(),
+            &[
+                program_as_signer.to_account_info(),
+                metadata.to_account_info(),
+                pnft_accounts.edition.to_account_info(),
+                pnft_accounts.token_record.to_account_info(),
+                token_mint.to_account_info(),
+                token_account.to_account_info(),
+                wallet.to_account_info(),
+                system_program.to_account_info(),
+                pnft_accounts.sysvar_instructions.to_account_info(),
+                token_program.to_account_info(),
+                pnft_accounts.authorization_rules_program.to_account_info(),
+                pnft_accounts.authorization_rules.to_account_info(),
+                pnft_accounts.token_metadata_program.to_account_info(),
+            ],
+        )?;
+    } else if is_seller {
         invoke(
             &revoke(
                 &token_program.key(),
@@ -129,7 +345,13 @@ fn cancel_logic<'c, 'info>(
         )?;
     }
 
+    release_open_bid(
+        &escrow_ledger.to_account_info(),
+        &trade_state.to_account_info(),
+    )?;
+
     let curr_lamp = trade_state.lamports();
+    let trade_state_size = trade_state.data_len();
     **trade_state.lamports.borrow_mut() = 0;
 
     **fee_payer.lamports.borrow_mut() = fee_payer
@@ -138,7 +360,23 @@ fn cancel_logic<'c, 'info>(
         .ok_or(AuctionHouseError::NumericalOverflow)?;
 
     #[allow(clippy::explicit_auto_deref)]
-    sol_memset(*trade_state.try_borrow_mut_data()?, 0, TRADE_STATE_SIZE);
+    sol_memset(*trade_state.try_borrow_mut_data()?, 0, trade_state_size);
+
+    if !proceeds_split.data_is_empty() {
+        close_account(&proceeds_split.to_account_info(), &wallet.to_account_info())?;
+    }
+
+    if !referral.data_is_empty() {
+        close_account(&referral.to_account_info(), &wallet.to_account_info())?;
+    }
+
+    emit!(TradeStateCancelled {
+        auction_house: auction_house.key(),
+        wallet: wallet.key(),
+        trade_state: trade_state.key(),
+        price: buyer_price,
+        token_size,
+    });
 
     Ok(())
 }
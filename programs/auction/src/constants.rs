@@ -3,7 +3,20 @@ pub const FEE_PAYER: &str = "fee_payer";
 pub const TREASURY: &str = "treasury";
 pub const SIGNER: &str = "signer";
 pub const AUCTIONEER: &str = "auctioneer";
+pub const ESCROW_LEDGER: &str = "escrow_ledger";
+pub const CREATOR_CLAIM: &str = "creator_claim";
+pub const PROCEEDS_SPLIT: &str = "proceeds_split";
+pub const REFERRAL: &str = "referral";
+pub const MAX_PROCEEDS_PAYEES: usize = 5;
+// Kinds of `RoyaltyPolicy` as the auction house stores them
+pub const ROYALTY_POLICY_ENFORCED: u8 = 0;
+pub const ROYALTY_POLICY_VERIFIED_CREATORS_ONLY: u8 = 1;
+pub const ROYALTY_POLICY_CAPPED: u8 = 2;
+pub const ROYALTY_POLICY_BUYER_CHOICE: u8 = 3;
 pub const TRADE_STATE_SIZE: usize = 1;
+pub const BUYER_TRADE_STATE_SIZE: usize = 1 +               // bump
+1                                                           // counted as an open bid
+;
 pub const AUCTIONEER_SIZE: usize = 8 +                      // Anchor discriminator/sighash
 32 +                                                        // Auctioneer authority
 32 +                                                        // Auction house instance
@@ -11,6 +24,33 @@ pub const AUCTIONEER_SIZE: usize = 8 +                      // Anchor discrimina
 63                                                          // Padding
 ;
 
+pub const ESCROW_LEDGER_SIZE: usize = 8 +                   // Anchor discriminator/sighash
+32 +                                                        // auction house
+32 +                                                        // wallet
+8 +                                                         // locked amount
+4 +                                                         // open bids
+1                                                           // bump
+;
+
+pub const CREATOR_CLAIM_SIZE: usize = 8 +                   // Anchor discriminator/sighash
+32 +                                                        // auction house
+32 +                                                        // creator
+8 +                                                         // amount
+1                                                           // bump
+;
+
+pub const PROCEEDS_SPLIT_SIZE: usize = 8 +                  // Anchor discriminator/sighash
+32 +                                                        // seller trade state
+4 + MAX_PROCEEDS_PAYEES * (32 + 1) +                        // payees
+1                                                           // bump
+;
+
+pub const REFERRAL_SIZE: usize = 8 +                        // Anchor discriminator/sighash
+32 +                                                        // seller trade state
+32 +                                                        // referrer
+1                                                           // bump
+;
+
 pub const AUCTION_HOUSE_SIZE: usize = 8 +                   // key
 32 +                                                        // fee Payer
 32 +                                                        // treasury
@@ -27,5 +67,13 @@ pub const AUCTION_HOUSE_SIZE: usize = 8 +                   // key
 8 +                                                         // escrow payment bump
 1 +                                                         // has external auctioneer program as an authority
 32 +                                                         // auctioneer address
-172                                                         // padding
+8 +                                                         // min auction duration
+8 +                                                         // max auction duration
+8 +                                                         // max time extension
+1 +                                                         // royalty policy
+2 +                                                         // royalty basis points
+2 +                                                         // referral basis points
+2 +                                                         // buyer premium basis points
+2 +                                                         // max auctioneer fee basis points
+139                                                         // padding
 ;
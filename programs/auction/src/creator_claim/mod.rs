@@ -0,0 +1,68 @@
+use anchor_lang::{prelude::*, AnchorDeserialize};
+
+use crate::{constants::*, errors::*, events::*, AuctionHouse, CreatorClaim};
+
+#[derive(Accounts)]
+pub struct ClaimCreatorRoyalties<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            creator.key().as_ref(),
+            CREATOR_CLAIM.as_bytes()
+        ],
+        bump = creator_claim.bump,
+        has_one = creator,
+        has_one = auction_house,
+        close = auction_house_fee_account
+    )]
+    pub creator_claim: Account<'info, CreatorClaim>,
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump = auction_house.bump,
+        has_one = auction_house_fee_account
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+    // The claim rent was paid when the royalties were escrowed, it goes back to the house
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            FEE_PAYER.as_bytes()
+        ],
+        bump = auction_house.fee_payer_bump
+    )]
+    pub auction_house_fee_account: UncheckedAccount<'info>,
+}
+
+// Pays out the held royalties, closing the claim returns its rent to the house fee account
+pub fn claim_creator_royalties(ctx: Context<ClaimCreatorRoyalties>) -> Result<()> {
+    let creator_claim = ctx.accounts.creator_claim.to_account_info();
+    let creator = ctx.accounts.creator.to_account_info();
+    let amount = ctx.accounts.creator_claim.amount;
+
+    **creator_claim.lamports.borrow_mut() = creator_claim
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(AuctionHouseError::NumericalOverflow)?;
+    **creator.lamports.borrow_mut() = creator
+        .lamports()
+        .checked_add(amount)
+        .ok_or(AuctionHouseError::NumericalOverflow)?;
+
+    emit!(CreatorRoyaltiesClaimed {
+        auction_house: ctx.accounts.auction_house.key(),
+        creator: ctx.accounts.creator.key(),
+        amount,
+    });
+
+    Ok(())
+}
@@ -19,7 +19,7 @@ pub struct AuctioneerDeposit<'info> {
         bump
     )]
     pub escrow_payment_account: UncheckedAccount<'info>,
-    pub treasury_mint: Box<Account<'info, Mint>>,
+    pub treasury_mint: Box<InterfaceAccount<'info, Mint>>,
     pub authority: UncheckedAccount<'info>,
     pub auctioneer_authority: Signer<'info>,
     #[account(
@@ -54,7 +54,7 @@ pub struct AuctioneerDeposit<'info> {
     )]
     pub auctioneer: Account<'info, Auctioneer>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
@@ -125,6 +125,9 @@ fn deposit_logic<'info>(
         &seeds,
     )?;
 
+    // The escrow only moves through the program of the treasury mint
+    assert_keys_equal(token_program.key(), *treasury_mint.to_account_info().owner)?;
+
     let is_native = treasury_mint.key() == spl_token::native_mint::id();
 
     create_program_token_account_if_not_present(
@@ -140,24 +143,18 @@ fn deposit_logic<'info>(
         is_native,
     )?;
 
-    if !is_native {
+    let amount_received = if !is_native {
         assert_is_ata(payment_account, &wallet.key(), &treasury_mint.key())?;
-        invoke(
-            &spl_token::instruction::transfer(
-                token_program.key,
-                &payment_account.key(),
-                &escrow_payment_account.key(),
-                &transfer_authority.key(),
-                &[],
-                amount,
-            )?,
-            &[
-                escrow_payment_account.to_account_info(),
-                payment_account.to_account_info(),
-                token_program.to_account_info(),
-                transfer_authority.to_account_info(),
-            ],
-        )?;
+        // The escrow is credited what arrives once any transfer fee is withheld
+        token_transfer_checked(
+            &token_program.to_account_info(),
+            &payment_account.to_account_info(),
+            &treasury_mint.to_account_info(),
+            &escrow_payment_account.to_account_info(),
+            &transfer_authority.to_account_info(),
+            amount,
+            &[],
+        )?
     } else {
         assert_keys_equal(payment_account.key(), wallet.key())?;
 
@@ -178,7 +175,14 @@ fn deposit_logic<'info>(
                 system_program.to_account_info(),
             ],
         )?;
-    }
+        amount
+    };
+
+    emit!(EscrowDeposited {
+        auction_house: auction_house_key,
+        wallet: wallet_key,
+        amount: amount_received,
+    });
 
     Ok(())
 }
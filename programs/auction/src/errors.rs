@@ -89,4 +89,92 @@ pub enum AuctionHouseError {
     // 6021
     #[msg("Insufficient funds in escrow account.")]
     InsufficientFunds,
+
+    // 6022
+    #[msg("Invalid auction duration or time extension bounds.")]
+    InvalidAuctionBounds,
+
+    // 6023
+    #[msg("Escrow funds are locked by an outstanding bid.")]
+    EscrowFundsLocked,
+
+    // 6024
+    #[msg("Escrow account still has open bids.")]
+    EscrowHasOpenBids,
+
+    // 6025
+    #[msg("Auction house has an auctioneer, use the auctioneer instructions.")]
+    MustUseAuctioneerHandler,
+
+    // 6026
+    #[msg("Partial orders need both a partial order size and a partial order price.")]
+    MissingElementForPartialOrder,
+
+    // 6027
+    #[msg("Partial order price does not match the listing price for the partial size.")]
+    PartialPriceMismatch,
+
+    // 6028
+    #[msg("Missing or invalid Token Metadata accounts for a programmable NFT.")]
+    InvalidProgrammableAccounts,
+
+    // 6029
+    #[msg("Token-2022 mint is non-transferable.")]
+    NonTransferableMint,
+
+    // 6030
+    #[msg("Token mint withholds a transfer fee, the buyer would not receive the full token size.")]
+    SaleTokenTransferFee,
+
+    // 6031
+    #[msg("Claim account is required to hold a royalty its creator cannot receive.")]
+    MissingCreatorClaim,
+
+    // 6032
+    #[msg("Chosen royalty is below the auction house minimum.")]
+    RoyaltyBelowMinimum,
+
+    // 6033
+    #[msg("Royalty can only be chosen by a signing buyer on a buyer-choice auction house.")]
+    RoyaltyChoiceNotAllowed,
+
+    // 6034
+    #[msg("Referrer cannot be the buyer or the seller of the sale.")]
+    InvalidReferrer,
+
+    // 6035
+    #[msg("Referrer token account is required when the treasury mint is not native.")]
+    MissingReferrerTokenAccount,
+
+    // 6036
+    #[msg("Auctioneer fee destination is required when the auctioneer charges a fee.")]
+    MissingAuctioneerFeeDestination,
+
+    // 6037
+    #[msg("Proceeds split needs 1 to 5 payees with shares adding up to 100.")]
+    InvalidProceedsSplit,
+
+    // 6038
+    #[msg("The locked escrow of the wallet does not cover the bid.")]
+    EscrowNotLocked,
+
+    // 6039
+    #[msg("Referrer does not match the one recorded for the listing.")]
+    ReferrerMismatch,
+
+    // 6040
+    #[msg("Auctioneer fee is above the maximum the auction house allows.")]
+    AuctioneerFeeAboveMaximum,
+
+    // 6041
+    #[msg("Only the seller can cancel the listing of a programmable NFT.")]
+    ProgrammableCancelRequiresSeller,
+
+    // 6042
+    #[msg("Auction house has an unknown royalty policy.")]
+    InvalidRoyaltyPolicy,
+
+    // 6043
+    #[msg("Claim account is required to hold a proceeds share its payee cannot receive.")]
+    MissingPayeeClaim,
 }
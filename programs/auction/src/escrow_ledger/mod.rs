@@ -0,0 +1,159 @@
+use anchor_lang::{prelude::*, AnchorDeserialize};
+
+use crate::{constants::*, errors::*, utils::*, AuctionHouse, *};
+
+#[derive(Accounts)]
+pub struct AuctioneerLockEscrow<'info> {
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            wallet.key().as_ref()
+        ],
+        bump
+    )]
+    pub escrow_payment_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            wallet.key().as_ref(),
+            ESCROW_LEDGER.as_bytes()
+        ],
+        bump
+    )]
+    pub escrow_ledger: UncheckedAccount<'info>,
+    pub treasury_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub auctioneer_authority: Signer<'info>,
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=treasury_mint
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+    #[account(
+        seeds = [
+            AUCTIONEER.as_bytes(),
+            auction_house.key().as_ref(),
+            auctioneer_authority.key().as_ref()
+        ],
+        bump = auctioneer.bump
+    )]
+    pub auctioneer: Account<'info, Auctioneer>,
+
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct AuctioneerUnlockEscrow<'info> {
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            escrow_ledger.wallet.as_ref(),
+            ESCROW_LEDGER.as_bytes()
+        ],
+        bump = escrow_ledger.bump
+    )]
+    pub escrow_ledger: Account<'info, EscrowLedger>,
+    pub auctioneer_authority: Signer<'info>,
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+    #[account(
+        seeds = [
+            AUCTIONEER.as_bytes(),
+            auction_house.key().as_ref(),
+            auctioneer_authority.key().as_ref()
+        ],
+        bump = auctioneer.bump
+    )]
+    pub auctioneer: Account<'info, Auctioneer>,
+}
+
+pub fn auctioneer_lock_escrow<'info>(
+    ctx: Context<'_, '_, '_, 'info, AuctioneerLockEscrow<'info>>,
+    amount: u64,
+) -> Result<()> {
+    let auction_house = &ctx.accounts.auction_house;
+
+    if !auction_house.has_auctioneer {
+        return Err(AuctionHouseError::NoAuctioneerProgramSet.into());
+    }
+
+    let escrow_ledger = &ctx.accounts.escrow_ledger;
+    let auction_house_key = auction_house.key();
+    let wallet_key = ctx.accounts.wallet.key();
+    let escrow_ledger_bump = *ctx
+        .bumps
+        .get("escrow_ledger")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+
+    let mut ledger = load_or_create_escrow_ledger(
+        &escrow_ledger.to_account_info(),
+        &auction_house_key,
+        &wallet_key,
+        escrow_ledger_bump,
+        &ctx.accounts.wallet.to_account_info(),
+        &[],
+        &ctx.accounts.system_program.to_account_info(),
+        &ctx.accounts.rent.to_account_info(),
+    )?;
+
+    ledger.locked_amount = ledger
+        .locked_amount
+        .checked_add(amount)
+        .ok_or(AuctionHouseError::NumericalOverflow)?;
+
+    let is_native = ctx.accounts.treasury_mint.key() == spl_token::native_mint::id();
+    let escrow_balance = get_escrow_balance(
+        &ctx.accounts.escrow_payment_account.to_account_info(),
+        is_native,
+    )?;
+    let required_amount = ledger
+        .locked_amount
+        .checked_add(get_buyer_premium(auction_house, ledger.locked_amount)?)
+        .ok_or(AuctionHouseError::NumericalOverflow)?;
+    // A bid funded through a mint with a transfer fee leaves the escrow short by that fee,
+    // which settlement absorbs
+    let transfer_fee = get_transfer_fee(
+        &ctx.accounts.treasury_mint.to_account_info(),
+        required_amount,
+    )?;
+    if escrow_balance.saturating_add(transfer_fee) < required_amount {
+        return Err(AuctionHouseError::InsufficientFunds.into());
+    }
+
+    ledger.try_serialize(&mut &mut escrow_ledger.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+pub fn auctioneer_unlock_escrow<'info>(
+    ctx: Context<'_, '_, '_, 'info, AuctioneerUnlockEscrow<'info>>,
+    amount: u64,
+) -> Result<()> {
+    if !ctx.accounts.auction_house.has_auctioneer {
+        return Err(AuctionHouseError::NoAuctioneerProgramSet.into());
+    }
+
+    let escrow_ledger = &mut ctx.accounts.escrow_ledger;
+    escrow_ledger.locked_amount = escrow_ledger.locked_amount.saturating_sub(amount);
+
+    Ok(())
+}
@@ -0,0 +1,116 @@
+use crate::{ProceedsPayee, RoyaltyPolicy};
+use anchor_lang::prelude::*;
+
+#[event]
+pub struct AuctionHouseCreated {
+    pub auction_house: Pubkey,
+    pub authority: Pubkey,
+    pub treasury_mint: Pubkey,
+    pub seller_fee_basis_points: u16,
+    pub can_change_sale_price: bool,
+}
+
+#[event]
+pub struct AuctionHouseUpdated {
+    pub auction_house: Pubkey,
+    pub authority: Pubkey,
+    pub seller_fee_basis_points: u16,
+    pub can_change_sale_price: bool,
+    pub min_auction_duration: i64,
+    pub max_auction_duration: i64,
+    pub max_time_extension: i64,
+    pub royalty_policy: RoyaltyPolicy,
+    pub referral_basis_points: u16,
+    pub buyer_premium_basis_points: u16,
+    pub max_auctioneer_fee_basis_points: u16,
+}
+
+#[event]
+pub struct AuctioneerDelegated {
+    pub auction_house: Pubkey,
+    pub auctioneer_authority: Pubkey,
+    pub auctioneer: Pubkey,
+}
+
+#[event]
+pub struct EscrowDeposited {
+    pub auction_house: Pubkey,
+    pub wallet: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct EscrowWithdrawn {
+    pub auction_house: Pubkey,
+    pub wallet: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct TradeStateCancelled {
+    pub auction_house: Pubkey,
+    pub wallet: Pubkey,
+    pub trade_state: Pubkey,
+    pub price: u64,
+    pub token_size: u64,
+}
+
+#[event]
+pub struct SaleExecuted {
+    pub auction_house: Pubkey,
+    pub buyer: Pubkey,
+    pub seller: Pubkey,
+    pub token_mint: Pubkey,
+    pub price: u64,
+    pub token_size: u64,
+    pub royalty_paid: u64,
+    pub auction_house_fee_paid: u64,
+    // What the seller received after royalties and the house fee
+    pub seller_proceeds: u64,
+    pub buyer_premium_paid: u64,
+    pub auctioneer_fee_paid: u64,
+    pub royalty_policy: RoyaltyPolicy,
+    pub royalty_basis_points: u16,
+}
+
+#[event]
+pub struct ReferralFeePaid {
+    pub auction_house: Pubkey,
+    pub referrer: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct ProceedsSplitSet {
+    pub auction_house: Pubkey,
+    pub seller_trade_state: Pubkey,
+    pub payees: Vec<ProceedsPayee>,
+}
+
+#[event]
+pub struct ReferralSet {
+    pub auction_house: Pubkey,
+    pub seller_trade_state: Pubkey,
+    pub referrer: Pubkey,
+}
+
+#[event]
+pub struct CreatorRoyaltiesEscrowed {
+    pub auction_house: Pubkey,
+    pub creator: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct ProceedsShareEscrowed {
+    pub auction_house: Pubkey,
+    pub payee: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct CreatorRoyaltiesClaimed {
+    pub auction_house: Pubkey,
+    pub creator: Pubkey,
+    pub amount: u64,
+}
@@ -1,8 +1,208 @@
 use crate::{constants::*, errors::*, utils::*, AuctionHouse, Auctioneer, *};
-use anchor_lang::{prelude::*, solana_program::program_pack::Pack, AnchorDeserialize};
-use spl_token::state::Account as SplAccount;
+use anchor_lang::{prelude::*, AnchorDeserialize};
+use mpl_token_metadata::{
+    instruction::{builders::TransferBuilder, InstructionBuilder, TransferArgs},
+    state::{Metadata, TokenMetadataAccount},
+};
 
-#[derive(Accounts)]
+/// Amounts paid out by an auctioneer sale, returned to the calling auctioneer program.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SaleAmounts {
+    pub royalty_paid: u64,
+    pub auction_house_fee_paid: u64,
+    pub seller_proceeds: u64,
+    pub buyer_premium_paid: u64,
+    pub auctioneer_fee_paid: u64,
+}
+
+#[derive(Accounts, Clone)]
+#[instruction(
+    escrow_payment_bump: u8,
+    free_trade_state_bump: u8,
+    program_as_signer_bump: u8,
+    buyer_price: u64,
+    token_size: u64
+)]
+pub struct ExecuteSale<'info> {
+    #[account(mut)]
+    pub buyer: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub seller: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub token_account: UncheckedAccount<'info>,
+    pub token_mint: UncheckedAccount<'info>,
+    pub metadata: UncheckedAccount<'info>,
+    pub treasury_mint: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            buyer.key().as_ref()
+        ],
+        bump
+    )]
+    pub escrow_payment_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            buyer.key().as_ref(),
+            ESCROW_LEDGER.as_bytes()
+        ],
+        bump
+    )]
+    pub escrow_ledger: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub seller_payment_receipt_account: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub buyer_receipt_token_account: UncheckedAccount<'info>,
+    pub authority: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=authority,
+        has_one=treasury_mint,
+        has_one=auction_house_treasury,
+        has_one=auction_house_fee_account
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            FEE_PAYER.as_bytes()
+        ],
+        bump=auction_house.fee_payer_bump
+    )]
+    pub auction_house_fee_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            TREASURY.as_bytes()
+        ],
+        bump=auction_house.treasury_bump
+    )]
+    pub auction_house_treasury: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub buyer_trade_state: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            seller.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_account.key().as_ref(),
+            auction_house.treasury_mint.as_ref(),
+            token_mint.key().as_ref(),
+            &buyer_price.to_le_bytes(),
+            &token_size.to_le_bytes()
+        ],
+        bump=seller_trade_state.to_account_info().data.borrow()[0]
+    )]
+    pub seller_trade_state: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            seller.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_account.key().as_ref(),
+            auction_house.treasury_mint.as_ref(),
+            token_mint.key().as_ref(),
+            &0u64.to_le_bytes(),
+            &token_size.to_le_bytes()
+        ],
+        bump
+    )]
+    pub free_trade_state: UncheckedAccount<'info>,
+    // Payees of the listing's proceeds, left empty when the seller keeps all of them
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            seller_trade_state.key().as_ref(),
+            PROCEEDS_SPLIT.as_bytes()
+        ],
+        bump
+    )]
+    pub proceeds_split: UncheckedAccount<'info>,
+    // Referrer the seller recorded for the listing, left empty when the listing was not referred
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            seller_trade_state.key().as_ref(),
+            REFERRAL.as_bytes()
+        ],
+        bump
+    )]
+    pub referral: UncheckedAccount<'info>,
+    // Frontend that routed the trade, paid its referral share out of the house fee
+    #[account(mut)]
+    pub referrer: Option<UncheckedAccount<'info>>,
+    #[account(mut)]
+    pub referrer_token_account: Option<UncheckedAccount<'info>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    // Program of the treasury mint, moves the escrow into fees, royalties and proceeds
+    pub treasury_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    #[account(
+        seeds=[
+            PREFIX.as_bytes(), SIGNER.as_bytes()
+        ],
+        bump
+    )]
+    pub program_as_signer: UncheckedAccount<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+impl<'info> From<AuctioneerExecuteSale<'info>> for ExecuteSale<'info> {
+    fn from(a: AuctioneerExecuteSale<'info>) -> ExecuteSale<'info> {
+        ExecuteSale {
+            buyer: a.buyer,
+            seller: a.seller,
+            token_account: a.token_account,
+            token_mint: a.token_mint,
+            metadata: a.metadata,
+            treasury_mint: a.treasury_mint,
+            escrow_payment_account: a.escrow_payment_account,
+            escrow_ledger: a.escrow_ledger,
+            seller_payment_receipt_account: a.seller_payment_receipt_account,
+            buyer_receipt_token_account: a.buyer_receipt_token_account,
+            authority: a.authority,
+            auction_house: a.auction_house,
+            auction_house_fee_account: a.auction_house_fee_account,
+            auction_house_treasury: a.auction_house_treasury,
+            buyer_trade_state: a.buyer_trade_state,
+            seller_trade_state: a.seller_trade_state,
+            free_trade_state: a.free_trade_state,
+            proceeds_split: a.proceeds_split,
+            referral: a.referral,
+            referrer: a.referrer,
+            referrer_token_account: a.referrer_token_account,
+            token_program: a.token_program,
+            treasury_token_program: a.treasury_token_program,
+            system_program: a.system_program,
+            associated_token_program: a.associated_token_program,
+            program_as_signer: a.program_as_signer,
+            rent: a.rent,
+        }
+    }
+}
+
+#[derive(Accounts, Clone)]
 #[instruction(
     escrow_payment_bump: u8,
     free_trade_state_bump: u8,
@@ -30,6 +230,17 @@ pub struct AuctioneerExecuteSale<'info> {
         bump
     )]
     pub escrow_payment_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            buyer.key().as_ref(),
+            ESCROW_LEDGER.as_bytes()
+        ],
+        bump
+    )]
+    pub escrow_ledger: UncheckedAccount<'info>,
     #[account(mut)]
     pub seller_payment_receipt_account: UncheckedAccount<'info>,
     #[account(mut)]
@@ -101,6 +312,28 @@ pub struct AuctioneerExecuteSale<'info> {
         bump
     )]
     pub free_trade_state: UncheckedAccount<'info>,
+    // Payees of the listing's proceeds, left empty when the seller keeps all of them
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            seller_trade_state.key().as_ref(),
+            PROCEEDS_SPLIT.as_bytes()
+        ],
+        bump
+    )]
+    pub proceeds_split: UncheckedAccount<'info>,
+    // Referrer the seller recorded for the listing, left empty when the listing was not referred
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            seller_trade_state.key().as_ref(),
+            REFERRAL.as_bytes()
+        ],
+        bump
+    )]
+    pub referral: UncheckedAccount<'info>,
     #[account(
         seeds = [
             AUCTIONEER.as_bytes(),
@@ -110,8 +343,21 @@ pub struct AuctioneerExecuteSale<'info> {
         bump = auctioneer.bump
     )]
     pub auctioneer: Box<Account<'info, Auctioneer>>,
+    // Frontend that routed the trade, paid its referral share out of the house fee
+    #[account(mut)]
+    pub referrer: Option<UncheckedAccount<'info>>,
+    #[account(mut)]
+    pub referrer_token_account: Option<UncheckedAccount<'info>>,
+    // Where the auctioneer program collects its own fee, a token account for SPL treasuries
+    #[account(mut)]
+    pub auctioneer_fee_destination: Option<UncheckedAccount<'info>>,
+    // Third party settling an ended auction, pays for the accounts the sale creates
+    #[account(mut)]
+    pub cranker: Option<Signer<'info>>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
+    // Program of the treasury mint, moves the escrow into fees, royalties and proceeds
+    pub treasury_token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     #[account(
@@ -125,6 +371,72 @@ pub struct AuctioneerExecuteSale<'info> {
     pub rent: Sysvar<'info, Rent>,
 }
 
+pub fn execute_sale<'info>(
+    ctx: Context<'_, '_, '_, 'info, ExecuteSale<'info>>,
+    escrow_payment_bump: u8,
+    free_trade_state_bump: u8,
+    program_as_signer_bump: u8,
+    buyer_price: u64,
+    token_size: u64,
+    partial_order_size: Option<u64>,
+    partial_order_price: Option<u64>,
+    buyer_royalty_basis_points: Option<u16>,
+) -> Result<SaleAmounts> {
+    let auction_house = &ctx.accounts.auction_house;
+
+    if auction_house.has_auctioneer {
+        return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
+    }
+
+    if buyer_royalty_basis_points.is_some() && !ctx.accounts.buyer.is_signer {
+        return Err(AuctionHouseError::RoyaltyChoiceNotAllowed.into());
+    }
+
+    // Without an auctioneer to sign off, one of the parties or the house has to sign.
+    if !ctx.accounts.buyer.is_signer
+        && !ctx.accounts.seller.is_signer
+        && !ctx.accounts.authority.is_signer
+    {
+        return Err(AuctionHouseError::NoValidSignerPresent.into());
+    }
+
+    let escrow_canonical_bump = *ctx
+        .bumps
+        .get("escrow_payment_account")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+    let free_trade_state_canonical_bump = *ctx
+        .bumps
+        .get("free_trade_state")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+    let program_as_signer_canonical_bump = *ctx
+        .bumps
+        .get("program_as_signer")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+
+    if (escrow_canonical_bump != escrow_payment_bump)
+        || (free_trade_state_canonical_bump != free_trade_state_bump)
+        || (program_as_signer_canonical_bump != program_as_signer_bump)
+    {
+        return Err(AuctionHouseError::BumpSeedNotInHashMap.into());
+    }
+
+    execute_sale_logic(
+        ctx.accounts,
+        ctx.remaining_accounts,
+        escrow_payment_bump,
+        free_trade_state_bump,
+        program_as_signer_bump,
+        buyer_price,
+        token_size,
+        partial_order_size,
+        partial_order_price,
+        buyer_royalty_basis_points,
+        false,
+        None,
+        None,
+    )
+}
+
 pub fn auctioneer_execute_sale<'info>(
     ctx: Context<'_, '_, '_, 'info, AuctioneerExecuteSale<'info>>,
     escrow_payment_bump: u8,
@@ -132,13 +444,29 @@ pub fn auctioneer_execute_sale<'info>(
     program_as_signer_bump: u8,
     buyer_price: u64,
     token_size: u64,
-) -> Result<()> {
+    partial_order_size: Option<u64>,
+    partial_order_price: Option<u64>,
+    auctioneer_fee_basis_points: u16,
+) -> Result<SaleAmounts> {
     let auction_house = &ctx.accounts.auction_house;
 
     if !auction_house.has_auctioneer {
         return Err(AuctionHouseError::NoAuctioneerProgramSet.into());
     }
 
+    // The house bounds what the auctioneer program can take from its sellers
+    if auctioneer_fee_basis_points > auction_house.max_auctioneer_fee_basis_points {
+        return Err(AuctionHouseError::AuctioneerFeeAboveMaximum.into());
+    }
+    let auctioneer_fee = match (
+        auctioneer_fee_basis_points,
+        &ctx.accounts.auctioneer_fee_destination,
+    ) {
+        (0, _) => None,
+        (basis_points, Some(destination)) => Some((destination.to_account_info(), basis_points)),
+        (_, None) => return Err(AuctionHouseError::MissingAuctioneerFeeDestination.into()),
+    };
+
     let escrow_canonical_bump = *ctx
         .bumps
         .get("escrow_payment_account")
@@ -159,27 +487,46 @@ pub fn auctioneer_execute_sale<'info>(
         return Err(AuctionHouseError::BumpSeedNotInHashMap.into());
     }
 
-    auctioneer_execute_sale_logic(
-        ctx.accounts,
+    let mut accounts: ExecuteSale<'info> = (*ctx.accounts).clone().into();
+
+    // The buyer does not sign auctioneer sales, so a buyer-choice house pays the full royalty,
+    // and only auctioneer winners owe the buyer's premium
+    execute_sale_logic(
+        &mut accounts,
         ctx.remaining_accounts,
         escrow_payment_bump,
         free_trade_state_bump,
         program_as_signer_bump,
         buyer_price,
         token_size,
+        partial_order_size,
+        partial_order_price,
+        None,
+        true,
+        auctioneer_fee,
+        ctx.accounts
+            .cranker
+            .as_ref()
+            .map(|cranker| cranker.to_account_info()),
     )
 }
 
 #[inline(never)]
-fn auctioneer_execute_sale_logic<'c, 'info>(
-    accounts: &mut AuctioneerExecuteSale<'info>,
+fn execute_sale_logic<'c, 'info>(
+    accounts: &mut ExecuteSale<'info>,
     remaining_accounts: &'c [AccountInfo<'info>],
     escrow_payment_bump: u8,
     _free_trade_state_bump: u8,
     program_as_signer_bump: u8,
     buyer_price: u64,
     token_size: u64,
-) -> Result<()> {
+    partial_order_size: Option<u64>,
+    partial_order_price: Option<u64>,
+    buyer_royalty_basis_points: Option<u16>,
+    charge_buyer_premium: bool,
+    auctioneer_fee: Option<(AccountInfo<'info>, u16)>,
+    cranker: Option<AccountInfo<'info>>,
+) -> Result<SaleAmounts> {
     let buyer = &accounts.buyer;
     let seller = &accounts.seller;
     let token_account = &accounts.token_account;
@@ -189,6 +536,7 @@ fn auctioneer_execute_sale_logic<'c, 'info>(
     let seller_payment_receipt_account = &accounts.seller_payment_receipt_account;
     let buyer_receipt_token_account = &accounts.buyer_receipt_token_account;
     let escrow_payment_account = &accounts.escrow_payment_account;
+    let escrow_ledger = &accounts.escrow_ledger;
     let authority = &accounts.authority;
     let auction_house = &accounts.auction_house;
     let auction_house_fee_account = &accounts.auction_house_fee_account;
@@ -196,7 +544,12 @@ fn auctioneer_execute_sale_logic<'c, 'info>(
     let buyer_trade_state = &accounts.buyer_trade_state;
     let seller_trade_state = &accounts.seller_trade_state;
     let free_trade_state = &accounts.free_trade_state;
+    let proceeds_split = &accounts.proceeds_split;
+    let referral = &accounts.referral;
+    let referrer = &accounts.referrer;
+    let referrer_token_account = &accounts.referrer_token_account;
     let token_program = &accounts.token_program;
+    let treasury_token_program = &accounts.treasury_token_program;
     let system_program = &accounts.system_program;
     let associated_token_program = &accounts.associated_token_program;
     let program_as_signer = &accounts.program_as_signer;
@@ -205,8 +558,10 @@ fn auctioneer_execute_sale_logic<'c, 'info>(
     let metadata_clone = metadata.to_account_info();
     let escrow_clone = escrow_payment_account.to_account_info();
     let auction_house_clone = auction_house.to_account_info();
+    let auction_house_fee_clone = auction_house_fee_account.to_account_info();
     let ata_clone = associated_token_program.to_account_info();
     let token_clone = token_program.to_account_info();
+    let treasury_token_clone = treasury_token_program.to_account_info();
     let sys_clone = system_program.to_account_info();
     let rent_clone = rent.to_account_info();
     let treasury_clone = auction_house_treasury.to_account_info();
@@ -215,8 +570,51 @@ fn auctioneer_execute_sale_logic<'c, 'info>(
     let token_account_clone = token_account.to_account_info();
 
     let is_native = treasury_mint.key() == spl_token::native_mint::id();
+    // The treasury mint and the sold token can belong to different token programs
+    assert_keys_equal(treasury_token_program.key(), *treasury_mint.owner)?;
+
+    // A partial order takes part of the listing at the listing's unit price, the
+    // rest stays listed under the seller trade state.
+    let (sale_price, sale_size) = match (partial_order_size, partial_order_price) {
+        (Some(size), Some(price)) => {
+            if size == 0 || size > token_size {
+                return Err(AuctionHouseError::NotEnoughTokensAvailableForPurchase.into());
+            }
+
+            let amount_to_pay = buyer_price
+                .checked_div(token_size)
+                .ok_or(AuctionHouseError::NumericalOverflow)?
+                .checked_mul(size)
+                .ok_or(AuctionHouseError::NumericalOverflow)?;
+
+            if amount_to_pay != price {
+                return Err(AuctionHouseError::PartialPriceMismatch.into());
+            }
+
+            (price, size)
+        }
+        (None, None) => (buyer_price, token_size),
+        _ => return Err(AuctionHouseError::MissingElementForPartialOrder.into()),
+    };
 
-    if buyer_price == 0 && !authority_clone.is_signer && !seller.is_signer {
+    // The premium is paid from the escrow on top of the price, the seller never sees it
+    let buyer_premium = if charge_buyer_premium {
+        get_buyer_premium(auction_house, sale_price)?
+    } else {
+        0
+    };
+    let escrow_spend = sale_price
+        .checked_add(buyer_premium)
+        .ok_or(AuctionHouseError::NumericalOverflow)?;
+
+    // The buyer has to receive the full size, Token-2022 mints that would withhold part of it
+    // or refuse to move at all cannot be sold
+    assert_transferable(token_mint)?;
+    if get_transfer_fee(token_mint, sale_size)? > 0 {
+        return Err(AuctionHouseError::SaleTokenTransferFee.into());
+    }
+
+    if sale_price == 0 && !authority_clone.is_signer && !seller.is_signer {
         return Err(
             AuctionHouseError::CannotMatchFreeSalesWithoutAuctionHouseOrSellerSignoff.into(),
         );
@@ -246,20 +644,20 @@ fn auctioneer_execute_sale_logic<'c, 'info>(
         return Err(AuctionHouseError::BothPartiesNeedToAgreeToSale.into());
     }
 
-    let token_account_data = SplAccount::unpack(&token_account.data.borrow())?;
+    let token_account_data = unpack_token_account(token_account)?;
 
     assert_valid_trade_state(
         &buyer.key(),
         auction_house,
-        buyer_price,
-        token_size,
+        sale_price,
+        sale_size,
         buyer_trade_state,
         &token_mint.key(),
         &token_account.key(),
         ts_bump,
     )?;
 
-    if token_account_data.amount < token_size {
+    if token_account_data.amount < sale_size {
         return Err(AuctionHouseError::NotEnoughTokensAvailableForPurchase.into());
     };
 
@@ -273,12 +671,23 @@ fn auctioneer_execute_sale_logic<'c, 'info>(
 
     let wallet_to_use = if buyer.is_signer { buyer } else { seller };
 
-    let (fee_payer, fee_payer_seeds) = get_fee_payer(
-        authority,
-        wallet_to_use.to_account_info(),
-        auction_house_fee_account.to_account_info(),
-        &seeds,
-    )?;
+    // Only auctioneer sales get here without a signing party, since the auctioneer signs
+    // off on them. A settlement cranked by a third party is paid for by the cranker, which only
+    // covers the accounts created here, the trade state rent goes back to buyer and seller.
+    let (fee_payer, fee_payer_seeds) = match cranker {
+        Some(cranker) if !authority.is_signer && !wallet_to_use.is_signer => get_fee_payer(
+            authority,
+            cranker,
+            auction_house_fee_account.to_account_info(),
+            &seeds,
+        )?,
+        _ => get_fee_payer(
+            authority,
+            wallet_to_use.to_account_info(),
+            auction_house_fee_account.to_account_info(),
+            &seeds,
+        )?,
+    };
     let fee_payer_clone = fee_payer.to_account_info();
 
     assert_is_ata(
@@ -298,7 +707,7 @@ fn auctioneer_execute_sale_logic<'c, 'info>(
 
     if is_native {
         let rent_shortfall =
-            verify_withdrawal(escrow_payment_account.to_account_info(), buyer_price)?;
+            verify_withdrawal(escrow_payment_account.to_account_info(), escrow_spend)?;
         if rent_shortfall > 0 {
             invoke_signed(
                 &system_instruction::transfer(
@@ -320,6 +729,15 @@ fn auctioneer_execute_sale_logic<'c, 'info>(
         return Err(AuctionHouseError::MetadataDoesntExist.into());
     }
 
+    let royalty_policy = get_royalty_policy(auction_house)?;
+    let royalty_basis_points = get_royalty_basis_points(
+        &royalty_policy,
+        Metadata::from_account_info(&metadata_clone)?
+            .data
+            .seller_fee_basis_points,
+        buyer_royalty_basis_points,
+    )?;
+
     let auction_house_key = auction_house.key();
     let wallet_key = buyer.key();
     let escrow_signer_seeds = [
@@ -344,39 +762,144 @@ fn auctioneer_execute_sale_logic<'c, 'info>(
 
     let remaining_accounts = &mut remaining_accounts.iter();
 
-    let buyer_leftover_after_royalties = pay_creator_fees(
+    // Funding the bid through a mint with a transfer fee may have left the escrow a little short
+    // of the price, the seller's remainder absorbs up to one transfer fee of it
+    let escrow_shortfall = if is_native {
+        0
+    } else {
+        let escrow_shortfall =
+            escrow_spend.saturating_sub(get_escrow_balance(&escrow_clone, is_native)?);
+        if escrow_shortfall > get_transfer_fee(treasury_mint, escrow_spend)? {
+            return Err(AuctionHouseError::InsufficientFunds.into());
+        }
+        escrow_shortfall
+    };
+
+    let royalties = pay_creator_fees(
         remaining_accounts,
+        &auction_house_key,
         &metadata_clone,
         &escrow_clone,
         &auction_house_clone,
+        &auction_house_fee_clone,
+        &seeds,
         &fee_payer_clone,
         treasury_mint,
         &ata_clone,
-        &token_clone,
+        &treasury_token_clone,
         &sys_clone,
         &rent_clone,
         &signer_seeds_for_royalties,
         fee_payer_seeds,
-        buyer_price,
+        sale_price,
         is_native,
+        &royalty_policy,
+        royalty_basis_points,
     )?;
 
-    let auction_house_fee_paid = pay_auction_house_fees(
+    let referrer_clone = referrer.as_ref().map(|r| r.to_account_info());
+    let referrer_token_clone = referrer_token_account.as_ref().map(|r| r.to_account_info());
+    // A party to the sale referring it would rebate the house fee to itself
+    if let Some(referrer) = &referrer_clone {
+        if referrer.key() == buyer.key() || referrer.key() == seller.key() {
+            return Err(AuctionHouseError::InvalidReferrer.into());
+        }
+    }
+
+    // Only the referrer recorded with the listing is paid, and it can not be left out
+    let recorded_referrer = if referral.data_is_empty() {
+        None
+    } else {
+        assert_owned_by(&referral.to_account_info(), &crate::id())?;
+        Some(Referral::try_deserialize(&mut referral.data.borrow().as_ref())?.referrer)
+    };
+    if recorded_referrer != referrer_clone.as_ref().map(|r| r.key()) {
+        return Err(AuctionHouseError::ReferrerMismatch.into());
+    }
+
+    let auction_house_fee = pay_auction_house_fees(
         auction_house,
         &treasury_clone,
         &escrow_clone,
-        &token_clone,
+        treasury_mint,
+        &treasury_token_clone,
         &sys_clone,
         &signer_seeds_for_royalties,
-        buyer_price,
+        sale_price,
         is_native,
+        referrer_clone.as_ref(),
+        referrer_token_clone.as_ref(),
+        &fee_payer_clone,
+        &ata_clone,
+        &rent_clone,
+        fee_payer_seeds,
     )?;
 
-    let buyer_leftover_after_royalties_and_house_fee = buyer_leftover_after_royalties
-        .checked_sub(auction_house_fee_paid)
+    let buyer_premium_paid = if buyer_premium > 0 {
+        pay_treasury(
+            auction_house,
+            &treasury_clone,
+            &escrow_clone,
+            treasury_mint,
+            &treasury_token_clone,
+            &sys_clone,
+            &signer_seeds_for_royalties,
+            buyer_premium,
+            is_native,
+        )?
+    } else {
+        0
+    };
+
+    // The auctioneer program's own fee comes out of the seller's proceeds like the house fee
+    let auctioneer_fee_payout = match &auctioneer_fee {
+        Some((destination, basis_points)) => pay_auctioneer_fee(
+            auction_house,
+            destination,
+            &escrow_clone,
+            treasury_mint,
+            &treasury_token_clone,
+            &sys_clone,
+            &signer_seeds_for_royalties,
+            *basis_points,
+            sale_price,
+            is_native,
+        )?,
+        None => Payout::default(),
+    };
+
+    let buyer_leftover_after_royalties_and_house_fee = sale_price
+        .checked_sub(royalties.paid)
+        .and_then(|amount| amount.checked_sub(auction_house_fee.paid))
+        .and_then(|amount| amount.checked_sub(auctioneer_fee_payout.paid))
+        .and_then(|amount| amount.checked_sub(escrow_shortfall))
         .ok_or(AuctionHouseError::NumericalOverflow)?;
 
-    if !is_native {
+    // The listing's payees come after the creators, the seller keeps whatever they are not paid
+    let proceeds_split_payout = pay_proceeds_split(
+        remaining_accounts,
+        &auction_house_key,
+        &proceeds_split.to_account_info(),
+        &escrow_clone,
+        &auction_house_clone,
+        &auction_house_fee_clone,
+        &seeds,
+        &fee_payer_clone,
+        treasury_mint,
+        &ata_clone,
+        &treasury_token_clone,
+        &sys_clone,
+        &rent_clone,
+        &signer_seeds_for_royalties,
+        fee_payer_seeds,
+        buyer_leftover_after_royalties_and_house_fee,
+        is_native,
+    )?;
+    let seller_leftover = buyer_leftover_after_royalties_and_house_fee
+        .checked_sub(proceeds_split_payout.paid)
+        .ok_or(AuctionHouseError::NumericalOverflow)?;
+
+    let seller_received = if !is_native {
         if seller_payment_receipt_account.data_is_empty() {
             make_ata(
                 seller_payment_receipt_account.to_account_info(),
@@ -384,7 +907,7 @@ fn auctioneer_execute_sale_logic<'c, 'info>(
                 treasury_mint.to_account_info(),
                 fee_payer.to_account_info(),
                 associated_token_program.to_account_info(),
-                token_program.to_account_info(),
+                treasury_token_program.to_account_info(),
                 system_program.to_account_info(),
                 rent.to_account_info(),
                 fee_payer_seeds,
@@ -401,30 +924,22 @@ fn auctioneer_execute_sale_logic<'c, 'info>(
             return Err(AuctionHouseError::SellerATACannotHaveDelegate.into());
         }
 
-        invoke_signed(
-            &spl_token::instruction::transfer(
-                token_program.key,
-                &escrow_payment_account.key(),
-                &seller_payment_receipt_account.key(),
-                &auction_house.key(),
-                &[],
-                buyer_leftover_after_royalties_and_house_fee,
-            )?,
-            &[
-                escrow_payment_account.to_account_info(),
-                seller_payment_receipt_account.to_account_info(),
-                token_program.to_account_info(),
-                auction_house.to_account_info(),
-            ],
+        token_transfer_checked(
+            &treasury_token_program.to_account_info(),
+            &escrow_payment_account.to_account_info(),
+            treasury_mint,
+            &seller_payment_receipt_account.to_account_info(),
+            &auction_house.to_account_info(),
+            seller_leftover,
             &[&ah_seeds],
-        )?;
+        )?
     } else {
         assert_keys_equal(seller_payment_receipt_account.key(), seller.key())?;
         invoke_signed(
             &system_instruction::transfer(
                 escrow_payment_account.key,
                 seller_payment_receipt_account.key,
-                buyer_leftover_after_royalties_and_house_fee,
+                seller_leftover,
             ),
             &[
                 escrow_payment_account.to_account_info(),
@@ -433,7 +948,11 @@ fn auctioneer_execute_sale_logic<'c, 'info>(
             ],
             &[&escrow_signer_seeds],
         )?;
-    }
+        seller_leftover
+    };
+    let seller_proceeds = seller_received
+        .checked_add(proceeds_split_payout.received)
+        .ok_or(AuctionHouseError::NumericalOverflow)?;
 
     if buyer_receipt_token_account.data_is_empty() {
         make_ata(
@@ -467,42 +986,128 @@ fn auctioneer_execute_sale_logic<'c, 'info>(
         &[program_as_signer_bump],
     ];
 
-    invoke_signed(
-        &spl_token::instruction::transfer(
-            token_program.key,
-            &token_account.key(),
-            &buyer_receipt_token_account.key(),
-            &program_as_signer.key(),
-            &[],
-            token_size,
-        )?,
-        &[
-            token_account.to_account_info(),
-            buyer_receipt_clone,
-            program_as_signer.to_account_info(),
-            token_clone,
-        ],
-        &[&program_as_signer_seeds],
-    )?;
+    // A programmable NFT moves through Token Metadata so its rule set validates the transfer,
+    // its accounts follow the creator and payee accounts
+    if is_programmable(&metadata_clone)? {
+        let pnft_accounts = next_programmable_accounts(remaining_accounts)?;
+        let destination_token_record = next_account_info(remaining_accounts)?;
+
+        let transfer = TransferBuilder::new()
+            .token(token_account.key())
+            .token_owner(seller.key())
+            .destination(buyer_receipt_token_account.key())
+            .destination_owner(buyer.key())
+            .mint(token_mint.key())
+            .metadata(metadata.key())
+            .edition(pnft_accounts.edition.key())
+            .owner_token_record(pnft_accounts.token_record.key())
+            .destination_token_record(destination_token_record.key())
+            .authority(program_as_signer.key())
+            .payer(fee_payer.key())
+            .system_program(system_program.key())
+            .sysvar_instructions(pnft_accounts.sysvar_instructions.key())
+            .spl_token_program(token_program.key())
+            .spl_ata_program(associated_token_program.key())
+            .authorization_rules_program(pnft_accounts.authorization_rules_program.key())
+            .authorization_rules(pnft_accounts.authorization_rules.key())
+            .build(TransferArgs::V1 {
+                amount: sale_size,
+                authorization_data: None,
+            })
+            .map_err(|_| AuctionHouseError::InvalidProgrammableAccounts)?;
 
+        invoke_signed(
+            &transfer.instruction(),
+            &[
+                token_account.to_account_info(),
+                seller.to_account_info(),
+                buyer_receipt_clone,
+                buyer.to_account_info(),
+                token_mint.to_account_info(),
+                metadata_clone,
+                pnft_accounts.edition.to_account_info(),
+                pnft_accounts.token_record.to_account_info(),
+                destination_token_record.to_account_info(),
+                program_as_signer.to_account_info(),
+                fee_payer.to_account_info(),
+                sys_clone,
+                pnft_accounts.sysvar_instructions.to_account_info(),
+                token_clone,
+                ata_clone,
+                pnft_accounts.authorization_rules_program.to_account_info(),
+                pnft_accounts.authorization_rules.to_account_info(),
+                pnft_accounts.token_metadata_program.to_account_info(),
+            ],
+            &[&program_as_signer_seeds, fee_payer_seeds],
+        )?;
+    } else {
+        token_transfer_checked(
+            &token_clone,
+            &token_account_clone,
+            token_mint,
+            &buyer_receipt_clone,
+            &program_as_signer.to_account_info(),
+            sale_size,
+            &[&program_as_signer_seeds],
+        )?;
+    }
+
+    release_open_bid(
+        &escrow_ledger.to_account_info(),
+        &buyer_trade_state.to_account_info(),
+    )?;
     close_account(
         &buyer_trade_state.to_account_info(),
-        &fee_payer.to_account_info(),
+        &buyer.to_account_info(),
     )?;
 
-    let token_account_data = SplAccount::unpack(&token_account.data.borrow())?;
+    // Payouts are reported as received, net of any transfer fee the treasury mint withheld
+    let sale_amounts = SaleAmounts {
+        royalty_paid: royalties.received,
+        auction_house_fee_paid: auction_house_fee.received,
+        seller_proceeds,
+        buyer_premium_paid,
+        auctioneer_fee_paid: auctioneer_fee_payout.received,
+    };
+
+    emit!(SaleExecuted {
+        auction_house: auction_house.key(),
+        buyer: buyer.key(),
+        seller: seller.key(),
+        token_mint: token_mint.key(),
+        price: sale_price,
+        token_size: sale_size,
+        royalty_paid: sale_amounts.royalty_paid,
+        auction_house_fee_paid: sale_amounts.auction_house_fee_paid,
+        seller_proceeds: sale_amounts.seller_proceeds,
+        buyer_premium_paid: sale_amounts.buyer_premium_paid,
+        auctioneer_fee_paid: sale_amounts.auctioneer_fee_paid,
+        royalty_policy,
+        royalty_basis_points,
+    });
+
+    let token_account_data = unpack_token_account(token_account)?;
     if token_account_data.delegated_amount == 0 {
         close_account(
             &seller_trade_state.to_account_info(),
-            &fee_payer.to_account_info(),
+            &seller.to_account_info(),
         )?;
 
+        // A split or referral left behind would apply to the next listing at the same price
+        if !proceeds_split.data_is_empty() {
+            close_account(&proceeds_split.to_account_info(), &seller.to_account_info())?;
+        }
+
+        if !referral.data_is_empty() {
+            close_account(&referral.to_account_info(), &seller.to_account_info())?;
+        }
+
         if free_trade_state.lamports() > 0 {
             close_account(
                 &free_trade_state.to_account_info(),
-                &fee_payer.to_account_info(),
+                &seller.to_account_info(),
             )?;
         }
     }
-    Ok(())
+    Ok(sale_amounts)
 }
@@ -4,10 +4,15 @@ pub mod auctioneer;
 pub mod bid;
 pub mod cancel;
 pub mod constants;
+pub mod creator_claim;
 pub mod deposit;
 pub mod errors;
+pub mod escrow_ledger;
+pub mod events;
 pub mod execute_sale;
 pub mod pda;
+pub mod proceeds_split;
+pub mod referral;
 pub mod sell;
 pub mod state;
 pub mod utils;
@@ -16,8 +21,9 @@ pub mod withdraw;
 pub use state::*;
 
 use crate::{
-    auctioneer::*, bid::*, cancel::*, constants::*, deposit::*, errors::AuctionHouseError,
-    execute_sale::*, sell::*, utils::*, withdraw::*,
+    auctioneer::*, bid::*, cancel::*, constants::*, creator_claim::*, deposit::*,
+    errors::AuctionHouseError, escrow_ledger::*, events::*, execute_sale::*, proceeds_split::*,
+    referral::*, sell::*, utils::*, withdraw::*,
 };
 
 use anchor_lang::{
@@ -27,7 +33,7 @@ use anchor_lang::{
 };
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{Mint, Token, TokenAccount},
+    token_interface::{Mint, TokenAccount, TokenInterface},
 };
 
 anchor_lang::declare_id!("FMrPvDk4xZNykJ2aWCmyKCzQ12qhJ6SR9tS67fhLbx8x");
@@ -97,21 +103,13 @@ pub mod auction_house {
             &[auction_house.treasury_bump],
         ];
         if !is_native {
-            invoke_signed(
-                &spl_token::instruction::transfer(
-                    token_program.key,
-                    &auction_house_treasury.key(),
-                    &treasury_withdrawal_destination.key(),
-                    &auction_house.key(),
-                    &[],
-                    amount,
-                )?,
-                &[
-                    auction_house_treasury.to_account_info(),
-                    treasury_withdrawal_destination.to_account_info(),
-                    token_program.to_account_info(),
-                    auction_house.to_account_info(),
-                ],
+            token_transfer_checked(
+                &token_program.to_account_info(),
+                &auction_house_treasury.to_account_info(),
+                &treasury_mint.to_account_info(),
+                &treasury_withdrawal_destination.to_account_info(),
+                &auction_house.to_account_info(),
+                amount,
                 &[&auction_house_seeds],
             )?;
         } else {
@@ -137,6 +135,13 @@ pub mod auction_house {
         ctx: Context<'_, '_, '_, 'info, UpdateAuctionHouse<'info>>,
         seller_fee_basis_points: Option<u16>,
         can_change_sale_price: Option<bool>,
+        min_auction_duration: Option<i64>,
+        max_auction_duration: Option<i64>,
+        max_time_extension: Option<i64>,
+        royalty_policy: Option<RoyaltyPolicy>,
+        referral_basis_points: Option<u16>,
+        buyer_premium_basis_points: Option<u16>,
+        max_auctioneer_fee_basis_points: Option<u16>,
     ) -> Result<()> {
         let treasury_mint = &ctx.accounts.treasury_mint;
         let payer = &ctx.accounts.payer;
@@ -164,6 +169,69 @@ pub mod auction_house {
             auction_house.can_change_sale_price = chsp;
         }
 
+        if let Some(mad) = min_auction_duration {
+            auction_house.min_auction_duration = mad;
+        }
+
+        if let Some(mad) = max_auction_duration {
+            auction_house.max_auction_duration = mad;
+        }
+
+        if let Some(mte) = max_time_extension {
+            auction_house.max_time_extension = mte;
+        }
+
+        if let Some(rp) = royalty_policy {
+            match rp {
+                RoyaltyPolicy::Capped {
+                    max_basis_points: bp,
+                }
+                | RoyaltyPolicy::BuyerChoice {
+                    min_basis_points: bp,
+                } if bp > 10000 => {
+                    return Err(AuctionHouseError::InvalidBasisPoints.into());
+                }
+                _ => {}
+            }
+
+            let (royalty_policy, royalty_basis_points) = get_royalty_policy_fields(&rp);
+            auction_house.royalty_policy = royalty_policy;
+            auction_house.royalty_basis_points = royalty_basis_points;
+        }
+
+        if let Some(rbp) = referral_basis_points {
+            if rbp > 10000 {
+                return Err(AuctionHouseError::InvalidBasisPoints.into());
+            }
+
+            auction_house.referral_basis_points = rbp;
+        }
+
+        if let Some(bpbp) = buyer_premium_basis_points {
+            if bpbp > 10000 {
+                return Err(AuctionHouseError::InvalidBasisPoints.into());
+            }
+
+            auction_house.buyer_premium_basis_points = bpbp;
+        }
+
+        if let Some(mafbp) = max_auctioneer_fee_basis_points {
+            if mafbp > 10000 {
+                return Err(AuctionHouseError::InvalidBasisPoints.into());
+            }
+
+            auction_house.max_auctioneer_fee_basis_points = mafbp;
+        }
+
+        if auction_house.min_auction_duration < 0
+            || auction_house.max_auction_duration < 0
+            || auction_house.max_time_extension < 0
+            || (auction_house.max_auction_duration > 0
+                && auction_house.min_auction_duration > auction_house.max_auction_duration)
+        {
+            return Err(AuctionHouseError::InvalidAuctionBounds.into());
+        }
+
         auction_house.authority = new_authority.key();
         auction_house.treasury_withdrawal_destination = treasury_withdrawal_destination.key();
         auction_house.fee_withdrawal_destination = fee_withdrawal_destination.key();
@@ -195,6 +263,20 @@ pub mod auction_house {
             )?;
         }
 
+        emit!(AuctionHouseUpdated {
+            auction_house: auction_house.key(),
+            authority: auction_house.authority,
+            seller_fee_basis_points: auction_house.seller_fee_basis_points,
+            can_change_sale_price: auction_house.can_change_sale_price,
+            min_auction_duration: auction_house.min_auction_duration,
+            max_auction_duration: auction_house.max_auction_duration,
+            max_time_extension: auction_house.max_time_extension,
+            royalty_policy: get_royalty_policy(auction_house)?,
+            referral_basis_points: auction_house.referral_basis_points,
+            buyer_premium_basis_points: auction_house.buyer_premium_basis_points,
+            max_auctioneer_fee_basis_points: auction_house.max_auctioneer_fee_basis_points,
+        });
+
         Ok(())
     }
 
@@ -260,6 +342,9 @@ pub mod auction_house {
         auction_house.fee_withdrawal_destination = fee_withdrawal_destination.key();
 
         let is_native = treasury_mint.key() == spl_token::native_mint::id();
+        if !is_native {
+            assert_transferable(&treasury_mint.to_account_info())?;
+        }
 
         let ah_key = auction_house.key();
 
@@ -310,9 +395,99 @@ pub mod auction_house {
             )?;
         }
 
+        emit!(AuctionHouseCreated {
+            auction_house: auction_house.key(),
+            authority: auction_house.authority,
+            treasury_mint: auction_house.treasury_mint,
+            seller_fee_basis_points,
+            can_change_sale_price,
+        });
+
         Ok(())
     }
 
+    pub fn sell<'info>(
+        ctx: Context<'_, '_, '_, 'info, Sell<'info>>,
+        trade_state_bump: u8,
+        free_trade_state_bump: u8,
+        program_as_signer_bump: u8,
+        buyer_price: u64,
+        token_size: u64,
+    ) -> Result<()> {
+        sell::sell(
+            ctx,
+            trade_state_bump,
+            free_trade_state_bump,
+            program_as_signer_bump,
+            buyer_price,
+            token_size,
+        )
+    }
+
+    pub fn buy<'info>(
+        ctx: Context<'_, '_, '_, 'info, Buy<'info>>,
+        trade_state_bump: u8,
+        escrow_payment_bump: u8,
+        buyer_price: u64,
+        token_size: u64,
+    ) -> Result<()> {
+        bid::private_bid(
+            ctx,
+            trade_state_bump,
+            escrow_payment_bump,
+            buyer_price,
+            token_size,
+        )
+    }
+
+    pub fn public_buy<'info>(
+        ctx: Context<'_, '_, '_, 'info, PublicBuy<'info>>,
+        trade_state_bump: u8,
+        escrow_payment_bump: u8,
+        buyer_price: u64,
+        token_size: u64,
+    ) -> Result<()> {
+        bid::public_bid(
+            ctx,
+            trade_state_bump,
+            escrow_payment_bump,
+            buyer_price,
+            token_size,
+        )
+    }
+
+    pub fn cancel<'info>(
+        ctx: Context<'_, '_, '_, 'info, Cancel<'info>>,
+        buyer_price: u64,
+        token_size: u64,
+    ) -> Result<()> {
+        cancel::cancel(ctx, buyer_price, token_size)
+    }
+
+    pub fn execute_sale<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteSale<'info>>,
+        escrow_payment_bump: u8,
+        free_trade_state_bump: u8,
+        program_as_signer_bump: u8,
+        buyer_price: u64,
+        token_size: u64,
+        partial_order_size: Option<u64>,
+        partial_order_price: Option<u64>,
+        buyer_royalty_basis_points: Option<u16>,
+    ) -> Result<SaleAmounts> {
+        execute_sale::execute_sale(
+            ctx,
+            escrow_payment_bump,
+            free_trade_state_bump,
+            program_as_signer_bump,
+            buyer_price,
+            token_size,
+            partial_order_size,
+            partial_order_price,
+            buyer_royalty_basis_points,
+        )
+    }
+
     pub fn auctioneer_buy<'info>(
         ctx: Context<'_, '_, '_, 'info, AuctioneerBuy<'info>>,
         trade_state_bump: u8,
@@ -329,6 +504,15 @@ pub mod auction_house {
         )
     }
 
+    pub fn auctioneer_buy_from_escrow<'info>(
+        ctx: Context<'_, '_, '_, 'info, AuctioneerBuyFromEscrow<'info>>,
+        trade_state_bump: u8,
+        buyer_price: u64,
+        token_size: u64,
+    ) -> Result<()> {
+        bid::auctioneer_buy_from_escrow(ctx, trade_state_bump, buyer_price, token_size)
+    }
+
     pub fn auctioneer_cancel<'info>(
         ctx: Context<'_, '_, '_, 'info, AuctioneerCancel<'info>>,
         buyer_price: u64,
@@ -352,7 +536,10 @@ pub mod auction_house {
         program_as_signer_bump: u8,
         buyer_price: u64,
         token_size: u64,
-    ) -> Result<()> {
+        partial_order_size: Option<u64>,
+        partial_order_price: Option<u64>,
+        auctioneer_fee_basis_points: u16,
+    ) -> Result<SaleAmounts> {
         execute_sale::auctioneer_execute_sale(
             ctx,
             escrow_payment_bump,
@@ -360,6 +547,9 @@ pub mod auction_house {
             program_as_signer_bump,
             buyer_price,
             token_size,
+            partial_order_size,
+            partial_order_price,
+            auctioneer_fee_basis_points,
         )
     }
 
@@ -391,32 +581,165 @@ pub mod auction_house {
         ctx: Context<'_, '_, '_, 'info, CloseEscrowAccount<'info>>,
         escrow_payment_bump: u8,
     ) -> Result<()> {
-        let auction_house_key = ctx.accounts.auction_house.key();
+        if get_locked_escrow_amount(&ctx.accounts.escrow_ledger)? > 0 {
+            return Err(AuctionHouseError::EscrowFundsLocked.into());
+        }
+        if get_open_bid_count(&ctx.accounts.escrow_ledger)? > 0 {
+            return Err(AuctionHouseError::EscrowHasOpenBids.into());
+        }
+
+        let auction_house = &ctx.accounts.auction_house;
+        let auction_house_key = auction_house.key();
         let wallet_key = ctx.accounts.wallet.key();
+        let treasury_mint = &ctx.accounts.treasury_mint;
+        let is_native = treasury_mint.key() == spl_token::native_mint::id();
 
-        let escrow_signer_seeds = [
-            PREFIX.as_bytes(),
-            auction_house_key.as_ref(),
-            wallet_key.as_ref(),
-            &[escrow_payment_bump],
-        ];
+        if is_native {
+            let escrow_signer_seeds = [
+                PREFIX.as_bytes(),
+                auction_house_key.as_ref(),
+                wallet_key.as_ref(),
+                &[escrow_payment_bump],
+            ];
+
+            invoke_signed(
+                &system_instruction::transfer(
+                    &ctx.accounts.escrow_payment_account.key(),
+                    &ctx.accounts.wallet.key(),
+                    ctx.accounts.escrow_payment_account.lamports(),
+                ),
+                &[
+                    ctx.accounts.escrow_payment_account.to_account_info(),
+                    ctx.accounts.wallet.to_account_info(),
+                    ctx.accounts.system_program.to_account_info(),
+                ],
+                &[&escrow_signer_seeds],
+            )?;
+        } else if !ctx.accounts.escrow_payment_account.data_is_empty() {
+            let receipt_account = &ctx.accounts.receipt_account;
+            if receipt_account.data_is_empty() {
+                make_ata(
+                    receipt_account.to_account_info(),
+                    ctx.accounts.wallet.to_account_info(),
+                    treasury_mint.to_account_info(),
+                    ctx.accounts.wallet.to_account_info(),
+                    ctx.accounts.associated_token_program.to_account_info(),
+                    ctx.accounts.token_program.to_account_info(),
+                    ctx.accounts.system_program.to_account_info(),
+                    ctx.accounts.rent.to_account_info(),
+                    &[],
+                )?;
+            }
+
+            let rec_acct = assert_is_ata(
+                &receipt_account.to_account_info(),
+                &wallet_key,
+                &treasury_mint.key(),
+            )?;
+            if rec_acct.delegate.is_some() {
+                return Err(AuctionHouseError::BuyerATACannotHaveDelegate.into());
+            }
+
+            // The token escrow is owned by the auction house, which signs its emptying
+            let ah_seeds = [
+                PREFIX.as_bytes(),
+                auction_house.creator.as_ref(),
+                auction_house.treasury_mint.as_ref(),
+                &[auction_house.bump],
+            ];
+            let escrow_balance =
+                get_escrow_balance(&ctx.accounts.escrow_payment_account, is_native)?;
+            if escrow_balance > 0 {
+                token_transfer_checked(
+                    &ctx.accounts.token_program.to_account_info(),
+                    &ctx.accounts.escrow_payment_account.to_account_info(),
+                    &treasury_mint.to_account_info(),
+                    &receipt_account.to_account_info(),
+                    &auction_house.to_account_info(),
+                    escrow_balance,
+                    &[&ah_seeds],
+                )?;
+            }
+
+            harvest_withheld_tokens(
+                &ctx.accounts.token_program.to_account_info(),
+                &treasury_mint.to_account_info(),
+                &ctx.accounts.escrow_payment_account.to_account_info(),
+            )?;
+
+            invoke_signed(
+                &spl_token_2022::instruction::close_account(
+                    &ctx.accounts.token_program.key(),
+                    &ctx.accounts.escrow_payment_account.key(),
+                    &wallet_key,
+                    &auction_house_key,
+                    &[],
+                )?,
+                &[
+                    ctx.accounts.escrow_payment_account.to_account_info(),
+                    ctx.accounts.wallet.to_account_info(),
+                    auction_house.to_account_info(),
+                    ctx.accounts.token_program.to_account_info(),
+                ],
+                &[&ah_seeds],
+            )?;
+        }
+
+        if !ctx.accounts.escrow_ledger.data_is_empty() {
+            close_account(
+                &ctx.accounts.escrow_ledger.to_account_info(),
+                &ctx.accounts.wallet.to_account_info(),
+            )?;
+        }
 
-        invoke_signed(
-            &system_instruction::transfer(
-                &ctx.accounts.escrow_payment_account.key(),
-                &ctx.accounts.wallet.key(),
-                ctx.accounts.escrow_payment_account.lamports(),
-            ),
-            &[
-                ctx.accounts.escrow_payment_account.to_account_info(),
-                ctx.accounts.wallet.to_account_info(),
-                ctx.accounts.system_program.to_account_info(),
-            ],
-            &[&escrow_signer_seeds],
-        )?;
         Ok(())
     }
 
+    pub fn auctioneer_lock_escrow<'info>(
+        ctx: Context<'_, '_, '_, 'info, AuctioneerLockEscrow<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        escrow_ledger::auctioneer_lock_escrow(ctx, amount)
+    }
+
+    pub fn auctioneer_unlock_escrow<'info>(
+        ctx: Context<'_, '_, '_, 'info, AuctioneerUnlockEscrow<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        escrow_ledger::auctioneer_unlock_escrow(ctx, amount)
+    }
+
+    pub fn claim_creator_royalties<'info>(
+        ctx: Context<'_, '_, '_, 'info, ClaimCreatorRoyalties<'info>>,
+    ) -> Result<()> {
+        creator_claim::claim_creator_royalties(ctx)
+    }
+
+    pub fn set_proceeds_split<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetProceedsSplit<'info>>,
+        _buyer_price: u64,
+        _token_size: u64,
+        payees: Vec<ProceedsPayee>,
+    ) -> Result<()> {
+        proceeds_split::set_proceeds_split(ctx, payees)
+    }
+
+    pub fn close_proceeds_split<'info>(
+        ctx: Context<'_, '_, '_, 'info, CloseProceedsSplit<'info>>,
+        _buyer_price: u64,
+        _token_size: u64,
+    ) -> Result<()> {
+        proceeds_split::close_proceeds_split(ctx)
+    }
+
+    pub fn set_referral<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetReferral<'info>>,
+        _buyer_price: u64,
+        _token_size: u64,
+    ) -> Result<()> {
+        referral::set_referral(ctx)
+    }
+
     pub fn delegate_auctioneer<'info>(
         ctx: Context<'_, '_, '_, 'info, DelegateAuctioneer<'info>>,
     ) -> Result<()> {
@@ -427,7 +750,7 @@ pub mod auction_house {
 #[derive(Accounts)]
 #[instruction(bump: u8, fee_payer_bump: u8, treasury_bump: u8)]
 pub struct CreateAuctionHouse<'info> {
-    pub treasury_mint: Account<'info, Mint>,
+    pub treasury_mint: InterfaceAccount<'info, Mint>,
     #[account(mut)]
     pub payer: Signer<'info>,
     pub authority: UncheckedAccount<'info>,
@@ -442,7 +765,7 @@ pub struct CreateAuctionHouse<'info> {
     pub auction_house_fee_account: UncheckedAccount<'info>,
     #[account(mut, seeds=[PREFIX.as_bytes(), auction_house.key().as_ref(), TREASURY.as_bytes()], bump)]
     pub auction_house_treasury: UncheckedAccount<'info>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub rent: Sysvar<'info, Rent>,
@@ -450,7 +773,7 @@ pub struct CreateAuctionHouse<'info> {
 
 #[derive(Accounts)]
 pub struct UpdateAuctionHouse<'info> {
-    pub treasury_mint: Account<'info, Mint>,
+    pub treasury_mint: InterfaceAccount<'info, Mint>,
     pub payer: Signer<'info>,
     pub authority: Signer<'info>,
     pub new_authority: UncheckedAccount<'info>,
@@ -461,7 +784,7 @@ pub struct UpdateAuctionHouse<'info> {
     pub treasury_withdrawal_destination_owner: UncheckedAccount<'info>,
     #[account(mut, seeds=[PREFIX.as_bytes(), auction_house.creator.as_ref(), treasury_mint.key().as_ref()], bump=auction_house.bump, has_one=authority, has_one=treasury_mint)]
     pub auction_house: Account<'info, AuctionHouse>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub rent: Sysvar<'info, Rent>,
@@ -469,7 +792,7 @@ pub struct UpdateAuctionHouse<'info> {
 
 #[derive(Accounts)]
 pub struct WithdrawFromTreasury<'info> {
-    pub treasury_mint: Account<'info, Mint>,
+    pub treasury_mint: InterfaceAccount<'info, Mint>,
     pub authority: Signer<'info>,
     #[account(mut)]
     pub treasury_withdrawal_destination: UncheckedAccount<'info>,
@@ -477,7 +800,7 @@ pub struct WithdrawFromTreasury<'info> {
     pub auction_house_treasury: UncheckedAccount<'info>,
     #[account(mut, seeds=[PREFIX.as_bytes(), auction_house.creator.as_ref(), treasury_mint.key().as_ref()], bump=auction_house.bump, has_one=authority, has_one=treasury_mint, has_one=treasury_withdrawal_destination, has_one=auction_house_treasury)]
     pub auction_house: Account<'info, AuctionHouse>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
 
@@ -508,14 +831,33 @@ pub struct CloseEscrowAccount<'info> {
         bump = escrow_payment_bump
     )]
     pub escrow_payment_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            wallet.key().as_ref(),
+            ESCROW_LEDGER.as_bytes()
+        ],
+        bump
+    )]
+    pub escrow_ledger: UncheckedAccount<'info>,
+    // Receives the tokens of an SPL treasury escrow, the wallet itself for native escrows
+    #[account(mut)]
+    pub receipt_account: UncheckedAccount<'info>,
+    pub treasury_mint: Box<InterfaceAccount<'info, Mint>>,
     #[account(
         seeds = [
             PREFIX.as_bytes(),
             auction_house.creator.as_ref(),
             auction_house.treasury_mint.as_ref()
         ],
-        bump = auction_house.bump
+        bump = auction_house.bump,
+        has_one = treasury_mint
     )]
     pub auction_house: Account<'info, AuctionHouse>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub rent: Sysvar<'info, Rent>,
 }
@@ -1,7 +1,10 @@
 use anchor_lang::prelude::Pubkey;
 
 use crate::{
-    constants::{AUCTIONEER, FEE_PAYER, PREFIX, SIGNER, TREASURY},
+    constants::{
+        AUCTIONEER, CREATOR_CLAIM, ESCROW_LEDGER, FEE_PAYER, PREFIX, PROCEEDS_SPLIT, REFERRAL,
+        SIGNER, TREASURY,
+    },
     id,
 };
 
@@ -89,6 +92,18 @@ pub fn find_escrow_payment_account_address(
     )
 }
 
+pub fn find_escrow_ledger_address(auction_house: &Pubkey, wallet: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            auction_house.as_ref(),
+            wallet.as_ref(),
+            ESCROW_LEDGER.as_bytes(),
+        ],
+        &id(),
+    )
+}
+
 pub fn find_auctioneer_address(
     auction_house: &Pubkey,
     auctioneer_authority: &Pubkey,
@@ -102,3 +117,37 @@ pub fn find_auctioneer_address(
         &id(),
     )
 }
+
+pub fn find_creator_claim_address(auction_house: &Pubkey, creator: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            auction_house.as_ref(),
+            creator.as_ref(),
+            CREATOR_CLAIM.as_bytes(),
+        ],
+        &id(),
+    )
+}
+
+pub fn find_proceeds_split_address(seller_trade_state: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            seller_trade_state.as_ref(),
+            PROCEEDS_SPLIT.as_bytes(),
+        ],
+        &id(),
+    )
+}
+
+pub fn find_referral_address(seller_trade_state: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            seller_trade_state.as_ref(),
+            REFERRAL.as_bytes(),
+        ],
+        &id(),
+    )
+}
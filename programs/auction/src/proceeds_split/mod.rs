@@ -0,0 +1,130 @@
+use anchor_lang::{prelude::*, AnchorDeserialize};
+use anchor_spl::token_interface::TokenAccount;
+
+use crate::{constants::*, errors::*, events::*, AuctionHouse, ProceedsPayee, ProceedsSplit};
+
+#[derive(Accounts)]
+#[instruction(buyer_price: u64, token_size: u64)]
+pub struct SetProceedsSplit<'info> {
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+    pub token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump = auction_house.bump
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            wallet.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_account.key().as_ref(),
+            auction_house.treasury_mint.as_ref(),
+            token_account.mint.as_ref(),
+            &buyer_price.to_le_bytes(),
+            &token_size.to_le_bytes()
+        ],
+        bump
+    )]
+    pub seller_trade_state: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = wallet,
+        space = PROCEEDS_SPLIT_SIZE,
+        seeds = [
+            PREFIX.as_bytes(),
+            seller_trade_state.key().as_ref(),
+            PROCEEDS_SPLIT.as_bytes()
+        ],
+        bump
+    )]
+    pub proceeds_split: Account<'info, ProceedsSplit>,
+    pub system_program: Program<'info, System>,
+}
+
+// The split is keyed by the seller trade state, so only the seller of a live listing can set it
+pub fn set_proceeds_split(
+    ctx: Context<SetProceedsSplit>,
+    payees: Vec<ProceedsPayee>,
+) -> Result<()> {
+    if ctx.accounts.seller_trade_state.data_is_empty() {
+        return err!(AuctionHouseError::UninitializedAccount);
+    }
+
+    if payees.is_empty()
+        || payees.len() > MAX_PROCEEDS_PAYEES
+        || payees.iter().any(|payee| payee.share == 0)
+        || payees.iter().map(|payee| payee.share as u16).sum::<u16>() != 100
+    {
+        return err!(AuctionHouseError::InvalidProceedsSplit);
+    }
+
+    let proceeds_split = &mut ctx.accounts.proceeds_split;
+    proceeds_split.seller_trade_state = ctx.accounts.seller_trade_state.key();
+    proceeds_split.payees = payees;
+    proceeds_split.bump = *ctx
+        .bumps
+        .get("proceeds_split")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+
+    emit!(ProceedsSplitSet {
+        auction_house: ctx.accounts.auction_house.key(),
+        seller_trade_state: proceeds_split.seller_trade_state,
+        payees: proceeds_split.payees.clone(),
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(buyer_price: u64, token_size: u64)]
+pub struct CloseProceedsSplit<'info> {
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+    pub token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump = auction_house.bump
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            wallet.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_account.key().as_ref(),
+            auction_house.treasury_mint.as_ref(),
+            token_account.mint.as_ref(),
+            &buyer_price.to_le_bytes(),
+            &token_size.to_le_bytes()
+        ],
+        bump
+    )]
+    pub seller_trade_state: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            seller_trade_state.key().as_ref(),
+            PROCEEDS_SPLIT.as_bytes()
+        ],
+        bump = proceeds_split.bump,
+        has_one = seller_trade_state,
+        close = wallet
+    )]
+    pub proceeds_split: Account<'info, ProceedsSplit>,
+}
+
+// Sends the whole of the proceeds back to the seller, the split rent is refunded on close
+pub fn close_proceeds_split(_ctx: Context<CloseProceedsSplit>) -> Result<()> {
+    Ok(())
+}
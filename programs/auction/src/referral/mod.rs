@@ -0,0 +1,77 @@
+use anchor_lang::{prelude::*, AnchorDeserialize};
+use anchor_spl::token_interface::TokenAccount;
+
+use crate::{constants::*, errors::*, events::*, AuctionHouse, Referral};
+
+#[derive(Accounts)]
+#[instruction(buyer_price: u64, token_size: u64)]
+pub struct SetReferral<'info> {
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+    pub token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump = auction_house.bump
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            wallet.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_account.key().as_ref(),
+            auction_house.treasury_mint.as_ref(),
+            token_account.mint.as_ref(),
+            &buyer_price.to_le_bytes(),
+            &token_size.to_le_bytes()
+        ],
+        bump
+    )]
+    pub seller_trade_state: UncheckedAccount<'info>,
+    pub referrer: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = wallet,
+        space = REFERRAL_SIZE,
+        seeds = [
+            PREFIX.as_bytes(),
+            seller_trade_state.key().as_ref(),
+            REFERRAL.as_bytes()
+        ],
+        bump
+    )]
+    pub referral: Account<'info, Referral>,
+    pub system_program: Program<'info, System>,
+}
+
+// Recorded by the seller alongside the listing, so whoever submits the sale can not swap in
+// a referrer of their own
+pub fn set_referral(ctx: Context<SetReferral>) -> Result<()> {
+    if ctx.accounts.seller_trade_state.data_is_empty() {
+        return err!(AuctionHouseError::UninitializedAccount);
+    }
+
+    if ctx.accounts.referrer.key() == ctx.accounts.wallet.key() {
+        return err!(AuctionHouseError::InvalidReferrer);
+    }
+
+    let referral = &mut ctx.accounts.referral;
+    referral.seller_trade_state = ctx.accounts.seller_trade_state.key();
+    referral.referrer = ctx.accounts.referrer.key();
+    referral.bump = *ctx
+        .bumps
+        .get("referral")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+
+    emit!(ReferralSet {
+        auction_house: ctx.accounts.auction_house.key(),
+        seller_trade_state: referral.seller_trade_state,
+        referrer: referral.referrer,
+    });
+
+    Ok(())
+}
@@ -1,8 +1,103 @@
 use anchor_lang::{prelude::*, solana_program::program::invoke, AnchorDeserialize};
-use spl_token::instruction::approve;
+use mpl_token_metadata::instruction::{
+    builders::DelegateBuilder, DelegateArgs, InstructionBuilder,
+};
+use spl_token_2022::instruction::approve;
 
 use crate::{constants::*, errors::*, utils::*, AuctionHouse, *};
 
+#[derive(Accounts, Clone)]
+#[instruction(
+    trade_state_bump: u8,
+    free_trade_state_bump: u8,
+    program_as_signer_bump: u8,
+    buyer_price: u64,
+    token_size: u64
+)]
+pub struct Sell<'info> {
+    #[account(mut)]
+    pub wallet: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub metadata: UncheckedAccount<'info>,
+    pub authority: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=authority,
+        has_one=auction_house_fee_account
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            FEE_PAYER.as_bytes()
+        ],
+        bump=auction_house.fee_payer_bump
+    )]
+    pub auction_house_fee_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            wallet.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_account.key().as_ref(),
+            auction_house.treasury_mint.as_ref(),
+            token_account.mint.as_ref(),
+            &buyer_price.to_le_bytes(),
+            &token_size.to_le_bytes()
+        ],
+        bump
+    )]
+    pub seller_trade_state: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            wallet.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_account.key().as_ref(),
+            auction_house.treasury_mint.as_ref(),
+            token_account.mint.as_ref(),
+            &0u64.to_le_bytes(),
+            &token_size.to_le_bytes()
+        ],
+        bump
+    )]
+    pub free_seller_trade_state: UncheckedAccount<'info>,
+    #[account(seeds=[PREFIX.as_bytes(), SIGNER.as_bytes()], bump)]
+    pub program_as_signer: UncheckedAccount<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+impl<'info> From<AuctioneerSell<'info>> for Sell<'info> {
+    fn from(a: AuctioneerSell<'info>) -> Sell<'info> {
+        Sell {
+            wallet: a.wallet,
+            token_account: a.token_account,
+            metadata: a.metadata,
+            authority: a.authority,
+            auction_house: a.auction_house,
+            auction_house_fee_account: a.auction_house_fee_account,
+            seller_trade_state: a.seller_trade_state,
+            free_seller_trade_state: a.free_seller_trade_state,
+            program_as_signer: a.program_as_signer,
+            token_program: a.token_program,
+            system_program: a.system_program,
+            rent: a.rent,
+        }
+    }
+}
+
 #[derive(Accounts, Clone)]
 #[instruction(
     trade_state_bump: u8,
@@ -14,7 +109,7 @@ pub struct AuctioneerSell<'info> {
     #[account(mut)]
     pub wallet: UncheckedAccount<'info>,
     #[account(mut)]
-    pub token_account: Box<Account<'info, TokenAccount>>,
+    pub token_account: Box<InterfaceAccount<'info, TokenAccount>>,
     pub metadata: UncheckedAccount<'info>,
     pub authority: UncheckedAccount<'info>,
     pub auctioneer_authority: Signer<'info>,
@@ -80,11 +175,55 @@ pub struct AuctioneerSell<'info> {
     pub auctioneer: Account<'info, Auctioneer>,
     #[account(seeds=[PREFIX.as_bytes(), SIGNER.as_bytes()], bump)]
     pub program_as_signer: UncheckedAccount<'info>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
 
+pub fn sell<'info>(
+    ctx: Context<'_, '_, '_, 'info, Sell<'info>>,
+    trade_state_bump: u8,
+    free_trade_state_bump: u8,
+    program_as_signer_bump: u8,
+    buyer_price: u64,
+    token_size: u64,
+) -> Result<()> {
+    if ctx.accounts.auction_house.has_auctioneer {
+        return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
+    }
+
+    let trade_state_canonical_bump = *ctx
+        .bumps
+        .get("seller_trade_state")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+    let free_trade_state_canonical_bump = *ctx
+        .bumps
+        .get("free_seller_trade_state")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+    let program_as_signer_canonical_bump = *ctx
+        .bumps
+        .get("program_as_signer")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+
+    if (trade_state_canonical_bump != trade_state_bump)
+        || (free_trade_state_canonical_bump != free_trade_state_bump)
+        || (program_as_signer_canonical_bump != program_as_signer_bump)
+    {
+        return Err(AuctionHouseError::BumpSeedNotInHashMap.into());
+    }
+
+    sell_logic(
+        ctx.accounts,
+        ctx.remaining_accounts,
+        ctx.program_id,
+        trade_state_bump,
+        free_trade_state_bump,
+        program_as_signer_bump,
+        buyer_price,
+        token_size,
+    )
+}
+
 pub fn auctioneer_sell<'info>(
     ctx: Context<'_, '_, '_, 'info, AuctioneerSell<'info>>,
     trade_state_bump: u8,
@@ -118,10 +257,11 @@ pub fn auctioneer_sell<'info>(
         return Err(AuctionHouseError::BumpSeedNotInHashMap.into());
     }
 
-    let mut accounts: AuctioneerSell<'info> = (*ctx.accounts).clone();
+    let mut accounts: Sell<'info> = (*ctx.accounts).clone().into();
 
     sell_logic(
         &mut accounts,
+        ctx.remaining_accounts,
         ctx.program_id,
         trade_state_bump,
         free_trade_state_bump,
@@ -131,9 +271,9 @@ pub fn auctioneer_sell<'info>(
     )
 }
 
-#[allow(clippy::extra_unused_lifetimes)]
 fn sell_logic<'c, 'info>(
-    accounts: &mut AuctioneerSell<'info>,
+    accounts: &mut Sell<'info>,
+    remaining_accounts: &'c [AccountInfo<'info>],
     program_id: &Pubkey,
     trade_state_bump: u8,
     _free_trade_state_bump: u8,
@@ -190,7 +330,53 @@ fn sell_logic<'c, 'info>(
         return Err(AuctionHouseError::InvalidTokenAmount.into());
     }
 
-    if wallet.is_signer {
+    // A programmable NFT is delegated through Token Metadata, the sale delegate also locks the
+    // token until the listing is sold or cancelled
+    if wallet.is_signer && is_programmable(metadata)? {
+        let remaining_accounts = &mut remaining_accounts.iter();
+        let pnft_accounts = next_programmable_accounts(remaining_accounts)?;
+        let token_mint = next_account_info(remaining_accounts)?;
+        assert_keys_equal(token_mint.key(), token_account.mint)?;
+
+        let delegate_sale = DelegateBuilder::new()
+            .delegate(program_as_signer.key())
+            .metadata(metadata.key())
+            .master_edition(pnft_accounts.edition.key())
+            .token_record(pnft_accounts.token_record.key())
+            .mint(token_mint.key())
+            .token(token_account.key())
+            .authority(wallet.key())
+            .payer(wallet.key())
+            .system_program(system_program.key())
+            .sysvar_instructions(pnft_accounts.sysvar_instructions.key())
+            .spl_token_program(token_program.key())
+            .authorization_rules_program(pnft_accounts.authorization_rules_program.key())
+            .authorization_rules(pnft_accounts.authorization_rules.key())
+            .build(DelegateArgs::SaleV1 {
+                amount: token_size,
+                authorization_data: None,
+            })
+            .map_err(|_| AuctionHouseError::InvalidProgrammableAccounts)?;
+
+        invoke(
+            &delegate_sale.instruction(),
+            &[
+                program_as_signer.to_account_info(),
+                metadata.to_account_info(),
+                pnft_accounts.edition.to_account_info(),
+                pnft_accounts.token_record.to_account_info(),
+                token_mint.to_account_info(),
+                token_account.to_account_info(),
+                wallet.to_account_info(),
+                system_program.to_account_info(),
+                pnft_accounts.sysvar_instructions.to_account_info(),
+                token_program.to_account_info(),
+                pnft_accounts.authorization_rules_program.to_account_info(),
+                pnft_accounts.authorization_rules.to_account_info(),
+                pnft_accounts.token_metadata_program.to_account_info(),
+            ],
+        )?;
+    } else if wallet.is_signer {
         invoke(
             &approve(
                 &token_program.key(),
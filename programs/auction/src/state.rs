@@ -17,6 +17,77 @@ pub struct AuctionHouse {
     pub escrow_payment_bump: u8,
     pub has_auctioneer: bool,
     pub auctioneer_address: Pubkey,
+    // Auction bounds enforced by the auctioneer, zero means unbounded
+    pub min_auction_duration: i64,
+    pub max_auction_duration: i64,
+    pub max_time_extension: i64,
+    // Kind of `RoyaltyPolicy` and the basis points it carries, fixed-size so the fields after
+    // them keep their offsets
+    pub royalty_policy: u8,
+    pub royalty_basis_points: u16,
+    // Share of the house fee paid to the referrer of a sale, in basis points of the fee
+    pub referral_basis_points: u16,
+    // Premium the winner of an auctioneer sale pays the treasury on top of the hammer price
+    pub buyer_premium_basis_points: u16,
+    // Highest fee the auctioneer program may take from the seller's proceeds, zero allows none
+    pub max_auctioneer_fee_basis_points: u16,
+}
+
+// How much of the metadata royalty a sale pays, and to which creators. Houses created before
+// the policy existed read the zeroed padding as `Enforced`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RoyaltyPolicy {
+    #[default]
+    Enforced,
+    VerifiedCreatorsOnly,
+    Capped {
+        max_basis_points: u16,
+    },
+    BuyerChoice {
+        min_basis_points: u16,
+    },
+}
+
+// Part of the wallet escrow committed to outstanding bids, only the rest can be spent
+#[account]
+pub struct EscrowLedger {
+    pub auction_house: Pubkey,
+    pub wallet: Pubkey,
+    pub locked_amount: u64,
+    pub open_bids: u32,
+    pub bump: u8,
+}
+
+// Royalties or proceeds shares held for a creator or payee who could not be paid directly,
+// claimable by that wallet
+#[account]
+pub struct CreatorClaim {
+    pub auction_house: Pubkey,
+    pub creator: Pubkey,
+    pub amount: u64,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ProceedsPayee {
+    pub address: Pubkey,
+    pub share: u8,
+}
+
+// How the seller's net proceeds of a listing are divided, shares are percentages adding up to 100
+#[account]
+pub struct ProceedsSplit {
+    pub seller_trade_state: Pubkey,
+    pub payees: Vec<ProceedsPayee>,
+    pub bump: u8,
+}
+
+// Frontend the seller listed through, the only referrer a sale of the listing pays
+#[account]
+pub struct Referral {
+    pub seller_trade_state: Pubkey,
+    pub referrer: Pubkey,
+    pub bump: u8,
 }
 
 #[account]
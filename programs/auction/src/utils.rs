@@ -1,4 +1,12 @@
-use crate::{errors::AuctionHouseError, AuctionHouse, PREFIX};
+use crate::{
+    errors::AuctionHouseError,
+    events::{CreatorRoyaltiesEscrowed, ProceedsShareEscrowed, ReferralFeePaid},
+    pda::find_creator_claim_address,
+    AuctionHouse, CreatorClaim, EscrowLedger, ProceedsSplit, RoyaltyPolicy, BUYER_TRADE_STATE_SIZE,
+    CREATOR_CLAIM, CREATOR_CLAIM_SIZE, ESCROW_LEDGER, ESCROW_LEDGER_SIZE, PREFIX,
+    ROYALTY_POLICY_BUYER_CHOICE, ROYALTY_POLICY_CAPPED, ROYALTY_POLICY_ENFORCED,
+    ROYALTY_POLICY_VERIFIED_CREATORS_ONLY, TRADE_STATE_SIZE,
+};
 
 use anchor_lang::{
     prelude::*,
@@ -7,18 +15,28 @@ use anchor_lang::{
         program_memory::{sol_memcmp, sol_memset},
         program_pack::{IsInitialized, Pack},
         pubkey::PUBKEY_BYTES,
-        system_instruction,
+        system_instruction, sysvar,
     },
 };
-use anchor_spl::token::{Mint, Token, TokenAccount};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 use arrayref::array_ref;
-use mpl_token_metadata::state::{Metadata, TokenMetadataAccount};
-use spl_token::{instruction::initialize_account2, state::Account as SplAccount};
+use mpl_token_metadata::state::{Metadata, TokenMetadataAccount, TokenStandard};
+use spl_token_2022::{
+    extension::{
+        non_transferable::NonTransferable,
+        transfer_fee::{
+            instruction::harvest_withheld_tokens_to_mint, TransferFeeAmount, TransferFeeConfig,
+        },
+        BaseStateWithExtensions, ExtensionType, StateWithExtensions,
+    },
+    instruction::{initialize_account2, transfer_checked},
+    state::{Account as SplAccount, Mint as SplMint},
+};
 use std::{convert::TryInto, slice::Iter};
 
 pub fn assert_is_ata(ata: &AccountInfo, wallet: &Pubkey, mint: &Pubkey) -> Result<SplAccount> {
-    assert_owned_by(ata, &spl_token::id())?;
-    let ata_account: SplAccount = assert_initialized(ata)?;
+    assert_owned_by_token_program(ata)?;
+    let ata_account = unpack_token_account(ata)?;
     assert_keys_equal(ata_account.owner, *wallet)?;
     assert_keys_equal(ata_account.mint, *mint)?;
 
@@ -49,7 +67,7 @@ pub fn make_ata<'a>(
             fee_payer.key,
             wallet.key,
             mint.key,
-            &spl_token::ID,
+            token_program.key,
         ),
         &[
             ata,
@@ -69,7 +87,7 @@ pub fn make_ata<'a>(
 
 pub fn assert_metadata_valid<'a>(
     metadata: &UncheckedAccount,
-    token_account: &anchor_lang::prelude::Account<'a, TokenAccount>,
+    token_account: &InterfaceAccount<'a, TokenAccount>,
 ) -> Result<()> {
     assert_derivation(
         &mpl_token_metadata::id(),
@@ -132,65 +150,428 @@ pub fn assert_owned_by(account: &AccountInfo, owner: &Pubkey) -> Result<()> {
     }
 }
 
+pub fn assert_owned_by_token_program(account: &AccountInfo) -> Result<()> {
+    if *account.owner != spl_token::id() && *account.owner != spl_token_2022::id() {
+        err!(AuctionHouseError::IncorrectOwner)
+    } else {
+        Ok(())
+    }
+}
+
+/// Unpacks a token account of either token program, skipping any Token-2022 extensions.
+pub fn unpack_token_account(account: &AccountInfo) -> Result<SplAccount> {
+    let data = account.try_borrow_data()?;
+    let token_account = StateWithExtensions::<SplAccount>::unpack(&data)?;
+    Ok(token_account.base)
+}
+
+/// Transfers tokens of either token program and returns the amount the destination received,
+/// which is short of `amount` when a Token-2022 mint withholds a transfer fee.
+pub fn token_transfer_checked<'a>(
+    token_program: &AccountInfo<'a>,
+    source: &AccountInfo<'a>,
+    mint: &AccountInfo<'a>,
+    destination: &AccountInfo<'a>,
+    authority: &AccountInfo<'a>,
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<u64> {
+    let decimals = {
+        let data = mint.try_borrow_data()?;
+        StateWithExtensions::<SplMint>::unpack(&data)?.base.decimals
+    };
+    let transfer_fee = get_transfer_fee(mint, amount)?;
+
+    invoke_signed(
+        &transfer_checked(
+            token_program.key,
+            source.key,
+            mint.key,
+            destination.key,
+            authority.key,
+            &[],
+            amount,
+            decimals,
+        )?,
+        &[
+            source.clone(),
+            mint.clone(),
+            destination.clone(),
+            authority.clone(),
+            token_program.clone(),
+        ],
+        signer_seeds,
+    )?;
+
+    Ok(amount
+        .checked_sub(transfer_fee)
+        .ok_or(AuctionHouseError::NumericalOverflow)?)
+}
+
+/// Returns the fee a Token-2022 mint withholds when `amount` is transferred this epoch.
+pub fn get_transfer_fee(mint: &AccountInfo, amount: u64) -> Result<u64> {
+    if *mint.owner != spl_token_2022::id() {
+        return Ok(0);
+    }
+
+    let data = mint.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<SplMint>::unpack(&data)?;
+    match mint_state.get_extension::<TransferFeeConfig>() {
+        Ok(transfer_fee_config) => Ok(transfer_fee_config
+            .calculate_epoch_fee(Clock::get()?.epoch, amount)
+            .ok_or(AuctionHouseError::NumericalOverflow)?),
+        Err(_) => Ok(0),
+    }
+}
+
+/// Moves the transfer fees withheld in a Token-2022 account to its mint, an account still
+/// holding withheld fees cannot be closed.
+pub fn harvest_withheld_tokens<'a>(
+    token_program: &AccountInfo<'a>,
+    mint: &AccountInfo<'a>,
+    account: &AccountInfo<'a>,
+) -> Result<()> {
+    if *account.owner != spl_token_2022::id() {
+        return Ok(());
+    }
+
+    let withheld_amount = {
+        let data = account.try_borrow_data()?;
+        let token_account = StateWithExtensions::<SplAccount>::unpack(&data)?;
+        token_account
+            .get_extension::<TransferFeeAmount>()
+            .map(|transfer_fee_amount| u64::from(transfer_fee_amount.withheld_amount))
+            .unwrap_or(0)
+    };
+    if withheld_amount == 0 {
+        return Ok(());
+    }
+
+    invoke_signed(
+        &harvest_withheld_tokens_to_mint(token_program.key, mint.key, &[account.key])?,
+        &[mint.clone(), account.clone(), token_program.clone()],
+        &[],
+    )?;
+
+    Ok(())
+}
+
+pub fn assert_transferable(mint: &AccountInfo) -> Result<()> {
+    if *mint.owner != spl_token_2022::id() {
+        return Ok(());
+    }
+
+    let data = mint.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<SplMint>::unpack(&data)?;
+    if mint_state.get_extension::<NonTransferable>().is_ok() {
+        return err!(AuctionHouseError::NonTransferableMint);
+    }
+
+    Ok(())
+}
+
+/// Returns the size of a token account for the mint, including the extensions a Token-2022
+/// mint requires on its accounts.
+pub fn get_token_account_space(mint: &AccountInfo) -> Result<usize> {
+    let data = mint.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<SplMint>::unpack(&data)?;
+    let account_extensions =
+        ExtensionType::get_required_init_account_extensions(&mint_state.get_extension_types()?);
+    Ok(ExtensionType::get_account_len::<SplAccount>(
+        &account_extensions,
+    ))
+}
+
+/// An amount paid out of the escrow and the part of it that reached the recipients, which is
+/// less when the treasury mint withholds a transfer fee.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Payout {
+    pub paid: u64,
+    pub received: u64,
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn pay_auction_house_fees<'a>(
     auction_house: &anchor_lang::prelude::Account<'a, AuctionHouse>,
     auction_house_treasury: &AccountInfo<'a>,
     escrow_payment_account: &AccountInfo<'a>,
+    treasury_mint: &AccountInfo<'a>,
     token_program: &AccountInfo<'a>,
     system_program: &AccountInfo<'a>,
     signer_seeds: &[&[u8]],
     size: u64,
     is_native: bool,
-) -> Result<u64> {
+    referrer: Option<&AccountInfo<'a>>,
+    referrer_token_account: Option<&AccountInfo<'a>>,
+    fee_payer: &AccountInfo<'a>,
+    ata_program: &AccountInfo<'a>,
+    rent: &AccountInfo<'a>,
+    fee_payer_seeds: &[&[u8]],
+) -> Result<Payout> {
     let fees = auction_house.seller_fee_basis_points;
     let total_fee = (fees as u128)
         .checked_mul(size as u128)
         .ok_or(AuctionHouseError::NumericalOverflow)?
         .checked_div(10000)
         .ok_or(AuctionHouseError::NumericalOverflow)? as u64;
+
+    let referral = match referrer {
+        Some(referrer) => pay_referral_fee(
+            auction_house,
+            referrer,
+            referrer_token_account,
+            escrow_payment_account,
+            treasury_mint,
+            fee_payer,
+            ata_program,
+            token_program,
+            system_program,
+            rent,
+            signer_seeds,
+            fee_payer_seeds,
+            total_fee,
+            is_native,
+        )?,
+        None => Payout {
+            paid: 0,
+            received: 0,
+        },
+    };
+    let treasury_fee = total_fee
+        .checked_sub(referral.paid)
+        .ok_or(AuctionHouseError::NumericalOverflow)?;
+
+    let received = pay_treasury(
+        auction_house,
+        auction_house_treasury,
+        escrow_payment_account,
+        treasury_mint,
+        token_program,
+        system_program,
+        signer_seeds,
+        treasury_fee,
+        is_native,
+    )?;
+
+    Ok(Payout {
+        paid: total_fee,
+        received: received
+            .checked_add(referral.received)
+            .ok_or(AuctionHouseError::NumericalOverflow)?,
+    })
+}
+
+/// Moves `amount` from the escrow into the house treasury, returning what the treasury received.
+#[allow(clippy::too_many_arguments)]
+pub fn pay_treasury<'a>(
+    auction_house: &anchor_lang::prelude::Account<'a, AuctionHouse>,
+    auction_house_treasury: &AccountInfo<'a>,
+    escrow_payment_account: &AccountInfo<'a>,
+    treasury_mint: &AccountInfo<'a>,
+    token_program: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    signer_seeds: &[&[u8]],
+    amount: u64,
+    is_native: bool,
+) -> Result<u64> {
     if !is_native {
+        token_transfer_checked(
+            token_program,
+            escrow_payment_account,
+            treasury_mint,
+            auction_house_treasury,
+            &auction_house.to_account_info(),
+            amount,
+            &[signer_seeds],
+        )
+    } else {
         invoke_signed(
-            &spl_token::instruction::transfer(
-                token_program.key,
+            &system_instruction::transfer(
                 escrow_payment_account.key,
                 auction_house_treasury.key,
-                &auction_house.key(),
-                &[],
-                total_fee,
-            )?,
+                amount,
+            ),
             &[
                 escrow_payment_account.clone(),
                 auction_house_treasury.clone(),
-                token_program.clone(),
-                auction_house.to_account_info(),
+                system_program.clone(),
             ],
             &[signer_seeds],
         )?;
+        Ok(amount)
+    }
+}
+
+/// Pays the auctioneer program's fee on `price` to its fee destination, the wallet for a native
+/// treasury and a treasury mint token account otherwise.
+#[allow(clippy::too_many_arguments)]
+pub fn pay_auctioneer_fee<'a>(
+    auction_house: &anchor_lang::prelude::Account<'a, AuctionHouse>,
+    fee_destination: &AccountInfo<'a>,
+    escrow_payment_account: &AccountInfo<'a>,
+    treasury_mint: &AccountInfo<'a>,
+    token_program: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    signer_seeds: &[&[u8]],
+    fee_basis_points: u16,
+    price: u64,
+    is_native: bool,
+) -> Result<Payout> {
+    let fee = (fee_basis_points as u128)
+        .checked_mul(price as u128)
+        .ok_or(AuctionHouseError::NumericalOverflow)?
+        .checked_div(10000)
+        .ok_or(AuctionHouseError::NumericalOverflow)? as u64;
+    if fee == 0 {
+        return Ok(Payout::default());
+    }
+
+    let received = if !is_native {
+        assert_owned_by_token_program(fee_destination)?;
+        assert_keys_equal(
+            unpack_token_account(fee_destination)?.mint,
+            treasury_mint.key(),
+        )?;
+
+        token_transfer_checked(
+            token_program,
+            escrow_payment_account,
+            treasury_mint,
+            fee_destination,
+            &auction_house.to_account_info(),
+            fee,
+            &[signer_seeds],
+        )?
     } else {
+        // A fee that would leave the destination below rent exemption stays with the seller
+        let destination_rent_minimum = Rent::get()?.minimum_balance(fee_destination.data_len());
+        if fee + fee_destination.lamports() < destination_rent_minimum {
+            return Ok(Payout::default());
+        }
+
         invoke_signed(
-            &system_instruction::transfer(
-                escrow_payment_account.key,
-                auction_house_treasury.key,
-                total_fee,
-            ),
+            &system_instruction::transfer(escrow_payment_account.key, fee_destination.key, fee),
             &[
                 escrow_payment_account.clone(),
-                auction_house_treasury.clone(),
+                fee_destination.clone(),
                 system_program.clone(),
             ],
             &[signer_seeds],
         )?;
+        fee
+    };
+
+    Ok(Payout {
+        paid: fee,
+        received,
+    })
+}
+
+/// Premium an auctioneer sale charges the winner on top of `price`.
+pub fn get_buyer_premium(auction_house: &AuctionHouse, price: u64) -> Result<u64> {
+    Ok((auction_house.buyer_premium_basis_points as u128)
+        .checked_mul(price as u128)
+        .ok_or(AuctionHouseError::NumericalOverflow)?
+        .checked_div(10000)
+        .ok_or(AuctionHouseError::NumericalOverflow)? as u64)
+}
+
+/// Pays the house's referral share of `house_fee` to the referrer, to its wallet for a native
+/// treasury and to its treasury mint ATA otherwise.
+#[allow(clippy::too_many_arguments)]
+pub fn pay_referral_fee<'a>(
+    auction_house: &anchor_lang::prelude::Account<'a, AuctionHouse>,
+    referrer: &AccountInfo<'a>,
+    referrer_token_account: Option<&AccountInfo<'a>>,
+    escrow_payment_account: &AccountInfo<'a>,
+    treasury_mint: &AccountInfo<'a>,
+    fee_payer: &AccountInfo<'a>,
+    ata_program: &AccountInfo<'a>,
+    token_program: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    rent: &AccountInfo<'a>,
+    signer_seeds: &[&[u8]],
+    fee_payer_seeds: &[&[u8]],
+    house_fee: u64,
+    is_native: bool,
+) -> Result<Payout> {
+    let referral_fee = (auction_house.referral_basis_points as u128)
+        .checked_mul(house_fee as u128)
+        .ok_or(AuctionHouseError::NumericalOverflow)?
+        .checked_div(10000)
+        .ok_or(AuctionHouseError::NumericalOverflow)? as u64;
+    let unpaid = Payout {
+        paid: 0,
+        received: 0,
+    };
+    if referral_fee == 0 {
+        return Ok(unpaid);
     }
-    Ok(total_fee)
+
+    let received = if !is_native {
+        let referrer_token_account =
+            referrer_token_account.ok_or(AuctionHouseError::MissingReferrerTokenAccount)?;
+        if referrer_token_account.data_is_empty() {
+            make_ata(
+                referrer_token_account.to_account_info(),
+                referrer.to_account_info(),
+                treasury_mint.to_account_info(),
+                fee_payer.to_account_info(),
+                ata_program.to_account_info(),
+                token_program.to_account_info(),
+                system_program.to_account_info(),
+                rent.to_account_info(),
+                fee_payer_seeds,
+            )?;
+        }
+        assert_is_ata(referrer_token_account, referrer.key, &treasury_mint.key())?;
+
+        token_transfer_checked(
+            token_program,
+            escrow_payment_account,
+            treasury_mint,
+            referrer_token_account,
+            &auction_house.to_account_info(),
+            referral_fee,
+            &[signer_seeds],
+        )?
+    } else {
+        // A share that would leave the referrer below rent exemption stays with the treasury
+        let referrer_rent_minimum = Rent::get()?.minimum_balance(referrer.data_len());
+        if referral_fee + referrer.lamports() < referrer_rent_minimum {
+            return Ok(unpaid);
+        }
+
+        invoke_signed(
+            &system_instruction::transfer(escrow_payment_account.key, referrer.key, referral_fee),
+            &[
+                escrow_payment_account.clone(),
+                referrer.clone(),
+                system_program.clone(),
+            ],
+            &[signer_seeds],
+        )?;
+        referral_fee
+    };
+
+    emit!(ReferralFeePaid {
+        auction_house: auction_house.key(),
+        referrer: *referrer.key,
+        amount: received,
+    });
+
+    Ok(Payout {
+        paid: referral_fee,
+        received,
+    })
 }
 
 pub fn create_program_token_account_if_not_present<'a>(
     payment_account: &UncheckedAccount<'a>,
     system_program: &Program<'a, System>,
     fee_payer: &AccountInfo<'a>,
-    token_program: &Program<'a, Token>,
-    treasury_mint: &anchor_lang::prelude::Account<'a, Mint>,
+    token_program: &Interface<'a, TokenInterface>,
+    treasury_mint: &InterfaceAccount<'a, Mint>,
     owner: &AccountInfo<'a>,
     rent: &Sysvar<'a, Rent>,
     signer_seeds: &[&[u8]],
@@ -204,7 +585,7 @@ pub fn create_program_token_account_if_not_present<'a>(
             &rent.to_account_info(),
             system_program,
             fee_payer,
-            spl_token::state::Account::LEN,
+            get_token_account_space(&treasury_mint.to_account_info())?,
             fee_seeds,
             signer_seeds,
         )?;
@@ -229,12 +610,71 @@ pub fn create_program_token_account_if_not_present<'a>(
     Ok(())
 }
 
+/// Royalty policy of the house, rebuilt from the kind and basis points it stores.
+pub fn get_royalty_policy(auction_house: &AuctionHouse) -> Result<RoyaltyPolicy> {
+    let basis_points = auction_house.royalty_basis_points;
+    match auction_house.royalty_policy {
+        ROYALTY_POLICY_ENFORCED => Ok(RoyaltyPolicy::Enforced),
+        ROYALTY_POLICY_VERIFIED_CREATORS_ONLY => Ok(RoyaltyPolicy::VerifiedCreatorsOnly),
+        ROYALTY_POLICY_CAPPED => Ok(RoyaltyPolicy::Capped {
+            max_basis_points: basis_points,
+        }),
+        ROYALTY_POLICY_BUYER_CHOICE => Ok(RoyaltyPolicy::BuyerChoice {
+            min_basis_points: basis_points,
+        }),
+        _ => Err(AuctionHouseError::InvalidRoyaltyPolicy.into()),
+    }
+}
+
+/// Splits a royalty policy into the kind and basis points the house stores.
+pub fn get_royalty_policy_fields(royalty_policy: &RoyaltyPolicy) -> (u8, u16) {
+    match *royalty_policy {
+        RoyaltyPolicy::Enforced => (ROYALTY_POLICY_ENFORCED, 0),
+        RoyaltyPolicy::VerifiedCreatorsOnly => (ROYALTY_POLICY_VERIFIED_CREATORS_ONLY, 0),
+        RoyaltyPolicy::Capped { max_basis_points } => (ROYALTY_POLICY_CAPPED, max_basis_points),
+        RoyaltyPolicy::BuyerChoice { min_basis_points } => {
+            (ROYALTY_POLICY_BUYER_CHOICE, min_basis_points)
+        }
+    }
+}
+
+/// Royalty a sale pays under the house policy, in basis points of the sale price.
+pub fn get_royalty_basis_points(
+    royalty_policy: &RoyaltyPolicy,
+    seller_fee_basis_points: u16,
+    buyer_royalty_basis_points: Option<u16>,
+) -> Result<u16> {
+    match (royalty_policy, buyer_royalty_basis_points) {
+        (RoyaltyPolicy::Capped { max_basis_points }, None) => {
+            Ok(seller_fee_basis_points.min(*max_basis_points))
+        }
+        // The buyer may pay less than the metadata asks, never more, and the house minimum
+        // never exceeds the metadata royalty itself
+        (RoyaltyPolicy::BuyerChoice { min_basis_points }, Some(chosen)) => {
+            if chosen < seller_fee_basis_points.min(*min_basis_points) {
+                return Err(AuctionHouseError::RoyaltyBelowMinimum.into());
+            }
+
+            Ok(chosen.min(seller_fee_basis_points))
+        }
+        (_, Some(_)) => Err(AuctionHouseError::RoyaltyChoiceNotAllowed.into()),
+        (_, None) => Ok(seller_fee_basis_points),
+    }
+}
+
+/// Creator accounts are passed in metadata order. A creator paid in SPL tokens is followed by
+/// its treasury mint ATA, a creator paid in SOL may be followed by its claim account, which holds
+/// the share when paying the creator directly would leave it below rent exemption. Claim rent is
+/// paid by the house fee account, which gets it back when the creator claims.
 #[allow(clippy::too_many_arguments)]
 pub fn pay_creator_fees<'a>(
     remaining_accounts: &mut Iter<AccountInfo<'a>>,
+    auction_house_key: &Pubkey,
     metadata_info: &AccountInfo<'a>,
     escrow_payment_account: &AccountInfo<'a>,
     payment_account_owner: &AccountInfo<'a>,
+    auction_house_fee_account: &AccountInfo<'a>,
+    auction_house_fee_seeds: &[&[u8]],
     fee_payer: &AccountInfo<'a>,
     treasury_mint: &AccountInfo<'a>,
     ata_program: &AccountInfo<'a>,
@@ -245,18 +685,18 @@ pub fn pay_creator_fees<'a>(
     fee_payer_seeds: &[&[u8]],
     size: u64,
     is_native: bool,
-) -> Result<u64> {
+    royalty_policy: &RoyaltyPolicy,
+    royalty_basis_points: u16,
+) -> Result<Payout> {
     let metadata = Metadata::from_account_info(metadata_info)?;
-    let fees = metadata.data.seller_fee_basis_points;
+    let fees = royalty_basis_points;
     let total_fee = (fees as u128)
         .checked_mul(size as u128)
         .ok_or(AuctionHouseError::NumericalOverflow)?
         .checked_div(10000)
         .ok_or(AuctionHouseError::NumericalOverflow)? as u64;
     let mut remaining_fee = total_fee;
-    let remaining_size = size
-        .checked_sub(total_fee)
-        .ok_or(AuctionHouseError::NumericalOverflow)?;
+    let mut received = 0u64;
     match metadata.data.creators {
         Some(creators) => {
             for creator in creators {
@@ -267,25 +707,61 @@ pub fn pay_creator_fees<'a>(
                         .checked_div(100)
                         .ok_or(AuctionHouseError::NumericalOverflow)? as u64;
                 let current_creator_info = next_account_info(remaining_accounts)?;
+                assert_keys_equal(creator.address, *current_creator_info.key)?;
+                let creator_claim = if is_native {
+                    next_creator_claim(remaining_accounts, auction_house_key, &creator.address)
+                } else {
+                    None
+                };
+
+                // An unverified creator's share is not charged, it stays with the seller
+                if *royalty_policy == RoyaltyPolicy::VerifiedCreatorsOnly && !creator.verified {
+                    if !is_native {
+                        next_account_info(remaining_accounts)?;
+                    }
+                    continue;
+                }
+
+                remaining_fee = remaining_fee
+                    .checked_sub(creator_fee)
+                    .ok_or(AuctionHouseError::NumericalOverflow)?;
+
                 let creator_rent_minimum =
                     Rent::get()?.minimum_balance(current_creator_info.data.borrow().len());
                 if is_native
                     && ((creator_fee + **current_creator_info.lamports.borrow())
                         < creator_rent_minimum)
                 {
-                    msg!(
-                        "cannot pay creator {} {} lamports since balance violates rent exempt minimum",
-                        current_creator_info.key,
-                        creator_fee
-                    );
-                    continue;
-                }
+                    if creator_fee == 0 {
+                        continue;
+                    }
 
-                remaining_fee = remaining_fee
-                    .checked_sub(creator_fee)
-                    .ok_or(AuctionHouseError::NumericalOverflow)?;
-                assert_keys_equal(creator.address, *current_creator_info.key)?;
-                if !is_native {
+                    // The payment would leave the creator below rent exemption, so the share
+                    // is held in the creator's claim account instead of going to the seller
+                    let (creator_claim_info, creator_claim_bump) =
+                        creator_claim.ok_or(AuctionHouseError::MissingCreatorClaim)?;
+                    escrow_to_claim(
+                        creator_claim_info,
+                        creator_claim_bump,
+                        auction_house_key,
+                        current_creator_info.key,
+                        escrow_payment_account,
+                        auction_house_fee_account,
+                        system_program,
+                        rent,
+                        signer_seeds,
+                        auction_house_fee_seeds,
+                        creator_fee,
+                    )?;
+                    emit!(CreatorRoyaltiesEscrowed {
+                        auction_house: *auction_house_key,
+                        creator: creator.address,
+                        amount: creator_fee,
+                    });
+                    received = received
+                        .checked_add(creator_fee)
+                        .ok_or(AuctionHouseError::NumericalOverflow)?;
+                } else if !is_native {
                     let current_creator_token_account_info = next_account_info(remaining_accounts)?;
                     if current_creator_token_account_info.data_is_empty() {
                         make_ata(
@@ -306,23 +782,18 @@ pub fn pay_creator_fees<'a>(
                         &treasury_mint.key(),
                     )?;
                     if creator_fee > 0 {
-                        invoke_signed(
-                            &spl_token::instruction::transfer(
-                                token_program.key,
-                                escrow_payment_account.key,
-                                current_creator_token_account_info.key,
-                                payment_account_owner.key,
-                                &[],
-                                creator_fee,
-                            )?,
-                            &[
-                                escrow_payment_account.clone(),
-                                current_creator_token_account_info.clone(),
-                                token_program.clone(),
-                                payment_account_owner.clone(),
-                            ],
+                        let creator_received = token_transfer_checked(
+                            token_program,
+                            escrow_payment_account,
+                            treasury_mint,
+                            current_creator_token_account_info,
+                            payment_account_owner,
+                            creator_fee,
                             &[signer_seeds],
                         )?;
+                        received = received
+                            .checked_add(creator_received)
+                            .ok_or(AuctionHouseError::NumericalOverflow)?;
                     }
                 } else if creator_fee > 0 {
                     invoke_signed(
@@ -338,6 +809,9 @@ pub fn pay_creator_fees<'a>(
                         ],
                         &[signer_seeds],
                     )?;
+                    received = received
+                        .checked_add(creator_fee)
+                        .ok_or(AuctionHouseError::NumericalOverflow)?;
                 }
             }
         }
@@ -346,9 +820,240 @@ pub fn pay_creator_fees<'a>(
         }
     }
 
-    Ok(remaining_size
-        .checked_add(remaining_fee)
-        .ok_or(AuctionHouseError::NumericalOverflow)?)
+    Ok(Payout {
+        paid: total_fee
+            .checked_sub(remaining_fee)
+            .ok_or(AuctionHouseError::NumericalOverflow)?,
+        received,
+    })
+}
+
+/// Takes the creator's claim account when it was passed right after the creator account.
+pub fn next_creator_claim<'a, 'b>(
+    remaining_accounts: &mut Iter<'b, AccountInfo<'a>>,
+    auction_house_key: &Pubkey,
+    creator_key: &Pubkey,
+) -> Option<(&'b AccountInfo<'a>, u8)> {
+    let (creator_claim_key, creator_claim_bump) =
+        find_creator_claim_address(auction_house_key, creator_key);
+    match remaining_accounts.as_slice().first() {
+        Some(creator_claim_info) if *creator_claim_info.key == creator_claim_key => {
+            remaining_accounts.next();
+            Some((creator_claim_info, creator_claim_bump))
+        }
+        _ => None,
+    }
+}
+
+/// Moves a native royalty or proceeds share from the escrow into the recipient's claim account,
+/// creating the claim at the house fee account's expense when the recipient has none yet.
+#[allow(clippy::too_many_arguments)]
+pub fn escrow_to_claim<'a>(
+    claim_info: &AccountInfo<'a>,
+    claim_bump: u8,
+    auction_house_key: &Pubkey,
+    recipient_key: &Pubkey,
+    escrow_payment_account: &AccountInfo<'a>,
+    auction_house_fee_account: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    rent: &AccountInfo<'a>,
+    signer_seeds: &[&[u8]],
+    auction_house_fee_seeds: &[&[u8]],
+    amount: u64,
+) -> Result<()> {
+    let mut claim = if claim_info.data_is_empty() {
+        create_or_allocate_account_raw(
+            crate::id(),
+            claim_info,
+            rent,
+            system_program,
+            auction_house_fee_account,
+            CREATOR_CLAIM_SIZE,
+            auction_house_fee_seeds,
+            &[
+                PREFIX.as_bytes(),
+                auction_house_key.as_ref(),
+                recipient_key.as_ref(),
+                CREATOR_CLAIM.as_bytes(),
+                &[claim_bump],
+            ],
+        )?;
+
+        CreatorClaim {
+            auction_house: *auction_house_key,
+            creator: *recipient_key,
+            amount: 0,
+            bump: claim_bump,
+        }
+    } else {
+        assert_owned_by(claim_info, &crate::id())?;
+        CreatorClaim::try_deserialize(&mut claim_info.data.borrow().as_ref())?
+    };
+
+    invoke_signed(
+        &system_instruction::transfer(escrow_payment_account.key, claim_info.key, amount),
+        &[
+            escrow_payment_account.clone(),
+            claim_info.clone(),
+            system_program.clone(),
+        ],
+        &[signer_seeds],
+    )?;
+
+    claim.amount = claim
+        .amount
+        .checked_add(amount)
+        .ok_or(AuctionHouseError::NumericalOverflow)?;
+    claim.try_serialize(&mut &mut claim_info.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+/// Pays the listing's payees their shares of the seller's proceeds. Like the creators, each
+/// payee is passed in `remaining_accounts` followed by its treasury mint token account when the
+/// treasury mint is not native, or optionally by its claim account when it is. Returns nothing
+/// paid when the listing has no split.
+#[allow(clippy::too_many_arguments)]
+pub fn pay_proceeds_split<'a>(
+    remaining_accounts: &mut Iter<AccountInfo<'a>>,
+    auction_house_key: &Pubkey,
+    proceeds_split_info: &AccountInfo<'a>,
+    escrow_payment_account: &AccountInfo<'a>,
+    payment_account_owner: &AccountInfo<'a>,
+    auction_house_fee_account: &AccountInfo<'a>,
+    auction_house_fee_seeds: &[&[u8]],
+    fee_payer: &AccountInfo<'a>,
+    treasury_mint: &AccountInfo<'a>,
+    ata_program: &AccountInfo<'a>,
+    token_program: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    rent: &AccountInfo<'a>,
+    signer_seeds: &[&[u8]],
+    fee_payer_seeds: &[&[u8]],
+    size: u64,
+    is_native: bool,
+) -> Result<Payout> {
+    if proceeds_split_info.data_is_empty() {
+        return Ok(Payout::default());
+    }
+
+    assert_owned_by(proceeds_split_info, &crate::id())?;
+    let proceeds_split =
+        ProceedsSplit::try_deserialize(&mut proceeds_split_info.data.borrow().as_ref())?;
+
+    let mut paid = 0u64;
+    let mut received = 0u64;
+    for payee in proceeds_split.payees {
+        let payee_amount = (payee.share as u128)
+            .checked_mul(size as u128)
+            .ok_or(AuctionHouseError::NumericalOverflow)?
+            .checked_div(100)
+            .ok_or(AuctionHouseError::NumericalOverflow)? as u64;
+        let current_payee_info = next_account_info(remaining_accounts)?;
+        assert_keys_equal(payee.address, *current_payee_info.key)?;
+
+        if !is_native {
+            let current_payee_token_account_info = next_account_info(remaining_accounts)?;
+            if current_payee_token_account_info.data_is_empty() {
+                make_ata(
+                    current_payee_token_account_info.to_account_info(),
+                    current_payee_info.to_account_info(),
+                    treasury_mint.to_account_info(),
+                    fee_payer.to_account_info(),
+                    ata_program.to_account_info(),
+                    token_program.to_account_info(),
+                    system_program.to_account_info(),
+                    rent.to_account_info(),
+                    fee_payer_seeds,
+                )?;
+            }
+            assert_is_ata(
+                current_payee_token_account_info,
+                current_payee_info.key,
+                &treasury_mint.key(),
+            )?;
+            if payee_amount > 0 {
+                let payee_received = token_transfer_checked(
+                    token_program,
+                    escrow_payment_account,
+                    treasury_mint,
+                    current_payee_token_account_info,
+                    payment_account_owner,
+                    payee_amount,
+                    &[signer_seeds],
+                )?;
+                received = received
+                    .checked_add(payee_received)
+                    .ok_or(AuctionHouseError::NumericalOverflow)?;
+            }
+        } else {
+            let payee_claim =
+                next_creator_claim(remaining_accounts, auction_house_key, &payee.address);
+            if payee_amount == 0 {
+                continue;
+            }
+
+            // A share that would leave the payee below rent exemption is held in its claim
+            // account, which the payee claims like a creator does
+            let payee_rent_minimum =
+                Rent::get()?.minimum_balance(current_payee_info.data.borrow().len());
+            let payee_balance = payee_amount
+                .checked_add(**current_payee_info.lamports.borrow())
+                .ok_or(AuctionHouseError::NumericalOverflow)?;
+            if payee_balance < payee_rent_minimum {
+                let (payee_claim_info, payee_claim_bump) =
+                    payee_claim.ok_or(AuctionHouseError::MissingPayeeClaim)?;
+                escrow_to_claim(
+                    payee_claim_info,
+                    payee_claim_bump,
+                    auction_house_key,
+                    current_payee_info.key,
+                    escrow_payment_account,
+                    auction_house_fee_account,
+                    system_program,
+                    rent,
+                    signer_seeds,
+                    auction_house_fee_seeds,
+                    payee_amount,
+                )?;
+                emit!(ProceedsShareEscrowed {
+                    auction_house: *auction_house_key,
+                    payee: payee.address,
+                    amount: payee_amount,
+                });
+                received = received
+                    .checked_add(payee_amount)
+                    .ok_or(AuctionHouseError::NumericalOverflow)?;
+                paid = paid
+                    .checked_add(payee_amount)
+                    .ok_or(AuctionHouseError::NumericalOverflow)?;
+                continue;
+            }
+
+            invoke_signed(
+                &system_instruction::transfer(
+                    escrow_payment_account.key,
+                    current_payee_info.key,
+                    payee_amount,
+                ),
+                &[
+                    escrow_payment_account.clone(),
+                    current_payee_info.clone(),
+                    system_program.clone(),
+                ],
+                &[signer_seeds],
+            )?;
+            received = received
+                .checked_add(payee_amount)
+                .ok_or(AuctionHouseError::NumericalOverflow)?;
+        }
+
+        paid = paid
+            .checked_add(payee_amount)
+            .ok_or(AuctionHouseError::NumericalOverflow)?;
+    }
+
+    Ok(Payout { paid, received })
 }
 
 pub fn get_mint_from_token_account(token_account_info: &AccountInfo) -> Result<Pubkey> {
@@ -530,3 +1235,188 @@ pub fn close_account<'a>(
 
     Ok(())
 }
+
+pub fn get_escrow_balance(escrow_payment_account: &AccountInfo, is_native: bool) -> Result<u64> {
+    if is_native {
+        let rent_minimum = (Rent::get()?).minimum_balance(escrow_payment_account.data_len());
+        Ok(escrow_payment_account
+            .lamports()
+            .saturating_sub(rent_minimum))
+    } else {
+        let escrow_payment_loaded = unpack_token_account(escrow_payment_account)?;
+        Ok(escrow_payment_loaded.amount)
+    }
+}
+
+/// Loads the escrow ledger of a wallet, creating it first when the wallet has none yet.
+#[allow(clippy::too_many_arguments)]
+pub fn load_or_create_escrow_ledger<'a>(
+    escrow_ledger: &AccountInfo<'a>,
+    auction_house_key: &Pubkey,
+    wallet_key: &Pubkey,
+    escrow_ledger_bump: u8,
+    payer: &AccountInfo<'a>,
+    payer_seeds: &[&[u8]],
+    system_program: &AccountInfo<'a>,
+    rent: &AccountInfo<'a>,
+) -> Result<EscrowLedger> {
+    if !escrow_ledger.data_is_empty() {
+        return Ok(EscrowLedger::try_deserialize(
+            &mut escrow_ledger.data.borrow().as_ref(),
+        )?);
+    }
+
+    create_or_allocate_account_raw(
+        crate::id(),
+        escrow_ledger,
+        rent,
+        system_program,
+        payer,
+        ESCROW_LEDGER_SIZE,
+        payer_seeds,
+        &[
+            PREFIX.as_bytes(),
+            auction_house_key.as_ref(),
+            wallet_key.as_ref(),
+            ESCROW_LEDGER.as_bytes(),
+            &[escrow_ledger_bump],
+        ],
+    )?;
+
+    Ok(EscrowLedger {
+        auction_house: *auction_house_key,
+        wallet: *wallet_key,
+        locked_amount: 0,
+        open_bids: 0,
+        bump: escrow_ledger_bump,
+    })
+}
+
+/// Flags a new buyer trade state as counted in the open bids of the wallet escrow ledger.
+pub fn mark_open_bid_counted(trade_state: &AccountInfo) -> Result<()> {
+    trade_state.try_borrow_mut_data()?[TRADE_STATE_SIZE] = 1;
+
+    Ok(())
+}
+
+/// Removes a closed buyer trade state from the open bids of the wallet escrow ledger.
+pub fn release_open_bid(escrow_ledger: &AccountInfo, trade_state: &AccountInfo) -> Result<()> {
+    // Bids placed before the wallet had a ledger were never counted, their trade states carry
+    // no flag
+    let trade_state_data = trade_state.try_borrow_data()?;
+    if trade_state_data.len() < BUYER_TRADE_STATE_SIZE || trade_state_data[TRADE_STATE_SIZE] == 0 {
+        return Ok(());
+    }
+    if escrow_ledger.data_is_empty() {
+        return Ok(());
+    }
+
+    let mut ledger = EscrowLedger::try_deserialize(&mut escrow_ledger.data.borrow().as_ref())?;
+    ledger.open_bids = ledger.open_bids.saturating_sub(1);
+    ledger.try_serialize(&mut &mut escrow_ledger.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+/// Returns the number of buyer trade states still open against the wallet escrow.
+pub fn get_open_bid_count(escrow_ledger: &AccountInfo) -> Result<u32> {
+    if escrow_ledger.data_is_empty() {
+        return Ok(0);
+    }
+
+    let ledger = EscrowLedger::try_deserialize(&mut escrow_ledger.data.borrow().as_ref())?;
+    Ok(ledger.open_bids)
+}
+
+/// Returns the escrow amount locked by outstanding bids, zero when the wallet has no ledger yet.
+pub fn get_locked_escrow_amount(escrow_ledger: &AccountInfo) -> Result<u64> {
+    if escrow_ledger.data_is_empty() {
+        return Ok(0);
+    }
+
+    let ledger = EscrowLedger::try_deserialize(&mut escrow_ledger.data.borrow().as_ref())?;
+    Ok(ledger.locked_amount)
+}
+
+/// Returns how much of the escrow the locked bids of the wallet hold back, including the
+/// premium their winner will owe.
+pub fn get_locked_escrow_requirement(
+    auction_house: &AuctionHouse,
+    escrow_ledger: &AccountInfo,
+) -> Result<u64> {
+    let locked_amount = get_locked_escrow_amount(escrow_ledger)?;
+    let locked_premium = get_buyer_premium(auction_house, locked_amount)?;
+    let locked_requirement = locked_amount
+        .checked_add(locked_premium)
+        .ok_or(AuctionHouseError::NumericalOverflow)?;
+
+    Ok(locked_requirement)
+}
+
+/// Fails when spending `amount` from the escrow would dip into the locked funds.
+pub fn assert_free_escrow_balance(
+    auction_house: &AuctionHouse,
+    escrow_payment_account: &AccountInfo,
+    escrow_ledger: &AccountInfo,
+    is_native: bool,
+    amount: u64,
+) -> Result<()> {
+    let locked_amount = get_locked_escrow_requirement(auction_house, escrow_ledger)?;
+    if locked_amount == 0 {
+        return Ok(());
+    }
+
+    let free_amount =
+        get_escrow_balance(escrow_payment_account, is_native)?.saturating_sub(locked_amount);
+    if amount > free_amount {
+        return Err(AuctionHouseError::EscrowFundsLocked.into());
+    }
+
+    Ok(())
+}
+
+/// Returns whether the metadata belongs to a programmable NFT, whose token account stays
+/// frozen and can only be delegated and transferred through Token Metadata.
+pub fn is_programmable(metadata_info: &AccountInfo) -> Result<bool> {
+    let metadata = Metadata::from_account_info(metadata_info)?;
+    Ok(matches!(
+        metadata.token_standard,
+        Some(TokenStandard::ProgrammableNonFungible)
+    ))
+}
+
+/// Token Metadata accounts a programmable NFT needs, passed in this order as remaining accounts.
+pub struct ProgrammableAccounts<'a, 'info> {
+    pub token_metadata_program: &'a AccountInfo<'info>,
+    pub edition: &'a AccountInfo<'info>,
+    pub token_record: &'a AccountInfo<'info>,
+    pub authorization_rules_program: &'a AccountInfo<'info>,
+    pub authorization_rules: &'a AccountInfo<'info>,
+    pub sysvar_instructions: &'a AccountInfo<'info>,
+}
+
+pub fn next_programmable_accounts<'a, 'info>(
+    remaining_accounts: &mut Iter<'a, AccountInfo<'info>>,
+) -> Result<ProgrammableAccounts<'a, 'info>> {
+    let mut next = || {
+        next_account_info(remaining_accounts)
+            .map_err(|_| error!(AuctionHouseError::InvalidProgrammableAccounts))
+    };
+
+    let accounts = ProgrammableAccounts {
+        token_metadata_program: next()?,
+        edition: next()?,
+        token_record: next()?,
+        authorization_rules_program: next()?,
+        authorization_rules: next()?,
+        sysvar_instructions: next()?,
+    };
+
+    if accounts.token_metadata_program.key() != mpl_token_metadata::id()
+        || accounts.sysvar_instructions.key() != sysvar::instructions::id()
+    {
+        return err!(AuctionHouseError::InvalidProgrammableAccounts);
+    }
+
+    Ok(accounts)
+}
@@ -18,7 +18,17 @@ pub struct AuctioneerWithdraw<'info> {
         bump
     )]
     pub escrow_payment_account: UncheckedAccount<'info>,
-    pub treasury_mint: Box<Account<'info, Mint>>,
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            wallet.key().as_ref(),
+            ESCROW_LEDGER.as_bytes()
+        ],
+        bump
+    )]
+    pub escrow_ledger: UncheckedAccount<'info>,
+    pub treasury_mint: Box<InterfaceAccount<'info, Mint>>,
     pub authority: UncheckedAccount<'info>,
     pub auctioneer_authority: Signer<'info>,
     #[account(
@@ -51,7 +61,7 @@ pub struct AuctioneerWithdraw<'info> {
         bump = auctioneer.bump
     )]
     pub auctioneer: Account<'info, Auctioneer>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub rent: Sysvar<'info, Rent>,
@@ -91,6 +101,7 @@ fn withdraw_logic<'info>(
     let wallet = &accounts.wallet;
     let receipt_account = &accounts.receipt_account;
     let escrow_payment_account = &accounts.escrow_payment_account;
+    let escrow_ledger = &accounts.escrow_ledger;
     let authority = &accounts.authority;
     let auction_house = &accounts.auction_house;
     let auction_house_fee_account = &accounts.auction_house_fee_account;
@@ -136,8 +147,19 @@ fn withdraw_logic<'info>(
         &seeds,
     )?;
 
+    // The escrow only moves through the program of the treasury mint
+    assert_keys_equal(token_program.key(), *treasury_mint.to_account_info().owner)?;
+
     let is_native = treasury_mint.key() == spl_token::native_mint::id();
 
+    assert_free_escrow_balance(
+        auction_house,
+        &escrow_payment_account.to_account_info(),
+        &escrow_ledger.to_account_info(),
+        is_native,
+        amount,
+    )?;
+
     if !is_native {
         if receipt_account.data_is_empty() {
             make_ata(
@@ -165,21 +187,13 @@ fn withdraw_logic<'info>(
         }
 
         assert_is_ata(receipt_account, &wallet.key(), &treasury_mint.key())?;
-        invoke_signed(
-            &spl_token::instruction::transfer(
-                token_program.key,
-                &escrow_payment_account.key(),
-                &receipt_account.key(),
-                &auction_house.key(),
-                &[],
-                amount,
-            )?,
-            &[
-                escrow_payment_account.to_account_info(),
-                receipt_account.to_account_info(),
-                token_program.to_account_info(),
-                auction_house.to_account_info(),
-            ],
+        token_transfer_checked(
+            &token_program.to_account_info(),
+            &escrow_payment_account.to_account_info(),
+            &treasury_mint.to_account_info(),
+            &receipt_account.to_account_info(),
+            &auction_house.to_account_info(),
+            amount,
             &[&ah_seeds],
         )?;
     } else {
@@ -204,5 +218,11 @@ fn withdraw_logic<'info>(
         )?;
     }
 
+    emit!(EscrowWithdrawn {
+        auction_house: auction_house_key,
+        wallet: wallet_key,
+        amount,
+    });
+
     Ok(())
 }
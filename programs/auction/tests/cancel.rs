@@ -0,0 +1,112 @@
+use anchor_client::solana_sdk::{signature::Keypair, signer::Signer};
+use auction_house::errors::AuctionHouseError;
+use solana_program_test::tokio;
+
+mod utils;
+use utils::setup_functions::*;
+
+#[tokio::test]
+async fn cancel_listing_by_authority_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+
+    let (authority, auction_house, auction_house_data) =
+        create_auction_house(&mut context, 100, false)
+            .await
+            .expect("Failed to create Auction House");
+
+    let token = create_nft(&mut context, None)
+        .await
+        .expect("Failed to create NFT");
+
+    let (sell_accounts, sell_tx) = sell(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        ONE_SOL,
+        1,
+        &token.owner,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    let (_, cancel_tx) = cancel(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &token.owner.pubkey(),
+        &sell_accounts.seller_trade_state,
+        ONE_SOL,
+        1,
+        &authority,
+    );
+    context
+        .banks_client
+        .process_transaction(cancel_tx)
+        .await
+        .unwrap();
+
+    let seller_trade_state = context
+        .banks_client
+        .get_account(sell_accounts.seller_trade_state)
+        .await
+        .unwrap();
+    assert!(seller_trade_state.is_none());
+}
+
+#[tokio::test]
+async fn failure_cancel_no_valid_signer() {
+    let mut context = auction_house_program_test().start_with_context().await;
+
+    let (_, auction_house, auction_house_data) = create_auction_house(&mut context, 100, false)
+        .await
+        .expect("Failed to create Auction House");
+
+    let token = create_nft(&mut context, None)
+        .await
+        .expect("Failed to create NFT");
+
+    let (sell_accounts, sell_tx) = sell(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        ONE_SOL,
+        1,
+        &token.owner,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    // Neither the seller nor the authority signs
+    let stranger = Keypair::new();
+    airdrop(&mut context, &stranger.pubkey(), ONE_SOL)
+        .await
+        .unwrap();
+
+    let (_, cancel_tx) = cancel(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &token.owner.pubkey(),
+        &sell_accounts.seller_trade_state,
+        ONE_SOL,
+        1,
+        &stranger,
+    );
+    let tx_error = context
+        .banks_client
+        .process_transaction(cancel_tx)
+        .await
+        .unwrap_err();
+
+    assert_error(tx_error, AuctionHouseError::NoValidSignerPresent.into());
+}
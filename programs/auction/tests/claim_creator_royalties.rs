@@ -0,0 +1,332 @@
+use anchor_client::solana_sdk::transaction::Transaction;
+use anchor_client::solana_sdk::{signature::Keypair, signer::Signer};
+use anchor_lang::error::ErrorCode;
+use anchor_lang::prelude::{AccountMeta, Pubkey};
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas};
+use auction_house::{
+    constants::CREATOR_CLAIM_SIZE, errors::AuctionHouseError, pda::*, AuctionHouse, CreatorClaim,
+};
+use nft_minter::utils::Creator;
+use solana_program_test::{tokio, ProgramTestContext};
+
+mod utils;
+use utils::setup_functions::*;
+
+// 0.1% of the price, below the rent exemption of an empty wallet
+const SALE_PRICE: u64 = ONE_SOL / 2;
+const ROYALTY: u64 = SALE_PRICE / 1000;
+
+// Lists and bids on an NFT whose only creator is a brand-new wallet, so its royalty can not be
+// paid directly
+async fn setup_unpayable_creator_sale(
+    context: &mut ProgramTestContext,
+) -> (
+    Pubkey,
+    AuctionHouse,
+    NFT,
+    auction_house::accounts::Sell,
+    auction_house::accounts::Buy,
+    Keypair,
+    Keypair,
+) {
+    let (_, auction_house, auction_house_data) = create_auction_house(context, 100, false)
+        .await
+        .expect("Failed to create Auction House");
+    airdrop(
+        context,
+        &auction_house_data.auction_house_fee_account,
+        ONE_SOL,
+    )
+    .await
+    .unwrap();
+
+    let creator = Keypair::new();
+    let token = create_nft(
+        context,
+        Some(vec![Creator {
+            address: creator.pubkey(),
+            verified: false,
+            share: 100,
+        }]),
+    )
+    .await
+    .expect("Failed to create NFT");
+
+    let (sell_accounts, sell_tx) = sell(
+        context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        SALE_PRICE,
+        1,
+        &token.owner,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    let buyer = Keypair::new();
+    airdrop(context, &buyer.pubkey(), 10 * ONE_SOL)
+        .await
+        .unwrap();
+    let (buy_accounts, buy_tx) = buy(
+        context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer,
+        SALE_PRICE,
+        1,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    (
+        auction_house,
+        auction_house_data,
+        token,
+        sell_accounts,
+        buy_accounts,
+        buyer,
+        creator,
+    )
+}
+
+fn claim_creator_royalties(
+    context: &mut ProgramTestContext,
+    auction_house: &Pubkey,
+    auction_house_data: &AuctionHouse,
+    creator: &Keypair,
+    signer_payer: &Keypair,
+) -> Transaction {
+    let data = auction_house::instruction::ClaimCreatorRoyalties {};
+
+    let accounts = auction_house::accounts::ClaimCreatorRoyalties {
+        creator: creator.pubkey(),
+        creator_claim: find_creator_claim_address(auction_house, &creator.pubkey()).0,
+        auction_house: *auction_house,
+        auction_house_fee_account: auction_house_data.auction_house_fee_account,
+    };
+
+    let ix = Instruction {
+        program_id: auction_house::id(),
+        data: data.data(),
+        accounts: accounts.to_account_metas(None),
+    };
+
+    Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&signer_payer.pubkey()),
+        &[signer_payer, creator],
+        context.last_blockhash,
+    )
+}
+
+#[tokio::test]
+async fn claim_creator_royalties_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (auction_house, auction_house_data, token, sell_accounts, buy_accounts, buyer, creator) =
+        setup_unpayable_creator_sale(&mut context).await;
+    let (creator_claim, _) = find_creator_claim_address(&auction_house, &creator.pubkey());
+
+    let claim_rent = context
+        .banks_client
+        .get_rent()
+        .await
+        .unwrap()
+        .minimum_balance(CREATOR_CLAIM_SIZE);
+    let fee_account_before = context
+        .banks_client
+        .get_balance(auction_house_data.auction_house_fee_account)
+        .await
+        .unwrap();
+
+    let (_, execute_sale_tx) = execute_sale(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &sell_accounts,
+        &buy_accounts,
+        SALE_PRICE,
+        1,
+        None,
+        None,
+        vec![
+            AccountMeta::new(creator.pubkey(), false),
+            AccountMeta::new(creator_claim, false),
+        ],
+        &buyer,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(execute_sale_tx)
+        .await
+        .unwrap();
+
+    // The royalty is held in the claim, whose rent came out of the house fee account
+    let creator_claim_account = context
+        .banks_client
+        .get_account(creator_claim)
+        .await
+        .unwrap()
+        .unwrap();
+    let creator_claim_data =
+        CreatorClaim::try_deserialize(&mut creator_claim_account.data.as_ref()).unwrap();
+    assert_eq!(creator_claim_data.amount, ROYALTY);
+    assert_eq!(creator_claim_account.lamports, claim_rent + ROYALTY);
+    assert_eq!(
+        context
+            .banks_client
+            .get_balance(auction_house_data.auction_house_fee_account)
+            .await
+            .unwrap(),
+        fee_account_before - claim_rent
+    );
+
+    // The creator has to be rent exempt on its own to receive less than the rent exemption
+    airdrop(&mut context, &creator.pubkey(), ONE_SOL)
+        .await
+        .unwrap();
+    let payer = Keypair::new();
+    airdrop(&mut context, &payer.pubkey(), ONE_SOL)
+        .await
+        .unwrap();
+
+    let claim_tx = claim_creator_royalties(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &creator,
+        &payer,
+    );
+    context
+        .banks_client
+        .process_transaction(claim_tx)
+        .await
+        .unwrap();
+
+    // The creator gets the royalty alone, the rent goes back to the house
+    assert_eq!(
+        context
+            .banks_client
+            .get_balance(creator.pubkey())
+            .await
+            .unwrap(),
+        ONE_SOL + ROYALTY
+    );
+    assert_eq!(
+        context
+            .banks_client
+            .get_balance(auction_house_data.auction_house_fee_account)
+            .await
+            .unwrap(),
+        fee_account_before
+    );
+    assert!(context
+        .banks_client
+        .get_account(creator_claim)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn failure_execute_sale_missing_creator_claim() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (auction_house, auction_house_data, token, sell_accounts, buy_accounts, buyer, creator) =
+        setup_unpayable_creator_sale(&mut context).await;
+
+    let (_, execute_sale_tx) = execute_sale(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &sell_accounts,
+        &buy_accounts,
+        SALE_PRICE,
+        1,
+        None,
+        None,
+        vec![AccountMeta::new(creator.pubkey(), false)],
+        &buyer,
+    )
+    .await;
+    let tx_error = context
+        .banks_client
+        .process_transaction(execute_sale_tx)
+        .await
+        .unwrap_err();
+
+    assert_error(tx_error, AuctionHouseError::MissingCreatorClaim.into());
+}
+
+#[tokio::test]
+async fn failure_claim_creator_royalties_other_creator() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (auction_house, auction_house_data, token, sell_accounts, buy_accounts, buyer, creator) =
+        setup_unpayable_creator_sale(&mut context).await;
+    let (creator_claim, _) = find_creator_claim_address(&auction_house, &creator.pubkey());
+
+    let (_, execute_sale_tx) = execute_sale(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &sell_accounts,
+        &buy_accounts,
+        SALE_PRICE,
+        1,
+        None,
+        None,
+        vec![
+            AccountMeta::new(creator.pubkey(), false),
+            AccountMeta::new(creator_claim, false),
+        ],
+        &buyer,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(execute_sale_tx)
+        .await
+        .unwrap();
+
+    // Someone else signs for the creator's claim
+    let impostor = Keypair::new();
+    airdrop(&mut context, &impostor.pubkey(), ONE_SOL)
+        .await
+        .unwrap();
+    let accounts = auction_house::accounts::ClaimCreatorRoyalties {
+        creator: impostor.pubkey(),
+        creator_claim,
+        auction_house,
+        auction_house_fee_account: auction_house_data.auction_house_fee_account,
+    };
+    let ix = Instruction {
+        program_id: auction_house::id(),
+        data: auction_house::instruction::ClaimCreatorRoyalties {}.data(),
+        accounts: accounts.to_account_metas(None),
+    };
+    let claim_tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&impostor.pubkey()),
+        &[&impostor],
+        context.last_blockhash,
+    );
+    let tx_error = context
+        .banks_client
+        .process_transaction(claim_tx)
+        .await
+        .unwrap_err();
+
+    assert_error(tx_error, ErrorCode::ConstraintSeeds.into());
+}
@@ -0,0 +1,232 @@
+use anchor_client::solana_sdk::{account::Account, signature::Keypair, signer::Signer};
+use auction_house::{constants::TRADE_STATE_SIZE, errors::AuctionHouseError, pda::*};
+use solana_program_test::tokio;
+
+mod utils;
+use utils::setup_functions::*;
+
+#[tokio::test]
+async fn close_escrow_account_spl_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+
+    let treasury_mint = create_spl_mint(&mut context).await.unwrap();
+    let (_, auction_house, auction_house_data) =
+        create_auction_house_with_treasury_mint(&mut context, &treasury_mint.pubkey(), 100, false)
+            .await
+            .expect("Failed to create Auction House");
+
+    let token = create_nft(&mut context, None)
+        .await
+        .expect("Failed to create NFT");
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), ONE_SOL)
+        .await
+        .unwrap();
+    let payment_account = mint_tokens(&mut context, &treasury_mint.pubkey(), &buyer.pubkey(), 1000)
+        .await
+        .unwrap();
+
+    // The bid moves the price into the escrow token account
+
+    let (buy_accounts, buy_tx) = buy(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer,
+        400,
+        1,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+    assert_eq!(
+        get_token_balance(&mut context, &buy_accounts.escrow_payment_account).await,
+        400
+    );
+
+    let (_, cancel_tx) = cancel(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer.pubkey(),
+        &buy_accounts.buyer_trade_state,
+        400,
+        1,
+        &buyer,
+    );
+    context
+        .banks_client
+        .process_transaction(cancel_tx)
+        .await
+        .unwrap();
+
+    // Close
+
+    let (_, close_escrow_account_tx) =
+        close_escrow_account(&mut context, &auction_house, &auction_house_data, &buyer).await;
+    context
+        .banks_client
+        .process_transaction(close_escrow_account_tx)
+        .await
+        .unwrap();
+
+    let escrow_payment_account = context
+        .banks_client
+        .get_account(buy_accounts.escrow_payment_account)
+        .await
+        .unwrap();
+    assert!(escrow_payment_account.is_none());
+    let escrow_ledger = context
+        .banks_client
+        .get_account(buy_accounts.escrow_ledger)
+        .await
+        .unwrap();
+    assert!(escrow_ledger.is_none());
+    assert_eq!(
+        get_token_balance(&mut context, &payment_account).await,
+        1000
+    );
+}
+
+#[tokio::test]
+async fn failure_close_escrow_account_spl_open_bid() {
+    let mut context = auction_house_program_test().start_with_context().await;
+
+    let treasury_mint = create_spl_mint(&mut context).await.unwrap();
+    let (_, auction_house, auction_house_data) =
+        create_auction_house_with_treasury_mint(&mut context, &treasury_mint.pubkey(), 100, false)
+            .await
+            .expect("Failed to create Auction House");
+
+    let token = create_nft(&mut context, None)
+        .await
+        .expect("Failed to create NFT");
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), ONE_SOL)
+        .await
+        .unwrap();
+    mint_tokens(&mut context, &treasury_mint.pubkey(), &buyer.pubkey(), 1000)
+        .await
+        .unwrap();
+
+    let (_, buy_tx) = buy(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer,
+        400,
+        1,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    let (_, close_escrow_account_tx) =
+        close_escrow_account(&mut context, &auction_house, &auction_house_data, &buyer).await;
+    let tx_error = context
+        .banks_client
+        .process_transaction(close_escrow_account_tx)
+        .await
+        .unwrap_err();
+
+    assert_error(tx_error, AuctionHouseError::EscrowHasOpenBids.into());
+}
+
+#[tokio::test]
+async fn failure_close_escrow_account_after_cancelling_uncounted_bid() {
+    let mut context = auction_house_program_test().start_with_context().await;
+
+    let (_, auction_house, auction_house_data) = create_auction_house(&mut context, 100, false)
+        .await
+        .expect("Failed to create Auction House");
+
+    let legacy_token = create_nft(&mut context, None)
+        .await
+        .expect("Failed to create NFT");
+    let token = create_nft(&mut context, None)
+        .await
+        .expect("Failed to create NFT");
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), 10 * ONE_SOL)
+        .await
+        .unwrap();
+
+    // A bid placed before the wallet had an escrow ledger, its trade state only holds the bump
+    let (legacy_trade_state, legacy_trade_state_bump) = find_trade_state_address(
+        &buyer.pubkey(),
+        &auction_house,
+        &legacy_token.ata,
+        &auction_house_data.treasury_mint,
+        &legacy_token.mint.pubkey(),
+        ONE_SOL,
+        1,
+    );
+    let rent = context.banks_client.get_rent().await.unwrap();
+    context.set_account(
+        &legacy_trade_state,
+        &Account {
+            lamports: rent.minimum_balance(TRADE_STATE_SIZE),
+            data: vec![legacy_trade_state_bump],
+            owner: auction_house::id(),
+            executable: false,
+            rent_epoch: 0,
+        }
+        .into(),
+    );
+
+    let (_, buy_tx) = buy(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer,
+        ONE_SOL,
+        1,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    // Cancelling the uncounted bid leaves the count of the bid placed since untouched
+    let (_, cancel_tx) = cancel(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &legacy_token,
+        &buyer.pubkey(),
+        &legacy_trade_state,
+        ONE_SOL,
+        1,
+        &buyer,
+    );
+    context
+        .banks_client
+        .process_transaction(cancel_tx)
+        .await
+        .unwrap();
+
+    let (_, close_escrow_account_tx) =
+        close_escrow_account(&mut context, &auction_house, &auction_house_data, &buyer).await;
+    let tx_error = context
+        .banks_client
+        .process_transaction(close_escrow_account_tx)
+        .await
+        .unwrap_err();
+
+    assert_error(tx_error, AuctionHouseError::EscrowHasOpenBids.into());
+}
@@ -1,11 +1,20 @@
 use anchor_client::solana_sdk::{signature::Keypair, signer::Signer, transaction::Transaction};
 use anchor_lang::{
+    prelude::Pubkey,
     solana_program::{instruction::Instruction, system_program, sysvar},
     AccountDeserialize, InstructionData, ToAccountMetas,
 };
 use anchor_spl::associated_token;
-use auction_house::{pda::*, AuctionHouse};
-use solana_program_test::tokio;
+use auction_house::{errors::AuctionHouseError, pda::*, AuctionHouse};
+use solana_program_test::{tokio, ProgramTestContext};
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+use spl_token_2022::{
+    extension::{
+        transfer_fee::TransferFeeAmount, BaseStateWithExtensions, ExtensionType,
+        StateWithExtensions,
+    },
+    state::Account as TokenAccount,
+};
 
 mod utils;
 use utils::setup_functions::*;
@@ -193,3 +202,115 @@ async fn failure_create_auction_house_reinitialization() {
 
     assert_error(tx_error, ERR_AUCTION_HOUSE_ALREADY_INITIALIZED);
 }
+
+fn create_token_2022_auction_house_tx(
+    context: &ProgramTestContext,
+    authority: &Keypair,
+    treasury_mint: &Pubkey,
+) -> (Pubkey, Transaction) {
+    let (auction_house, auction_house_bump) =
+        find_auction_house_address(&authority.pubkey(), treasury_mint);
+    let (auction_house_fee_account, auction_house_fee_account_bump) =
+        find_auction_house_fee_account_address(&auction_house);
+    let (auction_house_treasury, auction_house_treasury_bump) =
+        find_auction_house_treasury_address(&auction_house);
+    let treasury_withdrawal_destination = get_associated_token_address_with_program_id(
+        &authority.pubkey(),
+        treasury_mint,
+        &spl_token_2022::id(),
+    );
+
+    let data = auction_house::instruction::CreateAuctionHouse {
+        _bump: auction_house_bump,
+        fee_payer_bump: auction_house_fee_account_bump,
+        treasury_bump: auction_house_treasury_bump,
+        seller_fee_basis_points: 10,
+        can_change_sale_price: false,
+    };
+
+    let accounts = auction_house::accounts::CreateAuctionHouse {
+        treasury_mint: *treasury_mint,
+        payer: authority.pubkey(),
+        authority: authority.pubkey(),
+        fee_withdrawal_destination: authority.pubkey(),
+        treasury_withdrawal_destination,
+        treasury_withdrawal_destination_owner: authority.pubkey(),
+        auction_house,
+        auction_house_fee_account,
+        auction_house_treasury,
+        token_program: spl_token_2022::id(),
+        system_program: system_program::id(),
+        associated_token_program: associated_token::ID,
+        rent: sysvar::rent::id(),
+    };
+
+    let ix = Instruction {
+        program_id: auction_house::id(),
+        data: data.data(),
+        accounts: accounts.to_account_metas(None),
+    };
+
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&authority.pubkey()),
+        &[authority],
+        context.last_blockhash,
+    );
+
+    (auction_house_treasury, tx)
+}
+
+#[tokio::test]
+async fn create_auction_house_token_2022_treasury_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+
+    let authority = Keypair::new();
+    airdrop(&mut context, &authority.pubkey(), 10 * ONE_SOL)
+        .await
+        .unwrap();
+
+    let treasury_mint = create_token_2022_mint(&mut context, &[ExtensionType::TransferFeeConfig])
+        .await
+        .unwrap();
+
+    let (auction_house_treasury, tx) =
+        create_token_2022_auction_house_tx(&context, &authority, &treasury_mint.pubkey());
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    // The treasury is sized for the transfer fee the mint withholds on its accounts
+    let auction_house_treasury_account = context
+        .banks_client
+        .get_account(auction_house_treasury)
+        .await
+        .unwrap()
+        .expect("Auction House treasury account not found");
+    assert_eq!(auction_house_treasury_account.owner, spl_token_2022::id());
+
+    let treasury =
+        StateWithExtensions::<TokenAccount>::unpack(&auction_house_treasury_account.data).unwrap();
+    assert_eq!(treasury.base.mint, treasury_mint.pubkey());
+    assert!(treasury.get_extension::<TransferFeeAmount>().is_ok());
+}
+
+#[tokio::test]
+async fn failure_create_auction_house_non_transferable_treasury() {
+    let mut context = auction_house_program_test().start_with_context().await;
+
+    let authority = Keypair::new();
+    airdrop(&mut context, &authority.pubkey(), 10 * ONE_SOL)
+        .await
+        .unwrap();
+
+    let treasury_mint = create_token_2022_mint(&mut context, &[ExtensionType::NonTransferable])
+        .await
+        .unwrap();
+
+    let (_, tx) = create_token_2022_auction_house_tx(&context, &authority, &treasury_mint.pubkey());
+    let tx_error = context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err();
+
+    assert_error(tx_error, AuctionHouseError::NonTransferableMint.into());
+}
@@ -0,0 +1,270 @@
+use anchor_client::solana_sdk::transaction::Transaction;
+use anchor_client::solana_sdk::{signature::Keypair, signer::Signer};
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::{system_program, sysvar};
+use anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas};
+use auction_house::{
+    errors::AuctionHouseError, events::EscrowDeposited, pda::*, AuctionHouse, EscrowLedger,
+};
+use solana_program_test::{tokio, ProgramTestContext};
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+use spl_token_2022::extension::ExtensionType;
+
+mod utils;
+use utils::setup_functions::*;
+
+const DEPOSIT_AMOUNT: u64 = 1_000_000;
+
+// Auction house with a Token-2022 treasury mint withholding 1% of every transfer, delegated
+// to an auctioneer authority held by the test
+async fn setup_token_2022_auctioneer(
+    context: &mut ProgramTestContext,
+) -> (Pubkey, AuctionHouse, Keypair, Keypair) {
+    let treasury_mint = create_token_2022_mint(context, &[ExtensionType::TransferFeeConfig])
+        .await
+        .unwrap();
+    let (authority, auction_house, _) =
+        create_auction_house_with_treasury_mint(context, &treasury_mint.pubkey(), 100, false)
+            .await
+            .expect("Failed to create Auction House");
+
+    let auctioneer_authority = Keypair::new();
+    let (auctioneer, _) = find_auctioneer_address(&auction_house, &auctioneer_authority.pubkey());
+    let delegate_tx = delegate(
+        context,
+        &auction_house,
+        &authority,
+        &auctioneer_authority.pubkey(),
+        &auctioneer,
+    );
+    context
+        .banks_client
+        .process_transaction(delegate_tx)
+        .await
+        .unwrap();
+    let auction_house_data = get_auction_house(context, &auction_house).await;
+
+    let wallet = Keypair::new();
+    airdrop(context, &wallet.pubkey(), 10 * ONE_SOL)
+        .await
+        .unwrap();
+    mint_tokens(
+        context,
+        &treasury_mint.pubkey(),
+        &wallet.pubkey(),
+        DEPOSIT_AMOUNT,
+    )
+    .await
+    .unwrap();
+
+    (
+        auction_house,
+        auction_house_data,
+        auctioneer_authority,
+        wallet,
+    )
+}
+
+fn auctioneer_deposit(
+    context: &mut ProgramTestContext,
+    auction_house: &Pubkey,
+    auction_house_data: &AuctionHouse,
+    auctioneer_authority: &Keypair,
+    wallet: &Keypair,
+    amount: u64,
+) -> Transaction {
+    let (escrow_payment_account, escrow_payment_bump) =
+        find_escrow_payment_account_address(auction_house, &wallet.pubkey());
+
+    let data = auction_house::instruction::AuctioneerDeposit {
+        escrow_payment_bump,
+        amount,
+    };
+
+    let accounts = auction_house::accounts::AuctioneerDeposit {
+        wallet: wallet.pubkey(),
+        payment_account: get_associated_token_address_with_program_id(
+            &wallet.pubkey(),
+            &auction_house_data.treasury_mint,
+            &spl_token_2022::id(),
+        ),
+        transfer_authority: wallet.pubkey(),
+        escrow_payment_account,
+        treasury_mint: auction_house_data.treasury_mint,
+        authority: auction_house_data.authority,
+        auctioneer_authority: auctioneer_authority.pubkey(),
+        auction_house: *auction_house,
+        auction_house_fee_account: auction_house_data.auction_house_fee_account,
+        auctioneer: find_auctioneer_address(auction_house, &auctioneer_authority.pubkey()).0,
+        token_program: spl_token_2022::id(),
+        system_program: system_program::id(),
+        rent: sysvar::rent::id(),
+    };
+
+    let ix = Instruction {
+        program_id: auction_house::id(),
+        data: data.data(),
+        accounts: accounts.to_account_metas(None),
+    };
+
+    Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&wallet.pubkey()),
+        &[wallet, auctioneer_authority],
+        context.last_blockhash,
+    )
+}
+
+fn auctioneer_lock_escrow(
+    context: &mut ProgramTestContext,
+    auction_house: &Pubkey,
+    auction_house_data: &AuctionHouse,
+    auctioneer_authority: &Keypair,
+    wallet: &Keypair,
+    amount: u64,
+) -> Transaction {
+    let data = auction_house::instruction::AuctioneerLockEscrow { amount };
+
+    let accounts = auction_house::accounts::AuctioneerLockEscrow {
+        wallet: wallet.pubkey(),
+        escrow_payment_account: find_escrow_payment_account_address(
+            auction_house,
+            &wallet.pubkey(),
+        )
+        .0,
+        escrow_ledger: find_escrow_ledger_address(auction_house, &wallet.pubkey()).0,
+        treasury_mint: auction_house_data.treasury_mint,
+        auctioneer_authority: auctioneer_authority.pubkey(),
+        auction_house: *auction_house,
+        auctioneer: find_auctioneer_address(auction_house, &auctioneer_authority.pubkey()).0,
+        system_program: system_program::id(),
+        rent: sysvar::rent::id(),
+    };
+
+    let ix = Instruction {
+        program_id: auction_house::id(),
+        data: data.data(),
+        accounts: accounts.to_account_metas(None),
+    };
+
+    Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&wallet.pubkey()),
+        &[wallet, auctioneer_authority],
+        context.last_blockhash,
+    )
+}
+
+#[tokio::test]
+async fn auctioneer_deposit_token_2022_reports_received_amount_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (auction_house, auction_house_data, auctioneer_authority, wallet) =
+        setup_token_2022_auctioneer(&mut context).await;
+
+    let deposit_tx = auctioneer_deposit(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &auctioneer_authority,
+        &wallet,
+        DEPOSIT_AMOUNT,
+    );
+    let events: Vec<EscrowDeposited> = process_transaction_events(&mut context, deposit_tx).await;
+
+    // The escrow is credited what arrived, not what was sent
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].amount, 990_000);
+
+    let (escrow_payment_account, _) =
+        find_escrow_payment_account_address(&auction_house, &wallet.pubkey());
+    assert_eq!(
+        get_token_balance(&mut context, &escrow_payment_account).await,
+        990_000
+    );
+}
+
+#[tokio::test]
+async fn auctioneer_lock_escrow_token_2022_within_transfer_fee_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (auction_house, auction_house_data, auctioneer_authority, wallet) =
+        setup_token_2022_auctioneer(&mut context).await;
+
+    let deposit_tx = auctioneer_deposit(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &auctioneer_authority,
+        &wallet,
+        DEPOSIT_AMOUNT,
+    );
+    context
+        .banks_client
+        .process_transaction(deposit_tx)
+        .await
+        .unwrap();
+
+    // The escrow holds 990_000, short of the bid by exactly the fee withheld on the way in
+    let lock_tx = auctioneer_lock_escrow(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &auctioneer_authority,
+        &wallet,
+        DEPOSIT_AMOUNT,
+    );
+    context
+        .banks_client
+        .process_transaction(lock_tx)
+        .await
+        .unwrap();
+
+    let (escrow_ledger, _) = find_escrow_ledger_address(&auction_house, &wallet.pubkey());
+    let escrow_ledger_account = context
+        .banks_client
+        .get_account(escrow_ledger)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    let escrow_ledger_data =
+        EscrowLedger::try_deserialize(&mut escrow_ledger_account.as_ref()).unwrap();
+    assert_eq!(escrow_ledger_data.locked_amount, DEPOSIT_AMOUNT);
+}
+
+#[tokio::test]
+async fn failure_auctioneer_lock_escrow_token_2022_beyond_transfer_fee() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (auction_house, auction_house_data, auctioneer_authority, wallet) =
+        setup_token_2022_auctioneer(&mut context).await;
+
+    let deposit_tx = auctioneer_deposit(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &auctioneer_authority,
+        &wallet,
+        DEPOSIT_AMOUNT,
+    );
+    context
+        .banks_client
+        .process_transaction(deposit_tx)
+        .await
+        .unwrap();
+
+    let lock_tx = auctioneer_lock_escrow(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &auctioneer_authority,
+        &wallet,
+        DEPOSIT_AMOUNT + DEPOSIT_AMOUNT / 10,
+    );
+    let tx_error = context
+        .banks_client
+        .process_transaction(lock_tx)
+        .await
+        .unwrap_err();
+
+    assert_error(tx_error, AuctionHouseError::InsufficientFunds.into());
+}
@@ -0,0 +1,1303 @@
+use anchor_client::solana_sdk::transaction::Transaction;
+use anchor_client::solana_sdk::{signature::Keypair, signer::Signer};
+use anchor_lang::prelude::{AccountMeta, Pubkey};
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::{InstructionData, ToAccountMetas};
+use anchor_spl::token::spl_token;
+use auction_house::{
+    errors::AuctionHouseError, events::SaleExecuted, pda::*, AuctionHouse, RoyaltyPolicy,
+};
+use mpl_token_metadata::instruction::sign_metadata;
+use nft_minter::utils::{token_metadata_program_id, Creator};
+use solana_program_test::{tokio, ProgramTestContext};
+use spl_token_2022::extension::{ExtensionType, StateWithExtensionsMut};
+
+mod utils;
+use utils::setup_functions::*;
+
+#[tokio::test]
+async fn execute_sale_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+
+    let (_, auction_house, auction_house_data) = create_auction_house(&mut context, 100, false)
+        .await
+        .expect("Failed to create Auction House");
+
+    let token = create_nft(&mut context, None)
+        .await
+        .expect("Failed to create NFT");
+
+    // Sell
+
+    let (sell_accounts, sell_tx) = sell(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        ONE_SOL,
+        1,
+        &token.owner,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    // Buy
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), 10 * ONE_SOL)
+        .await
+        .unwrap();
+
+    let (buy_accounts, buy_tx) = buy(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer,
+        ONE_SOL,
+        1,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    // Execute sale
+
+    let seller_before = context
+        .banks_client
+        .get_balance(token.owner.pubkey())
+        .await
+        .unwrap();
+    // The seller paid for the trade state and gets its rent back along with the proceeds
+    let seller_trade_state_rent = context
+        .banks_client
+        .get_balance(sell_accounts.seller_trade_state)
+        .await
+        .unwrap();
+    let free_trade_state_rent = context
+        .banks_client
+        .get_balance(sell_accounts.free_seller_trade_state)
+        .await
+        .unwrap();
+    let treasury_before = context
+        .banks_client
+        .get_balance(auction_house_data.auction_house_treasury)
+        .await
+        .unwrap();
+
+    let (execute_sale_accounts, execute_sale_tx) = execute_sale(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &sell_accounts,
+        &buy_accounts,
+        ONE_SOL,
+        1,
+        None,
+        None,
+        vec![],
+        &buyer,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(execute_sale_tx)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        get_token_balance(
+            &mut context,
+            &execute_sale_accounts.buyer_receipt_token_account
+        )
+        .await,
+        1
+    );
+    assert_eq!(get_token_balance(&mut context, &token.ata).await, 0);
+
+    let seller_after = context
+        .banks_client
+        .get_balance(token.owner.pubkey())
+        .await
+        .unwrap();
+    let treasury_after = context
+        .banks_client
+        .get_balance(auction_house_data.auction_house_treasury)
+        .await
+        .unwrap();
+    assert_eq!(
+        seller_after - seller_before,
+        ONE_SOL - ONE_SOL / 100 + seller_trade_state_rent + free_trade_state_rent
+    );
+    assert_eq!(treasury_after - treasury_before, ONE_SOL / 100);
+
+    let seller_trade_state = context
+        .banks_client
+        .get_account(sell_accounts.seller_trade_state)
+        .await
+        .unwrap();
+    assert!(seller_trade_state.is_none());
+    let buyer_trade_state = context
+        .banks_client
+        .get_account(buy_accounts.buyer_trade_state)
+        .await
+        .unwrap();
+    assert!(buyer_trade_state.is_none());
+}
+
+#[tokio::test]
+async fn execute_sale_free_sale_seller_signoff_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+
+    let (_, auction_house, auction_house_data) = create_auction_house(&mut context, 100, false)
+        .await
+        .expect("Failed to create Auction House");
+
+    let token = create_nft(&mut context, None)
+        .await
+        .expect("Failed to create NFT");
+
+    let (sell_accounts, sell_tx) = sell(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        0,
+        1,
+        &token.owner,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), ONE_SOL)
+        .await
+        .unwrap();
+
+    let (buy_accounts, buy_tx) = buy(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer,
+        0,
+        1,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    // The buyer alone can not take the token for free
+
+    let (_, execute_sale_tx) = execute_sale(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &sell_accounts,
+        &buy_accounts,
+        0,
+        1,
+        None,
+        None,
+        vec![],
+        &buyer,
+    )
+    .await;
+    let tx_error = context
+        .banks_client
+        .process_transaction(execute_sale_tx)
+        .await
+        .unwrap_err();
+
+    assert_error(
+        tx_error,
+        AuctionHouseError::CannotMatchFreeSalesWithoutAuctionHouseOrSellerSignoff.into(),
+    );
+
+    // The seller signs off on giving it away
+
+    let (execute_sale_accounts, execute_sale_tx) = execute_sale(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &sell_accounts,
+        &buy_accounts,
+        0,
+        1,
+        None,
+        None,
+        vec![],
+        &token.owner,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(execute_sale_tx)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        get_token_balance(
+            &mut context,
+            &execute_sale_accounts.buyer_receipt_token_account
+        )
+        .await,
+        1
+    );
+}
+
+#[tokio::test]
+async fn execute_sale_partial_order_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+
+    let (_, auction_house, auction_house_data) = create_auction_house(&mut context, 100, false)
+        .await
+        .expect("Failed to create Auction House");
+
+    let token = create_sft(&mut context, 3)
+        .await
+        .expect("Failed to create SFT");
+
+    // Sell three units for three SOL
+
+    let (sell_accounts, sell_tx) = sell(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        3 * ONE_SOL,
+        3,
+        &token.owner,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    // The first buyer takes one unit, the rest stays listed
+
+    let buyer1 = Keypair::new();
+    airdrop(&mut context, &buyer1.pubkey(), 10 * ONE_SOL)
+        .await
+        .unwrap();
+    let (buy_accounts, buy_tx) = buy(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer1,
+        ONE_SOL,
+        1,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    let (execute_sale_accounts, execute_sale_tx) = execute_sale(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &sell_accounts,
+        &buy_accounts,
+        3 * ONE_SOL,
+        3,
+        Some(1),
+        Some(ONE_SOL),
+        vec![],
+        &buyer1,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(execute_sale_tx)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        get_token_balance(
+            &mut context,
+            &execute_sale_accounts.buyer_receipt_token_account
+        )
+        .await,
+        1
+    );
+    assert_eq!(get_token_balance(&mut context, &token.ata).await, 2);
+    let seller_trade_state = context
+        .banks_client
+        .get_account(sell_accounts.seller_trade_state)
+        .await
+        .unwrap();
+    assert!(seller_trade_state.is_some());
+
+    // The second buyer takes the remaining two units and closes the listing
+
+    let buyer2 = Keypair::new();
+    airdrop(&mut context, &buyer2.pubkey(), 10 * ONE_SOL)
+        .await
+        .unwrap();
+    let (buy_accounts, buy_tx) = buy(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer2,
+        2 * ONE_SOL,
+        2,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    let (execute_sale_accounts, execute_sale_tx) = execute_sale(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &sell_accounts,
+        &buy_accounts,
+        3 * ONE_SOL,
+        3,
+        Some(2),
+        Some(2 * ONE_SOL),
+        vec![],
+        &buyer2,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(execute_sale_tx)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        get_token_balance(
+            &mut context,
+            &execute_sale_accounts.buyer_receipt_token_account
+        )
+        .await,
+        2
+    );
+    assert_eq!(get_token_balance(&mut context, &token.ata).await, 0);
+    let seller_trade_state = context
+        .banks_client
+        .get_account(sell_accounts.seller_trade_state)
+        .await
+        .unwrap();
+    assert!(seller_trade_state.is_none());
+}
+
+// Lists three units for three SOL and places a one unit bid for one SOL
+async fn setup_partial_order(
+    context: &mut ProgramTestContext,
+) -> (
+    Pubkey,
+    AuctionHouse,
+    NFT,
+    auction_house::accounts::Sell,
+    auction_house::accounts::Buy,
+    Keypair,
+) {
+    let (_, auction_house, auction_house_data) = create_auction_house(context, 100, false)
+        .await
+        .expect("Failed to create Auction House");
+
+    let token = create_sft(context, 3).await.expect("Failed to create SFT");
+
+    let (sell_accounts, sell_tx) = sell(
+        context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        3 * ONE_SOL,
+        3,
+        &token.owner,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    let buyer = Keypair::new();
+    airdrop(context, &buyer.pubkey(), 10 * ONE_SOL)
+        .await
+        .unwrap();
+    let (buy_accounts, buy_tx) = buy(
+        context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer,
+        ONE_SOL,
+        1,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    (
+        auction_house,
+        auction_house_data,
+        token,
+        sell_accounts,
+        buy_accounts,
+        buyer,
+    )
+}
+
+#[tokio::test]
+async fn failure_execute_sale_partial_price_mismatch() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (auction_house, auction_house_data, token, sell_accounts, buy_accounts, buyer) =
+        setup_partial_order(&mut context).await;
+
+    // One unit of the listing costs one SOL, not half of it
+    let (_, execute_sale_tx) = execute_sale(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &sell_accounts,
+        &buy_accounts,
+        3 * ONE_SOL,
+        3,
+        Some(1),
+        Some(ONE_SOL / 2),
+        vec![],
+        &buyer,
+    )
+    .await;
+    let tx_error = context
+        .banks_client
+        .process_transaction(execute_sale_tx)
+        .await
+        .unwrap_err();
+
+    assert_error(tx_error, AuctionHouseError::PartialPriceMismatch.into());
+}
+
+#[tokio::test]
+async fn failure_execute_sale_missing_element_for_partial_order() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (auction_house, auction_house_data, token, sell_accounts, buy_accounts, buyer) =
+        setup_partial_order(&mut context).await;
+
+    let (_, execute_sale_tx) = execute_sale(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &sell_accounts,
+        &buy_accounts,
+        3 * ONE_SOL,
+        3,
+        Some(1),
+        None,
+        vec![],
+        &buyer,
+    )
+    .await;
+    let tx_error = context
+        .banks_client
+        .process_transaction(execute_sale_tx)
+        .await
+        .unwrap_err();
+
+    assert_error(
+        tx_error,
+        AuctionHouseError::MissingElementForPartialOrder.into(),
+    );
+}
+
+#[tokio::test]
+async fn failure_execute_sale_partial_order_above_listing_size() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (auction_house, auction_house_data, token, sell_accounts, buy_accounts, buyer) =
+        setup_partial_order(&mut context).await;
+
+    let (_, execute_sale_tx) = execute_sale(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &sell_accounts,
+        &buy_accounts,
+        3 * ONE_SOL,
+        3,
+        Some(4),
+        Some(4 * ONE_SOL),
+        vec![],
+        &buyer,
+    )
+    .await;
+    let tx_error = context
+        .banks_client
+        .process_transaction(execute_sale_tx)
+        .await
+        .unwrap_err();
+
+    assert_error(
+        tx_error,
+        AuctionHouseError::NotEnoughTokensAvailableForPurchase.into(),
+    );
+}
+
+const TOKEN_2022_PRICE: u64 = 1_000_000;
+
+// Auction house whose treasury mint is a Token-2022 mint withholding 1% of every transfer,
+// while the sold NFT stays under the legacy token program
+async fn setup_token_2022_treasury_sale(
+    context: &mut ProgramTestContext,
+) -> (
+    Pubkey,
+    AuctionHouse,
+    NFT,
+    auction_house::accounts::Sell,
+    auction_house::accounts::Buy,
+    Keypair,
+) {
+    let treasury_mint = create_token_2022_mint(context, &[ExtensionType::TransferFeeConfig])
+        .await
+        .unwrap();
+    let (_, auction_house, auction_house_data) =
+        create_auction_house_with_treasury_mint(context, &treasury_mint.pubkey(), 100, false)
+            .await
+            .expect("Failed to create Auction House");
+
+    let token = create_nft(context, None)
+        .await
+        .expect("Failed to create NFT");
+
+    let (sell_accounts, sell_tx) = sell(
+        context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        TOKEN_2022_PRICE,
+        1,
+        &token.owner,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    let buyer = Keypair::new();
+    airdrop(context, &buyer.pubkey(), 10 * ONE_SOL)
+        .await
+        .unwrap();
+    mint_tokens(
+        context,
+        &treasury_mint.pubkey(),
+        &buyer.pubkey(),
+        TOKEN_2022_PRICE,
+    )
+    .await
+    .unwrap();
+
+    let (buy_accounts, buy_tx) = buy(
+        context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer,
+        TOKEN_2022_PRICE,
+        1,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    (
+        auction_house,
+        auction_house_data,
+        token,
+        sell_accounts,
+        buy_accounts,
+        buyer,
+    )
+}
+
+#[tokio::test]
+async fn execute_sale_token_2022_treasury_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (auction_house, auction_house_data, token, sell_accounts, buy_accounts, buyer) =
+        setup_token_2022_treasury_sale(&mut context).await;
+
+    // The escrow was credited the price less the 1% withheld on the way in
+    assert_eq!(
+        get_token_balance(&mut context, &buy_accounts.escrow_payment_account).await,
+        990_000
+    );
+
+    let (execute_sale_accounts, execute_sale_tx) = execute_sale(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &sell_accounts,
+        &buy_accounts,
+        TOKEN_2022_PRICE,
+        1,
+        None,
+        None,
+        vec![],
+        &buyer,
+    )
+    .await;
+    assert_eq!(
+        execute_sale_accounts.treasury_token_program,
+        spl_token_2022::id()
+    );
+    context
+        .banks_client
+        .process_transaction(execute_sale_tx)
+        .await
+        .unwrap();
+
+    // The house fee of 10_000 arrives less its own transfer fee, the seller absorbs the
+    // escrow shortfall and the fee withheld on the proceeds
+    assert_eq!(
+        get_token_balance(&mut context, &auction_house_data.auction_house_treasury).await,
+        9_900
+    );
+    assert_eq!(
+        get_token_balance(
+            &mut context,
+            &execute_sale_accounts.seller_payment_receipt_account
+        )
+        .await,
+        970_200
+    );
+    assert_eq!(
+        get_token_balance(
+            &mut context,
+            &execute_sale_accounts.buyer_receipt_token_account
+        )
+        .await,
+        1
+    );
+}
+
+#[tokio::test]
+async fn failure_execute_sale_nft_token_program_for_token_2022_treasury() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (auction_house, auction_house_data, token, sell_accounts, buy_accounts, buyer) =
+        setup_token_2022_treasury_sale(&mut context).await;
+
+    let (mut execute_sale_accounts, _) = execute_sale(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &sell_accounts,
+        &buy_accounts,
+        TOKEN_2022_PRICE,
+        1,
+        None,
+        None,
+        vec![],
+        &buyer,
+    )
+    .await;
+
+    // The legacy program of the NFT cannot move the Token-2022 escrow
+    execute_sale_accounts.treasury_token_program = spl_token::id();
+    let (_, escrow_payment_bump) =
+        find_escrow_payment_account_address(&auction_house, &buyer.pubkey());
+    let (_, free_trade_state_bump) = find_trade_state_address(
+        &token.owner.pubkey(),
+        &auction_house,
+        &token.ata,
+        &auction_house_data.treasury_mint,
+        &token.mint.pubkey(),
+        0,
+        1,
+    );
+    let (_, program_as_signer_bump) = find_program_as_signer_address();
+    let ix = Instruction {
+        program_id: auction_house::id(),
+        data: auction_house::instruction::ExecuteSale {
+            escrow_payment_bump,
+            free_trade_state_bump,
+            program_as_signer_bump,
+            buyer_price: TOKEN_2022_PRICE,
+            token_size: 1,
+            partial_order_size: None,
+            partial_order_price: None,
+            buyer_royalty_basis_points: None,
+        }
+        .data(),
+        accounts: execute_sale_accounts.to_account_metas(None),
+    };
+    let execute_sale_tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        context.last_blockhash,
+    );
+    let tx_error = context
+        .banks_client
+        .process_transaction(execute_sale_tx)
+        .await
+        .unwrap_err();
+
+    assert_error(tx_error, AuctionHouseError::PublicKeyMismatch.into());
+}
+
+#[tokio::test]
+async fn execute_sale_token_2022_treasury_reports_received_amounts() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (auction_house, auction_house_data, token, sell_accounts, buy_accounts, buyer) =
+        setup_token_2022_treasury_sale(&mut context).await;
+
+    let (_, execute_sale_tx) = execute_sale(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &sell_accounts,
+        &buy_accounts,
+        TOKEN_2022_PRICE,
+        1,
+        None,
+        None,
+        vec![],
+        &buyer,
+    )
+    .await;
+    let events: Vec<SaleExecuted> = process_transaction_events(&mut context, execute_sale_tx).await;
+
+    // Payouts are reported as they arrived, net of the withheld transfer fees
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].price, TOKEN_2022_PRICE);
+    assert_eq!(events[0].auction_house_fee_paid, 9_900);
+    assert_eq!(events[0].seller_proceeds, 970_200);
+    assert_eq!(events[0].royalty_paid, 0);
+}
+
+#[tokio::test]
+async fn failure_execute_sale_token_2022_escrow_short_beyond_transfer_fee() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (auction_house, auction_house_data, token, sell_accounts, buy_accounts, buyer) =
+        setup_token_2022_treasury_sale(&mut context).await;
+
+    // Only up to one transfer fee of the price, 10_000 here, may be missing from the escrow
+    let mut escrow = context
+        .banks_client
+        .get_account(buy_accounts.escrow_payment_account)
+        .await
+        .unwrap()
+        .unwrap();
+    let mut escrow_state =
+        StateWithExtensionsMut::<spl_token_2022::state::Account>::unpack(&mut escrow.data).unwrap();
+    escrow_state.base.amount = 980_000;
+    escrow_state.pack_base();
+    context.set_account(&buy_accounts.escrow_payment_account, &escrow.into());
+
+    let (_, execute_sale_tx) = execute_sale(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &sell_accounts,
+        &buy_accounts,
+        TOKEN_2022_PRICE,
+        1,
+        None,
+        None,
+        vec![],
+        &buyer,
+    )
+    .await;
+    let tx_error = context
+        .banks_client
+        .process_transaction(execute_sale_tx)
+        .await
+        .unwrap_err();
+
+    assert_error(tx_error, AuctionHouseError::InsufficientFunds.into());
+}
+
+// The test NFTs carry a 0.1% royalty, 10_000_000 lamports or 10_000 tokens at this price
+const ROYALTY_POLICY_PRICE: u64 = 10_000_000_000;
+const ROYALTY_POLICY_SPL_PRICE: u64 = 10_000_000;
+
+// Lists and bids on an NFT with the given creators and shares under a house with the given
+// royalty policy, the creators marked verified sign the metadata before the sale
+async fn setup_royalty_policy_sale(
+    context: &mut ProgramTestContext,
+    royalty_policy: RoyaltyPolicy,
+    creators: &[(&Keypair, u8, bool)],
+    spl_treasury: bool,
+) -> (
+    Pubkey,
+    AuctionHouse,
+    NFT,
+    auction_house::accounts::Sell,
+    auction_house::accounts::Buy,
+    Keypair,
+) {
+    let (treasury_mint, price) = if spl_treasury {
+        (
+            Some(create_spl_mint(context).await.unwrap()),
+            ROYALTY_POLICY_SPL_PRICE,
+        )
+    } else {
+        (None, ROYALTY_POLICY_PRICE)
+    };
+    let (authority, auction_house, auction_house_data) = match &treasury_mint {
+        Some(treasury_mint) => {
+            create_auction_house_with_treasury_mint(context, &treasury_mint.pubkey(), 100, false)
+                .await
+        }
+        None => create_auction_house(context, 100, false).await,
+    }
+    .expect("Failed to create Auction House");
+
+    let update_tx = update_auction_house(
+        context,
+        &authority,
+        &auction_house,
+        &auction_house_data,
+        None,
+        None,
+        None,
+        Some(royalty_policy),
+        None,
+    );
+    context
+        .banks_client
+        .process_transaction(update_tx)
+        .await
+        .unwrap();
+    let auction_house_data = get_auction_house(context, &auction_house).await;
+
+    let token = create_nft(
+        context,
+        Some(
+            creators
+                .iter()
+                .map(|(creator, share, _)| Creator {
+                    address: creator.pubkey(),
+                    verified: false,
+                    share: *share,
+                })
+                .collect(),
+        ),
+    )
+    .await
+    .expect("Failed to create NFT");
+    for (creator, _, verified) in creators {
+        if !verified {
+            continue;
+        }
+        let sign_metadata_tx = Transaction::new_signed_with_payer(
+            &[sign_metadata(
+                token_metadata_program_id(),
+                token.metadata,
+                creator.pubkey(),
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, *creator],
+            context.last_blockhash,
+        );
+        context
+            .banks_client
+            .process_transaction(sign_metadata_tx)
+            .await
+            .unwrap();
+    }
+
+    let (sell_accounts, sell_tx) = sell(
+        context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        price,
+        1,
+        &token.owner,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    let buyer = Keypair::new();
+    airdrop(context, &buyer.pubkey(), 20 * ONE_SOL)
+        .await
+        .unwrap();
+    if let Some(treasury_mint) = &treasury_mint {
+        mint_tokens(context, &treasury_mint.pubkey(), &buyer.pubkey(), price)
+            .await
+            .unwrap();
+    }
+
+    let (buy_accounts, buy_tx) = buy(
+        context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer,
+        price,
+        1,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    (
+        auction_house,
+        auction_house_data,
+        token,
+        sell_accounts,
+        buy_accounts,
+        buyer,
+    )
+}
+
+// Native execute_sale of the royalty policy listing with the buyer's choice of royalty
+#[allow(clippy::too_many_arguments)]
+async fn execute_sale_with_royalty_choice(
+    context: &mut ProgramTestContext,
+    auction_house: &Pubkey,
+    auction_house_data: &AuctionHouse,
+    token: &NFT,
+    sell_accounts: &auction_house::accounts::Sell,
+    buy_accounts: &auction_house::accounts::Buy,
+    remaining_accounts: Vec<AccountMeta>,
+    buyer_royalty_basis_points: Option<u16>,
+    signer_payer: &Keypair,
+) -> Transaction {
+    let (execute_sale_accounts, _) = execute_sale(
+        context,
+        auction_house,
+        auction_house_data,
+        token,
+        sell_accounts,
+        buy_accounts,
+        ROYALTY_POLICY_PRICE,
+        1,
+        None,
+        None,
+        vec![],
+        signer_payer,
+    )
+    .await;
+
+    let (_, escrow_payment_bump) =
+        find_escrow_payment_account_address(auction_house, &buy_accounts.wallet);
+    let (_, free_trade_state_bump) = find_trade_state_address(
+        &sell_accounts.wallet,
+        auction_house,
+        &sell_accounts.token_account,
+        &auction_house_data.treasury_mint,
+        &token.mint.pubkey(),
+        0,
+        1,
+    );
+    let (_, program_as_signer_bump) = find_program_as_signer_address();
+    let mut account_metas = execute_sale_accounts.to_account_metas(None);
+    account_metas.extend(remaining_accounts);
+    let ix = Instruction {
+        program_id: auction_house::id(),
+        data: auction_house::instruction::ExecuteSale {
+            escrow_payment_bump,
+            free_trade_state_bump,
+            program_as_signer_bump,
+            buyer_price: ROYALTY_POLICY_PRICE,
+            token_size: 1,
+            partial_order_size: None,
+            partial_order_price: None,
+            buyer_royalty_basis_points,
+        }
+        .data(),
+        accounts: account_metas,
+    };
+
+    Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&signer_payer.pubkey()),
+        &[signer_payer],
+        context.last_blockhash,
+    )
+}
+
+// Royalty paid to the only creator of a native sale on a house capping royalties
+async fn capped_royalty_paid(max_basis_points: u16) -> u64 {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let creator = Keypair::new();
+    let (auction_house, auction_house_data, token, sell_accounts, buy_accounts, buyer) =
+        setup_royalty_policy_sale(
+            &mut context,
+            RoyaltyPolicy::Capped { max_basis_points },
+            &[(&creator, 100, false)],
+            false,
+        )
+        .await;
+
+    let (_, execute_sale_tx) = execute_sale(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &sell_accounts,
+        &buy_accounts,
+        ROYALTY_POLICY_PRICE,
+        1,
+        None,
+        None,
+        vec![AccountMeta::new(creator.pubkey(), false)],
+        &buyer,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(execute_sale_tx)
+        .await
+        .unwrap();
+
+    context
+        .banks_client
+        .get_balance(creator.pubkey())
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn execute_sale_capped_royalty_below_metadata_success() {
+    // A cap of 0.05% under the 0.1% metadata royalty
+    assert_eq!(capped_royalty_paid(5).await, 5_000_000);
+}
+
+#[tokio::test]
+async fn execute_sale_capped_royalty_above_metadata_success() {
+    // A cap of 0.5% leaves the 0.1% metadata royalty as it is
+    assert_eq!(capped_royalty_paid(50).await, 10_000_000);
+}
+
+#[tokio::test]
+async fn execute_sale_verified_creators_only_spl_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let unverified_creator = Keypair::new();
+    let verified_creator = Keypair::new();
+    let (auction_house, auction_house_data, token, sell_accounts, buy_accounts, buyer) =
+        setup_royalty_policy_sale(
+            &mut context,
+            RoyaltyPolicy::VerifiedCreatorsOnly,
+            &[
+                (&unverified_creator, 50, false),
+                (&verified_creator, 50, true),
+            ],
+            true,
+        )
+        .await;
+
+    // The unverified creator comes first, its token account slot has to be skipped along with it
+    let creators = [
+        Creator {
+            address: unverified_creator.pubkey(),
+            verified: false,
+            share: 50,
+        },
+        Creator {
+            address: verified_creator.pubkey(),
+            verified: true,
+            share: 50,
+        },
+    ];
+    let remaining_accounts = creator_accounts(&creators, &auction_house_data, &spl_token::id());
+    let unverified_creator_token_account = remaining_accounts[1].pubkey;
+    let verified_creator_token_account = remaining_accounts[3].pubkey;
+
+    let (execute_sale_accounts, execute_sale_tx) = execute_sale(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &sell_accounts,
+        &buy_accounts,
+        ROYALTY_POLICY_SPL_PRICE,
+        1,
+        None,
+        None,
+        remaining_accounts,
+        &buyer,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(execute_sale_tx)
+        .await
+        .unwrap();
+
+    // Only the verified half of the 10_000 royalty is charged, the rest stays with the seller
+    assert_eq!(
+        get_token_balance(&mut context, &verified_creator_token_account).await,
+        5_000
+    );
+    assert!(context
+        .banks_client
+        .get_account(unverified_creator_token_account)
+        .await
+        .unwrap()
+        .is_none());
+    assert_eq!(
+        get_token_balance(
+            &mut context,
+            &execute_sale_accounts.seller_payment_receipt_account
+        )
+        .await,
+        ROYALTY_POLICY_SPL_PRICE - ROYALTY_POLICY_SPL_PRICE / 100 - 5_000
+    );
+}
+
+#[tokio::test]
+async fn execute_sale_buyer_choice_royalty_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let creator = Keypair::new();
+    let (auction_house, auction_house_data, token, sell_accounts, buy_accounts, buyer) =
+        setup_royalty_policy_sale(
+            &mut context,
+            RoyaltyPolicy::BuyerChoice {
+                min_basis_points: 5,
+            },
+            &[(&creator, 100, false)],
+            false,
+        )
+        .await;
+
+    let execute_sale_tx = execute_sale_with_royalty_choice(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &sell_accounts,
+        &buy_accounts,
+        vec![AccountMeta::new(creator.pubkey(), false)],
+        Some(8),
+        &buyer,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(execute_sale_tx)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        context
+            .banks_client
+            .get_balance(creator.pubkey())
+            .await
+            .unwrap(),
+        8_000_000
+    );
+}
+
+#[tokio::test]
+async fn failure_execute_sale_buyer_choice_below_minimum() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let creator = Keypair::new();
+    let (auction_house, auction_house_data, token, sell_accounts, buy_accounts, buyer) =
+        setup_royalty_policy_sale(
+            &mut context,
+            RoyaltyPolicy::BuyerChoice {
+                min_basis_points: 5,
+            },
+            &[(&creator, 100, false)],
+            false,
+        )
+        .await;
+
+    let execute_sale_tx = execute_sale_with_royalty_choice(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &sell_accounts,
+        &buy_accounts,
+        vec![AccountMeta::new(creator.pubkey(), false)],
+        Some(4),
+        &buyer,
+    )
+    .await;
+    let tx_error = context
+        .banks_client
+        .process_transaction(execute_sale_tx)
+        .await
+        .unwrap_err();
+
+    assert_error(tx_error, AuctionHouseError::RoyaltyBelowMinimum.into());
+}
+
+#[tokio::test]
+async fn failure_execute_sale_buyer_choice_non_signing_buyer() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let creator = Keypair::new();
+    let (auction_house, auction_house_data, token, sell_accounts, buy_accounts, _) =
+        setup_royalty_policy_sale(
+            &mut context,
+            RoyaltyPolicy::BuyerChoice {
+                min_basis_points: 5,
+            },
+            &[(&creator, 100, false)],
+            false,
+        )
+        .await;
+
+    // The seller can not lower the royalty on the buyer's behalf
+    let execute_sale_tx = execute_sale_with_royalty_choice(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &sell_accounts,
+        &buy_accounts,
+        vec![AccountMeta::new(creator.pubkey(), false)],
+        Some(5),
+        &token.owner,
+    )
+    .await;
+    let tx_error = context
+        .banks_client
+        .process_transaction(execute_sale_tx)
+        .await
+        .unwrap_err();
+
+    assert_error(tx_error, AuctionHouseError::RoyaltyChoiceNotAllowed.into());
+}
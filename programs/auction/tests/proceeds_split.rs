@@ -0,0 +1,398 @@
+use anchor_client::solana_sdk::transaction::Transaction;
+use anchor_client::solana_sdk::{signature::Keypair, signer::Signer};
+use anchor_lang::prelude::{AccountMeta, Pubkey};
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::system_program;
+use anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas};
+use auction_house::{
+    errors::AuctionHouseError, events::ProceedsShareEscrowed, pda::*, AuctionHouse, CreatorClaim,
+    ProceedsPayee,
+};
+use solana_program_test::{tokio, ProgramTestContext};
+
+mod utils;
+use utils::setup_functions::*;
+
+// Half of the proceeds of a sale this small is below the rent exemption of an empty wallet
+const SMALL_PRICE: u64 = 1_000_000;
+
+// Lists an NFT, splits its proceeds evenly between the seller and the payee and places a
+// matching bid
+async fn setup_split_sale(
+    context: &mut ProgramTestContext,
+    payee: &Pubkey,
+    price: u64,
+) -> (
+    Pubkey,
+    AuctionHouse,
+    NFT,
+    auction_house::accounts::Sell,
+    auction_house::accounts::Buy,
+    Keypair,
+) {
+    let (_, auction_house, auction_house_data) = create_auction_house(context, 100, false)
+        .await
+        .expect("Failed to create Auction House");
+    airdrop(
+        context,
+        &auction_house_data.auction_house_fee_account,
+        ONE_SOL,
+    )
+    .await
+    .unwrap();
+
+    let token = create_nft(context, None)
+        .await
+        .expect("Failed to create NFT");
+
+    let (sell_accounts, sell_tx) = sell(
+        context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        price,
+        1,
+        &token.owner,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    let set_proceeds_split_tx = set_proceeds_split(
+        context,
+        &auction_house,
+        &token,
+        &sell_accounts,
+        vec![
+            ProceedsPayee {
+                address: token.owner.pubkey(),
+                share: 50,
+            },
+            ProceedsPayee {
+                address: *payee,
+                share: 50,
+            },
+        ],
+        price,
+    );
+    context
+        .banks_client
+        .process_transaction(set_proceeds_split_tx)
+        .await
+        .unwrap();
+
+    let buyer = Keypair::new();
+    airdrop(context, &buyer.pubkey(), 10 * ONE_SOL)
+        .await
+        .unwrap();
+    let (buy_accounts, buy_tx) = buy(
+        context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer,
+        price,
+        1,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    (
+        auction_house,
+        auction_house_data,
+        token,
+        sell_accounts,
+        buy_accounts,
+        buyer,
+    )
+}
+
+fn set_proceeds_split(
+    context: &mut ProgramTestContext,
+    auction_house: &Pubkey,
+    token: &NFT,
+    sell_accounts: &auction_house::accounts::Sell,
+    payees: Vec<ProceedsPayee>,
+    buyer_price: u64,
+) -> Transaction {
+    let data = auction_house::instruction::SetProceedsSplit {
+        _buyer_price: buyer_price,
+        _token_size: 1,
+        payees,
+    };
+
+    let accounts = auction_house::accounts::SetProceedsSplit {
+        wallet: token.owner.pubkey(),
+        token_account: token.ata,
+        auction_house: *auction_house,
+        seller_trade_state: sell_accounts.seller_trade_state,
+        proceeds_split: find_proceeds_split_address(&sell_accounts.seller_trade_state).0,
+        system_program: system_program::id(),
+    };
+
+    let ix = Instruction {
+        program_id: auction_house::id(),
+        data: data.data(),
+        accounts: accounts.to_account_metas(None),
+    };
+
+    Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&token.owner.pubkey()),
+        &[&token.owner],
+        context.last_blockhash,
+    )
+}
+
+#[tokio::test]
+async fn execute_sale_proceeds_split_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let payee = Keypair::new();
+    airdrop(&mut context, &payee.pubkey(), ONE_SOL)
+        .await
+        .unwrap();
+    let (auction_house, auction_house_data, token, sell_accounts, buy_accounts, buyer) =
+        setup_split_sale(&mut context, &payee.pubkey(), ONE_SOL).await;
+
+    let (_, execute_sale_tx) = execute_sale(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &sell_accounts,
+        &buy_accounts,
+        ONE_SOL,
+        1,
+        None,
+        None,
+        vec![
+            AccountMeta::new(token.owner.pubkey(), false),
+            AccountMeta::new(payee.pubkey(), false),
+        ],
+        &buyer,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(execute_sale_tx)
+        .await
+        .unwrap();
+
+    // The payee gets half of what is left after the 1% house fee
+    assert_eq!(
+        context
+            .banks_client
+            .get_balance(payee.pubkey())
+            .await
+            .unwrap(),
+        ONE_SOL + (ONE_SOL - ONE_SOL / 100) / 2
+    );
+
+    // The split closes with the listing, a relist at the same price starts without it
+    assert!(context
+        .banks_client
+        .get_account(find_proceeds_split_address(&sell_accounts.seller_trade_state).0)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn execute_sale_proceeds_split_payee_claim_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let payee = Keypair::new();
+    let (auction_house, auction_house_data, token, sell_accounts, buy_accounts, buyer) =
+        setup_split_sale(&mut context, &payee.pubkey(), SMALL_PRICE).await;
+    let (payee_claim, _) = find_creator_claim_address(&auction_house, &payee.pubkey());
+
+    let (_, execute_sale_tx) = execute_sale(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &sell_accounts,
+        &buy_accounts,
+        SMALL_PRICE,
+        1,
+        None,
+        None,
+        vec![
+            AccountMeta::new(token.owner.pubkey(), false),
+            AccountMeta::new(payee.pubkey(), false),
+            AccountMeta::new(payee_claim, false),
+        ],
+        &buyer,
+    )
+    .await;
+    let events: Vec<ProceedsShareEscrowed> =
+        process_transaction_events(&mut context, execute_sale_tx).await;
+
+    // The payee's share is held for it instead of going back to the seller
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].payee, payee.pubkey());
+    assert_eq!(events[0].amount, (SMALL_PRICE - SMALL_PRICE / 100) / 2);
+    let payee_claim_account = context
+        .banks_client
+        .get_account(payee_claim)
+        .await
+        .unwrap()
+        .unwrap();
+    let payee_claim_data =
+        CreatorClaim::try_deserialize(&mut payee_claim_account.data.as_ref()).unwrap();
+    assert_eq!(payee_claim_data.creator, payee.pubkey());
+    assert_eq!(
+        payee_claim_data.amount,
+        (SMALL_PRICE - SMALL_PRICE / 100) / 2
+    );
+
+    // Once rent exempt on its own, the payee claims it like a creator
+    airdrop(&mut context, &payee.pubkey(), ONE_SOL)
+        .await
+        .unwrap();
+    let accounts = auction_house::accounts::ClaimCreatorRoyalties {
+        creator: payee.pubkey(),
+        creator_claim: payee_claim,
+        auction_house,
+        auction_house_fee_account: auction_house_data.auction_house_fee_account,
+    };
+    let ix = Instruction {
+        program_id: auction_house::id(),
+        data: auction_house::instruction::ClaimCreatorRoyalties {}.data(),
+        accounts: accounts.to_account_metas(None),
+    };
+    let claim_tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&payee.pubkey()),
+        &[&payee],
+        context.last_blockhash,
+    );
+    context
+        .banks_client
+        .process_transaction(claim_tx)
+        .await
+        .unwrap();
+
+    assert!(context
+        .banks_client
+        .get_account(payee_claim)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn failure_execute_sale_proceeds_split_missing_payee_claim() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let payee = Keypair::new();
+    let (auction_house, auction_house_data, token, sell_accounts, buy_accounts, buyer) =
+        setup_split_sale(&mut context, &payee.pubkey(), SMALL_PRICE).await;
+
+    let (_, execute_sale_tx) = execute_sale(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &sell_accounts,
+        &buy_accounts,
+        SMALL_PRICE,
+        1,
+        None,
+        None,
+        vec![
+            AccountMeta::new(token.owner.pubkey(), false),
+            AccountMeta::new(payee.pubkey(), false),
+        ],
+        &buyer,
+    )
+    .await;
+    let tx_error = context
+        .banks_client
+        .process_transaction(execute_sale_tx)
+        .await
+        .unwrap_err();
+
+    assert_error(tx_error, AuctionHouseError::MissingPayeeClaim.into());
+}
+
+#[tokio::test]
+async fn cancel_closes_proceeds_split_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let payee = Keypair::new();
+    let (auction_house, auction_house_data, token, sell_accounts, _, _) =
+        setup_split_sale(&mut context, &payee.pubkey(), ONE_SOL).await;
+
+    let (_, cancel_tx) = cancel(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &token.owner.pubkey(),
+        &sell_accounts.seller_trade_state,
+        ONE_SOL,
+        1,
+        &token.owner,
+    );
+    context
+        .banks_client
+        .process_transaction(cancel_tx)
+        .await
+        .unwrap();
+
+    assert!(context
+        .banks_client
+        .get_account(find_proceeds_split_address(&sell_accounts.seller_trade_state).0)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn failure_set_proceeds_split_shares_not_100() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (_, auction_house, auction_house_data) = create_auction_house(&mut context, 100, false)
+        .await
+        .expect("Failed to create Auction House");
+    let token = create_nft(&mut context, None)
+        .await
+        .expect("Failed to create NFT");
+
+    let (sell_accounts, sell_tx) = sell(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        ONE_SOL,
+        1,
+        &token.owner,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    let set_proceeds_split_tx = set_proceeds_split(
+        &mut context,
+        &auction_house,
+        &token,
+        &sell_accounts,
+        vec![ProceedsPayee {
+            address: Keypair::new().pubkey(),
+            share: 60,
+        }],
+        ONE_SOL,
+    );
+    let tx_error = context
+        .banks_client
+        .process_transaction(set_proceeds_split_tx)
+        .await
+        .unwrap_err();
+
+    assert_error(tx_error, AuctionHouseError::InvalidProceedsSplit.into());
+}
@@ -0,0 +1,525 @@
+use anchor_client::solana_sdk::transaction::Transaction;
+use anchor_client::solana_sdk::{signature::Keypair, signer::Signer};
+use anchor_lang::prelude::{AccountMeta, Pubkey};
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::solana_program::{system_program, sysvar};
+use anchor_lang::{InstructionData, ToAccountMetas};
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::spl_token;
+use auction_house::pda::*;
+use auction_house::{errors::AuctionHouseError, AuctionHouse};
+use mpl_token_auth_rules::{
+    instruction::{
+        builders::CreateOrUpdateBuilder, CreateOrUpdateArgs,
+        InstructionBuilder as RuleSetInstructionBuilder,
+    },
+    pda::find_rule_set_address,
+    state::{Rule, RuleSetV1},
+};
+use mpl_token_metadata::{
+    instruction::{
+        builders::{CreateBuilder, MintBuilder},
+        CreateArgs, InstructionBuilder, MintArgs,
+    },
+    pda::find_token_record_account,
+    state::{AssetData, PrintSupply, TokenStandard},
+};
+use nft_minter::pda::*;
+use nft_minter::utils::token_metadata_program_id;
+use serde::Serialize;
+use solana_program_test::{tokio, ProgramTest, ProgramTestContext};
+
+mod utils;
+use utils::setup_functions::*;
+
+const RULE_SET_NAME: &str = "auction_house";
+
+fn programmable_program_test() -> ProgramTest {
+    let mut program = auction_house_program_test();
+    program.add_program("mpl_token_auth_rules", mpl_token_auth_rules::id(), None);
+    program
+}
+
+// Programmable NFT behind a rule set that lets the sale delegate move it
+async fn create_programmable_nft(context: &mut ProgramTestContext) -> (NFT, Pubkey) {
+    let mint = Keypair::new();
+    let owner = Keypair::new();
+    airdrop(context, &owner.pubkey(), 10 * ONE_SOL)
+        .await
+        .unwrap();
+
+    let ata = get_associated_token_address(&owner.pubkey(), &mint.pubkey());
+    let (metadata, _) = find_metadata_account(&mint.pubkey());
+    let (master_edition, _) = find_master_edition_account(&mint.pubkey());
+    let (token_record, _) = find_token_record_account(&mint.pubkey(), &ata);
+    let (rule_set, _) = find_rule_set_address(owner.pubkey(), RULE_SET_NAME.to_string());
+
+    let mut rule_set_data = RuleSetV1::new(RULE_SET_NAME.to_string(), owner.pubkey());
+    for operation in ["Delegate:Sale", "Transfer:SaleDelegate", "Transfer:Owner"] {
+        rule_set_data
+            .add(operation.to_string(), Rule::Pass)
+            .unwrap();
+    }
+    let mut serialized_rule_set = Vec::new();
+    rule_set_data
+        .serialize(&mut rmp_serde::Serializer::new(&mut serialized_rule_set))
+        .unwrap();
+
+    let create_rule_set_ix = CreateOrUpdateBuilder::new()
+        .payer(owner.pubkey())
+        .rule_set_pda(rule_set)
+        .system_program(system_program::id())
+        .build(CreateOrUpdateArgs::V1 {
+            serialized_rule_set,
+        })
+        .unwrap()
+        .instruction();
+
+    let mut asset_data = AssetData::new(
+        TokenStandard::ProgrammableNonFungible,
+        "Solana Course pNFT".to_string(),
+        "SOLC".to_string(),
+        "https://raw.githubusercontent.com/arsenijkovalov/nft-assets/main/assets/nft.json"
+            .to_string(),
+    );
+    asset_data.seller_fee_basis_points = 10;
+    asset_data.rule_set = Some(rule_set);
+
+    let create_ix = CreateBuilder::new()
+        .metadata(metadata)
+        .master_edition(master_edition)
+        .mint(mint.pubkey())
+        .authority(owner.pubkey())
+        .payer(owner.pubkey())
+        .update_authority(owner.pubkey())
+        .system_program(system_program::id())
+        .sysvar_instructions(sysvar::instructions::id())
+        .spl_token_program(spl_token::id())
+        .initialize_mint(true)
+        .update_authority_as_signer(true)
+        .build(CreateArgs::V1 {
+            asset_data,
+            decimals: Some(0),
+            print_supply: Some(PrintSupply::Zero),
+        })
+        .unwrap()
+        .instruction();
+
+    let mint_ix = MintBuilder::new()
+        .token(ata)
+        .token_owner(owner.pubkey())
+        .metadata(metadata)
+        .master_edition(master_edition)
+        .token_record(token_record)
+        .mint(mint.pubkey())
+        .authority(owner.pubkey())
+        .payer(owner.pubkey())
+        .system_program(system_program::id())
+        .sysvar_instructions(sysvar::instructions::id())
+        .spl_token_program(spl_token::id())
+        .spl_ata_program(anchor_spl::associated_token::ID)
+        .authorization_rules_program(mpl_token_auth_rules::id())
+        .authorization_rules(rule_set)
+        .build(MintArgs::V1 {
+            amount: 1,
+            authorization_data: None,
+        })
+        .unwrap()
+        .instruction();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[create_rule_set_ix, create_ix, mint_ix],
+        Some(&owner.pubkey()),
+        &[&mint, &owner],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    (
+        NFT {
+            mint,
+            owner,
+            ata,
+            metadata,
+            master_edition,
+        },
+        rule_set,
+    )
+}
+
+// Token Metadata accounts in the order `next_programmable_accounts` reads them
+fn programmable_accounts(token: &NFT, rule_set: &Pubkey) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new_readonly(token_metadata_program_id(), false),
+        AccountMeta::new_readonly(token.master_edition, false),
+        AccountMeta::new(
+            find_token_record_account(&token.mint.pubkey(), &token.ata).0,
+            false,
+        ),
+        AccountMeta::new_readonly(mpl_token_auth_rules::id(), false),
+        AccountMeta::new_readonly(*rule_set, false),
+        AccountMeta::new_readonly(sysvar::instructions::id(), false),
+    ]
+}
+
+fn sell_programmable(
+    context: &mut ProgramTestContext,
+    auction_house: &Pubkey,
+    auction_house_data: &AuctionHouse,
+    token: &NFT,
+    rule_set: &Pubkey,
+    buyer_price: u64,
+) -> (auction_house::accounts::Sell, Transaction) {
+    let (seller_trade_state, trade_state_bump) = find_trade_state_address(
+        &token.owner.pubkey(),
+        auction_house,
+        &token.ata,
+        &auction_house_data.treasury_mint,
+        &token.mint.pubkey(),
+        buyer_price,
+        1,
+    );
+    let (free_seller_trade_state, free_trade_state_bump) = find_trade_state_address(
+        &token.owner.pubkey(),
+        auction_house,
+        &token.ata,
+        &auction_house_data.treasury_mint,
+        &token.mint.pubkey(),
+        0,
+        1,
+    );
+    let (program_as_signer, program_as_signer_bump) = find_program_as_signer_address();
+
+    let data = auction_house::instruction::Sell {
+        trade_state_bump,
+        free_trade_state_bump,
+        program_as_signer_bump,
+        buyer_price,
+        token_size: 1,
+    };
+
+    let accounts = auction_house::accounts::Sell {
+        wallet: token.owner.pubkey(),
+        token_account: token.ata,
+        metadata: token.metadata,
+        authority: auction_house_data.authority,
+        auction_house: *auction_house,
+        auction_house_fee_account: auction_house_data.auction_house_fee_account,
+        seller_trade_state,
+        free_seller_trade_state,
+        program_as_signer,
+        token_program: spl_token::id(),
+        system_program: system_program::id(),
+        rent: sysvar::rent::id(),
+    };
+
+    let mut account_metas = accounts.to_account_metas(None);
+    account_metas.append(&mut programmable_accounts(token, rule_set));
+    account_metas.push(AccountMeta::new_readonly(token.mint.pubkey(), false));
+    // Token Metadata writes the metadata while delegating
+    account_metas.push(AccountMeta::new(token.metadata, false));
+
+    let ix = Instruction {
+        program_id: auction_house::id(),
+        data: data.data(),
+        accounts: account_metas,
+    };
+
+    (
+        accounts,
+        Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&token.owner.pubkey()),
+            &[&token.owner],
+            context.last_blockhash,
+        ),
+    )
+}
+
+fn cancel_programmable(
+    context: &mut ProgramTestContext,
+    auction_house: &Pubkey,
+    auction_house_data: &AuctionHouse,
+    token: &NFT,
+    rule_set: &Pubkey,
+    trade_state: &Pubkey,
+    buyer_price: u64,
+    signer: &Keypair,
+) -> Transaction {
+    let data = auction_house::instruction::Cancel {
+        buyer_price,
+        token_size: 1,
+    };
+
+    let accounts = auction_house::accounts::Cancel {
+        wallet: token.owner.pubkey(),
+        token_account: token.ata,
+        token_mint: token.mint.pubkey(),
+        authority: auction_house_data.authority,
+        auction_house: *auction_house,
+        auction_house_fee_account: auction_house_data.auction_house_fee_account,
+        trade_state: *trade_state,
+        proceeds_split: find_proceeds_split_address(trade_state).0,
+        referral: find_referral_address(trade_state).0,
+        escrow_ledger: find_escrow_ledger_address(auction_house, &token.owner.pubkey()).0,
+        token_program: spl_token::id(),
+    };
+
+    let mut account_metas = accounts.to_account_metas(None);
+    account_metas.push(AccountMeta::new(token.metadata, false));
+    account_metas.append(&mut programmable_accounts(token, rule_set));
+    account_metas.push(AccountMeta::new_readonly(
+        find_program_as_signer_address().0,
+        false,
+    ));
+    account_metas.push(AccountMeta::new_readonly(system_program::id(), false));
+
+    let ix = Instruction {
+        program_id: auction_house::id(),
+        data: data.data(),
+        accounts: account_metas,
+    };
+
+    Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&signer.pubkey()),
+        &[signer],
+        context.last_blockhash,
+    )
+}
+
+async fn get_token_account(
+    context: &mut ProgramTestContext,
+    token_account: &Pubkey,
+) -> spl_token::state::Account {
+    spl_token::state::Account::unpack_from_slice(
+        context
+            .banks_client
+            .get_account(*token_account)
+            .await
+            .unwrap()
+            .unwrap()
+            .data
+            .as_slice(),
+    )
+    .unwrap()
+}
+
+#[tokio::test]
+async fn programmable_nft_sell_and_cancel_success() {
+    let mut context = programmable_program_test().start_with_context().await;
+
+    let (_, auction_house, auction_house_data) = create_auction_house(&mut context, 100, false)
+        .await
+        .expect("Failed to create Auction House");
+
+    let (token, rule_set) = create_programmable_nft(&mut context).await;
+
+    // Sell, the sale delegate locks the token
+
+    let (sell_accounts, sell_tx) = sell_programmable(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &rule_set,
+        ONE_SOL,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    let token_account = get_token_account(&mut context, &token.ata).await;
+    assert_eq!(
+        token_account.delegate,
+        Some(sell_accounts.program_as_signer).into()
+    );
+    assert!(token_account.is_frozen());
+
+    // Cancel, revoking the delegate unlocks the token
+
+    let cancel_tx = cancel_programmable(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &rule_set,
+        &sell_accounts.seller_trade_state,
+        ONE_SOL,
+        &token.owner,
+    );
+    context
+        .banks_client
+        .process_transaction(cancel_tx)
+        .await
+        .unwrap();
+
+    let token_account = get_token_account(&mut context, &token.ata).await;
+    assert_eq!(token_account.delegate, None.into());
+    assert_eq!(token_account.amount, 1);
+
+    let seller_trade_state = context
+        .banks_client
+        .get_account(sell_accounts.seller_trade_state)
+        .await
+        .unwrap();
+    assert!(seller_trade_state.is_none());
+}
+
+#[tokio::test]
+async fn failure_programmable_nft_authority_cancel() {
+    let mut context = programmable_program_test().start_with_context().await;
+
+    let (authority, auction_house, auction_house_data) =
+        create_auction_house(&mut context, 100, false)
+            .await
+            .expect("Failed to create Auction House");
+
+    let (token, rule_set) = create_programmable_nft(&mut context).await;
+
+    let (sell_accounts, sell_tx) = sell_programmable(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &rule_set,
+        ONE_SOL,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    // Only the seller can revoke the sale delegate, the authority would leave the token locked
+
+    let cancel_tx = cancel_programmable(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &rule_set,
+        &sell_accounts.seller_trade_state,
+        ONE_SOL,
+        &authority,
+    );
+    let tx_error = context
+        .banks_client
+        .process_transaction(cancel_tx)
+        .await
+        .unwrap_err();
+
+    assert_error(
+        tx_error,
+        AuctionHouseError::ProgrammableCancelRequiresSeller.into(),
+    );
+
+    let token_account = get_token_account(&mut context, &token.ata).await;
+    assert!(token_account.is_frozen());
+}
+
+#[tokio::test]
+async fn programmable_nft_execute_sale_success() {
+    let mut context = programmable_program_test().start_with_context().await;
+
+    let (_, auction_house, auction_house_data) = create_auction_house(&mut context, 100, false)
+        .await
+        .expect("Failed to create Auction House");
+
+    let (token, rule_set) = create_programmable_nft(&mut context).await;
+
+    // Sell
+
+    let (sell_accounts, sell_tx) = sell_programmable(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &rule_set,
+        ONE_SOL,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    // Buy
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), 10 * ONE_SOL)
+        .await
+        .unwrap();
+
+    let (buy_accounts, buy_tx) = buy(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer,
+        ONE_SOL,
+        1,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    // Execute sale, the token moves through a rule set validated transfer
+
+    let buyer_receipt_token_account =
+        get_associated_token_address(&buyer.pubkey(), &token.mint.pubkey());
+    let mut remaining_accounts = programmable_accounts(&token, &rule_set);
+    remaining_accounts.push(AccountMeta::new(
+        find_token_record_account(&token.mint.pubkey(), &buyer_receipt_token_account).0,
+        false,
+    ));
+    // Token Metadata writes the metadata while transferring
+    remaining_accounts.push(AccountMeta::new(token.metadata, false));
+
+    let (execute_sale_accounts, execute_sale_tx) = execute_sale(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &sell_accounts,
+        &buy_accounts,
+        ONE_SOL,
+        1,
+        None,
+        None,
+        remaining_accounts,
+        &buyer,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(execute_sale_tx)
+        .await
+        .unwrap();
+
+    let buyer_token_account = get_token_account(
+        &mut context,
+        &execute_sale_accounts.buyer_receipt_token_account,
+    )
+    .await;
+    assert_eq!(buyer_token_account.amount, 1);
+    assert_eq!(get_token_account(&mut context, &token.ata).await.amount, 0);
+
+    for closed_account in [
+        sell_accounts.seller_trade_state,
+        buy_accounts.buyer_trade_state,
+    ] {
+        assert!(context
+            .banks_client
+            .get_account(closed_account)
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
@@ -0,0 +1,454 @@
+use anchor_client::solana_sdk::transaction::Transaction;
+use anchor_client::solana_sdk::{signature::Keypair, signer::Signer};
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::system_program;
+use anchor_lang::{InstructionData, ToAccountMetas};
+use anchor_spl::token::spl_token;
+use auction_house::{errors::AuctionHouseError, pda::*, AuctionHouse};
+use solana_program_test::{tokio, ProgramTestContext};
+use spl_associated_token_account::get_associated_token_address;
+
+mod utils;
+use utils::setup_functions::*;
+
+const NATIVE_PRICE: u64 = ONE_SOL;
+const SPL_PRICE: u64 = 10_000_000;
+
+// Half of the 1% house fee goes to the referrer
+const REFERRAL_BASIS_POINTS: u16 = 5000;
+
+// Lists an NFT through a referring frontend, records the referrer and places a matching bid
+async fn setup_referred_sale(
+    context: &mut ProgramTestContext,
+    spl_treasury: bool,
+) -> (
+    Pubkey,
+    AuctionHouse,
+    NFT,
+    auction_house::accounts::Sell,
+    auction_house::accounts::Buy,
+    Keypair,
+    Keypair,
+    u64,
+) {
+    let (treasury_mint, price) = if spl_treasury {
+        (Some(create_spl_mint(context).await.unwrap()), SPL_PRICE)
+    } else {
+        (None, NATIVE_PRICE)
+    };
+    let (authority, auction_house, auction_house_data) = match &treasury_mint {
+        Some(treasury_mint) => {
+            create_auction_house_with_treasury_mint(context, &treasury_mint.pubkey(), 100, false)
+                .await
+        }
+        None => create_auction_house(context, 100, false).await,
+    }
+    .expect("Failed to create Auction House");
+
+    let update_tx = update_auction_house(
+        context,
+        &authority,
+        &auction_house,
+        &auction_house_data,
+        None,
+        None,
+        None,
+        None,
+        Some(REFERRAL_BASIS_POINTS),
+    );
+    context
+        .banks_client
+        .process_transaction(update_tx)
+        .await
+        .unwrap();
+    let auction_house_data = get_auction_house(context, &auction_house).await;
+
+    let token = create_nft(context, None)
+        .await
+        .expect("Failed to create NFT");
+
+    let (sell_accounts, sell_tx) = sell(
+        context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        price,
+        1,
+        &token.owner,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    let referrer = Keypair::new();
+    let set_referral_tx = set_referral(
+        context,
+        &auction_house,
+        &token,
+        &sell_accounts,
+        &referrer.pubkey(),
+        price,
+    );
+    context
+        .banks_client
+        .process_transaction(set_referral_tx)
+        .await
+        .unwrap();
+
+    let buyer = Keypair::new();
+    airdrop(context, &buyer.pubkey(), 10 * ONE_SOL)
+        .await
+        .unwrap();
+    if let Some(treasury_mint) = &treasury_mint {
+        mint_tokens(context, &treasury_mint.pubkey(), &buyer.pubkey(), price)
+            .await
+            .unwrap();
+    }
+
+    let (buy_accounts, buy_tx) = buy(
+        context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer,
+        price,
+        1,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    (
+        auction_house,
+        auction_house_data,
+        token,
+        sell_accounts,
+        buy_accounts,
+        buyer,
+        referrer,
+        price,
+    )
+}
+
+fn set_referral(
+    context: &mut ProgramTestContext,
+    auction_house: &Pubkey,
+    token: &NFT,
+    sell_accounts: &auction_house::accounts::Sell,
+    referrer: &Pubkey,
+    buyer_price: u64,
+) -> Transaction {
+    let data = auction_house::instruction::SetReferral {
+        _buyer_price: buyer_price,
+        _token_size: 1,
+    };
+
+    let accounts = auction_house::accounts::SetReferral {
+        wallet: token.owner.pubkey(),
+        token_account: token.ata,
+        auction_house: *auction_house,
+        seller_trade_state: sell_accounts.seller_trade_state,
+        referrer: *referrer,
+        referral: find_referral_address(&sell_accounts.seller_trade_state).0,
+        system_program: system_program::id(),
+    };
+
+    let ix = Instruction {
+        program_id: auction_house::id(),
+        data: data.data(),
+        accounts: accounts.to_account_metas(None),
+    };
+
+    Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&token.owner.pubkey()),
+        &[&token.owner],
+        context.last_blockhash,
+    )
+}
+
+// execute_sale naming the given referrer, with its treasury mint ATA for SPL treasuries
+#[allow(clippy::too_many_arguments)]
+async fn execute_referred_sale(
+    context: &mut ProgramTestContext,
+    auction_house: &Pubkey,
+    auction_house_data: &AuctionHouse,
+    token: &NFT,
+    sell_accounts: &auction_house::accounts::Sell,
+    buy_accounts: &auction_house::accounts::Buy,
+    buyer_price: u64,
+    referrer: Option<&Pubkey>,
+    buyer: &Keypair,
+) -> (auction_house::accounts::ExecuteSale, Transaction) {
+    let (mut execute_sale_accounts, _) = execute_sale(
+        context,
+        auction_house,
+        auction_house_data,
+        token,
+        sell_accounts,
+        buy_accounts,
+        buyer_price,
+        1,
+        None,
+        None,
+        vec![],
+        buyer,
+    )
+    .await;
+    execute_sale_accounts.referrer = referrer.copied();
+    if auction_house_data.treasury_mint != spl_token::native_mint::id() {
+        execute_sale_accounts.referrer_token_account = referrer.map(|referrer| {
+            get_associated_token_address(referrer, &auction_house_data.treasury_mint)
+        });
+    }
+
+    let (_, escrow_payment_bump) =
+        find_escrow_payment_account_address(auction_house, &buyer.pubkey());
+    let (_, free_trade_state_bump) = find_trade_state_address(
+        &token.owner.pubkey(),
+        auction_house,
+        &token.ata,
+        &auction_house_data.treasury_mint,
+        &token.mint.pubkey(),
+        0,
+        1,
+    );
+    let (_, program_as_signer_bump) = find_program_as_signer_address();
+    let ix = Instruction {
+        program_id: auction_house::id(),
+        data: auction_house::instruction::ExecuteSale {
+            escrow_payment_bump,
+            free_trade_state_bump,
+            program_as_signer_bump,
+            buyer_price,
+            token_size: 1,
+            partial_order_size: None,
+            partial_order_price: None,
+            buyer_royalty_basis_points: None,
+        }
+        .data(),
+        accounts: execute_sale_accounts.to_account_metas(None),
+    };
+
+    (
+        execute_sale_accounts,
+        Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&buyer.pubkey()),
+            &[buyer],
+            context.last_blockhash,
+        ),
+    )
+}
+
+#[tokio::test]
+async fn execute_sale_referral_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (
+        auction_house,
+        auction_house_data,
+        token,
+        sell_accounts,
+        buy_accounts,
+        buyer,
+        referrer,
+        price,
+    ) = setup_referred_sale(&mut context, false).await;
+
+    let treasury_before = context
+        .banks_client
+        .get_balance(auction_house_data.auction_house_treasury)
+        .await
+        .unwrap();
+
+    let (_, execute_sale_tx) = execute_referred_sale(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &sell_accounts,
+        &buy_accounts,
+        price,
+        Some(&referrer.pubkey()),
+        &buyer,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(execute_sale_tx)
+        .await
+        .unwrap();
+
+    // The 1% house fee is shared evenly between the referrer and the treasury
+    assert_eq!(
+        context
+            .banks_client
+            .get_balance(referrer.pubkey())
+            .await
+            .unwrap(),
+        price / 200
+    );
+    let treasury_after = context
+        .banks_client
+        .get_balance(auction_house_data.auction_house_treasury)
+        .await
+        .unwrap();
+    assert_eq!(treasury_after - treasury_before, price / 200);
+
+    // The referral closes with the listing
+    assert!(context
+        .banks_client
+        .get_account(find_referral_address(&sell_accounts.seller_trade_state).0)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn execute_sale_referral_spl_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (
+        auction_house,
+        auction_house_data,
+        token,
+        sell_accounts,
+        buy_accounts,
+        buyer,
+        referrer,
+        price,
+    ) = setup_referred_sale(&mut context, true).await;
+
+    let (execute_sale_accounts, execute_sale_tx) = execute_referred_sale(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &sell_accounts,
+        &buy_accounts,
+        price,
+        Some(&referrer.pubkey()),
+        &buyer,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(execute_sale_tx)
+        .await
+        .unwrap();
+
+    // The referrer's token account is created on the way
+    assert_eq!(
+        get_token_balance(
+            &mut context,
+            &execute_sale_accounts.referrer_token_account.unwrap()
+        )
+        .await,
+        price / 200
+    );
+    assert_eq!(
+        get_token_balance(&mut context, &auction_house_data.auction_house_treasury).await,
+        price / 200
+    );
+    assert_eq!(
+        get_token_balance(
+            &mut context,
+            &execute_sale_accounts.seller_payment_receipt_account
+        )
+        .await,
+        price - price / 100
+    );
+}
+
+#[tokio::test]
+async fn failure_execute_sale_substitute_referrer() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (auction_house, auction_house_data, token, sell_accounts, buy_accounts, buyer, _, price) =
+        setup_referred_sale(&mut context, false).await;
+
+    // Whoever submits the sale names a referrer of their own
+    let substitute = Keypair::new();
+    let (_, execute_sale_tx) = execute_referred_sale(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &sell_accounts,
+        &buy_accounts,
+        price,
+        Some(&substitute.pubkey()),
+        &buyer,
+    )
+    .await;
+    let tx_error = context
+        .banks_client
+        .process_transaction(execute_sale_tx)
+        .await
+        .unwrap_err();
+
+    assert_error(tx_error, AuctionHouseError::ReferrerMismatch.into());
+}
+
+#[tokio::test]
+async fn failure_execute_sale_without_recorded_referrer() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (auction_house, auction_house_data, token, sell_accounts, buy_accounts, buyer, _, price) =
+        setup_referred_sale(&mut context, false).await;
+
+    // Leaving the referrer out would keep its share in the treasury
+    let (_, execute_sale_tx) = execute_referred_sale(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &sell_accounts,
+        &buy_accounts,
+        price,
+        None,
+        &buyer,
+    )
+    .await;
+    let tx_error = context
+        .banks_client
+        .process_transaction(execute_sale_tx)
+        .await
+        .unwrap_err();
+
+    assert_error(tx_error, AuctionHouseError::ReferrerMismatch.into());
+}
+
+#[tokio::test]
+async fn cancel_closes_referral_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (auction_house, auction_house_data, token, sell_accounts, _, _, _, price) =
+        setup_referred_sale(&mut context, false).await;
+
+    let (_, cancel_tx) = cancel(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &token.owner.pubkey(),
+        &sell_accounts.seller_trade_state,
+        price,
+        1,
+        &token.owner,
+    );
+    context
+        .banks_client
+        .process_transaction(cancel_tx)
+        .await
+        .unwrap();
+
+    assert!(context
+        .banks_client
+        .get_account(find_referral_address(&sell_accounts.seller_trade_state).0)
+        .await
+        .unwrap()
+        .is_none());
+}
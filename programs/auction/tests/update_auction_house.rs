@@ -0,0 +1,161 @@
+use anchor_lang::AccountDeserialize;
+use auction_house::{
+    constants::{ROYALTY_POLICY_CAPPED, ROYALTY_POLICY_ENFORCED},
+    errors::AuctionHouseError,
+    utils::get_royalty_policy,
+    AuctionHouse, RoyaltyPolicy,
+};
+use solana_program_test::tokio;
+
+mod utils;
+use utils::setup_functions::*;
+
+#[tokio::test]
+async fn update_auction_house_bounds_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+
+    let (authority, auction_house, auction_house_data) =
+        create_auction_house(&mut context, 100, false)
+            .await
+            .expect("Failed to create Auction House");
+
+    // UpdateAuctionHouse
+
+    let tx = update_auction_house(
+        &mut context,
+        &authority,
+        &auction_house,
+        &auction_house_data,
+        Some(3600),
+        Some(7 * 86400),
+        Some(86400),
+        None,
+        None,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let auction_house_account = context
+        .banks_client
+        .get_account(auction_house)
+        .await
+        .unwrap()
+        .unwrap();
+    let auction_house_data =
+        AuctionHouse::try_deserialize(&mut auction_house_account.data.as_ref()).unwrap();
+
+    assert_eq!(auction_house_data.min_auction_duration, 3600);
+    assert_eq!(auction_house_data.max_auction_duration, 7 * 86400);
+    assert_eq!(auction_house_data.max_time_extension, 86400);
+    assert_eq!(auction_house_data.seller_fee_basis_points, 100);
+}
+
+#[tokio::test]
+async fn failure_update_auction_house_min_above_max_duration() {
+    let mut context = auction_house_program_test().start_with_context().await;
+
+    let (authority, auction_house, auction_house_data) =
+        create_auction_house(&mut context, 100, false)
+            .await
+            .expect("Failed to create Auction House");
+
+    // UpdateAuctionHouse
+
+    let tx = update_auction_house(
+        &mut context,
+        &authority,
+        &auction_house,
+        &auction_house_data,
+        Some(86400),
+        Some(3600),
+        None,
+        None,
+        None,
+    );
+    let tx_error = context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err();
+
+    assert_error(tx_error, AuctionHouseError::InvalidAuctionBounds.into());
+}
+
+#[tokio::test]
+async fn update_auction_house_royalty_policy_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+
+    let (authority, auction_house, auction_house_data) =
+        create_auction_house(&mut context, 100, false)
+            .await
+            .expect("Failed to create Auction House");
+
+    assert_eq!(auction_house_data.royalty_policy, ROYALTY_POLICY_ENFORCED);
+
+    // UpdateAuctionHouse
+
+    let tx = update_auction_house(
+        &mut context,
+        &authority,
+        &auction_house,
+        &auction_house_data,
+        None,
+        None,
+        None,
+        Some(RoyaltyPolicy::Capped {
+            max_basis_points: 250,
+        }),
+        None,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let auction_house_account = context
+        .banks_client
+        .get_account(auction_house)
+        .await
+        .unwrap()
+        .unwrap();
+    let auction_house_data =
+        AuctionHouse::try_deserialize(&mut auction_house_account.data.as_ref()).unwrap();
+
+    assert_eq!(auction_house_data.royalty_policy, ROYALTY_POLICY_CAPPED);
+    assert_eq!(auction_house_data.royalty_basis_points, 250);
+    assert_eq!(
+        get_royalty_policy(&auction_house_data).unwrap(),
+        RoyaltyPolicy::Capped {
+            max_basis_points: 250
+        }
+    );
+}
+
+#[tokio::test]
+async fn failure_update_auction_house_royalty_minimum_above_max_basis_points() {
+    let mut context = auction_house_program_test().start_with_context().await;
+
+    let (authority, auction_house, auction_house_data) =
+        create_auction_house(&mut context, 100, false)
+            .await
+            .expect("Failed to create Auction House");
+
+    // UpdateAuctionHouse
+
+    let tx = update_auction_house(
+        &mut context,
+        &authority,
+        &auction_house,
+        &auction_house_data,
+        None,
+        None,
+        None,
+        Some(RoyaltyPolicy::BuyerChoice {
+            min_basis_points: 10001,
+        }),
+        None,
+    );
+    let tx_error = context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap_err();
+
+    assert_error(tx_error, AuctionHouseError::InvalidBasisPoints.into());
+}
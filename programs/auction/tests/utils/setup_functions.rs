@@ -2,16 +2,27 @@
 
 use anchor_client::solana_sdk::transaction::{Transaction, TransactionError};
 use anchor_client::solana_sdk::{signature::Keypair, signer::Signer};
+use anchor_lang::prelude::AccountMeta;
 use anchor_lang::solana_program::instruction::{Instruction, InstructionError};
+use anchor_lang::solana_program::program_pack::Pack;
 use anchor_lang::solana_program::{system_instruction, system_program, sysvar};
 use anchor_lang::{prelude::Pubkey, AccountDeserialize};
-use anchor_lang::{InstructionData, ToAccountMetas};
+use anchor_lang::{Event, InstructionData, ToAccountMetas};
 use anchor_spl::associated_token::{self};
 use anchor_spl::token::spl_token;
 use auction_house::pda::*;
-use auction_house::AuctionHouse;
+use auction_house::{AuctionHouse, RoyaltyPolicy};
+use nft_minter::pda::*;
+use nft_minter::utils::{token_metadata_program_id, Creator};
 use solana_program_test::{BanksClientError, ProgramTest, ProgramTestContext};
-use std::io;
+use spl_associated_token_account::{
+    get_associated_token_address_with_program_id,
+    instruction::create_associated_token_account_idempotent,
+};
+use spl_token_2022::{
+    extension::{transfer_fee, ExtensionType, StateWithExtensions},
+    state::Mint,
+};
 
 // Error = Error code
 pub const ERR_AUCTION_HOUSE_ALREADY_INITIALIZED: u32 = 0;
@@ -20,7 +31,9 @@ pub const ERR_INSUFFICIENT_FUNDS: u32 = 1;
 pub const ONE_SOL: u64 = 1_000_000_000;
 
 pub fn auction_house_program_test() -> ProgramTest {
-    let program = ProgramTest::new("auction_house", auction_house::id(), None);
+    let mut program = ProgramTest::new("auction_house", auction_house::id(), None);
+    program.add_program("nft_minter", nft_minter::id(), None);
+    program.add_program("mpl_token_metadata", token_metadata_program_id(), None);
     program
 }
 
@@ -34,16 +47,56 @@ pub fn assert_error(error: BanksClientError, expected_error: u32) {
     }
 }
 
+// Processes the transaction and decodes the events of type T it emitted
+pub async fn process_transaction_events<T: Event>(
+    context: &mut ProgramTestContext,
+    tx: Transaction,
+) -> Vec<T> {
+    let result = context
+        .banks_client
+        .process_transaction_with_metadata(tx)
+        .await
+        .unwrap();
+    result.result.unwrap();
+
+    result
+        .metadata
+        .unwrap()
+        .log_messages
+        .iter()
+        .filter_map(|log| log.strip_prefix("Program data: "))
+        .filter_map(|data| base64::decode(data).ok())
+        .filter(|data| data.starts_with(&T::discriminator()))
+        .map(|data| T::try_from_slice(&data[8..]).unwrap())
+        .collect()
+}
+
 pub async fn create_auction_house(
     context: &mut ProgramTestContext,
     seller_fee_basis_points: u16,
     can_change_sale_price: bool,
+) -> Result<(Keypair, Pubkey, AuctionHouse), BanksClientError> {
+    create_auction_house_with_treasury_mint(
+        context,
+        &spl_token::native_mint::id(),
+        seller_fee_basis_points,
+        can_change_sale_price,
+    )
+    .await
+}
+
+pub async fn create_auction_house_with_treasury_mint(
+    context: &mut ProgramTestContext,
+    treasury_mint: &Pubkey,
+    seller_fee_basis_points: u16,
+    can_change_sale_price: bool,
 ) -> Result<(Keypair, Pubkey, AuctionHouse), BanksClientError> {
     // CreateAuctionHouse
     let authority = Keypair::new();
     airdrop(context, &authority.pubkey(), 10 * ONE_SOL).await?;
 
-    let treasury_mint = spl_token::native_mint::id();
+    let treasury_mint = *treasury_mint;
+    let token_program = get_token_program(context, &treasury_mint).await;
 
     let (auction_house, auction_house_bump) =
         find_auction_house_address(&authority.pubkey(), &treasury_mint);
@@ -51,6 +104,15 @@ pub async fn create_auction_house(
         find_auction_house_fee_account_address(&auction_house);
     let (auction_house_treasury, auction_house_treasury_bump) =
         find_auction_house_treasury_address(&auction_house);
+    let treasury_withdrawal_destination = if treasury_mint == spl_token::native_mint::id() {
+        context.payer.pubkey()
+    } else {
+        get_associated_token_address_with_program_id(
+            &context.payer.pubkey(),
+            &treasury_mint,
+            &token_program,
+        )
+    };
 
     let create_auction_house_ix = Instruction {
         program_id: auction_house::id(),
@@ -67,12 +129,12 @@ pub async fn create_auction_house(
             payer: authority.pubkey(),
             authority: authority.pubkey(),
             fee_withdrawal_destination: context.payer.pubkey(),
-            treasury_withdrawal_destination: context.payer.pubkey(),
+            treasury_withdrawal_destination,
             treasury_withdrawal_destination_owner: context.payer.pubkey(),
             auction_house,
             auction_house_fee_account,
             auction_house_treasury,
-            token_program: spl_token::id(),
+            token_program,
             system_program: system_program::id(),
             associated_token_program: associated_token::ID,
             rent: sysvar::rent::id(),
@@ -88,16 +150,404 @@ pub async fn create_auction_house(
     );
     context.banks_client.process_transaction(tx).await?;
 
+    let auction_house_data = get_auction_house(context, &auction_house).await;
+
+    Ok((authority, auction_house, auction_house_data))
+}
+
+pub async fn get_auction_house(
+    context: &mut ProgramTestContext,
+    auction_house: &Pubkey,
+) -> AuctionHouse {
     let auction_house_account = context
         .banks_client
-        .get_account(auction_house)
-        .await?
+        .get_account(*auction_house)
+        .await
+        .unwrap()
         .expect("Auction House account not found");
-    let auction_house_data =
-        AuctionHouse::try_deserialize(&mut auction_house_account.data.as_ref())
-            .map_err(|e| BanksClientError::Io(io::Error::new(io::ErrorKind::InvalidData, e)))?;
 
-    Ok((authority, auction_house, auction_house_data))
+    AuctionHouse::try_deserialize(&mut auction_house_account.data.as_ref()).unwrap()
+}
+
+// Program owning the mint, which is the token program its accounts are moved with
+pub async fn get_token_program(context: &mut ProgramTestContext, mint: &Pubkey) -> Pubkey {
+    context
+        .banks_client
+        .get_account(*mint)
+        .await
+        .unwrap()
+        .expect("Mint account not found")
+        .owner
+}
+
+pub async fn create_spl_mint(
+    context: &mut ProgramTestContext,
+) -> Result<Keypair, BanksClientError> {
+    let mint = Keypair::new();
+    let rent = context.banks_client.get_rent().await?;
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            system_instruction::create_account(
+                &context.payer.pubkey(),
+                &mint.pubkey(),
+                rent.minimum_balance(spl_token::state::Mint::LEN),
+                spl_token::state::Mint::LEN as u64,
+                &spl_token::id(),
+            ),
+            spl_token::instruction::initialize_mint2(
+                &spl_token::id(),
+                &mint.pubkey(),
+                &context.payer.pubkey(),
+                None,
+                6,
+            )
+            .unwrap(),
+        ],
+        Some(&context.payer.pubkey()),
+        &[&context.payer, &mint],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await?;
+
+    Ok(mint)
+}
+
+// Mints treasury tokens into the wallet's associated token account, creating it when missing
+pub async fn mint_tokens(
+    context: &mut ProgramTestContext,
+    mint: &Pubkey,
+    wallet: &Pubkey,
+    amount: u64,
+) -> Result<Pubkey, BanksClientError> {
+    let token_program = get_token_program(context, mint).await;
+    let token_account = get_associated_token_address_with_program_id(wallet, mint, &token_program);
+
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            create_associated_token_account_idempotent(
+                &context.payer.pubkey(),
+                wallet,
+                mint,
+                &token_program,
+            ),
+            spl_token_2022::instruction::mint_to(
+                &token_program,
+                mint,
+                &token_account,
+                &context.payer.pubkey(),
+                &[],
+                amount,
+            )
+            .unwrap(),
+        ],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await?;
+
+    Ok(token_account)
+}
+
+pub async fn get_token_balance(context: &mut ProgramTestContext, token_account: &Pubkey) -> u64 {
+    let account = context
+        .banks_client
+        .get_account(*token_account)
+        .await
+        .unwrap()
+        .expect("Token account not found");
+
+    StateWithExtensions::<spl_token_2022::state::Account>::unpack(&account.data)
+        .unwrap()
+        .base
+        .amount
+}
+
+#[derive(Debug)]
+pub struct NFT {
+    pub mint: Keypair,          // Mint
+    pub owner: Keypair,         // Seller
+    pub ata: Pubkey,            // Token account
+    pub metadata: Pubkey,       // Metaplex Metadata
+    pub master_edition: Pubkey, // Metaplex Master Edition
+}
+
+pub async fn create_nft(
+    context: &mut ProgramTestContext,
+    metadata_creators: Option<Vec<Creator>>,
+) -> Result<NFT, BanksClientError> {
+    let mint = Keypair::new();
+    let owner = Keypair::new();
+    airdrop(context, &owner.pubkey(), 10 * ONE_SOL)
+        .await
+        .unwrap();
+
+    let ata = associated_token::get_associated_token_address(&owner.pubkey(), &mint.pubkey());
+    let (metadata, _) = find_metadata_account(&mint.pubkey());
+    let (master_edition, _) = find_master_edition_account(&mint.pubkey());
+
+    // CreateToken
+    let create_token_ix = Instruction {
+        program_id: nft_minter::id(),
+        data: nft_minter::instruction::CreateToken {
+            name: "Solana Course NFT".to_string(),
+            symbol: "SOLC".to_string(),
+            uri: "https://raw.githubusercontent.com/arsenijkovalov/nft-assets/main/assets/nft.json"
+                .to_string(),
+            creators: metadata_creators,
+            seller_fee_basis_points: 10,
+            is_mutable: false,
+        }
+        .data(),
+        accounts: nft_minter::accounts::CreateToken {
+            payer: owner.pubkey(),
+            mint_account: mint.pubkey(),
+            mint_authority: owner.pubkey(),
+            update_authority: owner.pubkey(),
+            metadata_account: metadata,
+            token_metadata_program: token_metadata_program_id(),
+            system_program: system_program::id(),
+            token_program: anchor_spl::token::ID,
+            rent: sysvar::rent::id(),
+        }
+        .to_account_metas(None),
+    };
+
+    // MintToken
+    let mint_token_ix = Instruction {
+        program_id: nft_minter::id(),
+        data: nft_minter::instruction::MintToken {
+            max_supply: Some(0),
+        }
+        .data(),
+        accounts: nft_minter::accounts::MintToken {
+            payer: owner.pubkey(),
+            mint_account: mint.pubkey(),
+            mint_authority: owner.pubkey(),
+            update_authority: owner.pubkey(),
+            associated_token_account: ata,
+            metadata_account: metadata,
+            edition_account: master_edition,
+            token_metadata_program: token_metadata_program_id(),
+            system_program: system_program::id(),
+            token_program: anchor_spl::token::ID,
+            associated_token_program: anchor_spl::associated_token::ID,
+            rent: sysvar::rent::id(),
+        }
+        .to_account_metas(None),
+    };
+
+    let tx = Transaction::new_signed_with_payer(
+        &[create_token_ix, mint_token_ix],
+        Some(&owner.pubkey()),
+        &[&mint, &owner],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await?;
+
+    Ok(NFT {
+        mint,
+        owner,
+        ata,
+        metadata,
+        master_edition,
+    })
+}
+
+// Semi-fungible token without a master edition, so more than one unit can be listed
+pub async fn create_sft(
+    context: &mut ProgramTestContext,
+    supply: u64,
+) -> Result<NFT, BanksClientError> {
+    let mint = Keypair::new();
+    let owner = Keypair::new();
+    airdrop(context, &owner.pubkey(), 10 * ONE_SOL)
+        .await
+        .unwrap();
+
+    let ata = associated_token::get_associated_token_address(&owner.pubkey(), &mint.pubkey());
+    let (metadata, _) = find_metadata_account(&mint.pubkey());
+    let (master_edition, _) = find_master_edition_account(&mint.pubkey());
+
+    // CreateToken
+    let create_token_ix = Instruction {
+        program_id: nft_minter::id(),
+        data: nft_minter::instruction::CreateToken {
+            name: "Solana Course SFT".to_string(),
+            symbol: "SOLC".to_string(),
+            uri: "https://raw.githubusercontent.com/arsenijkovalov/nft-assets/main/assets/nft.json"
+                .to_string(),
+            creators: None,
+            seller_fee_basis_points: 10,
+            is_mutable: false,
+        }
+        .data(),
+        accounts: nft_minter::accounts::CreateToken {
+            payer: owner.pubkey(),
+            mint_account: mint.pubkey(),
+            mint_authority: owner.pubkey(),
+            update_authority: owner.pubkey(),
+            metadata_account: metadata,
+            token_metadata_program: token_metadata_program_id(),
+            system_program: system_program::id(),
+            token_program: anchor_spl::token::ID,
+            rent: sysvar::rent::id(),
+        }
+        .to_account_metas(None),
+    };
+
+    let create_ata_ix = create_associated_token_account_idempotent(
+        &owner.pubkey(),
+        &owner.pubkey(),
+        &mint.pubkey(),
+        &spl_token::id(),
+    );
+
+    let mint_to_ix = spl_token::instruction::mint_to(
+        &spl_token::id(),
+        &mint.pubkey(),
+        &ata,
+        &owner.pubkey(),
+        &[],
+        supply,
+    )
+    .unwrap();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[create_token_ix, create_ata_ix, mint_to_ix],
+        Some(&owner.pubkey()),
+        &[&mint, &owner],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await?;
+
+    Ok(NFT {
+        mint,
+        owner,
+        ata,
+        metadata,
+        master_edition,
+    })
+}
+
+pub async fn create_token_2022_mint(
+    context: &mut ProgramTestContext,
+    extension_types: &[ExtensionType],
+) -> Result<Keypair, BanksClientError> {
+    let mint = Keypair::new();
+    let space = ExtensionType::get_account_len::<Mint>(extension_types);
+    let rent = context.banks_client.get_rent().await?;
+
+    let mut instructions = vec![system_instruction::create_account(
+        &context.payer.pubkey(),
+        &mint.pubkey(),
+        rent.minimum_balance(space),
+        space as u64,
+        &spl_token_2022::id(),
+    )];
+    for extension_type in extension_types {
+        match extension_type {
+            ExtensionType::TransferFeeConfig => instructions.push(
+                transfer_fee::instruction::initialize_transfer_fee_config(
+                    &spl_token_2022::id(),
+                    &mint.pubkey(),
+                    None,
+                    None,
+                    100,
+                    ONE_SOL,
+                )
+                .unwrap(),
+            ),
+            ExtensionType::NonTransferable => instructions.push(
+                spl_token_2022::instruction::initialize_non_transferable_mint(
+                    &spl_token_2022::id(),
+                    &mint.pubkey(),
+                )
+                .unwrap(),
+            ),
+            _ => unimplemented!("Unsupported mint extension {:?}", extension_type),
+        }
+    }
+    instructions.push(
+        spl_token_2022::instruction::initialize_mint2(
+            &spl_token_2022::id(),
+            &mint.pubkey(),
+            &context.payer.pubkey(),
+            None,
+            6,
+        )
+        .unwrap(),
+    );
+
+    let tx = Transaction::new_signed_with_payer(
+        &instructions,
+        Some(&context.payer.pubkey()),
+        &[&context.payer, &mint],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await?;
+
+    Ok(mint)
+}
+
+pub fn update_auction_house(
+    context: &mut ProgramTestContext,
+    authority: &Keypair,
+    auction_house: &Pubkey,
+    auction_house_data: &AuctionHouse,
+    min_auction_duration: Option<i64>,
+    max_auction_duration: Option<i64>,
+    max_time_extension: Option<i64>,
+    royalty_policy: Option<RoyaltyPolicy>,
+    referral_basis_points: Option<u16>,
+) -> Transaction {
+    let data = auction_house::instruction::UpdateAuctionHouse {
+        seller_fee_basis_points: None,
+        can_change_sale_price: None,
+        min_auction_duration,
+        max_auction_duration,
+        max_time_extension,
+        royalty_policy,
+        referral_basis_points,
+        buyer_premium_basis_points: None,
+        max_auctioneer_fee_basis_points: None,
+    };
+
+    let accounts = auction_house::accounts::UpdateAuctionHouse {
+        treasury_mint: auction_house_data.treasury_mint,
+        payer: authority.pubkey(),
+        authority: authority.pubkey(),
+        new_authority: authority.pubkey(),
+        fee_withdrawal_destination: auction_house_data.fee_withdrawal_destination,
+        treasury_withdrawal_destination: auction_house_data.treasury_withdrawal_destination,
+        // Houses are created with the context payer owning the treasury destination
+        treasury_withdrawal_destination_owner: if auction_house_data.treasury_mint
+            == spl_token::native_mint::id()
+        {
+            auction_house_data.treasury_withdrawal_destination
+        } else {
+            context.payer.pubkey()
+        },
+        auction_house: *auction_house,
+        token_program: spl_token::id(),
+        system_program: system_program::id(),
+        associated_token_program: associated_token::ID,
+        rent: sysvar::rent::id(),
+    };
+
+    let ix = Instruction {
+        program_id: auction_house::id(),
+        data: data.data(),
+        accounts: accounts.to_account_metas(None),
+    };
+
+    Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&authority.pubkey()),
+        &[authority],
+        context.last_blockhash,
+    )
 }
 
 pub fn delegate(
@@ -151,3 +601,375 @@ pub async fn airdrop(
 
     Ok(())
 }
+
+pub fn sell(
+    context: &mut ProgramTestContext,
+    auction_house: &Pubkey,
+    auction_house_data: &AuctionHouse,
+    token: &NFT,
+    buyer_price: u64,
+    token_size: u64,
+    signer_payer: &Keypair,
+) -> (auction_house::accounts::Sell, Transaction) {
+    let (seller_trade_state, trade_state_bump) = find_trade_state_address(
+        &token.owner.pubkey(),
+        auction_house,
+        &token.ata,
+        &auction_house_data.treasury_mint,
+        &token.mint.pubkey(),
+        buyer_price,
+        token_size,
+    );
+    let (free_seller_trade_state, free_trade_state_bump) = find_trade_state_address(
+        &token.owner.pubkey(),
+        auction_house,
+        &token.ata,
+        &auction_house_data.treasury_mint,
+        &token.mint.pubkey(),
+        0,
+        token_size,
+    );
+    let (program_as_signer, program_as_signer_bump) = find_program_as_signer_address();
+
+    let data = auction_house::instruction::Sell {
+        trade_state_bump,
+        free_trade_state_bump,
+        program_as_signer_bump,
+        buyer_price,
+        token_size,
+    };
+
+    let accounts = auction_house::accounts::Sell {
+        wallet: token.owner.pubkey(),
+        token_account: token.ata,
+        metadata: token.metadata,
+        authority: auction_house_data.authority,
+        auction_house: *auction_house,
+        auction_house_fee_account: auction_house_data.auction_house_fee_account,
+        seller_trade_state,
+        free_seller_trade_state,
+        program_as_signer,
+        token_program: spl_token::id(),
+        system_program: system_program::id(),
+        rent: sysvar::rent::id(),
+    };
+
+    let ix = Instruction {
+        program_id: auction_house::id(),
+        data: data.data(),
+        accounts: accounts.to_account_metas(None),
+    };
+
+    (
+        accounts,
+        Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&signer_payer.pubkey()),
+            &[signer_payer],
+            context.last_blockhash,
+        ),
+    )
+}
+
+pub async fn buy(
+    context: &mut ProgramTestContext,
+    auction_house: &Pubkey,
+    auction_house_data: &AuctionHouse,
+    token: &NFT,
+    buyer: &Keypair,
+    buyer_price: u64,
+    token_size: u64,
+) -> (auction_house::accounts::Buy, Transaction) {
+    let treasury_token_program =
+        get_token_program(context, &auction_house_data.treasury_mint).await;
+    let payment_account = if auction_house_data.treasury_mint == spl_token::native_mint::id() {
+        buyer.pubkey()
+    } else {
+        get_associated_token_address_with_program_id(
+            &buyer.pubkey(),
+            &auction_house_data.treasury_mint,
+            &treasury_token_program,
+        )
+    };
+
+    let (buyer_trade_state, trade_state_bump) = find_trade_state_address(
+        &buyer.pubkey(),
+        auction_house,
+        &token.ata,
+        &auction_house_data.treasury_mint,
+        &token.mint.pubkey(),
+        buyer_price,
+        token_size,
+    );
+    let (escrow_payment_account, escrow_payment_bump) =
+        find_escrow_payment_account_address(auction_house, &buyer.pubkey());
+    let (escrow_ledger, _) = find_escrow_ledger_address(auction_house, &buyer.pubkey());
+
+    let data = auction_house::instruction::Buy {
+        trade_state_bump,
+        escrow_payment_bump,
+        buyer_price,
+        token_size,
+    };
+
+    let accounts = auction_house::accounts::Buy {
+        wallet: buyer.pubkey(),
+        payment_account,
+        transfer_authority: buyer.pubkey(),
+        treasury_mint: auction_house_data.treasury_mint,
+        token_account: token.ata,
+        metadata: token.metadata,
+        escrow_payment_account,
+        escrow_ledger,
+        authority: auction_house_data.authority,
+        auction_house: *auction_house,
+        auction_house_fee_account: auction_house_data.auction_house_fee_account,
+        buyer_trade_state,
+        token_program: treasury_token_program,
+        system_program: system_program::id(),
+        rent: sysvar::rent::id(),
+    };
+
+    let ix = Instruction {
+        program_id: auction_house::id(),
+        data: data.data(),
+        accounts: accounts.to_account_metas(None),
+    };
+
+    (
+        accounts,
+        Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&buyer.pubkey()),
+            &[buyer],
+            context.last_blockhash,
+        ),
+    )
+}
+
+// Creator accounts paid out of a sale, followed by their token accounts for SPL treasuries
+pub fn creator_accounts(
+    creators: &[Creator],
+    auction_house_data: &AuctionHouse,
+    treasury_token_program: &Pubkey,
+) -> Vec<AccountMeta> {
+    let mut account_metas = vec![];
+    for creator in creators {
+        account_metas.push(AccountMeta::new(creator.address, false));
+        if auction_house_data.treasury_mint != spl_token::native_mint::id() {
+            account_metas.push(AccountMeta::new(
+                get_associated_token_address_with_program_id(
+                    &creator.address,
+                    &auction_house_data.treasury_mint,
+                    treasury_token_program,
+                ),
+                false,
+            ));
+        }
+    }
+
+    account_metas
+}
+
+pub async fn execute_sale(
+    context: &mut ProgramTestContext,
+    auction_house: &Pubkey,
+    auction_house_data: &AuctionHouse,
+    token: &NFT,
+    sell_accounts: &auction_house::accounts::Sell,
+    buy_accounts: &auction_house::accounts::Buy,
+    buyer_price: u64,
+    token_size: u64,
+    partial_order_size: Option<u64>,
+    partial_order_price: Option<u64>,
+    remaining_accounts: Vec<AccountMeta>,
+    signer_payer: &Keypair,
+) -> (auction_house::accounts::ExecuteSale, Transaction) {
+    let treasury_token_program =
+        get_token_program(context, &auction_house_data.treasury_mint).await;
+    let seller_payment_receipt_account =
+        if auction_house_data.treasury_mint == spl_token::native_mint::id() {
+            sell_accounts.wallet
+        } else {
+            get_associated_token_address_with_program_id(
+                &sell_accounts.wallet,
+                &auction_house_data.treasury_mint,
+                &treasury_token_program,
+            )
+        };
+    let buyer_receipt_token_account =
+        associated_token::get_associated_token_address(&buy_accounts.wallet, &token.mint.pubkey());
+    let (_, escrow_payment_bump) =
+        find_escrow_payment_account_address(auction_house, &buy_accounts.wallet);
+    let (_, free_trade_state_bump) = find_trade_state_address(
+        &sell_accounts.wallet,
+        auction_house,
+        &sell_accounts.token_account,
+        &auction_house_data.treasury_mint,
+        &token.mint.pubkey(),
+        0,
+        token_size,
+    );
+    let (_, program_as_signer_bump) = find_program_as_signer_address();
+
+    let data = auction_house::instruction::ExecuteSale {
+        escrow_payment_bump,
+        free_trade_state_bump,
+        program_as_signer_bump,
+        buyer_price,
+        token_size,
+        partial_order_size,
+        partial_order_price,
+        buyer_royalty_basis_points: None,
+    };
+
+    let accounts = auction_house::accounts::ExecuteSale {
+        buyer: buy_accounts.wallet,
+        seller: sell_accounts.wallet,
+        token_account: sell_accounts.token_account,
+        token_mint: token.mint.pubkey(),
+        metadata: token.metadata,
+        treasury_mint: auction_house_data.treasury_mint,
+        escrow_payment_account: buy_accounts.escrow_payment_account,
+        escrow_ledger: buy_accounts.escrow_ledger,
+        seller_payment_receipt_account,
+        buyer_receipt_token_account,
+        authority: auction_house_data.authority,
+        auction_house: *auction_house,
+        auction_house_fee_account: auction_house_data.auction_house_fee_account,
+        auction_house_treasury: auction_house_data.auction_house_treasury,
+        buyer_trade_state: buy_accounts.buyer_trade_state,
+        seller_trade_state: sell_accounts.seller_trade_state,
+        free_trade_state: sell_accounts.free_seller_trade_state,
+        proceeds_split: find_proceeds_split_address(&sell_accounts.seller_trade_state).0,
+        referral: find_referral_address(&sell_accounts.seller_trade_state).0,
+        referrer: None,
+        referrer_token_account: None,
+        token_program: spl_token::id(),
+        treasury_token_program,
+        system_program: system_program::id(),
+        associated_token_program: associated_token::ID,
+        program_as_signer: sell_accounts.program_as_signer,
+        rent: sysvar::rent::id(),
+    };
+
+    let mut account_metas = accounts.to_account_metas(None);
+    account_metas.extend(remaining_accounts);
+
+    let ix = Instruction {
+        program_id: auction_house::id(),
+        data: data.data(),
+        accounts: account_metas,
+    };
+
+    (
+        accounts,
+        Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&signer_payer.pubkey()),
+            &[signer_payer],
+            context.last_blockhash,
+        ),
+    )
+}
+
+pub fn cancel(
+    context: &mut ProgramTestContext,
+    auction_house: &Pubkey,
+    auction_house_data: &AuctionHouse,
+    token: &NFT,
+    wallet: &Pubkey,
+    trade_state: &Pubkey,
+    buyer_price: u64,
+    token_size: u64,
+    signer_payer: &Keypair,
+) -> (auction_house::accounts::Cancel, Transaction) {
+    let data = auction_house::instruction::Cancel {
+        buyer_price,
+        token_size,
+    };
+
+    let accounts = auction_house::accounts::Cancel {
+        wallet: *wallet,
+        token_account: token.ata,
+        token_mint: token.mint.pubkey(),
+        authority: auction_house_data.authority,
+        auction_house: *auction_house,
+        auction_house_fee_account: auction_house_data.auction_house_fee_account,
+        trade_state: *trade_state,
+        proceeds_split: find_proceeds_split_address(trade_state).0,
+        referral: find_referral_address(trade_state).0,
+        escrow_ledger: find_escrow_ledger_address(auction_house, wallet).0,
+        token_program: spl_token::id(),
+    };
+
+    let ix = Instruction {
+        program_id: auction_house::id(),
+        data: data.data(),
+        accounts: accounts.to_account_metas(None),
+    };
+
+    (
+        accounts,
+        Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&signer_payer.pubkey()),
+            &[signer_payer],
+            context.last_blockhash,
+        ),
+    )
+}
+
+pub async fn close_escrow_account(
+    context: &mut ProgramTestContext,
+    auction_house: &Pubkey,
+    auction_house_data: &AuctionHouse,
+    wallet: &Keypair,
+) -> (auction_house::accounts::CloseEscrowAccount, Transaction) {
+    let treasury_token_program =
+        get_token_program(context, &auction_house_data.treasury_mint).await;
+    let receipt_account = if auction_house_data.treasury_mint == spl_token::native_mint::id() {
+        wallet.pubkey()
+    } else {
+        get_associated_token_address_with_program_id(
+            &wallet.pubkey(),
+            &auction_house_data.treasury_mint,
+            &treasury_token_program,
+        )
+    };
+    let (escrow_payment_account, escrow_payment_bump) =
+        find_escrow_payment_account_address(auction_house, &wallet.pubkey());
+
+    let data = auction_house::instruction::CloseEscrowAccount {
+        escrow_payment_bump,
+    };
+
+    let accounts = auction_house::accounts::CloseEscrowAccount {
+        wallet: wallet.pubkey(),
+        escrow_payment_account,
+        escrow_ledger: find_escrow_ledger_address(auction_house, &wallet.pubkey()).0,
+        receipt_account,
+        treasury_mint: auction_house_data.treasury_mint,
+        auction_house: *auction_house,
+        token_program: treasury_token_program,
+        system_program: system_program::id(),
+        associated_token_program: associated_token::ID,
+        rent: sysvar::rent::id(),
+    };
+
+    let ix = Instruction {
+        program_id: auction_house::id(),
+        data: data.data(),
+        accounts: accounts.to_account_metas(None),
+    };
+
+    (
+        accounts,
+        Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&wallet.pubkey()),
+            &[wallet],
+            context.last_blockhash,
+        ),
+    )
+}
@@ -0,0 +1,17 @@
+use anchor_lang::prelude::*;
+
+pub const AUCTIONEER_CONFIG_SIZE: usize = 8 +               // Anchor discriminator/sighash
+32 +                                                        // auction house
+2 +                                                         // fee basis points
+32 +                                                        // fee destination
+1                                                           // bump
+;
+
+// Revenue of whoever runs the auctioneer program, collected on each auction sale
+#[account]
+pub struct AuctioneerConfig {
+    pub auction_house: Pubkey,
+    pub fee_basis_points: u16,
+    pub fee_destination: Pubkey,
+    pub bump: u8,
+}
@@ -1,3 +1,5 @@
+pub mod config;
+
 use anchor_lang::{prelude::*, AnchorDeserialize};
 
 use crate::errors::*;
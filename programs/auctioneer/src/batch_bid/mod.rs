@@ -0,0 +1,163 @@
+use anchor_lang::{prelude::*, AnchorDeserialize};
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+use auction_house::{
+    self,
+    constants::{AUCTIONEER, ESCROW_LEDGER, PREFIX},
+    cpi::accounts::AuctioneerLockEscrow as AHLockEscrow,
+    program::AuctionHouse as AuctionHouseProgram,
+    AuctionHouse,
+};
+
+use crate::{batch_sell::config::*, constants::*, errors::*, events::*, utils::*};
+
+#[derive(Accounts)]
+#[instruction(escrow_payment_bump: u8, token_size: u64)]
+pub struct AuctioneerBatchBid<'info> {
+    pub auction_house_program: Program<'info, AuctionHouseProgram>,
+    #[account(
+        seeds = [
+            BATCH_LISTING_CONFIG.as_bytes(),
+            seller.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_account.key().as_ref(),
+            auction_house.treasury_mint.as_ref(),
+            token_account.mint.as_ref(),
+            &token_size.to_le_bytes()
+        ],
+        bump = batch_listing_config.bump,
+    )]
+    pub batch_listing_config: Box<Account<'info, BatchListingConfig>>,
+    #[account(
+        mut,
+        seeds = [
+            BID_BOOK.as_bytes(),
+            batch_listing_config.key().as_ref()
+        ],
+        bump = bid_book.bump,
+    )]
+    pub bid_book: Box<Account<'info, BidBook>>,
+    pub seller: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+    pub token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            wallet.key().as_ref()
+        ],
+        seeds::program = auction_house_program,
+        bump = escrow_payment_bump
+    )]
+    pub escrow_payment_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            wallet.key().as_ref(),
+            ESCROW_LEDGER.as_bytes()
+        ],
+        seeds::program = auction_house_program,
+        bump
+    )]
+    pub escrow_ledger: UncheckedAccount<'info>,
+    pub treasury_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        seeds::program = auction_house_program,
+        bump = auction_house.bump,
+        has_one = treasury_mint,
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+    #[account(
+        seeds = [
+            AUCTIONEER.as_bytes(),
+            auction_house.key().as_ref()
+        ],
+        bump
+    )]
+    pub auctioneer_authority: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            AUCTIONEER.as_bytes(),
+            auction_house.key().as_ref(),
+            auctioneer_authority.key().as_ref()
+        ],
+        seeds::program = auction_house_program,
+        bump = auctioneer.bump,
+    )]
+    pub auctioneer: Account<'info, auction_house::Auctioneer>,
+
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn auctioneer_batch_bid(
+    ctx: Context<AuctioneerBatchBid>,
+    _escrow_payment_bump: u8,
+    token_size: u64,
+    price: u64,
+    quantity: u64,
+) -> Result<()> {
+    assert_batch_auction_active(&ctx.accounts.batch_listing_config)?;
+    if price == 0 || price < ctx.accounts.batch_listing_config.reserve_price {
+        return err!(AuctioneerError::BelowReservePrice);
+    }
+    if quantity == 0 || quantity > token_size {
+        return err!(AuctioneerError::InvalidBidQuantity);
+    }
+
+    // Winners settle without bidding again, so every bid is backed by locked escrow
+    let lock_amount = process_batch_bid(
+        &mut ctx.accounts.bid_book,
+        ctx.accounts.wallet.key(),
+        price,
+        quantity,
+    )?;
+
+    let ah_key = ctx.accounts.auction_house.key();
+    let auctioneer_authority_bump = *ctx
+        .bumps
+        .get("auctioneer_authority")
+        .ok_or(AuctioneerError::BumpSeedNotInHashMap)?;
+    let auctioneer_seeds = [
+        AUCTIONEER.as_bytes(),
+        ah_key.as_ref(),
+        &[auctioneer_authority_bump],
+    ];
+
+    let lock_accounts = AHLockEscrow {
+        wallet: ctx.accounts.wallet.to_account_info(),
+        escrow_payment_account: ctx.accounts.escrow_payment_account.to_account_info(),
+        escrow_ledger: ctx.accounts.escrow_ledger.to_account_info(),
+        treasury_mint: ctx.accounts.treasury_mint.to_account_info(),
+        auctioneer_authority: ctx.accounts.auctioneer_authority.to_account_info(),
+        auction_house: ctx.accounts.auction_house.to_account_info(),
+        auctioneer: ctx.accounts.auctioneer.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+        rent: ctx.accounts.rent.to_account_info(),
+    };
+    auction_house::cpi::auctioneer_lock_escrow(
+        CpiContext::new_with_signer(
+            ctx.accounts.auction_house_program.to_account_info(),
+            lock_accounts,
+            &[&auctioneer_seeds],
+        ),
+        lock_amount,
+    )?;
+
+    emit!(BatchBidPlaced {
+        batch_listing_config: ctx.accounts.batch_listing_config.key(),
+        bidder: ctx.accounts.wallet.key(),
+        price,
+        quantity,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use anchor_lang::prelude::*;
+
+pub const MAX_BATCH_BIDS: usize = 16;
+pub const BATCH_BID_SIZE: usize = 32 + 8 + 8 + 8 + 8 + 32 + 1;
+pub const BATCH_LISTING_CONFIG_SIZE: usize = 8 +            // Anchor discriminator/sighash
+8 +                                                         // start time
+8 +                                                         // end time
+8 +                                                         // reserve price
+8 +                                                         // token size
+8 +                                                         // clearing price
+1 +                                                         // cleared
+1                                                           // bump
+;
+pub const BID_BOOK_SIZE: usize = 8 +                        // Anchor discriminator/sighash
+32 +                                                        // batch listing config
+4 + MAX_BATCH_BIDS * BATCH_BID_SIZE +                       // bids
+1                                                           // bump
+;
+
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BatchBidState {
+    // Escrow of the full bid is locked until the auction clears
+    Open,
+    // The winner placed a trade state for the filled units at the clearing price
+    Settled,
+    // The filled units were sold to the winner
+    Executed,
+    // The bid lost and its escrow lock was released
+    Released,
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Debug)]
+pub struct BatchBid {
+    pub bidder: Pubkey,
+    // Price per unit
+    pub price: u64,
+    pub quantity: u64,
+    // Units won once the auction cleared
+    pub filled: u64,
+    pub locked_amount: u64,
+    pub buyer_trade_state: Pubkey,
+    pub state: BatchBidState,
+}
+
+#[account]
+pub struct BatchListingConfig {
+    pub start_time: i64,
+    pub end_time: i64,
+    // Minimum price per unit
+    pub reserve_price: u64,
+    pub token_size: u64,
+    // Uniform price per unit paid by every winner, the lowest winning bid
+    pub clearing_price: u64,
+    pub cleared: bool,
+    pub bump: u8,
+}
+
+#[account]
+pub struct BidBook {
+    pub batch_listing_config: Pubkey,
+    // Sorted by descending price per unit, ties go to the bid that was placed first
+    pub bids: Vec<BatchBid>,
+    pub bump: u8,
+}
@@ -0,0 +1,240 @@
+pub mod config;
+
+use crate::{batch_sell::config::*, constants::*, errors::*, utils::*};
+
+use anchor_lang::{
+    prelude::*, solana_program::program::invoke_signed, AnchorDeserialize, InstructionData,
+};
+use anchor_spl::token_interface::{TokenAccount, TokenInterface};
+
+use auction_house::{
+    self,
+    constants::{AUCTIONEER, FEE_PAYER, PREFIX, SIGNER},
+    cpi::accounts::AuctioneerSell as AHSell,
+    program::AuctionHouse as AuctionHouseProgram,
+    AuctionHouse,
+};
+
+#[derive(Accounts, Clone)]
+#[instruction(
+    trade_state_bump: u8,
+    free_trade_state_bump: u8,
+    program_as_signer_bump: u8,
+    auctioneer_authority_bump: u8,
+    token_size: u64
+)]
+pub struct AuctioneerBatchSell<'info> {
+    pub auction_house_program: Program<'info, AuctionHouseProgram>,
+    #[account(
+        init,
+        payer = wallet,
+        space = BATCH_LISTING_CONFIG_SIZE,
+        seeds = [
+            BATCH_LISTING_CONFIG.as_bytes(),
+            wallet.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_account.key().as_ref(),
+            auction_house.treasury_mint.as_ref(),
+            token_account.mint.as_ref(),
+            &token_size.to_le_bytes()
+        ],
+        bump,
+    )]
+    pub batch_listing_config: Box<Account<'info, BatchListingConfig>>,
+    #[account(
+        init,
+        payer = wallet,
+        space = BID_BOOK_SIZE,
+        seeds = [
+            BID_BOOK.as_bytes(),
+            batch_listing_config.key().as_ref()
+        ],
+        bump,
+    )]
+    pub bid_book: Box<Account<'info, BidBook>>,
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+    #[account(mut)]
+    pub token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub metadata: UncheckedAccount<'info>,
+    pub authority: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        seeds::program = auction_house_program,
+        bump = auction_house.bump,
+        has_one = auction_house_fee_account
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            FEE_PAYER.as_bytes()
+        ],
+        seeds::program = auction_house_program,
+        bump = auction_house.fee_payer_bump
+    )]
+    pub auction_house_fee_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            wallet.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_account.key().as_ref(),
+            auction_house.treasury_mint.as_ref(),
+            token_account.mint.as_ref(),
+            &u64::MAX.to_le_bytes(),
+            &token_size.to_le_bytes()
+        ],
+        seeds::program = auction_house_program,
+        bump = trade_state_bump
+    )]
+    pub seller_trade_state: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            wallet.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_account.key().as_ref(),
+            auction_house.treasury_mint.as_ref(),
+            token_account.mint.as_ref(),
+            &0u64.to_le_bytes(),
+            &token_size.to_le_bytes()
+        ],
+        seeds::program = auction_house_program,
+        bump = free_trade_state_bump
+    )]
+    pub free_seller_trade_state: UncheckedAccount<'info>,
+    pub auctioneer_authority: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            AUCTIONEER.as_bytes(),
+            auction_house.key().as_ref(),
+            auctioneer_authority.key().as_ref()
+        ],
+        seeds::program = auction_house_program,
+        bump = auctioneer.bump,
+    )]
+    pub auctioneer: Account<'info, auction_house::Auctioneer>,
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            SIGNER.as_bytes()
+        ],
+        seeds::program = auction_house_program,
+        bump = program_as_signer_bump
+    )]
+    pub program_as_signer: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn auctioneer_batch_sell(
+    ctx: Context<AuctioneerBatchSell>,
+    trade_state_bump: u8,
+    free_trade_state_bump: u8,
+    program_as_signer_bump: u8,
+    auctioneer_authority_bump: u8,
+    token_size: u64,
+    start_time: i64,
+    end_time: i64,
+    reserve_price: u64,
+) -> Result<()> {
+    if token_size < 2 {
+        return err!(AuctioneerError::InvalidBatchSize);
+    }
+    assert_valid_listing_params(
+        &ctx.accounts.auction_house,
+        start_time,
+        end_time,
+        reserve_price,
+        0,
+        0,
+        0,
+    )?;
+
+    let batch_listing_config = &mut ctx.accounts.batch_listing_config;
+    batch_listing_config.start_time = start_time;
+    batch_listing_config.end_time = end_time;
+    batch_listing_config.reserve_price = reserve_price;
+    batch_listing_config.token_size = token_size;
+    batch_listing_config.bump = *ctx
+        .bumps
+        .get("batch_listing_config")
+        .ok_or(AuctioneerError::BumpSeedNotInHashMap)?;
+
+    ctx.accounts.bid_book.batch_listing_config = ctx.accounts.batch_listing_config.key();
+    ctx.accounts.bid_book.bump = *ctx
+        .bumps
+        .get("bid_book")
+        .ok_or(AuctioneerError::BumpSeedNotInHashMap)?;
+
+    let cpi_program = ctx.accounts.auction_house_program.to_account_info();
+    let cpi_accounts = AHSell {
+        wallet: ctx.accounts.wallet.to_account_info(),
+        token_account: ctx.accounts.token_account.to_account_info(),
+        metadata: ctx.accounts.metadata.to_account_info(),
+        auction_house: ctx.accounts.auction_house.to_account_info(),
+        auction_house_fee_account: ctx.accounts.auction_house_fee_account.to_account_info(),
+        seller_trade_state: ctx.accounts.seller_trade_state.to_account_info(),
+        free_seller_trade_state: ctx.accounts.free_seller_trade_state.to_account_info(),
+        authority: ctx.accounts.authority.to_account_info(),
+        auctioneer_authority: ctx.accounts.auctioneer_authority.to_account_info(),
+        auctioneer: ctx.accounts.auctioneer.to_account_info(),
+        token_program: ctx.accounts.token_program.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+        program_as_signer: ctx.accounts.program_as_signer.to_account_info(),
+        rent: ctx.accounts.rent.to_account_info(),
+    };
+
+    let sell_data = auction_house::instruction::AuctioneerSell {
+        trade_state_bump,
+        free_trade_state_bump,
+        program_as_signer_bump,
+        token_size,
+    };
+
+    let mut cpi_account_metas: Vec<AccountMeta> = cpi_accounts
+        .to_account_metas(None)
+        .into_iter()
+        .zip(cpi_accounts.to_account_infos())
+        .map(|mut pair| {
+            pair.0.is_signer = pair.1.is_signer;
+            if pair.0.pubkey == ctx.accounts.auctioneer_authority.key() {
+                pair.0.is_signer = true;
+            }
+            pair.0
+        })
+        .collect();
+
+    cpi_account_metas.append(&mut ctx.remaining_accounts.to_vec().to_account_metas(None));
+
+    let mut cpi_account_infos: Vec<AccountInfo> = cpi_accounts.to_account_infos();
+    cpi_account_infos.append(&mut ctx.remaining_accounts.to_vec());
+
+    let ix = anchor_lang::solana_program::instruction::Instruction {
+        program_id: cpi_program.key(),
+        accounts: cpi_account_metas,
+        data: sell_data.data(),
+    };
+
+    let ah_key = ctx.accounts.auction_house.key();
+    let auctioneer_seeds = [
+        AUCTIONEER.as_bytes(),
+        ah_key.as_ref(),
+        &[auctioneer_authority_bump],
+    ];
+
+    invoke_signed(&ix, &cpi_account_infos, &[&auctioneer_seeds])?;
+
+    Ok(())
+}
@@ -1,15 +1,18 @@
 use anchor_lang::{prelude::*, AnchorDeserialize};
-use anchor_spl::token::{Mint, Token, TokenAccount};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 use auction_house::{
     self,
-    constants::{AUCTIONEER, FEE_PAYER, PREFIX},
-    cpi::accounts::AuctioneerBuy as AHBuy,
+    constants::{AUCTIONEER, ESCROW_LEDGER, FEE_PAYER, PREFIX},
+    cpi::accounts::{
+        AuctioneerBuy as AHBuy, AuctioneerLockEscrow as AHLockEscrow,
+        AuctioneerUnlockEscrow as AHUnlockEscrow,
+    },
     program::AuctionHouse as AuctionHouseProgram,
     AuctionHouse,
 };
 
-use crate::{constants::*, sell::config::*, utils::*};
+use crate::{constants::*, errors::*, events::*, sell::config::*, utils::*};
 
 #[derive(Accounts)]
 #[instruction(
@@ -36,12 +39,13 @@ pub struct AuctioneerBuy<'info> {
     )]
     pub listing_config: Account<'info, ListingConfig>,
     pub seller: UncheckedAccount<'info>,
+    #[account(mut)]
     wallet: Signer<'info>,
     #[account(mut)]
     payment_account: UncheckedAccount<'info>,
     transfer_authority: UncheckedAccount<'info>,
-    treasury_mint: Box<Account<'info, Mint>>,
-    token_account: Box<Account<'info, TokenAccount>>,
+    treasury_mint: Box<InterfaceAccount<'info, Mint>>,
+    token_account: Box<InterfaceAccount<'info, TokenAccount>>,
     metadata: UncheckedAccount<'info>,
     #[account(
         mut,
@@ -54,6 +58,30 @@ pub struct AuctioneerBuy<'info> {
         bump = escrow_payment_bump
     )]
     escrow_payment_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            wallet.key().as_ref(),
+            ESCROW_LEDGER.as_bytes()
+        ],
+        seeds::program = auction_house_program,
+        bump
+    )]
+    escrow_ledger: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            listing_config.highest_bidder.as_ref(),
+            ESCROW_LEDGER.as_bytes()
+        ],
+        seeds::program = auction_house_program,
+        bump
+    )]
+    highest_bidder_escrow_ledger: UncheckedAccount<'info>,
     authority: UncheckedAccount<'info>,
     #[account(
         seeds = [
@@ -107,7 +135,7 @@ pub struct AuctioneerBuy<'info> {
     )]
     pub auctioneer: Account<'info, auction_house::Auctioneer>,
 
-    token_program: Program<'info, Token>,
+    token_program: Interface<'info, TokenInterface>,
     system_program: Program<'info, System>,
     rent: Sysvar<'info, Rent>,
 }
@@ -121,12 +149,54 @@ pub fn auctioneer_buy(
     token_size: u64,
 ) -> Result<()> {
     assert_auction_active(&ctx.accounts.listing_config)?;
-    assert_higher_bid(&ctx.accounts.listing_config, buyer_price)?;
-    assert_exceeds_reserve_price(&ctx.accounts.listing_config, buyer_price)?;
-    process_time_extension(&mut ctx.accounts.listing_config)?;
-    ctx.accounts.listing_config.highest_bid.amount = buyer_price;
-    ctx.accounts.listing_config.highest_bid.buyer_trade_state =
-        ctx.accounts.buyer_trade_state.key();
+    let previous_bidder = ctx.accounts.listing_config.highest_bidder;
+    let wallet = ctx.accounts.wallet.key();
+    let buyer_trade_state = ctx.accounts.buyer_trade_state.key();
+    match ctx.accounts.listing_config.auction_type {
+        AuctionType::English => {
+            if is_buy_now_reached(&ctx.accounts.listing_config) {
+                return err!(AuctioneerError::AuctionAlreadySold);
+            }
+            assert_higher_bid(&ctx.accounts.listing_config, buyer_price)?;
+            assert_exceeds_reserve_price(&ctx.accounts.listing_config, buyer_price)?;
+            process_time_extension(&mut ctx.accounts.listing_config)?;
+            // The bid is still placed when a proxy bid outbids it right away
+            if process_proxy_defense(&mut ctx.accounts.listing_config, wallet, buyer_price)? {
+                record_runner_up_bid(
+                    &mut ctx.accounts.listing_config,
+                    wallet,
+                    buyer_price,
+                    buyer_trade_state,
+                );
+            } else {
+                process_highest_bid(
+                    &mut ctx.accounts.listing_config,
+                    wallet,
+                    buyer_price,
+                    buyer_trade_state,
+                );
+            }
+        }
+        AuctionType::Dutch => {
+            assert_dutch_auction_price(&ctx.accounts.listing_config, buyer_price)?;
+            process_highest_bid(
+                &mut ctx.accounts.listing_config,
+                wallet,
+                buyer_price,
+                buyer_trade_state,
+            );
+        }
+        AuctionType::SealedFirstPrice | AuctionType::SealedSecondPrice => {
+            return err!(AuctioneerError::BidMustBeSealed);
+        }
+    }
+
+    emit!(BidPlaced {
+        listing_config: ctx.accounts.listing_config.key(),
+        bidder: wallet,
+        amount: buyer_price,
+        is_proxy: false,
+    });
 
     let cpi_program = ctx.accounts.auction_house_program.to_account_info();
     let cpi_accounts = AHBuy {
@@ -137,6 +207,7 @@ pub fn auctioneer_buy(
         token_account: ctx.accounts.token_account.to_account_info(),
         metadata: ctx.accounts.metadata.to_account_info(),
         escrow_payment_account: ctx.accounts.escrow_payment_account.to_account_info(),
+        escrow_ledger: ctx.accounts.escrow_ledger.to_account_info(),
         auction_house: ctx.accounts.auction_house.to_account_info(),
         auction_house_fee_account: ctx.accounts.auction_house_fee_account.to_account_info(),
         buyer_trade_state: ctx.accounts.buyer_trade_state.to_account_info(),
@@ -159,6 +230,24 @@ pub fn auctioneer_buy(
         &[auctioneer_authority_bump],
     ];
 
+    // A bidder raising their own bid releases its lock first, the escrow then only has to be
+    // topped up by what the raise adds to it
+    if previous_bidder == wallet {
+        let own_unlock_accounts = AHUnlockEscrow {
+            escrow_ledger: ctx.accounts.highest_bidder_escrow_ledger.to_account_info(),
+            auctioneer_authority: ctx.accounts.auctioneer_authority.to_account_info(),
+            auction_house: ctx.accounts.auction_house.to_account_info(),
+            auctioneer: ctx.accounts.auctioneer.to_account_info(),
+        };
+        unlock_escrow(
+            ctx.accounts.auction_house_program.to_account_info(),
+            own_unlock_accounts,
+            &auctioneer_seeds,
+            ctx.accounts.listing_config.escrow_locked_amount,
+        )?;
+        ctx.accounts.listing_config.escrow_locked_amount = 0;
+    }
+
     let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
     auction_house::cpi::auctioneer_buy(
         cpi_ctx.with_signer(&[&auctioneer_seeds]),
@@ -166,5 +255,31 @@ pub fn auctioneer_buy(
         escrow_payment_bump,
         buyer_price,
         token_size,
+    )?;
+
+    let lock_accounts = AHLockEscrow {
+        wallet: ctx.accounts.wallet.to_account_info(),
+        escrow_payment_account: ctx.accounts.escrow_payment_account.to_account_info(),
+        escrow_ledger: ctx.accounts.escrow_ledger.to_account_info(),
+        treasury_mint: ctx.accounts.treasury_mint.to_account_info(),
+        auctioneer_authority: ctx.accounts.auctioneer_authority.to_account_info(),
+        auction_house: ctx.accounts.auction_house.to_account_info(),
+        auctioneer: ctx.accounts.auctioneer.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+        rent: ctx.accounts.rent.to_account_info(),
+    };
+    let unlock_accounts = AHUnlockEscrow {
+        escrow_ledger: ctx.accounts.highest_bidder_escrow_ledger.to_account_info(),
+        auctioneer_authority: ctx.accounts.auctioneer_authority.to_account_info(),
+        auction_house: ctx.accounts.auction_house.to_account_info(),
+        auctioneer: ctx.accounts.auctioneer.to_account_info(),
+    };
+    sync_escrow_lock(
+        &mut ctx.accounts.listing_config,
+        previous_bidder,
+        ctx.accounts.auction_house_program.to_account_info(),
+        lock_accounts,
+        unlock_accounts,
+        &auctioneer_seeds,
     )
 }
@@ -1,17 +1,17 @@
 use anchor_lang::{
     prelude::*, solana_program::program::invoke_signed, AnchorDeserialize, InstructionData,
 };
-use anchor_spl::token::{Mint, Token, TokenAccount};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 use auction_house::{
     self,
-    constants::{AUCTIONEER, FEE_PAYER, PREFIX},
-    cpi::accounts::AuctioneerCancel as AHCancel,
+    constants::{AUCTIONEER, ESCROW_LEDGER, FEE_PAYER, PREFIX, PROCEEDS_SPLIT, REFERRAL},
+    cpi::accounts::{AuctioneerCancel as AHCancel, AuctioneerUnlockEscrow as AHUnlockEscrow},
     program::AuctionHouse as AuctionHouseProgram,
     AuctionHouse,
 };
 
-use crate::{constants::*, errors::*, sell::config::*};
+use crate::{constants::*, errors::*, events::*, sell::config::*, utils::*};
 
 #[derive(Accounts, Clone)]
 #[instruction(
@@ -39,8 +39,8 @@ pub struct AuctioneerCancel<'info> {
     #[account(mut)]
     pub wallet: Signer<'info>,
     #[account(mut)]
-    pub token_account: Box<Account<'info, TokenAccount>>,
-    pub token_mint: Box<Account<'info, Mint>>,
+    pub token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
     pub authority: UncheckedAccount<'info>,
     #[account(
         seeds = [
@@ -67,6 +67,52 @@ pub struct AuctioneerCancel<'info> {
     pub auction_house_fee_account: UncheckedAccount<'info>,
     #[account(mut)]
     pub trade_state: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            trade_state.key().as_ref(),
+            PROCEEDS_SPLIT.as_bytes()
+        ],
+        seeds::program = auction_house_program,
+        bump
+    )]
+    pub proceeds_split: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            trade_state.key().as_ref(),
+            REFERRAL.as_bytes()
+        ],
+        seeds::program = auction_house_program,
+        bump
+    )]
+    pub referral: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            wallet.key().as_ref(),
+            ESCROW_LEDGER.as_bytes()
+        ],
+        seeds::program = auction_house_program,
+        bump
+    )]
+    pub escrow_ledger: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            listing_config.highest_bidder.as_ref(),
+            ESCROW_LEDGER.as_bytes()
+        ],
+        seeds::program = auction_house_program,
+        bump
+    )]
+    pub highest_bidder_escrow_ledger: UncheckedAccount<'info>,
     pub auctioneer_authority: UncheckedAccount<'info>,
     #[account(
         seeds = [
@@ -78,7 +124,7 @@ pub struct AuctioneerCancel<'info> {
         bump = auctioneer.bump,
     )]
     pub auctioneer: Account<'info, auction_house::Auctioneer>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 pub fn auctioneer_cancel(
@@ -99,6 +145,9 @@ pub fn auctioneer_cancel(
         auction_house: ctx.accounts.auction_house.to_account_info(),
         auction_house_fee_account: ctx.accounts.auction_house_fee_account.to_account_info(),
         trade_state: ctx.accounts.trade_state.to_account_info(),
+        proceeds_split: ctx.accounts.proceeds_split.to_account_info(),
+        referral: ctx.accounts.referral.to_account_info(),
+        escrow_ledger: ctx.accounts.escrow_ledger.to_account_info(),
         authority: ctx.accounts.authority.to_account_info(),
         auctioneer_authority: ctx.accounts.auctioneer_authority.to_account_info(),
         auctioneer: ctx.accounts.auctioneer.to_account_info(),
@@ -110,20 +159,27 @@ pub fn auctioneer_cancel(
         token_size,
     };
 
+    let mut cpi_account_metas: Vec<AccountMeta> = cpi_accounts
+        .to_account_metas(None)
+        .into_iter()
+        .zip(cpi_accounts.to_account_infos())
+        .map(|mut pair| {
+            pair.0.is_signer = pair.1.is_signer;
+            if pair.0.pubkey == ctx.accounts.auctioneer_authority.key() {
+                pair.0.is_signer = true;
+            }
+            pair.0
+        })
+        .collect();
+
+    cpi_account_metas.append(&mut ctx.remaining_accounts.to_vec().to_account_metas(None));
+
+    let mut cpi_account_infos: Vec<AccountInfo> = cpi_accounts.to_account_infos();
+    cpi_account_infos.append(&mut ctx.remaining_accounts.to_vec());
+
     let ix = anchor_lang::solana_program::instruction::Instruction {
         program_id: cpi_program.key(),
-        accounts: cpi_accounts
-            .to_account_metas(None)
-            .into_iter()
-            .zip(cpi_accounts.to_account_infos())
-            .map(|mut pair| {
-                pair.0.is_signer = pair.1.is_signer;
-                if pair.0.pubkey == ctx.accounts.auctioneer_authority.key() {
-                    pair.0.is_signer = true;
-                }
-                pair.0
-            })
-            .collect(),
+        accounts: cpi_account_metas,
         data: cancel_data.data(),
     };
 
@@ -138,11 +194,29 @@ pub fn auctioneer_cancel(
         &[auctioneer_authority_bump],
     ];
 
-    invoke_signed(&ix, &cpi_accounts.to_account_infos(), &[&auctioneer_seeds])?;
+    invoke_signed(&ix, &cpi_account_infos, &[&auctioneer_seeds])?;
 
     if ctx.accounts.token_account.owner == ctx.accounts.wallet.key()
         && ctx.accounts.wallet.is_signer
     {
+        emit!(ListingCancelled {
+            listing_config: ctx.accounts.listing_config.key(),
+            seller: ctx.accounts.wallet.key(),
+        });
+
+        let unlock_accounts = AHUnlockEscrow {
+            escrow_ledger: ctx.accounts.highest_bidder_escrow_ledger.to_account_info(),
+            auctioneer_authority: ctx.accounts.auctioneer_authority.to_account_info(),
+            auction_house: ctx.accounts.auction_house.to_account_info(),
+            auctioneer: ctx.accounts.auctioneer.to_account_info(),
+        };
+        unlock_escrow(
+            ctx.accounts.auction_house_program.to_account_info(),
+            unlock_accounts,
+            &auctioneer_seeds,
+            ctx.accounts.listing_config.escrow_locked_amount,
+        )?;
+
         let listing_config = &ctx.accounts.listing_config.to_account_info();
         let seller = &ctx.accounts.seller.to_account_info();
 
@@ -155,6 +229,15 @@ pub fn auctioneer_cancel(
 
         let mut source_data = listing_config.data.borrow_mut();
         source_data.fill(0);
+    } else {
+        let trade_state = ctx.accounts.trade_state.key();
+        remove_runner_up_bid(&mut ctx.accounts.listing_config, trade_state);
+
+        emit!(BidCancelled {
+            listing_config: ctx.accounts.listing_config.key(),
+            bidder: ctx.accounts.wallet.key(),
+            amount: buyer_price,
+        });
     }
 
     Ok(())
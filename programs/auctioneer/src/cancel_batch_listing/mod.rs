@@ -0,0 +1,208 @@
+use anchor_lang::{
+    prelude::*, solana_program::program::invoke_signed, AnchorDeserialize, InstructionData,
+};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use auction_house::{
+    self,
+    constants::{AUCTIONEER, ESCROW_LEDGER, FEE_PAYER, PREFIX, PROCEEDS_SPLIT, REFERRAL},
+    cpi::accounts::AuctioneerCancel as AHCancel,
+    program::AuctionHouse as AuctionHouseProgram,
+    AuctionHouse,
+};
+
+use crate::{batch_sell::config::*, constants::*, errors::*, events::*};
+
+#[derive(Accounts)]
+#[instruction(auctioneer_authority_bump: u8, token_size: u64)]
+pub struct AuctioneerCancelBatchListing<'info> {
+    pub auction_house_program: Program<'info, AuctionHouseProgram>,
+    #[account(
+        mut,
+        seeds = [
+            BATCH_LISTING_CONFIG.as_bytes(),
+            wallet.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_account.key().as_ref(),
+            auction_house.treasury_mint.as_ref(),
+            token_account.mint.as_ref(),
+            &token_size.to_le_bytes()
+        ],
+        bump = batch_listing_config.bump,
+        close = wallet
+    )]
+    pub batch_listing_config: Box<Account<'info, BatchListingConfig>>,
+    #[account(
+        mut,
+        seeds = [
+            BID_BOOK.as_bytes(),
+            batch_listing_config.key().as_ref()
+        ],
+        bump = bid_book.bump,
+        close = wallet
+    )]
+    pub bid_book: Box<Account<'info, BidBook>>,
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+    #[account(mut)]
+    pub token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub authority: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        seeds::program = auction_house_program,
+        bump = auction_house.bump,
+        has_one = authority,
+        has_one = auction_house_fee_account
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            FEE_PAYER.as_bytes()
+        ],
+        seeds::program = auction_house_program,
+        bump = auction_house.fee_payer_bump
+    )]
+    pub auction_house_fee_account: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub trade_state: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            trade_state.key().as_ref(),
+            PROCEEDS_SPLIT.as_bytes()
+        ],
+        seeds::program = auction_house_program,
+        bump
+    )]
+    pub proceeds_split: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            trade_state.key().as_ref(),
+            REFERRAL.as_bytes()
+        ],
+        seeds::program = auction_house_program,
+        bump
+    )]
+    pub referral: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            wallet.key().as_ref(),
+            ESCROW_LEDGER.as_bytes()
+        ],
+        seeds::program = auction_house_program,
+        bump
+    )]
+    pub escrow_ledger: UncheckedAccount<'info>,
+    pub auctioneer_authority: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            AUCTIONEER.as_bytes(),
+            auction_house.key().as_ref(),
+            auctioneer_authority.key().as_ref()
+        ],
+        seeds::program = auction_house_program,
+        bump = auctioneer.bump,
+    )]
+    pub auctioneer: Account<'info, auction_house::Auctioneer>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn auctioneer_cancel_batch_listing(
+    ctx: Context<AuctioneerCancelBatchListing>,
+    auctioneer_authority_bump: u8,
+    token_size: u64,
+) -> Result<()> {
+    // Bids are binding, the listing only closes before the first bid or once every bid
+    // was either executed or released after clearing
+    let bids = &ctx.accounts.bid_book.bids;
+    let has_outstanding_bids = if ctx.accounts.batch_listing_config.cleared {
+        bids.iter()
+            .any(|bid| matches!(bid.state, BatchBidState::Open | BatchBidState::Settled))
+    } else {
+        !bids.is_empty()
+    };
+    if has_outstanding_bids {
+        return err!(AuctioneerError::BatchBidsOutstanding);
+    }
+
+    // The auction house closes the seller trade state itself once every unit is sold
+    if ctx.accounts.trade_state.data_is_empty() {
+        return Ok(());
+    }
+
+    let cpi_program = ctx.accounts.auction_house_program.to_account_info();
+    let cpi_accounts = AHCancel {
+        wallet: ctx.accounts.wallet.to_account_info(),
+        token_account: ctx.accounts.token_account.to_account_info(),
+        token_mint: ctx.accounts.token_mint.to_account_info(),
+        auction_house: ctx.accounts.auction_house.to_account_info(),
+        auction_house_fee_account: ctx.accounts.auction_house_fee_account.to_account_info(),
+        trade_state: ctx.accounts.trade_state.to_account_info(),
+        proceeds_split: ctx.accounts.proceeds_split.to_account_info(),
+        referral: ctx.accounts.referral.to_account_info(),
+        escrow_ledger: ctx.accounts.escrow_ledger.to_account_info(),
+        authority: ctx.accounts.authority.to_account_info(),
+        auctioneer_authority: ctx.accounts.auctioneer_authority.to_account_info(),
+        auctioneer: ctx.accounts.auctioneer.to_account_info(),
+        token_program: ctx.accounts.token_program.to_account_info(),
+    };
+
+    let cancel_data = auction_house::instruction::AuctioneerCancel {
+        buyer_price: AUCTIONEER_BUYER_PRICE,
+        token_size,
+    };
+
+    let mut cpi_account_metas: Vec<AccountMeta> = cpi_accounts
+        .to_account_metas(None)
+        .into_iter()
+        .zip(cpi_accounts.to_account_infos())
+        .map(|mut pair| {
+            pair.0.is_signer = pair.1.is_signer;
+            if pair.0.pubkey == ctx.accounts.auctioneer_authority.key() {
+                pair.0.is_signer = true;
+            }
+            pair.0
+        })
+        .collect();
+
+    cpi_account_metas.append(&mut ctx.remaining_accounts.to_vec().to_account_metas(None));
+
+    let mut cpi_account_infos: Vec<AccountInfo> = cpi_accounts.to_account_infos();
+    cpi_account_infos.append(&mut ctx.remaining_accounts.to_vec());
+
+    let ix = anchor_lang::solana_program::instruction::Instruction {
+        program_id: cpi_program.key(),
+        accounts: cpi_account_metas,
+        data: cancel_data.data(),
+    };
+
+    let ah_key = ctx.accounts.auction_house.key();
+    let auctioneer_seeds = [
+        AUCTIONEER.as_bytes(),
+        ah_key.as_ref(),
+        &[auctioneer_authority_bump],
+    ];
+
+    invoke_signed(&ix, &cpi_account_infos, &[&auctioneer_seeds])?;
+
+    emit!(ListingCancelled {
+        listing_config: ctx.accounts.batch_listing_config.key(),
+        seller: ctx.accounts.wallet.key(),
+    });
+
+    Ok(())
+}
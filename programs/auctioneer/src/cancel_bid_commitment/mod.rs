@@ -0,0 +1,103 @@
+use anchor_lang::{prelude::*, AnchorDeserialize};
+
+use auction_house::{
+    self,
+    constants::{AUCTIONEER, ESCROW_LEDGER, PREFIX},
+    cpi::accounts::AuctioneerUnlockEscrow as AHUnlockEscrow,
+    program::AuctionHouse as AuctionHouseProgram,
+    AuctionHouse,
+};
+
+use crate::{commit_bid::config::*, constants::*, errors::*, sell::config::*, utils::*};
+
+#[derive(Accounts)]
+#[instruction(auctioneer_authority_bump: u8)]
+pub struct AuctioneerCancelBidCommitment<'info> {
+    pub auction_house_program: Program<'info, AuctionHouseProgram>,
+    // Closed once the listing is sold or cancelled
+    #[account(mut, address = bid_commitment.listing_config)]
+    pub listing_config: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            BID_COMMITMENT.as_bytes(),
+            listing_config.key().as_ref(),
+            wallet.key().as_ref()
+        ],
+        bump = bid_commitment.bump,
+        close = wallet,
+    )]
+    pub bid_commitment: Account<'info, BidCommitment>,
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            wallet.key().as_ref(),
+            ESCROW_LEDGER.as_bytes()
+        ],
+        seeds::program = auction_house_program,
+        bump
+    )]
+    pub escrow_ledger: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        seeds::program = auction_house_program,
+        bump = auction_house.bump,
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+    pub auctioneer_authority: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            AUCTIONEER.as_bytes(),
+            auction_house.key().as_ref(),
+            auctioneer_authority.key().as_ref()
+        ],
+        seeds::program = auction_house_program,
+        bump = auctioneer.bump,
+    )]
+    pub auctioneer: Account<'info, auction_house::Auctioneer>,
+}
+
+// Releases the deposit of a sealed bid that was never revealed
+pub fn auctioneer_cancel_bid_commitment(
+    ctx: Context<AuctioneerCancelBidCommitment>,
+    auctioneer_authority_bump: u8,
+) -> Result<()> {
+    let listing_config_info = ctx.accounts.listing_config.to_account_info();
+    if *listing_config_info.owner == crate::id() && !listing_config_info.data_is_empty() {
+        let mut listing_config: Account<ListingConfig> = Account::try_from(&listing_config_info)?;
+        assert_reveal_over(&listing_config)?;
+
+        listing_config.bid_commitments = listing_config
+            .bid_commitments
+            .checked_sub(1)
+            .ok_or(AuctioneerError::NumericalOverflow)?;
+        listing_config.exit(&crate::id())?;
+    }
+
+    let ah_key = ctx.accounts.auction_house.key();
+    let auctioneer_seeds = [
+        AUCTIONEER.as_bytes(),
+        ah_key.as_ref(),
+        &[auctioneer_authority_bump],
+    ];
+    let unlock_accounts = AHUnlockEscrow {
+        escrow_ledger: ctx.accounts.escrow_ledger.to_account_info(),
+        auctioneer_authority: ctx.accounts.auctioneer_authority.to_account_info(),
+        auction_house: ctx.accounts.auction_house.to_account_info(),
+        auctioneer: ctx.accounts.auctioneer.to_account_info(),
+    };
+    unlock_escrow(
+        ctx.accounts.auction_house_program.to_account_info(),
+        unlock_accounts,
+        &auctioneer_seeds,
+        ctx.accounts.bid_commitment.deposit_amount,
+    )
+}
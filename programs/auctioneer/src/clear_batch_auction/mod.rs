@@ -0,0 +1,44 @@
+use anchor_lang::{prelude::*, AnchorDeserialize};
+
+use crate::{batch_sell::config::*, constants::*, errors::*, events::*, utils::*};
+
+#[derive(Accounts)]
+pub struct AuctioneerClearBatchAuction<'info> {
+    #[account(mut)]
+    pub batch_listing_config: Box<Account<'info, BatchListingConfig>>,
+    #[account(
+        mut,
+        seeds = [
+            BID_BOOK.as_bytes(),
+            batch_listing_config.key().as_ref()
+        ],
+        bump = bid_book.bump,
+    )]
+    pub bid_book: Box<Account<'info, BidBook>>,
+}
+
+// Anyone can clear an ended batch auction, winners and losers then settle their own bids
+pub fn auctioneer_clear_batch_auction(ctx: Context<AuctioneerClearBatchAuction>) -> Result<()> {
+    let clock = Clock::get()?;
+    if clock.unix_timestamp < ctx.accounts.batch_listing_config.end_time {
+        return err!(AuctioneerError::AuctionActive);
+    }
+    if ctx.accounts.batch_listing_config.cleared {
+        return err!(AuctioneerError::AuctionAlreadyCleared);
+    }
+
+    let (clearing_price, units_sold) = clear_batch_bids(
+        &mut ctx.accounts.bid_book,
+        ctx.accounts.batch_listing_config.token_size,
+    );
+    ctx.accounts.batch_listing_config.clearing_price = clearing_price;
+    ctx.accounts.batch_listing_config.cleared = true;
+
+    emit!(BatchAuctionCleared {
+        batch_listing_config: ctx.accounts.batch_listing_config.key(),
+        clearing_price,
+        units_sold,
+    });
+
+    Ok(())
+}
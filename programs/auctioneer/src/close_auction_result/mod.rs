@@ -0,0 +1,25 @@
+use anchor_lang::{prelude::*, AnchorDeserialize};
+
+use crate::{constants::*, execute_sale::config::*};
+
+#[derive(Accounts)]
+pub struct AuctioneerCloseAuctionResult<'info> {
+    #[account(
+        mut,
+        seeds = [
+            AUCTION_RESULT.as_bytes(),
+            auction_result.listing_config.as_ref(),
+            auction_result.buyer_trade_state.as_ref()
+        ],
+        bump = auction_result.bump,
+        has_one = seller,
+        close = seller
+    )]
+    pub auction_result: Account<'info, AuctionResult>,
+    #[account(mut)]
+    pub seller: Signer<'info>,
+}
+
+pub fn auctioneer_close_auction_result(_ctx: Context<AuctioneerCloseAuctionResult>) -> Result<()> {
+    Ok(())
+}
@@ -0,0 +1,23 @@
+use anchor_lang::prelude::*;
+
+pub const BID_COMMITMENT_SIZE: usize = 8 +                  // Anchor discriminator/sighash
+32 +                                                        // listing config
+32 +                                                        // wallet
+32 +                                                        // commitment
+8 +                                                         // deposit amount
+1 +                                                         // bump
+8                                                           // escrow amount
+;
+
+#[account]
+pub struct BidCommitment {
+    pub listing_config: Pubkey,
+    pub wallet: Pubkey,
+    // keccak(amount, salt, wallet)
+    pub commitment: [u8; 32],
+    // Highest bid the deposit backs, locked until the reveal
+    pub deposit_amount: u64,
+    pub bump: u8,
+    // What was deposited for it, the buyer's premium included
+    pub escrow_amount: u64,
+}
@@ -0,0 +1,211 @@
+pub mod config;
+
+use anchor_lang::{prelude::*, AnchorDeserialize};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use auction_house::{
+    self,
+    constants::{AUCTIONEER, ESCROW_LEDGER, FEE_PAYER, PREFIX},
+    cpi::accounts::{AuctioneerDeposit as AHDeposit, AuctioneerLockEscrow as AHLockEscrow},
+    program::AuctionHouse as AuctionHouseProgram,
+    AuctionHouse,
+};
+
+use crate::{commit_bid::config::*, constants::*, errors::*, sell::config::*, utils::*};
+
+#[derive(Accounts)]
+#[instruction(
+    escrow_payment_bump: u8,
+    auctioneer_authority_bump: u8,
+    token_size: u64
+)]
+pub struct AuctioneerCommitBid<'info> {
+    pub auction_house_program: Program<'info, AuctionHouseProgram>,
+    #[account(
+        mut,
+        seeds = [
+            LISTING_CONFIG.as_bytes(),
+            seller.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_account.key().as_ref(),
+            auction_house.treasury_mint.as_ref(),
+            token_account.mint.as_ref(),
+            &token_size.to_le_bytes()
+        ],
+        bump = listing_config.bump,
+    )]
+    pub listing_config: Box<Account<'info, ListingConfig>>,
+    pub seller: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = wallet,
+        space = BID_COMMITMENT_SIZE,
+        seeds = [
+            BID_COMMITMENT.as_bytes(),
+            listing_config.key().as_ref(),
+            wallet.key().as_ref()
+        ],
+        bump,
+    )]
+    pub bid_commitment: Account<'info, BidCommitment>,
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+    #[account(mut)]
+    pub payment_account: UncheckedAccount<'info>,
+    pub transfer_authority: UncheckedAccount<'info>,
+    pub token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            wallet.key().as_ref()
+        ],
+        seeds::program = auction_house_program,
+        bump = escrow_payment_bump
+    )]
+    pub escrow_payment_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            wallet.key().as_ref(),
+            ESCROW_LEDGER.as_bytes()
+        ],
+        seeds::program = auction_house_program,
+        bump
+    )]
+    pub escrow_ledger: UncheckedAccount<'info>,
+    pub treasury_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub authority: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        seeds::program = auction_house_program,
+        bump = auction_house.bump,
+        has_one = authority,
+        has_one = treasury_mint,
+        has_one = auction_house_fee_account
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            FEE_PAYER.as_bytes()
+        ],
+        seeds::program = auction_house_program,
+        bump = auction_house.fee_payer_bump
+    )]
+    pub auction_house_fee_account: UncheckedAccount<'info>,
+    pub auctioneer_authority: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            AUCTIONEER.as_bytes(),
+            auction_house.key().as_ref(),
+            auctioneer_authority.key().as_ref()
+        ],
+        seeds::program = auction_house_program,
+        bump = auctioneer.bump,
+    )]
+    pub auctioneer: Account<'info, auction_house::Auctioneer>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn auctioneer_commit_bid(
+    ctx: Context<AuctioneerCommitBid>,
+    escrow_payment_bump: u8,
+    auctioneer_authority_bump: u8,
+    _token_size: u64,
+    commitment: [u8; 32],
+    deposit_amount: u64,
+) -> Result<()> {
+    if !is_sealed_bid_auction(&ctx.accounts.listing_config) {
+        return err!(AuctioneerError::NotSealedBidAuction);
+    }
+    assert_auction_active(&ctx.accounts.listing_config)?;
+
+    ctx.accounts.bid_commitment.listing_config = ctx.accounts.listing_config.key();
+    ctx.accounts.bid_commitment.wallet = ctx.accounts.wallet.key();
+    ctx.accounts.bid_commitment.commitment = commitment;
+    ctx.accounts.bid_commitment.deposit_amount = deposit_amount;
+    ctx.accounts.bid_commitment.bump = *ctx
+        .bumps
+        .get("bid_commitment")
+        .ok_or(AuctioneerError::BumpSeedNotInHashMap)?;
+
+    let cpi_program = ctx.accounts.auction_house_program.to_account_info();
+    let cpi_accounts = AHDeposit {
+        wallet: ctx.accounts.wallet.to_account_info(),
+        payment_account: ctx.accounts.payment_account.to_account_info(),
+        transfer_authority: ctx.accounts.transfer_authority.to_account_info(),
+        escrow_payment_account: ctx.accounts.escrow_payment_account.to_account_info(),
+        treasury_mint: ctx.accounts.treasury_mint.to_account_info(),
+        auction_house: ctx.accounts.auction_house.to_account_info(),
+        auction_house_fee_account: ctx.accounts.auction_house_fee_account.to_account_info(),
+        authority: ctx.accounts.authority.to_account_info(),
+        auctioneer_authority: ctx.accounts.auctioneer_authority.to_account_info(),
+        auctioneer: ctx.accounts.auctioneer.to_account_info(),
+        token_program: ctx.accounts.token_program.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+        rent: ctx.accounts.rent.to_account_info(),
+    };
+
+    let auction_house = &ctx.accounts.auction_house;
+    let ah_key = auction_house.key();
+
+    let auctioneer_seeds = [
+        AUCTIONEER.as_bytes(),
+        ah_key.as_ref(),
+        &[auctioneer_authority_bump],
+    ];
+
+    // The deposit backs bids up to deposit_amount, so it also has to fund their buyer's premium
+    let escrow_requirement = get_escrow_requirement(auction_house, deposit_amount)?;
+    ctx.accounts.bid_commitment.escrow_amount = escrow_requirement;
+
+    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+    auction_house::cpi::auctioneer_deposit(
+        cpi_ctx.with_signer(&[&auctioneer_seeds]),
+        escrow_payment_bump,
+        escrow_requirement,
+    )?;
+
+    // The deposit stays locked until the bid is revealed, so the winner can not withdraw it
+    let lock_accounts = AHLockEscrow {
+        wallet: ctx.accounts.wallet.to_account_info(),
+        escrow_payment_account: ctx.accounts.escrow_payment_account.to_account_info(),
+        escrow_ledger: ctx.accounts.escrow_ledger.to_account_info(),
+        treasury_mint: ctx.accounts.treasury_mint.to_account_info(),
+        auctioneer_authority: ctx.accounts.auctioneer_authority.to_account_info(),
+        auction_house: ctx.accounts.auction_house.to_account_info(),
+        auctioneer: ctx.accounts.auctioneer.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+        rent: ctx.accounts.rent.to_account_info(),
+    };
+    auction_house::cpi::auctioneer_lock_escrow(
+        CpiContext::new_with_signer(
+            ctx.accounts.auction_house_program.to_account_info(),
+            lock_accounts,
+            &[&auctioneer_seeds],
+        ),
+        deposit_amount,
+    )?;
+
+    ctx.accounts.listing_config.bid_commitments = ctx
+        .accounts
+        .listing_config
+        .bid_commitments
+        .checked_add(1)
+        .ok_or(AuctioneerError::NumericalOverflow)?;
+
+    Ok(())
+}
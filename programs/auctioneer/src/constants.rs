@@ -1,2 +1,15 @@
 pub const LISTING_CONFIG: &str = "listing_config";
 pub const AUCTIONEER_BUYER_PRICE: u64 = u64::MAX;
+pub const BID_COMMITMENT: &str = "bid_commitment";
+pub const AUCTION_RESULT: &str = "auction_result";
+pub const BATCH_LISTING_CONFIG: &str = "batch_listing_config";
+pub const BID_BOOK: &str = "bid_book";
+pub const AUCTIONEER_CONFIG: &str = "auctioneer_config";
+// Cap on the total time extension when the auction house does not set one
+pub const DEFAULT_MAX_TIME_EXTENSION: i64 = 86400;
+// Paid out of the reclaimed listing rent to a third party settling an ended auction
+pub const SETTLEMENT_BOUNTY: u64 = 1_000_000;
+// Time the winner has to settle before a funded runner-up bid can take their place
+pub const RUNNER_UP_GRACE_PERIOD: i64 = 86400;
+// Time the winners of a batch auction have to settle before their bids can be released
+pub const BATCH_SETTLEMENT_PERIOD: i64 = 86400;
@@ -1,5 +1,5 @@
 use anchor_lang::{prelude::*, AnchorDeserialize};
-use anchor_spl::token::{Mint, Token};
+use anchor_spl::token_interface::{Mint, TokenInterface};
 
 use auction_house::{
     self,
@@ -31,7 +31,7 @@ pub struct AuctioneerDeposit<'info> {
         bump = escrow_payment_bump
     )]
     pub escrow_payment_account: UncheckedAccount<'info>,
-    pub treasury_mint: Box<Account<'info, Mint>>,
+    pub treasury_mint: Box<InterfaceAccount<'info, Mint>>,
     pub authority: UncheckedAccount<'info>,
     #[account(
         seeds = [
@@ -69,7 +69,7 @@ pub struct AuctioneerDeposit<'info> {
     )]
     pub auctioneer: Account<'info, auction_house::Auctioneer>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
@@ -41,4 +41,168 @@ pub enum AuctioneerError {
     // 6009
     #[msg("The highest bidder is not allowed to cancel")]
     CannotCancelHighestBid,
+
+    // 6010
+    #[msg("The bid must be at or above the current Dutch auction price")]
+    BelowDutchAuctionPrice,
+
+    // 6011
+    #[msg("Dutch auction start price must not be below the floor price and the decay interval must be positive")]
+    InvalidDutchAuctionParams,
+
+    // 6012
+    #[msg("The auction has already been sold")]
+    AuctionAlreadySold,
+
+    // 6013
+    #[msg("The buy now price must be positive, not below the reserve price and is not supported by Dutch auctions")]
+    InvalidBuyNowPrice,
+
+    // 6014
+    #[msg("The reveal end time must be after the end time and sealed bids cannot be combined with other auction types")]
+    InvalidSealedBidParams,
+
+    // 6015
+    #[msg("Sealed-bid auctions only accept committed bids")]
+    BidMustBeSealed,
+
+    // 6016
+    #[msg("The listing is not a sealed-bid auction")]
+    NotSealedBidAuction,
+
+    // 6017
+    #[msg("The reveal period is not active")]
+    RevealPeriodNotActive,
+
+    // 6018
+    #[msg("The reveal period has not ended yet")]
+    RevealPeriodActive,
+
+    // 6019
+    #[msg("The revealed bid does not match the commitment")]
+    InvalidBidReveal,
+
+    // 6020
+    #[msg("The revealed bid exceeds the amount deposited with the commitment")]
+    BidNotBackedByDeposit,
+
+    // 6021
+    #[msg("Only the winning bidder can settle the auction")]
+    NotWinningBidder,
+
+    // 6022
+    #[msg("The escrow does not cover the proxy bid maximum")]
+    InsufficientProxyBidEscrow,
+
+    // 6023
+    #[msg("Proxy bids are only supported by English auctions")]
+    NotEnglishAuction,
+
+    // 6024
+    #[msg("The listing can no longer be updated once a bid was placed")]
+    ListingHasBids,
+
+    // 6025
+    #[msg("The auction must end after it starts")]
+    EndBeforeStart,
+
+    // 6026
+    #[msg("The auction end time is in the past")]
+    EndTimeInPast,
+
+    // 6027
+    #[msg("The auction is shorter than the auction house minimum duration")]
+    AuctionTooShort,
+
+    // 6028
+    #[msg("The auction is longer than the auction house maximum duration")]
+    AuctionTooLong,
+
+    // 6029
+    #[msg("Invalid time extension period or delta")]
+    InvalidTimeExtension,
+
+    // 6030
+    #[msg("Invalid reserve price or bid increment")]
+    InvalidBidIncrement,
+
+    // 6031
+    #[msg("Numerical overflow")]
+    NumericalOverflow,
+
+    // 6032
+    #[msg("Auction house did not return the sale amounts")]
+    MissingSaleAmounts,
+
+    // 6033
+    #[msg("No funded runner-up bid to promote")]
+    NoRunnerUpBid,
+
+    // 6034
+    #[msg("The winning bidder can still settle the auction")]
+    WinnerCanStillSettle,
+
+    // 6035
+    #[msg("Escrow account does not belong to the runner-up bidder")]
+    InvalidRunnerUpEscrow,
+
+    // 6036
+    #[msg("Batch auctions need a token size above one")]
+    InvalidBatchSize,
+
+    // 6037
+    #[msg("The bid quantity must be positive and not exceed the listing size")]
+    InvalidBidQuantity,
+
+    // 6038
+    #[msg("The bid book is full")]
+    BidBookFull,
+
+    // 6039
+    #[msg("The batch auction has not been cleared yet")]
+    AuctionNotCleared,
+
+    // 6040
+    #[msg("The batch auction has already been cleared")]
+    AuctionAlreadyCleared,
+
+    // 6041
+    #[msg("No bid of this bidder in the bid book")]
+    BatchBidNotFound,
+
+    // 6042
+    #[msg("The batch bid is not in the required state")]
+    InvalidBatchBidState,
+
+    // 6043
+    #[msg("The batch listing still has outstanding bids")]
+    BatchBidsOutstanding,
+
+    // 6044
+    #[msg("Auctioneer fee exceeds the maximum allowed by the auction house")]
+    InvalidAuctioneerFee,
+
+    // 6045
+    #[msg("Auctioneer fee destination must hold the treasury mint")]
+    InvalidAuctioneerFeeDestination,
+
+    // 6046
+    #[msg("The listing config is already on the current version")]
+    ListingConfigAlreadyMigrated,
+
+    // 6047
+    #[msg("The highest bidder does not match the trade state of the highest bid")]
+    InvalidHighestBidder,
+
+    // 6048
+    #[msg("Reserve price and end time can not change while sealed bids are committed")]
+    ListingHasBidCommitments,
+
+    // 6049
+    #[msg("The result of this sale has already been recorded")]
+    AuctionResultAlreadyExists,
+
+    // 6050
+    #[msg("The escrow locked for this listing does not cover the winning bid")]
+    ListingEscrowNotLocked,
 }
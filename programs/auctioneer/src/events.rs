@@ -0,0 +1,113 @@
+use anchor_lang::prelude::*;
+
+use crate::sell::config::AuctionType;
+
+#[event]
+pub struct ListingCreated {
+    pub listing_config: Pubkey,
+    pub auction_house: Pubkey,
+    pub seller: Pubkey,
+    pub token_mint: Pubkey,
+    pub auction_type: AuctionType,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub reserve_price: u64,
+}
+
+#[event]
+pub struct AuctionSettled {
+    pub listing_config: Pubkey,
+    pub auction_house: Pubkey,
+    pub seller: Pubkey,
+    pub winner: Pubkey,
+    pub price: u64,
+    pub royalty: u64,
+    pub auction_house_fee: u64,
+    pub buyer_premium: u64,
+    pub auctioneer_fee: u64,
+    pub seller_proceeds: u64,
+}
+
+#[event]
+pub struct AuctioneerConfigUpdated {
+    pub auction_house: Pubkey,
+    pub fee_basis_points: u16,
+    pub fee_destination: Pubkey,
+}
+
+#[event]
+pub struct ListingUpdated {
+    pub listing_config: Pubkey,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub reserve_price: u64,
+    pub min_bid_increment: u64,
+}
+
+#[event]
+pub struct ListingCancelled {
+    pub listing_config: Pubkey,
+    pub seller: Pubkey,
+}
+
+#[event]
+pub struct BidPlaced {
+    pub listing_config: Pubkey,
+    pub bidder: Pubkey,
+    pub amount: u64,
+    // True for proxy bids, amount is then the standing bid so the maximum stays hidden
+    pub is_proxy: bool,
+}
+
+#[event]
+pub struct BidCancelled {
+    pub listing_config: Pubkey,
+    pub bidder: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct Outbid {
+    pub listing_config: Pubkey,
+    pub bidder: Pubkey,
+    pub highest_bidder: Pubkey,
+    pub highest_bid: u64,
+}
+
+#[event]
+pub struct RunnerUpPromoted {
+    pub listing_config: Pubkey,
+    pub previous_bidder: Pubkey,
+    pub highest_bidder: Pubkey,
+    pub highest_bid: u64,
+}
+
+#[event]
+pub struct BatchBidPlaced {
+    pub batch_listing_config: Pubkey,
+    pub bidder: Pubkey,
+    pub price: u64,
+    pub quantity: u64,
+}
+
+#[event]
+pub struct BatchAuctionCleared {
+    pub batch_listing_config: Pubkey,
+    pub clearing_price: u64,
+    pub units_sold: u64,
+}
+
+#[event]
+pub struct BatchBidReleased {
+    pub batch_listing_config: Pubkey,
+    pub bidder: Pubkey,
+    // Units the bid had won, forfeited when the winner did not settle in time
+    pub filled: u64,
+    pub amount: u64,
+}
+
+#[event]
+pub struct TimeExtended {
+    pub listing_config: Pubkey,
+    pub end_time: i64,
+}
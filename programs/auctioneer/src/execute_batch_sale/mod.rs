@@ -0,0 +1,362 @@
+use anchor_lang::{
+    prelude::*, solana_program::program::invoke_signed, AnchorDeserialize, InstructionData,
+};
+use anchor_spl::{associated_token::AssociatedToken, token_interface::TokenInterface};
+
+use auction_house::{
+    self,
+    constants::{
+        AUCTIONEER, ESCROW_LEDGER, FEE_PAYER, PREFIX, PROCEEDS_SPLIT, REFERRAL, SIGNER, TREASURY,
+    },
+    cpi::accounts::{
+        AuctioneerExecuteSale as AHExecuteSale, AuctioneerUnlockEscrow as AHUnlockEscrow,
+    },
+    program::AuctionHouse as AuctionHouseProgram,
+    AuctionHouse,
+};
+
+use crate::{authorize::config::*, batch_sell::config::*, constants::*, errors::*, utils::*};
+
+#[derive(Accounts)]
+#[instruction(
+    escrow_payment_bump: u8,
+    free_trade_state_bump: u8,
+    program_as_signer_bump: u8,
+    auctioneer_authority_bump: u8,
+    token_size: u64
+)]
+pub struct AuctioneerExecuteBatchSale<'info> {
+    pub auction_house_program: Program<'info, AuctionHouseProgram>,
+    #[account(
+        seeds = [
+            BATCH_LISTING_CONFIG.as_bytes(),
+            seller.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_account.key().as_ref(),
+            auction_house.treasury_mint.as_ref(),
+            token_mint.key().as_ref(),
+            &token_size.to_le_bytes()
+        ],
+        bump = batch_listing_config.bump,
+    )]
+    pub batch_listing_config: Box<Account<'info, BatchListingConfig>>,
+    #[account(
+        mut,
+        seeds = [
+            BID_BOOK.as_bytes(),
+            batch_listing_config.key().as_ref()
+        ],
+        bump = bid_book.bump,
+    )]
+    pub bid_book: Box<Account<'info, BidBook>>,
+    #[account(mut)]
+    pub buyer: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub seller: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub token_account: UncheckedAccount<'info>,
+    pub token_mint: UncheckedAccount<'info>,
+    pub metadata: UncheckedAccount<'info>,
+    pub treasury_mint: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            buyer.key().as_ref()
+        ],
+        seeds::program = auction_house_program,
+        bump = escrow_payment_bump
+    )]
+    pub escrow_payment_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            buyer.key().as_ref(),
+            ESCROW_LEDGER.as_bytes()
+        ],
+        seeds::program = auction_house_program,
+        bump
+    )]
+    pub escrow_ledger: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub seller_payment_receipt_account: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub buyer_receipt_token_account: UncheckedAccount<'info>,
+    pub authority: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        seeds::program = auction_house_program,
+        bump = auction_house.bump,
+        has_one = treasury_mint,
+        has_one = auction_house_treasury,
+        has_one = auction_house_fee_account
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            FEE_PAYER.as_bytes()
+        ],
+        seeds::program = auction_house_program,
+        bump = auction_house.fee_payer_bump
+    )]
+    pub auction_house_fee_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            TREASURY.as_bytes()
+        ],
+        seeds::program = auction_house_program,
+        bump = auction_house.treasury_bump
+    )]
+    pub auction_house_treasury: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub buyer_trade_state: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            seller.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_account.key().as_ref(),
+            auction_house.treasury_mint.as_ref(),
+            token_mint.key().as_ref(),
+            &u64::MAX.to_le_bytes(),
+            &token_size.to_le_bytes()
+        ],
+        seeds::program = auction_house_program,
+        bump = seller_trade_state.to_account_info().data.borrow()[0]
+    )]
+    pub seller_trade_state: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            seller.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_account.key().as_ref(),
+            auction_house.treasury_mint.as_ref(),
+            token_mint.key().as_ref(),
+            &0u64.to_le_bytes(),
+            &token_size.to_le_bytes()
+        ],
+        seeds::program = auction_house_program,
+        bump = free_trade_state_bump
+    )]
+    pub free_trade_state: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            seller_trade_state.key().as_ref(),
+            PROCEEDS_SPLIT.as_bytes()
+        ],
+        seeds::program = auction_house_program,
+        bump
+    )]
+    pub proceeds_split: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            seller_trade_state.key().as_ref(),
+            REFERRAL.as_bytes()
+        ],
+        seeds::program = auction_house_program,
+        bump
+    )]
+    pub referral: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            AUCTIONEER.as_bytes(),
+            auction_house.key().as_ref()
+        ],
+        bump = auctioneer_authority_bump
+    )]
+    pub auctioneer_authority: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            AUCTIONEER.as_bytes(),
+            auction_house.key().as_ref(),
+            auctioneer_authority.key().as_ref()
+        ],
+        seeds::program = auction_house_program,
+        bump = auctioneer.bump,
+    )]
+    pub auctioneer: Account<'info, auction_house::Auctioneer>,
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            SIGNER.as_bytes()
+        ],
+        seeds::program = auction_house_program,
+        bump = program_as_signer_bump
+    )]
+    pub program_as_signer: UncheckedAccount<'info>,
+    // Frontend that routed the trade, paid its referral share out of the house fee
+    #[account(mut)]
+    pub referrer: Option<UncheckedAccount<'info>>,
+    #[account(mut)]
+    pub referrer_token_account: Option<UncheckedAccount<'info>>,
+    #[account(
+        seeds = [
+            AUCTIONEER_CONFIG.as_bytes(),
+            auction_house.key().as_ref()
+        ],
+        bump = auctioneer_config.bump
+    )]
+    pub auctioneer_config: Box<Account<'info, AuctioneerConfig>>,
+    #[account(mut, address = auctioneer_config.fee_destination)]
+    pub auctioneer_fee_destination: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub treasury_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn auctioneer_execute_batch_sale<'info>(
+    ctx: Context<'_, '_, '_, 'info, AuctioneerExecuteBatchSale<'info>>,
+    escrow_payment_bump: u8,
+    free_trade_state_bump: u8,
+    program_as_signer_bump: u8,
+    auctioneer_authority_bump: u8,
+    token_size: u64,
+) -> Result<()> {
+    assert_batch_auction_cleared(&ctx.accounts.batch_listing_config)?;
+
+    let index = get_batch_bid_index(&ctx.accounts.bid_book, ctx.accounts.buyer.key())?;
+    let bid = ctx.accounts.bid_book.bids[index].clone();
+    if bid.state != BatchBidState::Settled
+        || bid.buyer_trade_state != ctx.accounts.buyer_trade_state.key()
+    {
+        return err!(AuctioneerError::InvalidBatchBidState);
+    }
+
+    // Each winner takes their units out of the listing as a partial order at the clearing price
+    let buyer_price = ctx
+        .accounts
+        .batch_listing_config
+        .clearing_price
+        .checked_mul(token_size)
+        .ok_or(AuctioneerError::NumericalOverflow)?;
+
+    let cpi_program = ctx.accounts.auction_house_program.to_account_info();
+    let cpi_accounts = AHExecuteSale {
+        buyer: ctx.accounts.buyer.to_account_info(),
+        seller: ctx.accounts.seller.to_account_info(),
+        token_account: ctx.accounts.token_account.to_account_info(),
+        token_mint: ctx.accounts.token_mint.to_account_info(),
+        metadata: ctx.accounts.metadata.to_account_info(),
+        treasury_mint: ctx.accounts.treasury_mint.to_account_info(),
+        escrow_payment_account: ctx.accounts.escrow_payment_account.to_account_info(),
+        escrow_ledger: ctx.accounts.escrow_ledger.to_account_info(),
+        seller_payment_receipt_account: ctx
+            .accounts
+            .seller_payment_receipt_account
+            .to_account_info(),
+        buyer_receipt_token_account: ctx.accounts.buyer_receipt_token_account.to_account_info(),
+        auction_house: ctx.accounts.auction_house.to_account_info(),
+        auction_house_fee_account: ctx.accounts.auction_house_fee_account.to_account_info(),
+        auction_house_treasury: ctx.accounts.auction_house_treasury.to_account_info(),
+        buyer_trade_state: ctx.accounts.buyer_trade_state.to_account_info(),
+        seller_trade_state: ctx.accounts.seller_trade_state.to_account_info(),
+        free_trade_state: ctx.accounts.free_trade_state.to_account_info(),
+        proceeds_split: ctx.accounts.proceeds_split.to_account_info(),
+        referral: ctx.accounts.referral.to_account_info(),
+        referrer: ctx.accounts.referrer.as_ref().map(|r| r.to_account_info()),
+        referrer_token_account: ctx
+            .accounts
+            .referrer_token_account
+            .as_ref()
+            .map(|r| r.to_account_info()),
+        auctioneer_fee_destination: Some(ctx.accounts.auctioneer_fee_destination.to_account_info()),
+        cranker: None,
+        authority: ctx.accounts.authority.to_account_info(),
+        auctioneer_authority: ctx.accounts.auctioneer_authority.to_account_info(),
+        auctioneer: ctx.accounts.auctioneer.to_account_info(),
+        token_program: ctx.accounts.token_program.to_account_info(),
+        treasury_token_program: ctx.accounts.treasury_token_program.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+        associated_token_program: ctx.accounts.associated_token_program.to_account_info(),
+        program_as_signer: ctx.accounts.program_as_signer.to_account_info(),
+        rent: ctx.accounts.rent.to_account_info(),
+    };
+
+    let execute_sale_data = auction_house::instruction::AuctioneerExecuteSale {
+        escrow_payment_bump,
+        _free_trade_state_bump: free_trade_state_bump,
+        program_as_signer_bump,
+        buyer_price,
+        token_size,
+        partial_order_size: Some(bid.filled),
+        partial_order_price: Some(bid.locked_amount),
+        auctioneer_fee_basis_points: ctx.accounts.auctioneer_config.fee_basis_points,
+    };
+
+    // An absent referrer has a meta for the auction house program id but no account info, so
+    // signers are matched by key rather than by position
+    let mut cpi_account_infos: Vec<AccountInfo> = cpi_accounts.to_account_infos();
+    let mut cpi_account_metas: Vec<AccountMeta> = cpi_accounts
+        .to_account_metas(None)
+        .into_iter()
+        .map(|mut meta| {
+            meta.is_signer = meta.pubkey == ctx.accounts.auctioneer_authority.key()
+                || cpi_account_infos
+                    .iter()
+                    .any(|info| *info.key == meta.pubkey && info.is_signer);
+            meta
+        })
+        .collect();
+
+    cpi_account_metas.append(&mut ctx.remaining_accounts.to_vec().to_account_metas(None));
+
+    cpi_account_infos.push(ctx.accounts.auction_house_program.to_account_info());
+    cpi_account_infos.append(&mut ctx.remaining_accounts.to_vec());
+
+    let ix = anchor_lang::solana_program::instruction::Instruction {
+        program_id: cpi_program.key(),
+        accounts: cpi_account_metas,
+        data: execute_sale_data.data(),
+    };
+
+    let ah_key = ctx.accounts.auction_house.key();
+    let auctioneer_seeds = [
+        AUCTIONEER.as_bytes(),
+        ah_key.as_ref(),
+        &[auctioneer_authority_bump],
+    ];
+
+    let unlock_accounts = AHUnlockEscrow {
+        escrow_ledger: ctx.accounts.escrow_ledger.to_account_info(),
+        auctioneer_authority: ctx.accounts.auctioneer_authority.to_account_info(),
+        auction_house: ctx.accounts.auction_house.to_account_info(),
+        auctioneer: ctx.accounts.auctioneer.to_account_info(),
+    };
+    unlock_escrow(
+        ctx.accounts.auction_house_program.to_account_info(),
+        unlock_accounts,
+        &auctioneer_seeds,
+        bid.locked_amount,
+    )?;
+
+    invoke_signed(&ix, &cpi_account_infos, &[&auctioneer_seeds])?;
+
+    let executed_bid = &mut ctx.accounts.bid_book.bids[index];
+    executed_bid.locked_amount = 0;
+    executed_bid.state = BatchBidState::Executed;
+
+    Ok(())
+}
@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+
+pub const AUCTION_RESULT_SIZE: usize = 8 +                  // Anchor discriminator/sighash
+32 +                                                        // listing config
+32 +                                                        // auction house
+32 +                                                        // seller
+32 +                                                        // winner
+32 +                                                        // buyer trade state
+32 +                                                        // token mint
+8 +                                                         // price
+8 +                                                         // auction house fee
+8 +                                                         // royalty
+8 +                                                         // buyer premium
+8 +                                                         // auctioneer fee
+8 +                                                         // settled at
+1                                                           // bump
+;
+
+#[account]
+pub struct AuctionResult {
+    pub listing_config: Pubkey,
+    pub auction_house: Pubkey,
+    pub seller: Pubkey,
+    pub winner: Pubkey,
+    // Together with the listing config, identifies the sale across relists of the token
+    pub buyer_trade_state: Pubkey,
+    pub token_mint: Pubkey,
+    pub price: u64,
+    pub auction_house_fee: u64,
+    pub royalty: u64,
+    pub buyer_premium: u64,
+    pub auctioneer_fee: u64,
+    pub settled_at: i64,
+    pub bump: u8,
+}
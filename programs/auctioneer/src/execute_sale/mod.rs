@@ -1,17 +1,29 @@
-use anchor_lang::{prelude::*, AnchorDeserialize, InstructionData};
-use anchor_spl::{associated_token::AssociatedToken, token::Token};
+pub mod config;
+
+use anchor_lang::{
+    prelude::*,
+    solana_program::program::{get_return_data, invoke_signed},
+    AnchorDeserialize, InstructionData,
+};
+use anchor_spl::{associated_token::AssociatedToken, token_interface::TokenInterface};
 
 use auction_house::{
     self,
-    constants::{AUCTIONEER, FEE_PAYER, PREFIX, SIGNER, TREASURY},
-    cpi::accounts::AuctioneerExecuteSale as AHExecuteSale,
+    constants::{
+        AUCTIONEER, ESCROW_LEDGER, FEE_PAYER, PREFIX, PROCEEDS_SPLIT, REFERRAL, SIGNER, TREASURY,
+    },
+    cpi::accounts::{
+        AuctioneerExecuteSale as AHExecuteSale, AuctioneerUnlockEscrow as AHUnlockEscrow,
+    },
     program::AuctionHouse as AuctionHouseProgram,
-    AuctionHouse,
+    utils::create_or_allocate_account_raw,
+    AuctionHouse, SaleAmounts,
 };
 
-use crate::{constants::*, sell::config::*, utils::*};
-
-use anchor_lang::solana_program::program::invoke_signed;
+use crate::{
+    authorize::config::*, constants::*, errors::*, events::*, execute_sale::config::*,
+    sell::config::*, utils::*,
+};
 
 #[derive(Accounts)]
 #[instruction(
@@ -38,6 +50,16 @@ pub struct AuctioneerExecuteSale<'info> {
         bump = listing_config.bump,
     )]
     pub listing_config: Box<Account<'info, ListingConfig>>,
+    #[account(
+        mut,
+        seeds = [
+            AUCTION_RESULT.as_bytes(),
+            listing_config.key().as_ref(),
+            buyer_trade_state.key().as_ref()
+        ],
+        bump,
+    )]
+    pub auction_result: UncheckedAccount<'info>,
     #[account(mut)]
     pub buyer: UncheckedAccount<'info>,
     #[account(mut)]
@@ -58,6 +80,18 @@ pub struct AuctioneerExecuteSale<'info> {
         bump = escrow_payment_bump
     )]
     pub escrow_payment_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            buyer.key().as_ref(),
+            ESCROW_LEDGER.as_bytes()
+        ],
+        seeds::program = auction_house_program,
+        bump
+    )]
+    pub escrow_ledger: UncheckedAccount<'info>,
     #[account(mut)]
     pub seller_payment_receipt_account: UncheckedAccount<'info>,
     #[account(mut)]
@@ -132,6 +166,28 @@ pub struct AuctioneerExecuteSale<'info> {
         bump = free_trade_state_bump
     )]
     pub free_trade_state: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            seller_trade_state.key().as_ref(),
+            PROCEEDS_SPLIT.as_bytes()
+        ],
+        seeds::program = auction_house_program,
+        bump
+    )]
+    pub proceeds_split: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            seller_trade_state.key().as_ref(),
+            REFERRAL.as_bytes()
+        ],
+        seeds::program = auction_house_program,
+        bump
+    )]
+    pub referral: UncheckedAccount<'info>,
     #[account(
         seeds = [
             AUCTIONEER.as_bytes(),
@@ -159,8 +215,27 @@ pub struct AuctioneerExecuteSale<'info> {
         bump = program_as_signer_bump
     )]
     pub program_as_signer: UncheckedAccount<'info>,
+    // Anyone can settle an ended auction, a third party is paid the settlement bounty
+    #[account(mut)]
+    pub cranker: Option<Signer<'info>>,
+    // Frontend that routed the trade, paid its referral share out of the house fee
+    #[account(mut)]
+    pub referrer: Option<UncheckedAccount<'info>>,
+    #[account(mut)]
+    pub referrer_token_account: Option<UncheckedAccount<'info>>,
+    #[account(
+        seeds = [
+            AUCTIONEER_CONFIG.as_bytes(),
+            auction_house.key().as_ref()
+        ],
+        bump = auctioneer_config.bump
+    )]
+    pub auctioneer_config: Box<Account<'info, AuctioneerConfig>>,
+    #[account(mut, address = auctioneer_config.fee_destination)]
+    pub auctioneer_fee_destination: UncheckedAccount<'info>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub treasury_token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub rent: Sysvar<'info, Rent>,
@@ -175,7 +250,15 @@ pub fn auctioneer_execute_sale<'info>(
     buyer_price: u64,
     token_size: u64,
 ) -> Result<()> {
-    assert_auction_over(&ctx.accounts.listing_config)?;
+    match ctx.accounts.listing_config.auction_type {
+        AuctionType::English => assert_auction_over(&ctx.accounts.listing_config)?,
+        // The first bid at or above the current price wins a Dutch auction,
+        // so the sale can be executed without waiting for the end time.
+        AuctionType::Dutch => assert_auction_sold(&ctx.accounts.listing_config)?,
+        AuctionType::SealedFirstPrice | AuctionType::SealedSecondPrice => {
+            assert_sealed_bid_settled(&ctx.accounts.listing_config)?
+        }
+    }
     assert_highest_bidder(
         &ctx.accounts.listing_config,
         ctx.accounts.buyer_trade_state.key(),
@@ -190,6 +273,7 @@ pub fn auctioneer_execute_sale<'info>(
         metadata: ctx.accounts.metadata.to_account_info(),
         treasury_mint: ctx.accounts.treasury_mint.to_account_info(),
         escrow_payment_account: ctx.accounts.escrow_payment_account.to_account_info(),
+        escrow_ledger: ctx.accounts.escrow_ledger.to_account_info(),
         seller_payment_receipt_account: ctx
             .accounts
             .seller_payment_receipt_account
@@ -201,10 +285,25 @@ pub fn auctioneer_execute_sale<'info>(
         buyer_trade_state: ctx.accounts.buyer_trade_state.to_account_info(),
         seller_trade_state: ctx.accounts.seller_trade_state.to_account_info(),
         free_trade_state: ctx.accounts.free_trade_state.to_account_info(),
+        proceeds_split: ctx.accounts.proceeds_split.to_account_info(),
+        referral: ctx.accounts.referral.to_account_info(),
+        referrer: ctx.accounts.referrer.as_ref().map(|r| r.to_account_info()),
+        referrer_token_account: ctx
+            .accounts
+            .referrer_token_account
+            .as_ref()
+            .map(|r| r.to_account_info()),
+        auctioneer_fee_destination: Some(ctx.accounts.auctioneer_fee_destination.to_account_info()),
+        cranker: ctx
+            .accounts
+            .cranker
+            .as_ref()
+            .map(|cranker| cranker.to_account_info()),
         authority: ctx.accounts.authority.to_account_info(),
         auctioneer_authority: ctx.accounts.auctioneer_authority.to_account_info(),
         auctioneer: ctx.accounts.auctioneer.to_account_info(),
         token_program: ctx.accounts.token_program.to_account_info(),
+        treasury_token_program: ctx.accounts.treasury_token_program.to_account_info(),
         system_program: ctx.accounts.system_program.to_account_info(),
         associated_token_program: ctx.accounts.associated_token_program.to_account_info(),
         program_as_signer: ctx.accounts.program_as_signer.to_account_info(),
@@ -217,24 +316,29 @@ pub fn auctioneer_execute_sale<'info>(
         program_as_signer_bump,
         buyer_price,
         token_size,
+        partial_order_size: None,
+        partial_order_price: None,
+        auctioneer_fee_basis_points: ctx.accounts.auctioneer_config.fee_basis_points,
     };
 
+    // An absent referrer has a meta for the auction house program id but no account info, so
+    // signers are matched by key rather than by position
+    let mut cpi_account_infos: Vec<AccountInfo> = cpi_accounts.to_account_infos();
     let mut cpi_account_metas: Vec<AccountMeta> = cpi_accounts
         .to_account_metas(None)
         .into_iter()
-        .zip(cpi_accounts.to_account_infos())
-        .map(|mut pair| {
-            pair.0.is_signer = pair.1.is_signer;
-            if pair.0.pubkey == ctx.accounts.auctioneer_authority.key() {
-                pair.0.is_signer = true;
-            }
-            pair.0
+        .map(|mut meta| {
+            meta.is_signer = meta.pubkey == ctx.accounts.auctioneer_authority.key()
+                || cpi_account_infos
+                    .iter()
+                    .any(|info| *info.key == meta.pubkey && info.is_signer);
+            meta
         })
         .collect();
 
     cpi_account_metas.append(&mut ctx.remaining_accounts.to_vec().to_account_metas(None));
 
-    let mut cpi_account_infos: Vec<AccountInfo> = cpi_accounts.to_account_infos();
+    cpi_account_infos.push(ctx.accounts.auction_house_program.to_account_info());
     cpi_account_infos.append(&mut ctx.remaining_accounts.to_vec());
 
     let ix = anchor_lang::solana_program::instruction::Instruction {
@@ -254,10 +358,119 @@ pub fn auctioneer_execute_sale<'info>(
         &[auctioneer_authority_bump],
     ];
 
+    // The winning bid is released first so the sale can spend the escrow
+    let unlock_accounts = AHUnlockEscrow {
+        escrow_ledger: ctx.accounts.escrow_ledger.to_account_info(),
+        auctioneer_authority: ctx.accounts.auctioneer_authority.to_account_info(),
+        auction_house: ctx.accounts.auction_house.to_account_info(),
+        auctioneer: ctx.accounts.auctioneer.to_account_info(),
+    };
+    unlock_escrow(
+        ctx.accounts.auction_house_program.to_account_info(),
+        unlock_accounts,
+        &auctioneer_seeds,
+        ctx.accounts.listing_config.escrow_locked_amount,
+    )?;
+
     invoke_signed(&ix, &cpi_account_infos, &[&auctioneer_seeds])?;
 
+    let sale_amounts = match get_return_data() {
+        Some((program_id, data)) if program_id == auction_house::id() => {
+            SaleAmounts::try_from_slice(&data)?
+        }
+        _ => return err!(AuctioneerError::MissingSaleAmounts),
+    };
+
     let listing_config = &ctx.accounts.listing_config.to_account_info();
     let seller = &ctx.accounts.seller.to_account_info();
+    let auction_result = &ctx.accounts.auction_result.to_account_info();
+
+    let listing_config_key = listing_config.key();
+    let auction_result_bump = *ctx
+        .bumps
+        .get("auction_result")
+        .ok_or(AuctioneerError::BumpSeedNotInHashMap)?;
+    // Each sale keeps its own record, a relist won by the same bid of the same buyer can only
+    // settle once the seller has closed the record of the previous sale
+    if !auction_result.data_is_empty() {
+        return err!(AuctioneerError::AuctionResultAlreadyExists);
+    }
+
+    // The result record is funded from the listing rent, the seller reclaims it on close
+    let auction_result_lamports = Rent::get()?.minimum_balance(AUCTION_RESULT_SIZE);
+    **listing_config.lamports.borrow_mut() = listing_config
+        .lamports()
+        .checked_sub(auction_result_lamports)
+        .ok_or(AuctioneerError::NumericalOverflow)?;
+    **auction_result.lamports.borrow_mut() = auction_result
+        .lamports()
+        .checked_add(auction_result_lamports)
+        .ok_or(AuctioneerError::NumericalOverflow)?;
+
+    let buyer_trade_state_key = ctx.accounts.buyer_trade_state.key();
+    let auction_result_seeds = [
+        AUCTION_RESULT.as_bytes(),
+        listing_config_key.as_ref(),
+        buyer_trade_state_key.as_ref(),
+        &[auction_result_bump],
+    ];
+    create_or_allocate_account_raw(
+        crate::id(),
+        auction_result,
+        &ctx.accounts.rent.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        listing_config,
+        AUCTION_RESULT_SIZE,
+        &[],
+        &auction_result_seeds,
+    )?;
+
+    let result = AuctionResult {
+        listing_config: listing_config_key,
+        auction_house: ah_key,
+        seller: seller.key(),
+        winner: ctx.accounts.buyer.key(),
+        buyer_trade_state: ctx.accounts.buyer_trade_state.key(),
+        token_mint: ctx.accounts.token_mint.key(),
+        price: buyer_price,
+        auction_house_fee: sale_amounts.auction_house_fee_paid,
+        royalty: sale_amounts.royalty_paid,
+        buyer_premium: sale_amounts.buyer_premium_paid,
+        auctioneer_fee: sale_amounts.auctioneer_fee_paid,
+        settled_at: Clock::get()?.unix_timestamp,
+        bump: auction_result_bump,
+    };
+    {
+        let mut auction_result_data = auction_result.try_borrow_mut_data()?;
+        result.try_serialize(&mut &mut auction_result_data[..])?;
+    }
+
+    emit!(AuctionSettled {
+        listing_config: listing_config_key,
+        auction_house: ah_key,
+        seller: seller.key(),
+        winner: ctx.accounts.buyer.key(),
+        price: buyer_price,
+        royalty: sale_amounts.royalty_paid,
+        auction_house_fee: sale_amounts.auction_house_fee_paid,
+        buyer_premium: sale_amounts.buyer_premium_paid,
+        auctioneer_fee: sale_amounts.auctioneer_fee_paid,
+        seller_proceeds: sale_amounts.seller_proceeds,
+    });
+
+    if let Some(cranker) = &ctx.accounts.cranker {
+        if cranker.key() != seller.key() && cranker.key() != ctx.accounts.buyer.key() {
+            let bounty = SETTLEMENT_BOUNTY.min(listing_config.lamports());
+            **listing_config.lamports.borrow_mut() = listing_config
+                .lamports()
+                .checked_sub(bounty)
+                .ok_or(AuctioneerError::NumericalOverflow)?;
+            **cranker.lamports.borrow_mut() = cranker
+                .lamports()
+                .checked_add(bounty)
+                .ok_or(AuctioneerError::NumericalOverflow)?;
+        }
+    }
 
     let listing_config_lamports = listing_config.lamports();
     **seller.lamports.borrow_mut() = seller
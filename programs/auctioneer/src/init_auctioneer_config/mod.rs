@@ -0,0 +1,73 @@
+use anchor_lang::{prelude::*, AnchorDeserialize};
+
+use crate::{authorize::config::*, constants::*, errors::*, events::*, utils::*};
+
+use auction_house::{self, constants::PREFIX, AuctionHouse};
+
+// Authorize only creates the auctioneer authority, the house authority creates the fee config
+// here, houses authorized before the auctioneer fee existed included
+#[derive(Accounts)]
+pub struct AuctioneerInitConfig<'info> {
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        seeds::program = auction_house::id(),
+        bump = auction_house.bump
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    #[account(
+        init,
+        payer = wallet,
+        space = AUCTIONEER_CONFIG_SIZE,
+        seeds = [
+            AUCTIONEER_CONFIG.as_bytes(),
+            auction_house.key().as_ref()
+        ],
+        bump
+    )]
+    pub auctioneer_config: Account<'info, AuctioneerConfig>,
+
+    // Wallet for a native treasury, a treasury mint token account otherwise
+    pub fee_destination: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn auctioneer_init_config(
+    ctx: Context<AuctioneerInitConfig>,
+    fee_basis_points: u16,
+) -> Result<()> {
+    if ctx.accounts.wallet.key() != ctx.accounts.auction_house.authority {
+        return err!(AuctioneerError::SignerNotAuth);
+    }
+
+    assert_valid_auctioneer_fee(
+        &ctx.accounts.auction_house,
+        fee_basis_points,
+        &ctx.accounts.fee_destination,
+    )?;
+
+    let auctioneer_config = &mut ctx.accounts.auctioneer_config;
+    auctioneer_config.auction_house = ctx.accounts.auction_house.key();
+    auctioneer_config.fee_basis_points = fee_basis_points;
+    auctioneer_config.fee_destination = ctx.accounts.fee_destination.key();
+    auctioneer_config.bump = *ctx
+        .bumps
+        .get("auctioneer_config")
+        .ok_or(AuctioneerError::BumpSeedNotInHashMap)?;
+
+    emit!(AuctioneerConfigUpdated {
+        auction_house: auctioneer_config.auction_house,
+        fee_basis_points,
+        fee_destination: auctioneer_config.fee_destination,
+    });
+
+    Ok(())
+}
@@ -1,18 +1,45 @@
 #![allow(clippy::result_large_err)]
 #![allow(clippy::too_many_arguments)]
 pub mod authorize;
+pub mod batch_bid;
+pub mod batch_sell;
 pub mod bid;
 pub mod cancel;
+pub mod cancel_batch_listing;
+pub mod cancel_bid_commitment;
+pub mod clear_batch_auction;
+pub mod close_auction_result;
+pub mod commit_bid;
 pub mod constants;
 pub mod deposit;
 pub mod errors;
+pub mod events;
+pub mod execute_batch_sale;
 pub mod execute_sale;
+pub mod init_auctioneer_config;
+pub mod migrate_listing_config;
 pub mod pda;
+pub mod promote_runner_up;
+pub mod proxy_bid;
+pub mod release_batch_bid;
+pub mod reveal_bid;
 pub mod sell;
+pub mod settle;
+pub mod settle_batch_bid;
+pub mod settle_proxy_bid;
+pub mod update_auctioneer_config;
+pub mod update_listing;
 pub mod utils;
 pub mod withdraw;
 
-use crate::{authorize::*, bid::*, cancel::*, deposit::*, execute_sale::*, sell::*, withdraw::*};
+use crate::{
+    authorize::*, batch_bid::*, batch_sell::*, bid::*, cancel::*, cancel_batch_listing::*,
+    cancel_bid_commitment::*, clear_batch_auction::*, close_auction_result::*, commit_bid::*,
+    deposit::*, execute_batch_sale::*, execute_sale::*, init_auctioneer_config::*,
+    migrate_listing_config::*, promote_runner_up::*, proxy_bid::*, release_batch_bid::*,
+    reveal_bid::*, sell::config::*, sell::*, settle::*, settle_batch_bid::*, settle_proxy_bid::*,
+    update_auctioneer_config::*, update_listing::*, withdraw::*,
+};
 
 use anchor_lang::prelude::*;
 
@@ -27,6 +54,20 @@ pub mod auctioneer {
         auctioneer_authorize(ctx)
     }
 
+    pub fn init_auctioneer_config(
+        ctx: Context<AuctioneerInitConfig>,
+        fee_basis_points: u16,
+    ) -> Result<()> {
+        auctioneer_init_config(ctx, fee_basis_points)
+    }
+
+    pub fn update_auctioneer_config(
+        ctx: Context<AuctioneerUpdateConfig>,
+        fee_basis_points: Option<u16>,
+    ) -> Result<()> {
+        auctioneer_update_config(ctx, fee_basis_points)
+    }
+
     pub fn sell(
         ctx: Context<AuctioneerSell>,
         trade_state_bump: u8,
@@ -40,6 +81,10 @@ pub mod auctioneer {
         min_bid_increment: Option<u64>,
         time_ext_period: Option<u32>,
         time_ext_delta: Option<u32>,
+        dutch_auction: Option<DutchAuctionParams>,
+        buy_now_price: Option<u64>,
+        sealed_bid: Option<SealedBidParams>,
+        bid_increment: Option<BidIncrementParams>,
     ) -> Result<()> {
         auctioneer_sell(
             ctx,
@@ -54,6 +99,10 @@ pub mod auctioneer {
             min_bid_increment,
             time_ext_period,
             time_ext_delta,
+            dutch_auction,
+            buy_now_price,
+            sealed_bid,
+            bid_increment,
         )
     }
 
@@ -122,4 +171,195 @@ pub mod auctioneer {
     ) -> Result<()> {
         auctioneer_cancel(ctx, auctioneer_authority_bump, buyer_price, token_size)
     }
+
+    pub fn close_auction_result(ctx: Context<AuctioneerCloseAuctionResult>) -> Result<()> {
+        auctioneer_close_auction_result(ctx)
+    }
+
+    pub fn migrate_listing_config(
+        ctx: Context<AuctioneerMigrateListingConfig>,
+        token_size: u64,
+    ) -> Result<()> {
+        auctioneer_migrate_listing_config(ctx, token_size)
+    }
+
+    pub fn promote_runner_up<'info>(
+        ctx: Context<'_, '_, '_, 'info, AuctioneerPromoteRunnerUp<'info>>,
+        token_size: u64,
+    ) -> Result<()> {
+        auctioneer_promote_runner_up(ctx, token_size)
+    }
+
+    pub fn proxy_bid(
+        ctx: Context<AuctioneerProxyBid>,
+        escrow_payment_bump: u8,
+        token_size: u64,
+        max_amount: u64,
+    ) -> Result<()> {
+        auctioneer_proxy_bid(ctx, escrow_payment_bump, token_size, max_amount)
+    }
+
+    pub fn commit_bid(
+        ctx: Context<AuctioneerCommitBid>,
+        escrow_payment_bump: u8,
+        auctioneer_authority_bump: u8,
+        token_size: u64,
+        commitment: [u8; 32],
+        deposit_amount: u64,
+    ) -> Result<()> {
+        auctioneer_commit_bid(
+            ctx,
+            escrow_payment_bump,
+            auctioneer_authority_bump,
+            token_size,
+            commitment,
+            deposit_amount,
+        )
+    }
+
+    pub fn reveal_bid(
+        ctx: Context<AuctioneerRevealBid>,
+        auctioneer_authority_bump: u8,
+        token_size: u64,
+        amount: u64,
+        salt: [u8; 32],
+    ) -> Result<()> {
+        auctioneer_reveal_bid(ctx, auctioneer_authority_bump, token_size, amount, salt)
+    }
+
+    pub fn cancel_bid_commitment(
+        ctx: Context<AuctioneerCancelBidCommitment>,
+        auctioneer_authority_bump: u8,
+    ) -> Result<()> {
+        auctioneer_cancel_bid_commitment(ctx, auctioneer_authority_bump)
+    }
+
+    pub fn settle_sealed_bid(
+        ctx: Context<AuctioneerSettleSealedBid>,
+        trade_state_bump: u8,
+        auctioneer_authority_bump: u8,
+        token_size: u64,
+    ) -> Result<()> {
+        auctioneer_settle_sealed_bid(ctx, trade_state_bump, auctioneer_authority_bump, token_size)
+    }
+
+    pub fn settle_proxy_bid(
+        ctx: Context<AuctioneerSettleProxyBid>,
+        trade_state_bump: u8,
+        auctioneer_authority_bump: u8,
+        token_size: u64,
+    ) -> Result<()> {
+        auctioneer_settle_proxy_bid(ctx, trade_state_bump, auctioneer_authority_bump, token_size)
+    }
+
+    pub fn update_listing(
+        ctx: Context<AuctioneerUpdateListing>,
+        token_size: u64,
+        start_time: Option<i64>,
+        end_time: Option<i64>,
+        reserve_price: Option<u64>,
+        min_bid_increment: Option<u64>,
+        time_ext_period: Option<u32>,
+        time_ext_delta: Option<u32>,
+    ) -> Result<()> {
+        auctioneer_update_listing(
+            ctx,
+            token_size,
+            start_time,
+            end_time,
+            reserve_price,
+            min_bid_increment,
+            time_ext_period,
+            time_ext_delta,
+        )
+    }
+
+    pub fn batch_sell(
+        ctx: Context<AuctioneerBatchSell>,
+        trade_state_bump: u8,
+        free_trade_state_bump: u8,
+        program_as_signer_bump: u8,
+        auctioneer_authority_bump: u8,
+        token_size: u64,
+        start_time: i64,
+        end_time: i64,
+        reserve_price: u64,
+    ) -> Result<()> {
+        auctioneer_batch_sell(
+            ctx,
+            trade_state_bump,
+            free_trade_state_bump,
+            program_as_signer_bump,
+            auctioneer_authority_bump,
+            token_size,
+            start_time,
+            end_time,
+            reserve_price,
+        )
+    }
+
+    pub fn batch_bid(
+        ctx: Context<AuctioneerBatchBid>,
+        escrow_payment_bump: u8,
+        token_size: u64,
+        price: u64,
+        quantity: u64,
+    ) -> Result<()> {
+        auctioneer_batch_bid(ctx, escrow_payment_bump, token_size, price, quantity)
+    }
+
+    pub fn clear_batch_auction(ctx: Context<AuctioneerClearBatchAuction>) -> Result<()> {
+        auctioneer_clear_batch_auction(ctx)
+    }
+
+    pub fn settle_batch_bid(
+        ctx: Context<AuctioneerSettleBatchBid>,
+        trade_state_bump: u8,
+        escrow_payment_bump: u8,
+        auctioneer_authority_bump: u8,
+        token_size: u64,
+    ) -> Result<()> {
+        auctioneer_settle_batch_bid(
+            ctx,
+            trade_state_bump,
+            escrow_payment_bump,
+            auctioneer_authority_bump,
+            token_size,
+        )
+    }
+
+    #[inline(never)]
+    pub fn execute_batch_sale<'info>(
+        ctx: Context<'_, '_, '_, 'info, AuctioneerExecuteBatchSale<'info>>,
+        escrow_payment_bump: u8,
+        free_trade_state_bump: u8,
+        program_as_signer_bump: u8,
+        auctioneer_authority_bump: u8,
+        token_size: u64,
+    ) -> Result<()> {
+        auctioneer_execute_batch_sale(
+            ctx,
+            escrow_payment_bump,
+            free_trade_state_bump,
+            program_as_signer_bump,
+            auctioneer_authority_bump,
+            token_size,
+        )
+    }
+
+    pub fn cancel_batch_listing(
+        ctx: Context<AuctioneerCancelBatchListing>,
+        auctioneer_authority_bump: u8,
+        token_size: u64,
+    ) -> Result<()> {
+        auctioneer_cancel_batch_listing(ctx, auctioneer_authority_bump, token_size)
+    }
+
+    pub fn release_batch_bid(
+        ctx: Context<AuctioneerReleaseBatchBid>,
+        auctioneer_authority_bump: u8,
+        token_size: u64,
+    ) -> Result<()> {
+        auctioneer_release_batch_bid(ctx, auctioneer_authority_bump, token_size)
+    }
 }
@@ -0,0 +1,144 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{program::invoke, system_instruction},
+    AnchorDeserialize, Discriminator,
+};
+use anchor_spl::token_interface::TokenAccount;
+
+use auction_house::{self, constants::PREFIX, pda::find_trade_state_address, AuctionHouse};
+
+use crate::{constants::*, errors::*, sell::config::*};
+
+#[derive(Accounts)]
+#[instruction(token_size: u64)]
+pub struct AuctioneerMigrateListingConfig<'info> {
+    #[account(
+        mut,
+        seeds = [
+            LISTING_CONFIG.as_bytes(),
+            seller.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_account.key().as_ref(),
+            auction_house.treasury_mint.as_ref(),
+            token_account.mint.as_ref(),
+            &token_size.to_le_bytes()
+        ],
+        bump,
+    )]
+    pub listing_config: UncheckedAccount<'info>,
+    pub seller: UncheckedAccount<'info>,
+    pub token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        seeds::program = auction_house::id(),
+        bump = auction_house.bump,
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+    // Wallet behind the highest bid, the V0 layout only kept its trade state
+    pub highest_bidder: UncheckedAccount<'info>,
+    // Anyone can migrate a listing, the payer covers the rent of the grown account
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// Rewrites a V0 listing in the current layout, the fields added since keep the values of an
+// English auction without a time extension cap
+pub fn auctioneer_migrate_listing_config(
+    ctx: Context<AuctioneerMigrateListingConfig>,
+    token_size: u64,
+) -> Result<()> {
+    let listing_config_info = ctx.accounts.listing_config.to_account_info();
+    if *listing_config_info.owner != crate::id() {
+        return Err(ErrorCode::AccountOwnedByWrongProgram.into());
+    }
+
+    let legacy_listing_config = {
+        let data = listing_config_info.try_borrow_data()?;
+        if data.len() < LISTING_CONFIG_V0_SIZE || data[..8] != ListingConfig::discriminator() {
+            return Err(ErrorCode::AccountDiscriminatorMismatch.into());
+        }
+        ListingConfigV0::deserialize(&mut &data[8..])?
+    };
+    if !matches!(legacy_listing_config.version, ListingConfigVersion::V0) {
+        return err!(AuctioneerError::ListingConfigAlreadyMigrated);
+    }
+
+    let highest_bidder = if legacy_listing_config.highest_bid.amount > 0 {
+        let (buyer_trade_state, _) = find_trade_state_address(
+            &ctx.accounts.highest_bidder.key(),
+            &ctx.accounts.auction_house.key(),
+            &ctx.accounts.token_account.key(),
+            &ctx.accounts.auction_house.treasury_mint,
+            &ctx.accounts.token_account.mint,
+            legacy_listing_config.highest_bid.amount,
+            token_size,
+        );
+        if buyer_trade_state != legacy_listing_config.highest_bid.buyer_trade_state {
+            return err!(AuctioneerError::InvalidHighestBidder);
+        }
+
+        ctx.accounts.highest_bidder.key()
+    } else {
+        Pubkey::default()
+    };
+
+    let rent_shortfall = Rent::get()?
+        .minimum_balance(LISTING_CONFIG_SIZE)
+        .saturating_sub(listing_config_info.lamports());
+    if rent_shortfall > 0 {
+        invoke(
+            &system_instruction::transfer(
+                &ctx.accounts.payer.key(),
+                &listing_config_info.key(),
+                rent_shortfall,
+            ),
+            &[
+                ctx.accounts.payer.to_account_info(),
+                listing_config_info.clone(),
+                ctx.accounts.system_program.to_account_info(),
+            ],
+        )?;
+    }
+    listing_config_info.realloc(LISTING_CONFIG_SIZE, true)?;
+
+    let listing_config = ListingConfig {
+        version: ListingConfigVersion::V1,
+        start_time: legacy_listing_config.start_time,
+        end_time: legacy_listing_config.end_time,
+        highest_bid: Bid {
+            version: ListingConfigVersion::V1,
+            ..legacy_listing_config.highest_bid
+        },
+        bump: legacy_listing_config.bump,
+        reserve_price: legacy_listing_config.reserve_price,
+        min_bid_increment: legacy_listing_config.min_bid_increment,
+        time_ext_period: legacy_listing_config.time_ext_period,
+        time_ext_delta: legacy_listing_config.time_ext_delta,
+        auction_type: AuctionType::English,
+        start_price: 0,
+        floor_price: 0,
+        price_decay_interval: 0,
+        price_decay_amount: 0,
+        buy_now_price: None,
+        reveal_end_time: 0,
+        highest_bidder,
+        second_highest_amount: 0,
+        proxy_max_amount: 0,
+        max_end_time: 0,
+        bid_increment_mode: BidIncrementMode::Absolute,
+        bid_increment_bps: 0,
+        bid_increment_tiers: vec![],
+        promoted_at: 0,
+        runner_up_bids: vec![],
+        escrow_locked_amount: 0,
+        bid_commitments: 0,
+    };
+    listing_config.try_serialize(&mut &mut listing_config_info.try_borrow_mut_data()?[..])?;
+
+    Ok(())
+}
@@ -1,7 +1,13 @@
 use anchor_lang::prelude::Pubkey;
 use auction_house::constants::AUCTIONEER;
 
-use crate::{constants::LISTING_CONFIG, id};
+use crate::{
+    constants::{
+        AUCTIONEER_CONFIG, AUCTION_RESULT, BATCH_LISTING_CONFIG, BID_BOOK, BID_COMMITMENT,
+        LISTING_CONFIG,
+    },
+    id,
+};
 
 pub fn find_listing_config_address(
     wallet: &Pubkey,
@@ -28,3 +34,61 @@ pub fn find_listing_config_address(
 pub fn find_auctioneer_authority_address(auction_house: &Pubkey) -> (Pubkey, u8) {
     Pubkey::find_program_address(&[AUCTIONEER.as_bytes(), auction_house.as_ref()], &id())
 }
+
+pub fn find_auctioneer_config_address(auction_house: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[AUCTIONEER_CONFIG.as_bytes(), auction_house.as_ref()],
+        &id(),
+    )
+}
+
+pub fn find_bid_commitment_address(listing_config: &Pubkey, wallet: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            BID_COMMITMENT.as_bytes(),
+            listing_config.as_ref(),
+            wallet.as_ref(),
+        ],
+        &id(),
+    )
+}
+
+pub fn find_auction_result_address(
+    listing_config: &Pubkey,
+    buyer_trade_state: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            AUCTION_RESULT.as_bytes(),
+            listing_config.as_ref(),
+            buyer_trade_state.as_ref(),
+        ],
+        &id(),
+    )
+}
+
+pub fn find_batch_listing_config_address(
+    wallet: &Pubkey,
+    auction_house: &Pubkey,
+    token_account: &Pubkey,
+    treasury_mint: &Pubkey,
+    token_mint: &Pubkey,
+    token_size: u64,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            BATCH_LISTING_CONFIG.as_bytes(),
+            wallet.as_ref(),
+            auction_house.as_ref(),
+            token_account.as_ref(),
+            treasury_mint.as_ref(),
+            token_mint.as_ref(),
+            &token_size.to_le_bytes(),
+        ],
+        &id(),
+    )
+}
+
+pub fn find_bid_book_address(batch_listing_config: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[BID_BOOK.as_bytes(), batch_listing_config.as_ref()], &id())
+}
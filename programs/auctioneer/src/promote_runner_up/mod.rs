@@ -0,0 +1,186 @@
+use anchor_lang::{prelude::*, AnchorDeserialize};
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+use auction_house::{
+    self,
+    constants::{AUCTIONEER, ESCROW_LEDGER, PREFIX},
+    cpi::accounts::AuctioneerUnlockEscrow as AHUnlockEscrow,
+    program::AuctionHouse as AuctionHouseProgram,
+    AuctionHouse,
+};
+
+use crate::{constants::*, errors::*, events::*, sell::config::*, utils::*};
+
+#[derive(Accounts)]
+#[instruction(token_size: u64)]
+pub struct AuctioneerPromoteRunnerUp<'info> {
+    pub auction_house_program: Program<'info, AuctionHouseProgram>,
+    #[account(
+        mut,
+        seeds = [
+            LISTING_CONFIG.as_bytes(),
+            seller.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_account.key().as_ref(),
+            auction_house.treasury_mint.as_ref(),
+            token_account.mint.as_ref(),
+            &token_size.to_le_bytes()
+        ],
+        bump = listing_config.bump,
+    )]
+    pub listing_config: Box<Account<'info, ListingConfig>>,
+    pub seller: UncheckedAccount<'info>,
+    pub token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            listing_config.highest_bidder.as_ref()
+        ],
+        seeds::program = auction_house_program,
+        bump
+    )]
+    pub winner_escrow_payment_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            listing_config.highest_bidder.as_ref(),
+            ESCROW_LEDGER.as_bytes()
+        ],
+        seeds::program = auction_house_program,
+        bump
+    )]
+    pub winner_escrow_ledger: UncheckedAccount<'info>,
+    pub treasury_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        seeds::program = auction_house_program,
+        bump = auction_house.bump,
+        has_one = treasury_mint,
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+    #[account(
+        seeds = [
+            AUCTIONEER.as_bytes(),
+            auction_house.key().as_ref()
+        ],
+        bump
+    )]
+    pub auctioneer_authority: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            AUCTIONEER.as_bytes(),
+            auction_house.key().as_ref(),
+            auctioneer_authority.key().as_ref()
+        ],
+        seeds::program = auction_house_program,
+        bump = auctioneer.bump,
+    )]
+    pub auctioneer: Account<'info, auction_house::Auctioneer>,
+}
+
+// The escrow payment accounts of the runner-up bidders are passed as remaining accounts,
+// in the order of the runner-up bids, up to the first funded one.
+pub fn auctioneer_promote_runner_up<'info>(
+    ctx: Context<'_, '_, '_, 'info, AuctioneerPromoteRunnerUp<'info>>,
+    _token_size: u64,
+) -> Result<()> {
+    if ctx.accounts.listing_config.auction_type != AuctionType::English {
+        return err!(AuctioneerError::NotEnglishAuction);
+    }
+    assert_auction_over(&ctx.accounts.listing_config)?;
+
+    let treasury_mint = ctx.accounts.treasury_mint.key();
+    let winner_balance = get_escrow_balance(
+        &ctx.accounts.winner_escrow_payment_account.to_account_info(),
+        &treasury_mint,
+    )?;
+    let grace_period_end = ctx
+        .accounts
+        .listing_config
+        .end_time
+        .max(ctx.accounts.listing_config.promoted_at)
+        .saturating_add(RUNNER_UP_GRACE_PERIOD);
+    let current_timestamp = Clock::get()?.unix_timestamp;
+    if winner_balance
+        >= get_escrow_requirement(
+            &ctx.accounts.auction_house,
+            ctx.accounts.listing_config.highest_bid.amount,
+        )?
+        && current_timestamp < grace_period_end
+    {
+        return err!(AuctioneerError::WinnerCanStillSettle);
+    }
+
+    let auction_house_key = ctx.accounts.auction_house.key();
+    let auctioneer_authority_bump = *ctx
+        .bumps
+        .get("auctioneer_authority")
+        .ok_or(AuctioneerError::BumpSeedNotInHashMap)?;
+    let auctioneer_seeds = [
+        AUCTIONEER.as_bytes(),
+        auction_house_key.as_ref(),
+        &[auctioneer_authority_bump],
+    ];
+
+    // The promoted bidder's escrow is not locked since they do not sign the promotion
+    let unlock_accounts = AHUnlockEscrow {
+        escrow_ledger: ctx.accounts.winner_escrow_ledger.to_account_info(),
+        auctioneer_authority: ctx.accounts.auctioneer_authority.to_account_info(),
+        auction_house: ctx.accounts.auction_house.to_account_info(),
+        auctioneer: ctx.accounts.auctioneer.to_account_info(),
+    };
+    unlock_escrow(
+        ctx.accounts.auction_house_program.to_account_info(),
+        unlock_accounts,
+        &auctioneer_seeds,
+        ctx.accounts.listing_config.escrow_locked_amount,
+    )?;
+    ctx.accounts.listing_config.escrow_locked_amount = 0;
+
+    let auction_house = &ctx.accounts.auction_house;
+    let listing_config = &mut ctx.accounts.listing_config;
+    let mut escrow_payment_accounts = ctx.remaining_accounts.iter();
+    while !listing_config.runner_up_bids.is_empty() {
+        let runner_up_bid = listing_config.runner_up_bids.remove(0);
+        let escrow_payment_account = next_account_info(&mut escrow_payment_accounts)?;
+        let (expected_escrow_payment_account, _) = Pubkey::find_program_address(
+            &[
+                PREFIX.as_bytes(),
+                auction_house_key.as_ref(),
+                runner_up_bid.bidder.as_ref(),
+            ],
+            &ctx.accounts.auction_house_program.key(),
+        );
+        if escrow_payment_account.key() != expected_escrow_payment_account {
+            return err!(AuctioneerError::InvalidRunnerUpEscrow);
+        }
+
+        if get_escrow_balance(escrow_payment_account, &treasury_mint)?
+            >= get_escrow_requirement(auction_house, runner_up_bid.amount)?
+        {
+            emit!(RunnerUpPromoted {
+                listing_config: listing_config.key(),
+                previous_bidder: listing_config.highest_bidder,
+                highest_bidder: runner_up_bid.bidder,
+                highest_bid: runner_up_bid.amount,
+            });
+
+            listing_config.highest_bid.amount = runner_up_bid.amount;
+            listing_config.highest_bid.buyer_trade_state = runner_up_bid.buyer_trade_state;
+            listing_config.highest_bidder = runner_up_bid.bidder;
+            listing_config.proxy_max_amount = 0;
+            listing_config.promoted_at = current_timestamp;
+
+            return Ok(());
+        }
+    }
+
+    err!(AuctioneerError::NoRunnerUpBid)
+}
@@ -0,0 +1,183 @@
+use anchor_lang::{prelude::*, AnchorDeserialize};
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+use auction_house::{
+    self,
+    constants::{AUCTIONEER, ESCROW_LEDGER, PREFIX},
+    cpi::accounts::{
+        AuctioneerLockEscrow as AHLockEscrow, AuctioneerUnlockEscrow as AHUnlockEscrow,
+    },
+    program::AuctionHouse as AuctionHouseProgram,
+    AuctionHouse,
+};
+
+use crate::{constants::*, errors::*, events::*, sell::config::*, utils::*};
+
+#[derive(Accounts)]
+#[instruction(escrow_payment_bump: u8, token_size: u64)]
+pub struct AuctioneerProxyBid<'info> {
+    pub auction_house_program: Program<'info, AuctionHouseProgram>,
+    #[account(
+        mut,
+        seeds = [
+            LISTING_CONFIG.as_bytes(),
+            seller.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_account.key().as_ref(),
+            auction_house.treasury_mint.as_ref(),
+            token_account.mint.as_ref(),
+            &token_size.to_le_bytes()
+        ],
+        bump = listing_config.bump,
+    )]
+    pub listing_config: Box<Account<'info, ListingConfig>>,
+    pub seller: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+    pub token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            wallet.key().as_ref()
+        ],
+        seeds::program = auction_house_program,
+        bump = escrow_payment_bump
+    )]
+    pub escrow_payment_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            wallet.key().as_ref(),
+            ESCROW_LEDGER.as_bytes()
+        ],
+        seeds::program = auction_house_program,
+        bump
+    )]
+    pub escrow_ledger: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            listing_config.highest_bidder.as_ref(),
+            ESCROW_LEDGER.as_bytes()
+        ],
+        seeds::program = auction_house_program,
+        bump
+    )]
+    pub highest_bidder_escrow_ledger: UncheckedAccount<'info>,
+    pub treasury_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        seeds::program = auction_house_program,
+        bump = auction_house.bump,
+        has_one = treasury_mint,
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+    #[account(
+        seeds = [
+            AUCTIONEER.as_bytes(),
+            auction_house.key().as_ref()
+        ],
+        bump
+    )]
+    pub auctioneer_authority: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            AUCTIONEER.as_bytes(),
+            auction_house.key().as_ref(),
+            auctioneer_authority.key().as_ref()
+        ],
+        seeds::program = auction_house_program,
+        bump = auctioneer.bump,
+    )]
+    pub auctioneer: Account<'info, auction_house::Auctioneer>,
+
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn auctioneer_proxy_bid(
+    ctx: Context<AuctioneerProxyBid>,
+    _escrow_payment_bump: u8,
+    _token_size: u64,
+    max_amount: u64,
+) -> Result<()> {
+    assert_auction_active(&ctx.accounts.listing_config)?;
+    if ctx.accounts.listing_config.auction_type != AuctionType::English {
+        return err!(AuctioneerError::NotEnglishAuction);
+    }
+    if is_buy_now_reached(&ctx.accounts.listing_config) {
+        return err!(AuctioneerError::AuctionAlreadySold);
+    }
+
+    // The maximum must be fully funded since the winner settles without bidding again
+    let escrow_balance = get_escrow_balance(
+        &ctx.accounts.escrow_payment_account.to_account_info(),
+        &ctx.accounts.treasury_mint.key(),
+    )?;
+    if escrow_balance < get_escrow_requirement(&ctx.accounts.auction_house, max_amount)? {
+        return err!(AuctioneerError::InsufficientProxyBidEscrow);
+    }
+
+    let previous_bidder = ctx.accounts.listing_config.highest_bidder;
+    process_time_extension(&mut ctx.accounts.listing_config)?;
+    process_proxy_bid(
+        &mut ctx.accounts.listing_config,
+        ctx.accounts.wallet.key(),
+        max_amount,
+    )?;
+
+    let ah_key = ctx.accounts.auction_house.key();
+    let auctioneer_authority_bump = *ctx
+        .bumps
+        .get("auctioneer_authority")
+        .ok_or(AuctioneerError::BumpSeedNotInHashMap)?;
+    let auctioneer_seeds = [
+        AUCTIONEER.as_bytes(),
+        ah_key.as_ref(),
+        &[auctioneer_authority_bump],
+    ];
+
+    let lock_accounts = AHLockEscrow {
+        wallet: ctx.accounts.wallet.to_account_info(),
+        escrow_payment_account: ctx.accounts.escrow_payment_account.to_account_info(),
+        escrow_ledger: ctx.accounts.escrow_ledger.to_account_info(),
+        treasury_mint: ctx.accounts.treasury_mint.to_account_info(),
+        auctioneer_authority: ctx.accounts.auctioneer_authority.to_account_info(),
+        auction_house: ctx.accounts.auction_house.to_account_info(),
+        auctioneer: ctx.accounts.auctioneer.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+        rent: ctx.accounts.rent.to_account_info(),
+    };
+    let unlock_accounts = AHUnlockEscrow {
+        escrow_ledger: ctx.accounts.highest_bidder_escrow_ledger.to_account_info(),
+        auctioneer_authority: ctx.accounts.auctioneer_authority.to_account_info(),
+        auction_house: ctx.accounts.auction_house.to_account_info(),
+        auctioneer: ctx.accounts.auctioneer.to_account_info(),
+    };
+    sync_escrow_lock(
+        &mut ctx.accounts.listing_config,
+        previous_bidder,
+        ctx.accounts.auction_house_program.to_account_info(),
+        lock_accounts,
+        unlock_accounts,
+        &auctioneer_seeds,
+    )?;
+
+    emit!(BidPlaced {
+        listing_config: ctx.accounts.listing_config.key(),
+        bidder: ctx.accounts.wallet.key(),
+        amount: ctx.accounts.listing_config.highest_bid.amount,
+        is_proxy: true,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,141 @@
+use anchor_lang::{prelude::*, AnchorDeserialize};
+use anchor_spl::token_interface::TokenAccount;
+
+use auction_house::{
+    self,
+    constants::{AUCTIONEER, ESCROW_LEDGER, PREFIX},
+    cpi::accounts::AuctioneerUnlockEscrow as AHUnlockEscrow,
+    program::AuctionHouse as AuctionHouseProgram,
+    AuctionHouse,
+};
+
+use crate::{batch_sell::config::*, constants::*, errors::*, events::*, utils::*};
+
+#[derive(Accounts)]
+#[instruction(auctioneer_authority_bump: u8, token_size: u64)]
+pub struct AuctioneerReleaseBatchBid<'info> {
+    pub auction_house_program: Program<'info, AuctionHouseProgram>,
+    #[account(
+        seeds = [
+            BATCH_LISTING_CONFIG.as_bytes(),
+            seller.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_account.key().as_ref(),
+            auction_house.treasury_mint.as_ref(),
+            token_account.mint.as_ref(),
+            &token_size.to_le_bytes()
+        ],
+        bump = batch_listing_config.bump,
+    )]
+    pub batch_listing_config: Box<Account<'info, BatchListingConfig>>,
+    #[account(
+        mut,
+        seeds = [
+            BID_BOOK.as_bytes(),
+            batch_listing_config.key().as_ref()
+        ],
+        bump = bid_book.bump,
+    )]
+    pub bid_book: Box<Account<'info, BidBook>>,
+    pub seller: UncheckedAccount<'info>,
+    pub bidder: UncheckedAccount<'info>,
+    pub token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            bidder.key().as_ref(),
+            ESCROW_LEDGER.as_bytes()
+        ],
+        seeds::program = auction_house_program,
+        bump
+    )]
+    pub escrow_ledger: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        seeds::program = auction_house_program,
+        bump = auction_house.bump,
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+    #[account(
+        seeds = [
+            AUCTIONEER.as_bytes(),
+            auction_house.key().as_ref()
+        ],
+        bump = auctioneer_authority_bump
+    )]
+    pub auctioneer_authority: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            AUCTIONEER.as_bytes(),
+            auction_house.key().as_ref(),
+            auctioneer_authority.key().as_ref()
+        ],
+        seeds::program = auction_house_program,
+        bump = auctioneer.bump,
+    )]
+    pub auctioneer: Account<'info, auction_house::Auctioneer>,
+}
+
+// Anyone can release a losing bid once the auction cleared, and a winning bid that was not
+// settled within the settlement period, so no bid keeps the escrow or the listing locked
+pub fn auctioneer_release_batch_bid(
+    ctx: Context<AuctioneerReleaseBatchBid>,
+    auctioneer_authority_bump: u8,
+    _token_size: u64,
+) -> Result<()> {
+    assert_batch_auction_cleared(&ctx.accounts.batch_listing_config)?;
+
+    let index = get_batch_bid_index(&ctx.accounts.bid_book, ctx.accounts.bidder.key())?;
+    let bid = ctx.accounts.bid_book.bids[index].clone();
+    if bid.state != BatchBidState::Open {
+        return err!(AuctioneerError::InvalidBatchBidState);
+    }
+
+    let settlement_deadline = ctx
+        .accounts
+        .batch_listing_config
+        .end_time
+        .saturating_add(BATCH_SETTLEMENT_PERIOD);
+    if bid.filled > 0 && Clock::get()?.unix_timestamp <= settlement_deadline {
+        return err!(AuctioneerError::WinnerCanStillSettle);
+    }
+
+    let ah_key = ctx.accounts.auction_house.key();
+    let auctioneer_seeds = [
+        AUCTIONEER.as_bytes(),
+        ah_key.as_ref(),
+        &[auctioneer_authority_bump],
+    ];
+
+    let unlock_accounts = AHUnlockEscrow {
+        escrow_ledger: ctx.accounts.escrow_ledger.to_account_info(),
+        auctioneer_authority: ctx.accounts.auctioneer_authority.to_account_info(),
+        auction_house: ctx.accounts.auction_house.to_account_info(),
+        auctioneer: ctx.accounts.auctioneer.to_account_info(),
+    };
+    unlock_escrow(
+        ctx.accounts.auction_house_program.to_account_info(),
+        unlock_accounts,
+        &auctioneer_seeds,
+        bid.locked_amount,
+    )?;
+
+    let released_bid = &mut ctx.accounts.bid_book.bids[index];
+    released_bid.locked_amount = 0;
+    released_bid.state = BatchBidState::Released;
+
+    emit!(BatchBidReleased {
+        batch_listing_config: ctx.accounts.batch_listing_config.key(),
+        bidder: bid.bidder,
+        filled: bid.filled,
+        amount: bid.locked_amount,
+    });
+
+    Ok(())
+}
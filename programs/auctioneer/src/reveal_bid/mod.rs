@@ -0,0 +1,167 @@
+use anchor_lang::{prelude::*, AnchorDeserialize};
+use anchor_spl::token_interface::TokenAccount;
+
+use auction_house::{
+    self,
+    constants::{AUCTIONEER, ESCROW_LEDGER, PREFIX},
+    cpi::accounts::AuctioneerUnlockEscrow as AHUnlockEscrow,
+    program::AuctionHouse as AuctionHouseProgram,
+    AuctionHouse,
+};
+
+use crate::{commit_bid::config::*, constants::*, errors::*, sell::config::*, utils::*};
+
+#[derive(Accounts)]
+#[instruction(auctioneer_authority_bump: u8, token_size: u64)]
+pub struct AuctioneerRevealBid<'info> {
+    pub auction_house_program: Program<'info, AuctionHouseProgram>,
+    #[account(
+        mut,
+        seeds = [
+            LISTING_CONFIG.as_bytes(),
+            seller.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_account.key().as_ref(),
+            auction_house.treasury_mint.as_ref(),
+            token_account.mint.as_ref(),
+            &token_size.to_le_bytes()
+        ],
+        bump = listing_config.bump,
+    )]
+    pub listing_config: Box<Account<'info, ListingConfig>>,
+    pub seller: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            BID_COMMITMENT.as_bytes(),
+            listing_config.key().as_ref(),
+            wallet.key().as_ref()
+        ],
+        bump = bid_commitment.bump,
+        close = wallet,
+    )]
+    pub bid_commitment: Account<'info, BidCommitment>,
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+    pub token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            wallet.key().as_ref(),
+            ESCROW_LEDGER.as_bytes()
+        ],
+        seeds::program = auction_house_program,
+        bump
+    )]
+    pub escrow_ledger: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            listing_config.highest_bidder.as_ref(),
+            ESCROW_LEDGER.as_bytes()
+        ],
+        seeds::program = auction_house_program,
+        bump
+    )]
+    pub highest_bidder_escrow_ledger: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        seeds::program = auction_house_program,
+        bump = auction_house.bump,
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+    pub auctioneer_authority: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            AUCTIONEER.as_bytes(),
+            auction_house.key().as_ref(),
+            auctioneer_authority.key().as_ref()
+        ],
+        seeds::program = auction_house_program,
+        bump = auctioneer.bump,
+    )]
+    pub auctioneer: Account<'info, auction_house::Auctioneer>,
+}
+
+pub fn auctioneer_reveal_bid(
+    ctx: Context<AuctioneerRevealBid>,
+    auctioneer_authority_bump: u8,
+    _token_size: u64,
+    amount: u64,
+    salt: [u8; 32],
+) -> Result<()> {
+    assert_reveal_active(&ctx.accounts.listing_config)?;
+
+    let wallet = ctx.accounts.wallet.key();
+    if compute_bid_commitment(amount, &salt, &wallet) != ctx.accounts.bid_commitment.commitment {
+        return err!(AuctioneerError::InvalidBidReveal);
+    }
+    // The bid and its buyer's premium at the current rate have to fit in what was deposited
+    let deposit_amount = ctx.accounts.bid_commitment.deposit_amount;
+    if amount > deposit_amount
+        || get_escrow_requirement(&ctx.accounts.auction_house, amount)?
+            > ctx.accounts.bid_commitment.escrow_amount
+    {
+        return err!(AuctioneerError::BidNotBackedByDeposit);
+    }
+    assert_exceeds_reserve_price(&ctx.accounts.listing_config, amount)?;
+
+    process_revealed_bid(&mut ctx.accounts.listing_config, wallet, amount);
+
+    let ah_key = ctx.accounts.auction_house.key();
+    let auctioneer_seeds = [
+        AUCTIONEER.as_bytes(),
+        ah_key.as_ref(),
+        &[auctioneer_authority_bump],
+    ];
+
+    // Only the leading bid stays locked, the rest of the deposits are released
+    let unlock_amount = if ctx.accounts.listing_config.highest_bidder == wallet {
+        let unlock_accounts = AHUnlockEscrow {
+            escrow_ledger: ctx.accounts.highest_bidder_escrow_ledger.to_account_info(),
+            auctioneer_authority: ctx.accounts.auctioneer_authority.to_account_info(),
+            auction_house: ctx.accounts.auction_house.to_account_info(),
+            auctioneer: ctx.accounts.auctioneer.to_account_info(),
+        };
+        unlock_escrow(
+            ctx.accounts.auction_house_program.to_account_info(),
+            unlock_accounts,
+            &auctioneer_seeds,
+            ctx.accounts.listing_config.escrow_locked_amount,
+        )?;
+        ctx.accounts.listing_config.escrow_locked_amount = amount;
+
+        deposit_amount - amount
+    } else {
+        deposit_amount
+    };
+    let unlock_accounts = AHUnlockEscrow {
+        escrow_ledger: ctx.accounts.escrow_ledger.to_account_info(),
+        auctioneer_authority: ctx.accounts.auctioneer_authority.to_account_info(),
+        auction_house: ctx.accounts.auction_house.to_account_info(),
+        auctioneer: ctx.accounts.auctioneer.to_account_info(),
+    };
+    unlock_escrow(
+        ctx.accounts.auction_house_program.to_account_info(),
+        unlock_accounts,
+        &auctioneer_seeds,
+        unlock_amount,
+    )?;
+
+    ctx.accounts.listing_config.bid_commitments = ctx
+        .accounts
+        .listing_config
+        .bid_commitments
+        .checked_sub(1)
+        .ok_or(AuctioneerError::NumericalOverflow)?;
+
+    Ok(())
+}
@@ -1,11 +1,105 @@
 use anchor_lang::prelude::*;
 
 pub const BID_SIZE: usize = 8 + 1 + 32;
-pub const LISTING_CONFIG_SIZE: usize = 8 + 1 + 8 + 8 + BID_SIZE + 1 + 8 + 8 + 4 + 4;
+pub const MAX_BID_INCREMENT_TIERS: usize = 4;
+pub const BID_INCREMENT_TIER_SIZE: usize = 8 + 8;
+pub const MAX_RUNNER_UP_BIDS: usize = 4;
+pub const RUNNER_UP_BID_SIZE: usize = 32 + 8 + 32;
+pub const LISTING_CONFIG_SIZE: usize = 8 +                  // Anchor discriminator/sighash
+1 +                                                         // version
+8 +                                                         // start time
+8 +                                                         // end time
+BID_SIZE +                                                  // highest bid
+1 +                                                         // bump
+8 +                                                         // reserve price
+8 +                                                         // min bid increment
+4 +                                                         // time extension period
+4 +                                                         // time extension delta
+1 +                                                         // auction type
+8 +                                                         // dutch auction start price
+8 +                                                         // dutch auction floor price
+4 +                                                         // dutch auction price decay interval
+8 +                                                         // dutch auction price decay amount
+9 +                                                         // buy now price
+8 +                                                         // sealed bid reveal end time
+32 +                                                        // highest bidder
+8 +                                                         // second highest bid amount
+8 +                                                         // proxy bid maximum
+8 +                                                         // max end time
+1 +                                                         // bid increment mode
+2 +                                                         // bid increment basis points
+4 + MAX_BID_INCREMENT_TIERS * BID_INCREMENT_TIER_SIZE +     // bid increment tiers
+8 +                                                         // runner-up promoted at
+4 + MAX_RUNNER_UP_BIDS * RUNNER_UP_BID_SIZE +               // runner-up bids
+8 +                                                         // escrow locked amount
+4                                                           // open bid commitments
+;
+
+// Layout of the listings created before V1, migrated with migrate_listing_config
+pub const LISTING_CONFIG_V0_SIZE: usize = 8 +               // Anchor discriminator/sighash
+1 +                                                         // version
+8 +                                                         // start time
+8 +                                                         // end time
+BID_SIZE +                                                  // highest bid
+1 +                                                         // bump
+8 +                                                         // reserve price
+8 +                                                         // min bid increment
+4 +                                                         // time extension period
+4                                                           // time extension delta
+;
 
 #[derive(AnchorDeserialize, AnchorSerialize, Clone)]
 pub enum ListingConfigVersion {
     V0,
+    V1,
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AuctionType {
+    English,
+    Dutch,
+    SealedFirstPrice,
+    SealedSecondPrice,
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BidIncrementMode {
+    // Flat min_bid_increment in the treasury mint
+    Absolute,
+    // Basis points of the current highest bid
+    BasisPoints,
+    // Increment of the highest price band the current highest bid falls in
+    Tiered,
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Debug)]
+pub struct BidIncrementTier {
+    pub min_price: u64,
+    pub increment: u64,
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Debug)]
+pub struct BidIncrementParams {
+    pub mode: BidIncrementMode,
+    pub basis_points: u16,
+    // Sorted by ascending min_price, bids below the first band use min_bid_increment
+    pub tiers: Vec<BidIncrementTier>,
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Debug)]
+pub struct DutchAuctionParams {
+    pub start_price: u64,
+    pub floor_price: u64,
+    // Seconds between two consecutive price drops
+    pub price_decay_interval: u32,
+    pub price_decay_amount: u64,
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Debug)]
+pub struct SealedBidParams {
+    pub reveal_end_time: i64,
+    // Vickrey auction: the winner pays the second highest revealed bid
+    pub second_price: bool,
 }
 
 #[derive(AnchorDeserialize, AnchorSerialize, Clone)]
@@ -15,6 +109,13 @@ pub struct Bid {
     pub buyer_trade_state: Pubkey,
 }
 
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Debug)]
+pub struct RunnerUpBid {
+    pub bidder: Pubkey,
+    pub amount: u64,
+    pub buyer_trade_state: Pubkey,
+}
+
 #[account]
 pub struct ListingConfig {
     pub version: ListingConfigVersion,
@@ -26,4 +127,41 @@ pub struct ListingConfig {
     pub min_bid_increment: u64,
     pub time_ext_period: u32,
     pub time_ext_delta: u32,
+    pub auction_type: AuctionType,
+    pub start_price: u64,
+    pub floor_price: u64,
+    pub price_decay_interval: u32,
+    pub price_decay_amount: u64,
+    pub buy_now_price: Option<u64>,
+    pub reveal_end_time: i64,
+    pub highest_bidder: Pubkey,
+    pub second_highest_amount: u64,
+    // Maximum of the leading proxy bid, zero when the leader placed a regular bid
+    pub proxy_max_amount: u64,
+    // Time extensions can not push the end time past this
+    pub max_end_time: i64,
+    pub bid_increment_mode: BidIncrementMode,
+    pub bid_increment_bps: u16,
+    pub bid_increment_tiers: Vec<BidIncrementTier>,
+    // Restarts the settlement grace period of the newly promoted winner
+    pub promoted_at: i64,
+    // Best outbid regular bid of each bidder by descending amount, next in line to settle
+    pub runner_up_bids: Vec<RunnerUpBid>,
+    // Escrow of the highest bidder locked on the auction house for this listing
+    pub escrow_locked_amount: u64,
+    // Sealed bids committed and not revealed or reclaimed yet
+    pub bid_commitments: u32,
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize, Clone)]
+pub struct ListingConfigV0 {
+    pub version: ListingConfigVersion,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub highest_bid: Bid,
+    pub bump: u8,
+    pub reserve_price: u64,
+    pub min_bid_increment: u64,
+    pub time_ext_period: u32,
+    pub time_ext_delta: u32,
 }
@@ -2,12 +2,12 @@
 
 pub mod config;
 
-use crate::{constants::*, errors::*, sell::config::*};
+use crate::{constants::*, errors::*, events::*, sell::config::*, utils::*};
 
 use anchor_lang::{
     prelude::*, solana_program::program::invoke_signed, AnchorDeserialize, InstructionData,
 };
-use anchor_spl::token::{Token, TokenAccount};
+use anchor_spl::token_interface::{TokenAccount, TokenInterface};
 
 use auction_house::{
     self,
@@ -46,7 +46,7 @@ pub struct AuctioneerSell<'info> {
     #[account(mut)]
     pub wallet: Signer<'info>,
     #[account(mut)]
-    pub token_account: Box<Account<'info, TokenAccount>>,
+    pub token_account: Box<InterfaceAccount<'info, TokenAccount>>,
     pub metadata: UncheckedAccount<'info>,
     pub authority: UncheckedAccount<'info>,
     #[account(
@@ -124,7 +124,7 @@ pub struct AuctioneerSell<'info> {
     )]
     pub program_as_signer: UncheckedAccount<'info>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
@@ -142,15 +142,76 @@ pub fn auctioneer_sell(
     min_bid_increment: Option<u64>,
     time_ext_period: Option<u32>,
     time_ext_delta: Option<u32>,
+    dutch_auction: Option<DutchAuctionParams>,
+    buy_now_price: Option<u64>,
+    sealed_bid: Option<SealedBidParams>,
+    bid_increment: Option<BidIncrementParams>,
 ) -> Result<()> {
-    ctx.accounts.listing_config.version = ListingConfigVersion::V0;
-    ctx.accounts.listing_config.highest_bid.version = ListingConfigVersion::V0;
+    assert_valid_listing_params(
+        &ctx.accounts.auction_house,
+        start_time,
+        end_time,
+        reserve_price.unwrap_or(0),
+        min_bid_increment.unwrap_or(0),
+        time_ext_period.unwrap_or(0),
+        time_ext_delta.unwrap_or(0),
+    )?;
+
+    ctx.accounts.listing_config.version = ListingConfigVersion::V1;
+    ctx.accounts.listing_config.highest_bid.version = ListingConfigVersion::V1;
     ctx.accounts.listing_config.start_time = start_time;
     ctx.accounts.listing_config.end_time = end_time;
     ctx.accounts.listing_config.reserve_price = reserve_price.unwrap_or(0);
     ctx.accounts.listing_config.min_bid_increment = min_bid_increment.unwrap_or(0);
     ctx.accounts.listing_config.time_ext_period = time_ext_period.unwrap_or(0);
     ctx.accounts.listing_config.time_ext_delta = time_ext_delta.unwrap_or(0);
+    ctx.accounts.listing_config.max_end_time =
+        get_max_end_time(&ctx.accounts.auction_house, end_time)?;
+    if dutch_auction.is_some() && sealed_bid.is_some() {
+        return err!(AuctioneerError::InvalidSealedBidParams);
+    }
+    if let Some(params) = dutch_auction {
+        if params.start_price < params.floor_price || params.price_decay_interval == 0 {
+            return err!(AuctioneerError::InvalidDutchAuctionParams);
+        }
+
+        ctx.accounts.listing_config.auction_type = AuctionType::Dutch;
+        ctx.accounts.listing_config.start_price = params.start_price;
+        ctx.accounts.listing_config.floor_price = params.floor_price;
+        ctx.accounts.listing_config.price_decay_interval = params.price_decay_interval;
+        ctx.accounts.listing_config.price_decay_amount = params.price_decay_amount;
+    } else if let Some(params) = sealed_bid {
+        if params.reveal_end_time <= end_time {
+            return err!(AuctioneerError::InvalidSealedBidParams);
+        }
+
+        ctx.accounts.listing_config.auction_type = if params.second_price {
+            AuctionType::SealedSecondPrice
+        } else {
+            AuctionType::SealedFirstPrice
+        };
+        ctx.accounts.listing_config.reveal_end_time = params.reveal_end_time;
+    } else {
+        ctx.accounts.listing_config.auction_type = AuctionType::English;
+    }
+    if let Some(price) = buy_now_price {
+        if price == 0
+            || price < ctx.accounts.listing_config.reserve_price
+            || ctx.accounts.listing_config.auction_type != AuctionType::English
+        {
+            return err!(AuctioneerError::InvalidBuyNowPrice);
+        }
+    }
+    ctx.accounts.listing_config.buy_now_price = buy_now_price;
+    if let Some(params) = bid_increment {
+        assert_valid_bid_increment(&params)?;
+
+        ctx.accounts.listing_config.bid_increment_mode = params.mode;
+        ctx.accounts.listing_config.bid_increment_bps = params.basis_points;
+        ctx.accounts.listing_config.bid_increment_tiers = params.tiers;
+    } else {
+        ctx.accounts.listing_config.bid_increment_mode = BidIncrementMode::Absolute;
+    }
     ctx.accounts.listing_config.bump = *ctx
         .bumps
         .get("listing_config")
@@ -181,20 +242,28 @@ pub fn auctioneer_sell(
         token_size,
     };
 
+    let mut cpi_account_metas: Vec<AccountMeta> = cpi_accounts
+        .to_account_metas(None)
+        .into_iter()
+        .zip(cpi_accounts.to_account_infos())
+        .map(|mut pair| {
+            pair.0.is_signer = pair.1.is_signer;
+            if pair.0.pubkey == ctx.accounts.auctioneer_authority.key() {
+                pair.0.is_signer = true;
+            }
+            pair.0
+        })
+        .collect();
+
+    // Programmable NFTs pass their Token Metadata accounts through to the auction house
+    cpi_account_metas.append(&mut ctx.remaining_accounts.to_vec().to_account_metas(None));
+
+    let mut cpi_account_infos: Vec<AccountInfo> = cpi_accounts.to_account_infos();
+    cpi_account_infos.append(&mut ctx.remaining_accounts.to_vec());
+
     let ix = anchor_lang::solana_program::instruction::Instruction {
         program_id: cpi_program.key(),
-        accounts: cpi_accounts
-            .to_account_metas(None)
-            .into_iter()
-            .zip(cpi_accounts.to_account_infos())
-            .map(|mut pair| {
-                pair.0.is_signer = pair.1.is_signer;
-                if pair.0.pubkey == ctx.accounts.auctioneer_authority.key() {
-                    pair.0.is_signer = true;
-                }
-                pair.0
-            })
-            .collect(),
+        accounts: cpi_account_metas,
         data: sell_data.data(),
     };
 
@@ -209,7 +278,18 @@ pub fn auctioneer_sell(
         &[auctioneer_authority_bump],
     ];
 
-    invoke_signed(&ix, &cpi_accounts.to_account_infos(), &[&auctioneer_seeds])?;
+    invoke_signed(&ix, &cpi_account_infos, &[&auctioneer_seeds])?;
+
+    emit!(ListingCreated {
+        listing_config: ctx.accounts.listing_config.key(),
+        auction_house: ah_key,
+        seller: ctx.accounts.wallet.key(),
+        token_mint: ctx.accounts.token_account.mint,
+        auction_type: ctx.accounts.listing_config.auction_type,
+        start_time,
+        end_time,
+        reserve_price: ctx.accounts.listing_config.reserve_price,
+    });
 
     Ok(())
 }
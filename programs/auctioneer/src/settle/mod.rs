@@ -0,0 +1,138 @@
+use anchor_lang::{prelude::*, AnchorDeserialize};
+use anchor_spl::token_interface::TokenAccount;
+
+use auction_house::{
+    self,
+    constants::{AUCTIONEER, ESCROW_LEDGER, PREFIX},
+    cpi::accounts::AuctioneerBuyFromEscrow as AHBuyFromEscrow,
+    program::AuctionHouse as AuctionHouseProgram,
+    AuctionHouse,
+};
+
+use crate::{constants::*, errors::*, sell::config::*, utils::*};
+
+#[derive(Accounts)]
+#[instruction(
+    trade_state_bump: u8,
+    auctioneer_authority_bump: u8,
+    token_size: u64
+)]
+pub struct AuctioneerSettleSealedBid<'info> {
+    pub auction_house_program: Program<'info, AuctionHouseProgram>,
+    #[account(
+        mut,
+        seeds = [
+            LISTING_CONFIG.as_bytes(),
+            seller.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_account.key().as_ref(),
+            auction_house.treasury_mint.as_ref(),
+            token_account.mint.as_ref(),
+            &token_size.to_le_bytes()
+        ],
+        bump = listing_config.bump,
+    )]
+    pub listing_config: Box<Account<'info, ListingConfig>>,
+    pub seller: UncheckedAccount<'info>,
+    // The winner, whose escrow is locked for the winning bid
+    pub wallet: UncheckedAccount<'info>,
+    pub token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub metadata: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            wallet.key().as_ref(),
+            ESCROW_LEDGER.as_bytes()
+        ],
+        seeds::program = auction_house_program,
+        bump
+    )]
+    pub escrow_ledger: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        seeds::program = auction_house_program,
+        bump = auction_house.bump,
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+    /// The settlement price is only known on-chain, the seeds of the trade state are
+    /// verified by the Auction House during the CPI.
+    #[account(mut)]
+    pub buyer_trade_state: UncheckedAccount<'info>,
+    pub auctioneer_authority: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            AUCTIONEER.as_bytes(),
+            auction_house.key().as_ref(),
+            auctioneer_authority.key().as_ref()
+        ],
+        seeds::program = auction_house_program,
+        bump = auctioneer.bump,
+    )]
+    pub auctioneer: Account<'info, auction_house::Auctioneer>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn auctioneer_settle_sealed_bid(
+    ctx: Context<AuctioneerSettleSealedBid>,
+    trade_state_bump: u8,
+    auctioneer_authority_bump: u8,
+    token_size: u64,
+) -> Result<()> {
+    // Sealed bids win without a trade state, it is placed here from the escrow locked for the
+    // winning bid, so anyone can settle the auction
+    assert_reveal_over(&ctx.accounts.listing_config)?;
+    if ctx.accounts.wallet.key() != ctx.accounts.listing_config.highest_bidder {
+        return err!(AuctioneerError::NotWinningBidder);
+    }
+    if ctx.accounts.listing_config.highest_bid.buyer_trade_state != Pubkey::default() {
+        return err!(AuctioneerError::AuctionAlreadySold);
+    }
+
+    let buyer_price = get_sealed_bid_price(&ctx.accounts.listing_config);
+    assert_listing_escrow_locked(&ctx.accounts.listing_config, buyer_price)?;
+    ctx.accounts.listing_config.highest_bid.amount = buyer_price;
+    ctx.accounts.listing_config.highest_bid.buyer_trade_state =
+        ctx.accounts.buyer_trade_state.key();
+
+    let cpi_program = ctx.accounts.auction_house_program.to_account_info();
+    let cpi_accounts = AHBuyFromEscrow {
+        wallet: ctx.accounts.wallet.to_account_info(),
+        token_account: ctx.accounts.token_account.to_account_info(),
+        metadata: ctx.accounts.metadata.to_account_info(),
+        escrow_ledger: ctx.accounts.escrow_ledger.to_account_info(),
+        auctioneer_authority: ctx.accounts.auctioneer_authority.to_account_info(),
+        auction_house: ctx.accounts.auction_house.to_account_info(),
+        buyer_trade_state: ctx.accounts.buyer_trade_state.to_account_info(),
+        auctioneer: ctx.accounts.auctioneer.to_account_info(),
+        payer: ctx.accounts.payer.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+        rent: ctx.accounts.rent.to_account_info(),
+    };
+
+    let auction_house = &ctx.accounts.auction_house;
+    let ah_key = auction_house.key();
+
+    let auctioneer_seeds = [
+        AUCTIONEER.as_bytes(),
+        ah_key.as_ref(),
+        &[auctioneer_authority_bump],
+    ];
+
+    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+    auction_house::cpi::auctioneer_buy_from_escrow(
+        cpi_ctx.with_signer(&[&auctioneer_seeds]),
+        trade_state_bump,
+        buyer_price,
+        token_size,
+    )
+}
@@ -0,0 +1,64 @@
+use anchor_lang::{prelude::*, AnchorDeserialize};
+
+use crate::{authorize::config::*, constants::*, errors::*, events::*, utils::*};
+
+use auction_house::{self, constants::PREFIX, AuctionHouse};
+
+#[derive(Accounts)]
+pub struct AuctioneerUpdateConfig<'info> {
+    pub wallet: Signer<'info>,
+
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        seeds::program = auction_house::id(),
+        bump = auction_house.bump
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    #[account(
+        mut,
+        seeds = [
+            AUCTIONEER_CONFIG.as_bytes(),
+            auction_house.key().as_ref()
+        ],
+        bump = auctioneer_config.bump,
+        has_one = auction_house
+    )]
+    pub auctioneer_config: Account<'info, AuctioneerConfig>,
+
+    // Wallet for a native treasury, a treasury mint token account otherwise
+    pub fee_destination: UncheckedAccount<'info>,
+}
+
+pub fn auctioneer_update_config(
+    ctx: Context<AuctioneerUpdateConfig>,
+    fee_basis_points: Option<u16>,
+) -> Result<()> {
+    if ctx.accounts.wallet.key() != ctx.accounts.auction_house.authority {
+        return err!(AuctioneerError::SignerNotAuth);
+    }
+
+    let auctioneer_config = &mut ctx.accounts.auctioneer_config;
+    if let Some(fbp) = fee_basis_points {
+        auctioneer_config.fee_basis_points = fbp;
+    }
+    auctioneer_config.fee_destination = ctx.accounts.fee_destination.key();
+
+    assert_valid_auctioneer_fee(
+        &ctx.accounts.auction_house,
+        auctioneer_config.fee_basis_points,
+        &ctx.accounts.fee_destination,
+    )?;
+
+    emit!(AuctioneerConfigUpdated {
+        auction_house: auctioneer_config.auction_house,
+        fee_basis_points: auctioneer_config.fee_basis_points,
+        fee_destination: auctioneer_config.fee_destination,
+    });
+
+    Ok(())
+}
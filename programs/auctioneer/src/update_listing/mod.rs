@@ -0,0 +1,106 @@
+use anchor_lang::{prelude::*, AnchorDeserialize};
+use anchor_spl::token_interface::TokenAccount;
+
+use auction_house::{self, constants::PREFIX, AuctionHouse};
+
+use crate::{constants::*, errors::*, events::*, sell::config::*, utils::*};
+
+#[derive(Accounts)]
+#[instruction(token_size: u64)]
+pub struct AuctioneerUpdateListing<'info> {
+    #[account(
+        mut,
+        seeds = [
+            LISTING_CONFIG.as_bytes(),
+            wallet.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_account.key().as_ref(),
+            auction_house.treasury_mint.as_ref(),
+            token_account.mint.as_ref(),
+            &token_size.to_le_bytes()
+        ],
+        bump = listing_config.bump,
+    )]
+    pub listing_config: Account<'info, ListingConfig>,
+    pub wallet: Signer<'info>,
+    pub token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        seeds::program = auction_house::id(),
+        bump = auction_house.bump,
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+}
+
+pub fn auctioneer_update_listing(
+    ctx: Context<AuctioneerUpdateListing>,
+    _token_size: u64,
+    start_time: Option<i64>,
+    end_time: Option<i64>,
+    reserve_price: Option<u64>,
+    min_bid_increment: Option<u64>,
+    time_ext_period: Option<u32>,
+    time_ext_delta: Option<u32>,
+) -> Result<()> {
+    let listing_config = &mut ctx.accounts.listing_config;
+    if listing_config.highest_bid.amount > 0 {
+        return err!(AuctioneerError::ListingHasBids);
+    }
+
+    // Committed bidders locked their deposit against the reserve and end time they saw
+    if listing_config.bid_commitments > 0
+        && (end_time.map_or(false, |end_time| end_time != listing_config.end_time)
+            || reserve_price.map_or(false, |reserve_price| {
+                reserve_price != listing_config.reserve_price
+            }))
+    {
+        return err!(AuctioneerError::ListingHasBidCommitments);
+    }
+
+    let start_time = start_time.unwrap_or(listing_config.start_time);
+    let end_time = end_time.unwrap_or(listing_config.end_time);
+    let reserve_price = reserve_price.unwrap_or(listing_config.reserve_price);
+    let min_bid_increment = min_bid_increment.unwrap_or(listing_config.min_bid_increment);
+    let time_ext_period = time_ext_period.unwrap_or(listing_config.time_ext_period);
+    let time_ext_delta = time_ext_delta.unwrap_or(listing_config.time_ext_delta);
+    assert_valid_listing_params(
+        &ctx.accounts.auction_house,
+        start_time,
+        end_time,
+        reserve_price,
+        min_bid_increment,
+        time_ext_period,
+        time_ext_delta,
+    )?;
+
+    if is_sealed_bid_auction(listing_config) && listing_config.reveal_end_time <= end_time {
+        return err!(AuctioneerError::InvalidSealedBidParams);
+    }
+    if let Some(buy_now_price) = listing_config.buy_now_price {
+        if buy_now_price < reserve_price {
+            return err!(AuctioneerError::InvalidBuyNowPrice);
+        }
+    }
+
+    listing_config.start_time = start_time;
+    listing_config.end_time = end_time;
+    listing_config.max_end_time = get_max_end_time(&ctx.accounts.auction_house, end_time)?;
+    listing_config.reserve_price = reserve_price;
+    listing_config.min_bid_increment = min_bid_increment;
+    listing_config.time_ext_period = time_ext_period;
+    listing_config.time_ext_delta = time_ext_delta;
+
+    emit!(ListingUpdated {
+        listing_config: listing_config.key(),
+        start_time,
+        end_time,
+        reserve_price,
+        min_bid_increment,
+    });
+
+    Ok(())
+}
@@ -1,6 +1,16 @@
-use anchor_lang::prelude::*;
+use anchor_lang::{prelude::*, solana_program::keccak};
+use anchor_spl::{token::spl_token, token_interface::TokenAccount};
 
-use crate::{errors::*, sell::config::*};
+use auction_house::{
+    self,
+    cpi::accounts::{
+        AuctioneerLockEscrow as AHLockEscrow, AuctioneerUnlockEscrow as AHUnlockEscrow,
+    },
+    utils::{get_buyer_premium, unpack_token_account},
+    AuctionHouse,
+};
+
+use crate::{batch_sell::config::*, constants::*, errors::*, events::*, sell::config::*};
 
 pub fn assert_auction_active(listing_config: &Account<ListingConfig>) -> Result<()> {
     let clock = Clock::get()?;
@@ -15,10 +25,165 @@ pub fn assert_auction_active(listing_config: &Account<ListingConfig>) -> Result<
     Ok(())
 }
 
+pub fn assert_valid_listing_times(start_time: i64, end_time: i64) -> Result<()> {
+    let clock = Clock::get()?;
+    let current_timestamp = clock.unix_timestamp;
+
+    if end_time <= start_time {
+        return err!(AuctioneerError::EndBeforeStart);
+    } else if end_time <= current_timestamp {
+        return err!(AuctioneerError::EndTimeInPast);
+    }
+
+    Ok(())
+}
+
+pub fn get_max_time_extension(auction_house: &AuctionHouse) -> i64 {
+    if auction_house.max_time_extension > 0 {
+        auction_house.max_time_extension
+    } else {
+        DEFAULT_MAX_TIME_EXTENSION
+    }
+}
+
+// Time extensions can not push the end time of a listing past this
+pub fn get_max_end_time(auction_house: &AuctionHouse, end_time: i64) -> Result<i64> {
+    end_time
+        .checked_add(get_max_time_extension(auction_house))
+        .ok_or_else(|| error!(AuctioneerError::NumericalOverflow))
+}
+
+pub fn assert_valid_listing_params(
+    auction_house: &AuctionHouse,
+    start_time: i64,
+    end_time: i64,
+    reserve_price: u64,
+    min_bid_increment: u64,
+    time_ext_period: u32,
+    time_ext_delta: u32,
+) -> Result<()> {
+    assert_valid_listing_times(start_time, end_time)?;
+
+    let duration = end_time
+        .checked_sub(start_time)
+        .ok_or(AuctioneerError::NumericalOverflow)?;
+    if auction_house.min_auction_duration > 0 && duration < auction_house.min_auction_duration {
+        return err!(AuctioneerError::AuctionTooShort);
+    } else if auction_house.max_auction_duration > 0
+        && duration > auction_house.max_auction_duration
+    {
+        return err!(AuctioneerError::AuctionTooLong);
+    }
+
+    if (time_ext_period == 0) != (time_ext_delta == 0)
+        || i64::from(time_ext_period) > duration
+        || i64::from(time_ext_delta) > get_max_time_extension(auction_house)
+    {
+        return err!(AuctioneerError::InvalidTimeExtension);
+    }
+
+    if reserve_price.checked_add(min_bid_increment).is_none() {
+        return err!(AuctioneerError::InvalidBidIncrement);
+    }
+
+    Ok(())
+}
+
+pub fn assert_valid_bid_increment(params: &BidIncrementParams) -> Result<()> {
+    let valid = match params.mode {
+        BidIncrementMode::Absolute => true,
+        BidIncrementMode::BasisPoints => params.basis_points > 0 && params.basis_points <= 10000,
+        BidIncrementMode::Tiered => {
+            !params.tiers.is_empty()
+                && params.tiers.len() <= MAX_BID_INCREMENT_TIERS
+                && params
+                    .tiers
+                    .windows(2)
+                    .all(|tiers| tiers[0].min_price < tiers[1].min_price)
+        }
+    };
+    if !valid {
+        return err!(AuctioneerError::InvalidBidIncrement);
+    }
+
+    Ok(())
+}
+
+// The house caps the auctioneer fee, and a fee in a treasury mint is paid into a token account.
+// The cap starts at zero, the house authority raises it with update_auction_house before
+// setting any fee
+pub fn assert_valid_auctioneer_fee(
+    auction_house: &AuctionHouse,
+    fee_basis_points: u16,
+    fee_destination: &AccountInfo,
+) -> Result<()> {
+    if fee_basis_points > auction_house.max_auctioneer_fee_basis_points {
+        return err!(AuctioneerError::InvalidAuctioneerFee);
+    }
+
+    if auction_house.treasury_mint != spl_token::native_mint::id()
+        && unpack_token_account(fee_destination)?.mint != auction_house.treasury_mint
+    {
+        return err!(AuctioneerError::InvalidAuctioneerFeeDestination);
+    }
+
+    Ok(())
+}
+
+pub fn get_dutch_auction_price(listing_config: &Account<ListingConfig>, timestamp: i64) -> u64 {
+    if timestamp <= listing_config.start_time || listing_config.price_decay_interval == 0 {
+        return listing_config.start_price;
+    }
+
+    let elapsed_intervals = (timestamp - listing_config.start_time) as u64
+        / u64::from(listing_config.price_decay_interval);
+    let price_decay = elapsed_intervals.saturating_mul(listing_config.price_decay_amount);
+
+    listing_config
+        .start_price
+        .saturating_sub(price_decay)
+        .max(listing_config.floor_price)
+}
+
+pub fn assert_dutch_auction_price(
+    listing_config: &Account<ListingConfig>,
+    new_bid_price: u64,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let current_timestamp = clock.unix_timestamp;
+
+    if listing_config.highest_bid.amount > 0 {
+        return err!(AuctioneerError::AuctionAlreadySold);
+    } else if new_bid_price < get_dutch_auction_price(listing_config, current_timestamp) {
+        return err!(AuctioneerError::BelowDutchAuctionPrice);
+    }
+
+    Ok(())
+}
+
+pub fn assert_auction_sold(listing_config: &Account<ListingConfig>) -> Result<()> {
+    if listing_config.highest_bid.amount == 0 {
+        return err!(AuctioneerError::AuctionActive);
+    }
+
+    Ok(())
+}
+
+pub fn is_buy_now_reached(listing_config: &Account<ListingConfig>) -> bool {
+    match listing_config.buy_now_price {
+        Some(buy_now_price) => listing_config.highest_bid.amount >= buy_now_price,
+        None => false,
+    }
+}
+
 pub fn assert_auction_over(listing_config: &Account<ListingConfig>) -> Result<()> {
     let clock = Clock::get()?;
     let current_timestamp = clock.unix_timestamp;
 
+    if is_buy_now_reached(listing_config) {
+        return Ok(());
+    }
+
     if current_timestamp < listing_config.end_time {
         return err!(AuctioneerError::AuctionActive);
     }
@@ -26,18 +191,228 @@ pub fn assert_auction_over(listing_config: &Account<ListingConfig>) -> Result<()
     Ok(())
 }
 
+pub fn get_bid_increment(listing_config: &Account<ListingConfig>, price: u64) -> Result<u64> {
+    match listing_config.bid_increment_mode {
+        BidIncrementMode::Absolute => Ok(listing_config.min_bid_increment),
+        BidIncrementMode::BasisPoints => {
+            let increment = u128::from(price)
+                .checked_mul(u128::from(listing_config.bid_increment_bps))
+                .and_then(|increment| increment.checked_div(10000))
+                .ok_or(AuctioneerError::NumericalOverflow)?;
+
+            Ok(u64::try_from(increment).map_err(|_| AuctioneerError::NumericalOverflow)?)
+        }
+        BidIncrementMode::Tiered => Ok(listing_config
+            .bid_increment_tiers
+            .iter()
+            .rev()
+            .find(|tier| price >= tier.min_price)
+            .map_or(listing_config.min_bid_increment, |tier| tier.increment)),
+    }
+}
+
 pub fn assert_higher_bid(
     listing_config: &Account<ListingConfig>,
     new_bid_price: u64,
 ) -> Result<()> {
     if new_bid_price <= listing_config.highest_bid.amount {
         return err!(AuctioneerError::BidTooLow);
-    } else if (listing_config.highest_bid.amount > 0)
-        && (new_bid_price < (listing_config.highest_bid.amount + listing_config.min_bid_increment))
+    } else if listing_config.highest_bid.amount > 0 {
+        let min_bid_price = listing_config
+            .highest_bid
+            .amount
+            .checked_add(get_bid_increment(
+                listing_config,
+                listing_config.highest_bid.amount,
+            )?)
+            .ok_or(AuctioneerError::NumericalOverflow)?;
+
+        if new_bid_price < min_bid_price {
+            return err!(AuctioneerError::BelowBidIncrement);
+        }
+    }
+
+    Ok(())
+}
+
+pub fn get_min_next_bid(listing_config: &Account<ListingConfig>) -> Result<u64> {
+    if listing_config.highest_bid.amount == 0 {
+        return Ok(listing_config.reserve_price.max(1));
+    }
+
+    let increment = get_bid_increment(listing_config, listing_config.highest_bid.amount)?;
+    Ok(listing_config
+        .highest_bid
+        .amount
+        .checked_add(increment.max(1))
+        .ok_or(AuctioneerError::NumericalOverflow)?)
+}
+
+pub fn process_highest_bid(
+    listing_config: &mut Account<ListingConfig>,
+    bidder: Pubkey,
+    amount: u64,
+    buyer_trade_state: Pubkey,
+) {
+    let previous_bidder = listing_config.highest_bidder;
+    if previous_bidder != Pubkey::default() && previous_bidder != bidder {
+        emit!(Outbid {
+            listing_config: listing_config.key(),
+            bidder: previous_bidder,
+            highest_bidder: bidder,
+            highest_bid: amount,
+        });
+
+        let previous_bid = listing_config.highest_bid.clone();
+        record_runner_up_bid(
+            listing_config,
+            previous_bidder,
+            previous_bid.amount,
+            previous_bid.buyer_trade_state,
+        );
+    }
+    listing_config
+        .runner_up_bids
+        .retain(|runner_up_bid| runner_up_bid.bidder != bidder);
+
+    listing_config.highest_bid.amount = amount;
+    listing_config.highest_bid.buyer_trade_state = buyer_trade_state;
+    listing_config.highest_bidder = bidder;
+    listing_config.proxy_max_amount = 0;
+}
+
+// Keeps the best regular bid of each outbid bidder, the lowest ones are dropped past MAX_RUNNER_UP_BIDS
+pub fn record_runner_up_bid(
+    listing_config: &mut Account<ListingConfig>,
+    bidder: Pubkey,
+    amount: u64,
+    buyer_trade_state: Pubkey,
+) {
+    // Proxy standing bids have no trade state on the auction house to settle against
+    if buyer_trade_state == Pubkey::default()
+        || listing_config
+            .runner_up_bids
+            .iter()
+            .any(|runner_up_bid| runner_up_bid.bidder == bidder && runner_up_bid.amount >= amount)
+    {
+        return;
+    }
+
+    let runner_up_bids = &mut listing_config.runner_up_bids;
+    runner_up_bids.retain(|runner_up_bid| runner_up_bid.bidder != bidder);
+    // Ties go to the bid that was placed first
+    let index = runner_up_bids
+        .iter()
+        .position(|runner_up_bid| runner_up_bid.amount < amount)
+        .unwrap_or(runner_up_bids.len());
+    runner_up_bids.insert(
+        index,
+        RunnerUpBid {
+            bidder,
+            amount,
+            buyer_trade_state,
+        },
+    );
+    runner_up_bids.truncate(MAX_RUNNER_UP_BIDS);
+}
+
+pub fn remove_runner_up_bid(
+    listing_config: &mut Account<ListingConfig>,
+    buyer_trade_state: Pubkey,
+) {
+    listing_config
+        .runner_up_bids
+        .retain(|runner_up_bid| runner_up_bid.buyer_trade_state != buyer_trade_state);
+}
+
+// What a winner's escrow has to hold to settle a bid of `amount`, the buyer's premium included
+pub fn get_escrow_requirement(auction_house: &AuctionHouse, amount: u64) -> Result<u64> {
+    amount
+        .checked_add(get_buyer_premium(auction_house, amount)?)
+        .ok_or_else(|| error!(AuctioneerError::NumericalOverflow))
+}
+
+pub fn get_escrow_balance(
+    escrow_payment_account: &AccountInfo,
+    treasury_mint: &Pubkey,
+) -> Result<u64> {
+    if *treasury_mint == spl_token::native_mint::id() {
+        // The rent exempt minimum stays behind in the escrow and can not pay for a bid
+        let rent_minimum = Rent::get()?.minimum_balance(escrow_payment_account.data_len());
+        Ok(escrow_payment_account
+            .lamports()
+            .saturating_sub(rent_minimum))
+    } else if escrow_payment_account.data_is_empty()
+        || *escrow_payment_account.owner == system_program::ID
+    {
+        // A closed or never funded escrow token account holds nothing
+        Ok(0)
+    } else {
+        Ok(InterfaceAccount::<TokenAccount>::try_from(escrow_payment_account)?.amount)
+    }
+}
+
+// Raises the leading proxy bid over a competing bid, returns false when the proxy is outbid
+pub fn process_proxy_defense(
+    listing_config: &mut Account<ListingConfig>,
+    bidder: Pubkey,
+    amount: u64,
+) -> Result<bool> {
+    if listing_config.proxy_max_amount == 0
+        || listing_config.highest_bidder == bidder
+        || amount > listing_config.proxy_max_amount
     {
-        return err!(AuctioneerError::BelowBidIncrement);
+        return Ok(false);
     }
 
+    // Ties go to the proxy bid that was placed first
+    listing_config.highest_bid.amount = amount
+        .saturating_add(get_bid_increment(listing_config, amount)?)
+        .min(listing_config.proxy_max_amount);
+
+    emit!(Outbid {
+        listing_config: listing_config.key(),
+        bidder,
+        highest_bidder: listing_config.highest_bidder,
+        highest_bid: listing_config.highest_bid.amount,
+    });
+
+    Ok(true)
+}
+
+pub fn process_proxy_bid(
+    listing_config: &mut Account<ListingConfig>,
+    bidder: Pubkey,
+    max_amount: u64,
+) -> Result<()> {
+    assert_exceeds_reserve_price(listing_config, max_amount)?;
+
+    if listing_config.proxy_max_amount > 0 && listing_config.highest_bidder == bidder {
+        if max_amount <= listing_config.proxy_max_amount {
+            return err!(AuctioneerError::BidTooLow);
+        }
+        listing_config.proxy_max_amount = max_amount;
+
+        return Ok(());
+    }
+
+    assert_higher_bid(listing_config, max_amount)?;
+
+    let previous_max_amount = listing_config.proxy_max_amount;
+    if process_proxy_defense(listing_config, bidder, max_amount)? {
+        return Ok(());
+    }
+
+    let standing_amount = if previous_max_amount > 0 {
+        previous_max_amount
+            .saturating_add(get_bid_increment(listing_config, previous_max_amount)?)
+            .min(max_amount)
+    } else {
+        get_min_next_bid(listing_config)?.min(max_amount)
+    };
+    process_highest_bid(listing_config, bidder, standing_amount, Pubkey::default());
+    listing_config.proxy_max_amount = max_amount;
+
     Ok(())
 }
 
@@ -63,13 +438,288 @@ pub fn assert_highest_bidder(
     Ok(())
 }
 
+// The escrow ledger of a wallet pools its locks across listings, only the lock this listing
+// recorded for its highest bidder can back the winning bid
+pub fn assert_listing_escrow_locked(
+    listing_config: &Account<ListingConfig>,
+    buyer_price: u64,
+) -> Result<()> {
+    if listing_config.escrow_locked_amount < buyer_price {
+        return err!(AuctioneerError::ListingEscrowNotLocked);
+    }
+
+    Ok(())
+}
+
 pub fn process_time_extension(listing_config: &mut Account<ListingConfig>) -> Result<()> {
     let clock = Clock::get()?;
     let current_timestamp = clock.unix_timestamp;
 
-    if current_timestamp >= (listing_config.end_time - i64::from(listing_config.time_ext_period)) {
-        listing_config.end_time += i64::from(listing_config.time_ext_delta);
+    let extension_start = listing_config
+        .end_time
+        .checked_sub(i64::from(listing_config.time_ext_period))
+        .ok_or(AuctioneerError::NumericalOverflow)?;
+    if current_timestamp >= extension_start {
+        let mut end_time = listing_config
+            .end_time
+            .checked_add(i64::from(listing_config.time_ext_delta))
+            .ok_or(AuctioneerError::NumericalOverflow)?;
+        // Listings created before the cap existed have no max end time
+        if listing_config.max_end_time > 0 {
+            end_time = end_time.min(listing_config.max_end_time);
+        }
+
+        // Nothing to report once the cap is reached or the listing has no extension
+        if end_time > listing_config.end_time {
+            listing_config.end_time = end_time;
+
+            emit!(TimeExtended {
+                listing_config: listing_config.key(),
+                end_time,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+pub fn is_sealed_bid_auction(listing_config: &Account<ListingConfig>) -> bool {
+    matches!(
+        listing_config.auction_type,
+        AuctionType::SealedFirstPrice | AuctionType::SealedSecondPrice
+    )
+}
+
+pub fn compute_bid_commitment(amount: u64, salt: &[u8; 32], wallet: &Pubkey) -> [u8; 32] {
+    keccak::hashv(&[&amount.to_le_bytes(), salt.as_ref(), wallet.as_ref()]).to_bytes()
+}
+
+pub fn assert_reveal_active(listing_config: &Account<ListingConfig>) -> Result<()> {
+    let clock = Clock::get()?;
+    let current_timestamp = clock.unix_timestamp;
+
+    if !is_sealed_bid_auction(listing_config) {
+        return err!(AuctioneerError::NotSealedBidAuction);
+    } else if current_timestamp < listing_config.end_time
+        || current_timestamp > listing_config.reveal_end_time
+    {
+        return err!(AuctioneerError::RevealPeriodNotActive);
+    }
+
+    Ok(())
+}
+
+pub fn assert_reveal_over(listing_config: &Account<ListingConfig>) -> Result<()> {
+    let clock = Clock::get()?;
+    let current_timestamp = clock.unix_timestamp;
+
+    if !is_sealed_bid_auction(listing_config) {
+        return err!(AuctioneerError::NotSealedBidAuction);
+    } else if current_timestamp <= listing_config.reveal_end_time {
+        return err!(AuctioneerError::RevealPeriodActive);
+    }
+
+    Ok(())
+}
+
+pub fn assert_sealed_bid_settled(listing_config: &Account<ListingConfig>) -> Result<()> {
+    if listing_config.highest_bid.buyer_trade_state == Pubkey::default() {
+        return err!(AuctioneerError::RevealPeriodActive);
+    }
+
+    Ok(())
+}
+
+pub fn process_revealed_bid(
+    listing_config: &mut Account<ListingConfig>,
+    bidder: Pubkey,
+    amount: u64,
+) {
+    // Ties go to the bid that was revealed first
+    if amount > listing_config.highest_bid.amount {
+        listing_config.second_highest_amount = listing_config.highest_bid.amount;
+        listing_config.highest_bid.amount = amount;
+        listing_config.highest_bidder = bidder;
+    } else if amount > listing_config.second_highest_amount {
+        listing_config.second_highest_amount = amount;
+    }
+}
+
+pub fn get_sealed_bid_price(listing_config: &Account<ListingConfig>) -> u64 {
+    match listing_config.auction_type {
+        AuctionType::SealedSecondPrice => listing_config
+            .second_highest_amount
+            .max(listing_config.reserve_price),
+        _ => listing_config.highest_bid.amount,
+    }
+}
+
+// The leading bidder has to keep the full maximum of a proxy bid in escrow
+pub fn get_escrow_lock(listing_config: &Account<ListingConfig>) -> u64 {
+    if listing_config.proxy_max_amount > 0 {
+        listing_config.proxy_max_amount
+    } else {
+        listing_config.highest_bid.amount
+    }
+}
+
+// Moves the escrow lock of the listing over to the bidder now holding the highest bid
+pub fn sync_escrow_lock<'info>(
+    listing_config: &mut Account<ListingConfig>,
+    previous_bidder: Pubkey,
+    auction_house_program: AccountInfo<'info>,
+    lock_accounts: AHLockEscrow<'info>,
+    unlock_accounts: AHUnlockEscrow<'info>,
+    auctioneer_seeds: &[&[u8]],
+) -> Result<()> {
+    let bidder = lock_accounts.wallet.key();
+    if listing_config.highest_bidder != bidder {
+        return Ok(());
+    }
+
+    let locked_amount = get_escrow_lock(listing_config);
+    let previous_locked_amount = listing_config.escrow_locked_amount;
+    let (unlock_amount, lock_amount) = if previous_bidder == bidder {
+        (
+            previous_locked_amount.saturating_sub(locked_amount),
+            locked_amount.saturating_sub(previous_locked_amount),
+        )
+    } else {
+        (previous_locked_amount, locked_amount)
+    };
+
+    unlock_escrow(
+        auction_house_program.clone(),
+        unlock_accounts,
+        auctioneer_seeds,
+        unlock_amount,
+    )?;
+    if lock_amount > 0 {
+        auction_house::cpi::auctioneer_lock_escrow(
+            CpiContext::new_with_signer(auction_house_program, lock_accounts, &[auctioneer_seeds]),
+            lock_amount,
+        )?;
+    }
+    listing_config.escrow_locked_amount = locked_amount;
+
+    Ok(())
+}
+
+pub fn unlock_escrow<'info>(
+    auction_house_program: AccountInfo<'info>,
+    unlock_accounts: AHUnlockEscrow<'info>,
+    auctioneer_seeds: &[&[u8]],
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+
+    auction_house::cpi::auctioneer_unlock_escrow(
+        CpiContext::new_with_signer(auction_house_program, unlock_accounts, &[auctioneer_seeds]),
+        amount,
+    )
+}
+
+pub fn assert_batch_auction_active(
+    batch_listing_config: &Account<BatchListingConfig>,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let current_timestamp = clock.unix_timestamp;
+
+    if current_timestamp < batch_listing_config.start_time {
+        return err!(AuctioneerError::AuctionNotStarted);
+    } else if current_timestamp > batch_listing_config.end_time {
+        return err!(AuctioneerError::AuctionEnded);
     }
 
     Ok(())
 }
+
+pub fn assert_batch_auction_cleared(
+    batch_listing_config: &Account<BatchListingConfig>,
+) -> Result<()> {
+    if !batch_listing_config.cleared {
+        return err!(AuctioneerError::AuctionNotCleared);
+    }
+
+    Ok(())
+}
+
+pub fn get_batch_bid_index(bid_book: &Account<BidBook>, bidder: Pubkey) -> Result<usize> {
+    bid_book
+        .bids
+        .iter()
+        .position(|bid| bid.bidder == bidder)
+        .ok_or_else(|| error!(AuctioneerError::BatchBidNotFound))
+}
+
+// Places or raises the bid of the bidder, returns the escrow amount to add to the lock
+pub fn process_batch_bid(
+    bid_book: &mut Account<BidBook>,
+    bidder: Pubkey,
+    price: u64,
+    quantity: u64,
+) -> Result<u64> {
+    let amount = price
+        .checked_mul(quantity)
+        .ok_or(AuctioneerError::NumericalOverflow)?;
+
+    let bids = &mut bid_book.bids;
+    let previous_amount = match bids.iter().position(|bid| bid.bidder == bidder) {
+        Some(index) => {
+            let previous_bid = bids.remove(index);
+            if price < previous_bid.price
+                || quantity < previous_bid.quantity
+                || amount == previous_bid.locked_amount
+            {
+                return err!(AuctioneerError::BidTooLow);
+            }
+
+            previous_bid.locked_amount
+        }
+        None => {
+            if bids.len() >= MAX_BATCH_BIDS {
+                return err!(AuctioneerError::BidBookFull);
+            }
+
+            0
+        }
+    };
+
+    // Ties go to the bid that was placed first, a raised bid goes behind its equals
+    let index = bids
+        .iter()
+        .position(|bid| bid.price < price)
+        .unwrap_or(bids.len());
+    bids.insert(
+        index,
+        BatchBid {
+            bidder,
+            price,
+            quantity,
+            filled: 0,
+            locked_amount: amount,
+            buyer_trade_state: Pubkey::default(),
+            state: BatchBidState::Open,
+        },
+    );
+
+    Ok(amount - previous_amount)
+}
+
+// Fills the bids by descending price, returns the uniform clearing price and the units sold
+pub fn clear_batch_bids(bid_book: &mut Account<BidBook>, token_size: u64) -> (u64, u64) {
+    let mut remaining_units = token_size;
+    let mut clearing_price = 0;
+
+    for bid in bid_book.bids.iter_mut() {
+        bid.filled = bid.quantity.min(remaining_units);
+        if bid.filled > 0 {
+            clearing_price = bid.price;
+            remaining_units -= bid.filled;
+        }
+    }
+
+    (clearing_price, token_size - remaining_units)
+}
@@ -3,12 +3,12 @@ use anchor_lang::{
 };
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{Mint, Token},
+    token_interface::{Mint, TokenInterface},
 };
 
 use auction_house::{
     self,
-    constants::{AUCTIONEER, FEE_PAYER, PREFIX},
+    constants::{AUCTIONEER, ESCROW_LEDGER, FEE_PAYER, PREFIX},
     cpi::accounts::AuctioneerWithdraw as AHWithdraw,
     program::AuctionHouse as AuctionHouseProgram,
     AuctionHouse,
@@ -35,7 +35,18 @@ pub struct AuctioneerWithdraw<'info> {
         bump = escrow_payment_bump
     )]
     pub escrow_payment_account: UncheckedAccount<'info>,
-    pub treasury_mint: Box<Account<'info, Mint>>,
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            wallet.key().as_ref(),
+            ESCROW_LEDGER.as_bytes()
+        ],
+        seeds::program = auction_house_program,
+        bump
+    )]
+    pub escrow_ledger: UncheckedAccount<'info>,
+    pub treasury_mint: Box<InterfaceAccount<'info, Mint>>,
     pub authority: UncheckedAccount<'info>,
     #[account(
         seeds = [
@@ -79,7 +90,7 @@ pub struct AuctioneerWithdraw<'info> {
     )]
     pub auctioneer: Account<'info, auction_house::Auctioneer>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub rent: Sysvar<'info, Rent>,
@@ -96,6 +107,7 @@ pub fn auctioneer_withdraw(
         wallet: ctx.accounts.wallet.to_account_info(),
         receipt_account: ctx.accounts.receipt_account.to_account_info(),
         escrow_payment_account: ctx.accounts.escrow_payment_account.to_account_info(),
+        escrow_ledger: ctx.accounts.escrow_ledger.to_account_info(),
         treasury_mint: ctx.accounts.treasury_mint.to_account_info(),
         authority: ctx.accounts.authority.to_account_info(),
         auction_house: ctx.accounts.auction_house.to_account_info(),
@@ -0,0 +1,475 @@
+use anchor_client::solana_sdk::{signature::Keypair, signer::Signer, transaction::Transaction};
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{solana_program::clock::Clock, AccountDeserialize};
+use anchor_spl::{associated_token::get_associated_token_address, token::spl_token};
+use auction_house::AuctionHouse;
+use auctioneer::errors::AuctioneerError;
+use auctioneer::execute_sale::config::AuctionResult;
+use auctioneer::pda::find_auction_result_address;
+use solana_program_test::{tokio, ProgramTestContext};
+use std::time::SystemTime;
+
+mod utils;
+use utils::setup_functions::*;
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_secs() as i64
+}
+
+// Lists the token in an auction that starts now and places a bid on it, the bidder is funded
+async fn list_and_bid(
+    context: &mut ProgramTestContext,
+    auction_house: &Pubkey,
+    auction_house_data: &AuctionHouse,
+    token: &NFT,
+    buyer: &Keypair,
+    price: u64,
+) -> (
+    auctioneer::accounts::AuctioneerSell,
+    auctioneer::accounts::AuctioneerBuy,
+    i64,
+) {
+    let start_time = context
+        .banks_client
+        .get_sysvar::<Clock>()
+        .await
+        .unwrap()
+        .unix_timestamp;
+    let end_time = start_time + 60;
+    let (sell_accounts, sell_tx) = sell(
+        context,
+        auction_house,
+        auction_house_data,
+        token,
+        start_time - 1,
+        end_time,
+        None,
+        None,
+        None,
+        None,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    let (buy_accounts, buy_tx) = buy(
+        context,
+        auction_house,
+        auction_house_data,
+        token,
+        buyer,
+        price,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    (sell_accounts, buy_accounts, end_time)
+}
+
+// Sends the token back from the winner to the seller so it can be listed again
+async fn return_token(context: &mut ProgramTestContext, token: &NFT, buyer: &Keypair) {
+    let buyer_ata = get_associated_token_address(&buyer.pubkey(), &token.mint.pubkey());
+    let transfer_ix = spl_token::instruction::transfer(
+        &spl_token::id(),
+        &buyer_ata,
+        &token.ata,
+        &buyer.pubkey(),
+        &[],
+        1,
+    )
+    .unwrap();
+    let transfer_tx = Transaction::new_signed_with_payer(
+        &[transfer_ix],
+        Some(&buyer.pubkey()),
+        &[buyer],
+        context.last_blockhash,
+    );
+    context
+        .banks_client
+        .process_transaction(transfer_tx)
+        .await
+        .unwrap();
+}
+
+async fn get_auction_result(
+    context: &mut ProgramTestContext,
+    listing_config: &Pubkey,
+    buyer_trade_state: &Pubkey,
+) -> AuctionResult {
+    let (auction_result, _) = find_auction_result_address(listing_config, buyer_trade_state);
+    let auction_result_account = context
+        .banks_client
+        .get_account(auction_result)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    AuctionResult::try_deserialize(&mut auction_result_account.as_ref()).unwrap()
+}
+
+#[tokio::test]
+async fn auction_result_success() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+
+    let (_, auction_house, auction_house_data) = create_auction_house(&mut context, 100, false)
+        .await
+        .expect("Failed to create Auction House");
+
+    let token = create_nft(&mut context, None)
+        .await
+        .expect("Failed to create NFT");
+
+    let end_time = now() + 60;
+
+    // Sell
+
+    let (sell_accounts, sell_tx) = sell(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        now() - 60,
+        end_time,
+        None,
+        None,
+        None,
+        None,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .expect("Failed to sell NFT");
+
+    // Buy
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), 10 * ONE_SOL)
+        .await
+        .unwrap();
+
+    let (_, deposit_tx) = deposit(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &buyer,
+        5 * ONE_SOL,
+    );
+    context
+        .banks_client
+        .process_transaction(deposit_tx)
+        .await
+        .unwrap();
+
+    let (buy_accounts, buy_tx) = buy(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer,
+        ONE_SOL,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    // Execute sale
+
+    warp_to_timestamp(&mut context, end_time + 1).await;
+
+    let (_, execute_sale_tx) = execute_sale(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        None,
+        &sell_accounts,
+        &buy_accounts,
+        ONE_SOL,
+        &buyer,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(execute_sale_tx)
+        .await
+        .unwrap();
+
+    let (auction_result, _) = find_auction_result_address(
+        &sell_accounts.listing_config,
+        &buy_accounts.buyer_trade_state,
+    );
+    let auction_result_account = context
+        .banks_client
+        .get_account(auction_result)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    let auction_result_data =
+        AuctionResult::try_deserialize(&mut auction_result_account.as_ref()).unwrap();
+
+    assert_eq!(
+        auction_result_data.listing_config,
+        sell_accounts.listing_config
+    );
+    assert_eq!(auction_result_data.seller, token.owner.pubkey());
+    assert_eq!(auction_result_data.winner, buyer.pubkey());
+    assert_eq!(
+        auction_result_data.buyer_trade_state,
+        buy_accounts.buyer_trade_state
+    );
+    assert_eq!(auction_result_data.token_mint, token.mint.pubkey());
+    assert_eq!(auction_result_data.price, ONE_SOL);
+    assert_eq!(auction_result_data.auction_house_fee, ONE_SOL / 100);
+
+    // Close
+
+    let (_, close_tx) = close_auction_result(
+        &mut context,
+        &sell_accounts.listing_config,
+        &buy_accounts.buyer_trade_state,
+        &token.owner,
+    );
+    context
+        .banks_client
+        .process_transaction(close_tx)
+        .await
+        .unwrap();
+
+    let auction_result_account = context
+        .banks_client
+        .get_account(auction_result)
+        .await
+        .unwrap();
+    assert!(auction_result_account.is_none());
+}
+
+#[tokio::test]
+async fn auction_result_relist_success() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+
+    let (_, auction_house, auction_house_data) = create_auction_house(&mut context, 100, false)
+        .await
+        .expect("Failed to create Auction House");
+
+    let token = create_nft(&mut context, None)
+        .await
+        .expect("Failed to create NFT");
+
+    let first_buyer = Keypair::new();
+    let second_buyer = Keypair::new();
+    for buyer in [&first_buyer, &second_buyer] {
+        airdrop(&mut context, &buyer.pubkey(), 10 * ONE_SOL)
+            .await
+            .unwrap();
+        let (_, deposit_tx) = deposit(
+            &mut context,
+            &auction_house,
+            &auction_house_data,
+            buyer,
+            5 * ONE_SOL,
+        );
+        context
+            .banks_client
+            .process_transaction(deposit_tx)
+            .await
+            .unwrap();
+    }
+
+    // First sale
+
+    let (sell_accounts, first_buy_accounts, end_time) = list_and_bid(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &first_buyer,
+        ONE_SOL,
+    )
+    .await;
+    warp_to_timestamp(&mut context, end_time + 1).await;
+
+    let (_, execute_sale_tx) = execute_sale(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        None,
+        &sell_accounts,
+        &first_buy_accounts,
+        ONE_SOL,
+        &first_buyer,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(execute_sale_tx)
+        .await
+        .unwrap();
+
+    // The token goes back to the seller, who lists it again without closing the result
+
+    return_token(&mut context, &token, &first_buyer).await;
+    let (relist_accounts, second_buy_accounts, end_time) = list_and_bid(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &second_buyer,
+        2 * ONE_SOL,
+    )
+    .await;
+    assert_eq!(relist_accounts.listing_config, sell_accounts.listing_config);
+    warp_to_timestamp(&mut context, end_time + 1).await;
+
+    let (_, execute_sale_tx) = execute_sale(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        None,
+        &relist_accounts,
+        &second_buy_accounts,
+        2 * ONE_SOL,
+        &second_buyer,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(execute_sale_tx)
+        .await
+        .unwrap();
+
+    // Both sales keep their own record
+
+    let first_result = get_auction_result(
+        &mut context,
+        &sell_accounts.listing_config,
+        &first_buy_accounts.buyer_trade_state,
+    )
+    .await;
+    assert_eq!(first_result.winner, first_buyer.pubkey());
+    assert_eq!(first_result.price, ONE_SOL);
+    assert_eq!(first_result.auction_house_fee, ONE_SOL / 100);
+
+    let second_result = get_auction_result(
+        &mut context,
+        &relist_accounts.listing_config,
+        &second_buy_accounts.buyer_trade_state,
+    )
+    .await;
+    assert_eq!(second_result.seller, token.owner.pubkey());
+    assert_eq!(second_result.winner, second_buyer.pubkey());
+    assert_eq!(second_result.price, 2 * ONE_SOL);
+    assert_eq!(second_result.auction_house_fee, 2 * ONE_SOL / 100);
+}
+
+#[tokio::test]
+async fn failure_auction_result_relist_same_winning_bid() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+
+    let (_, auction_house, auction_house_data) = create_auction_house(&mut context, 100, false)
+        .await
+        .expect("Failed to create Auction House");
+
+    let token = create_nft(&mut context, None)
+        .await
+        .expect("Failed to create NFT");
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), 10 * ONE_SOL)
+        .await
+        .unwrap();
+    let (_, deposit_tx) = deposit(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &buyer,
+        5 * ONE_SOL,
+    );
+    context
+        .banks_client
+        .process_transaction(deposit_tx)
+        .await
+        .unwrap();
+
+    let (sell_accounts, buy_accounts, end_time) = list_and_bid(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer,
+        ONE_SOL,
+    )
+    .await;
+    warp_to_timestamp(&mut context, end_time + 1).await;
+
+    let (_, execute_sale_tx) = execute_sale(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        None,
+        &sell_accounts,
+        &buy_accounts,
+        ONE_SOL,
+        &buyer,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(execute_sale_tx)
+        .await
+        .unwrap();
+
+    // The same buyer wins the relisted token with the same bid, landing on the same record
+
+    return_token(&mut context, &token, &buyer).await;
+    let (relist_accounts, rebuy_accounts, end_time) = list_and_bid(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer,
+        ONE_SOL,
+    )
+    .await;
+    assert_eq!(
+        rebuy_accounts.buyer_trade_state,
+        buy_accounts.buyer_trade_state
+    );
+    warp_to_timestamp(&mut context, end_time + 1).await;
+
+    let (_, execute_sale_tx) = execute_sale(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        None,
+        &relist_accounts,
+        &rebuy_accounts,
+        ONE_SOL,
+        &buyer,
+    )
+    .await;
+    let tx_error = context
+        .banks_client
+        .process_transaction(execute_sale_tx)
+        .await
+        .unwrap_err();
+
+    assert_error(tx_error, AuctioneerError::AuctionResultAlreadyExists.into());
+}
@@ -0,0 +1,309 @@
+use anchor_client::solana_sdk::account::AccountSharedData;
+use anchor_client::solana_sdk::{signature::Keypair, signer::Signer};
+use anchor_lang::{prelude::Pubkey, AccountDeserialize};
+use auction_house::{errors::AuctionHouseError, AuctionHouse};
+use auctioneer::authorize::config::AuctioneerConfig;
+use auctioneer::errors::AuctioneerError;
+use auctioneer::events::AuctionSettled;
+use auctioneer::pda::find_auctioneer_config_address;
+use solana_program_test::{tokio, ProgramTestContext};
+use std::time::SystemTime;
+
+mod utils;
+use utils::setup_functions::*;
+
+const MAX_AUCTIONEER_FEE_BASIS_POINTS: u16 = 500;
+const AUCTIONEER_FEE_BASIS_POINTS: u16 = 200;
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_secs() as i64
+}
+
+// Auction house letting the auctioneer take up to 5%, with the auctioneer charging 2% to the
+// house authority
+async fn setup_auctioneer_fee(context: &mut ProgramTestContext) -> (Keypair, Pubkey, AuctionHouse) {
+    let (authority, auction_house, auction_house_data) = create_auction_house(context, 100, false)
+        .await
+        .expect("Failed to create Auction House");
+
+    let update_tx = update_auction_house(
+        context,
+        &authority,
+        &auction_house,
+        &auction_house_data,
+        None,
+        None,
+        None,
+        Some(MAX_AUCTIONEER_FEE_BASIS_POINTS),
+    );
+    context
+        .banks_client
+        .process_transaction(update_tx)
+        .await
+        .unwrap();
+
+    let (_, config_tx) = update_auctioneer_config(
+        context,
+        &authority,
+        &auction_house,
+        Some(AUCTIONEER_FEE_BASIS_POINTS),
+        &authority.pubkey(),
+    );
+    context
+        .banks_client
+        .process_transaction(config_tx)
+        .await
+        .unwrap();
+
+    let auction_house_data = get_auction_house(context, &auction_house).await;
+
+    (authority, auction_house, auction_house_data)
+}
+
+// Lists an NFT in an auction that has already ended with a 1 SOL winning bid
+async fn setup_won_auction(
+    context: &mut ProgramTestContext,
+    auction_house: &Pubkey,
+    auction_house_data: &AuctionHouse,
+) -> (
+    NFT,
+    auctioneer::accounts::AuctioneerSell,
+    auctioneer::accounts::AuctioneerBuy,
+    Keypair,
+) {
+    let token = create_nft(context, None)
+        .await
+        .expect("Failed to create NFT");
+
+    let end_time = now() + 60;
+    let (sell_accounts, sell_tx) = sell(
+        context,
+        auction_house,
+        auction_house_data,
+        &token,
+        now() - 60,
+        end_time,
+        None,
+        None,
+        None,
+        None,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    let buyer = Keypair::new();
+    airdrop(context, &buyer.pubkey(), 10 * ONE_SOL)
+        .await
+        .unwrap();
+    let (_, deposit_tx) = deposit(
+        context,
+        auction_house,
+        auction_house_data,
+        &buyer,
+        2 * ONE_SOL,
+    );
+    context
+        .banks_client
+        .process_transaction(deposit_tx)
+        .await
+        .unwrap();
+
+    let (buy_accounts, buy_tx) = buy(
+        context,
+        auction_house,
+        auction_house_data,
+        &token,
+        &buyer,
+        ONE_SOL,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    warp_to_timestamp(context, end_time + 1).await;
+
+    (token, sell_accounts, buy_accounts, buyer)
+}
+
+#[tokio::test]
+async fn execute_sale_auctioneer_fee_success() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+    let (authority, auction_house, auction_house_data) = setup_auctioneer_fee(&mut context).await;
+    let (token, sell_accounts, buy_accounts, buyer) =
+        setup_won_auction(&mut context, &auction_house, &auction_house_data).await;
+
+    let fee_destination_before = context
+        .banks_client
+        .get_balance(authority.pubkey())
+        .await
+        .unwrap();
+
+    let (_, execute_sale_tx) = execute_sale(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        None,
+        &sell_accounts,
+        &buy_accounts,
+        ONE_SOL,
+        &buyer,
+    )
+    .await;
+    let events: Vec<AuctionSettled> =
+        process_transaction_events(&mut context, execute_sale_tx).await;
+
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].auctioneer_fee, ONE_SOL / 50);
+    assert_eq!(
+        context
+            .banks_client
+            .get_balance(authority.pubkey())
+            .await
+            .unwrap(),
+        fee_destination_before + ONE_SOL / 50
+    );
+}
+
+#[tokio::test]
+async fn failure_execute_sale_auctioneer_fee_above_house_maximum() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+    let (authority, auction_house, auction_house_data) = setup_auctioneer_fee(&mut context).await;
+    let (token, sell_accounts, buy_accounts, buyer) =
+        setup_won_auction(&mut context, &auction_house, &auction_house_data).await;
+
+    // The house lowers its cap below the fee the auctioneer was configured with
+    let update_tx = update_auction_house(
+        &mut context,
+        &authority,
+        &auction_house,
+        &auction_house_data,
+        None,
+        None,
+        None,
+        Some(AUCTIONEER_FEE_BASIS_POINTS / 2),
+    );
+    context
+        .banks_client
+        .process_transaction(update_tx)
+        .await
+        .unwrap();
+
+    let (_, execute_sale_tx) = execute_sale(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        None,
+        &sell_accounts,
+        &buy_accounts,
+        ONE_SOL,
+        &buyer,
+    )
+    .await;
+    let tx_error = context
+        .banks_client
+        .process_transaction(execute_sale_tx)
+        .await
+        .unwrap_err();
+
+    assert_error(
+        tx_error,
+        AuctionHouseError::AuctioneerFeeAboveMaximum.into(),
+    );
+}
+
+#[tokio::test]
+async fn failure_update_auctioneer_config_above_house_maximum() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+
+    // Houses allow no auctioneer fee until they raise the cap
+    let (authority, auction_house, _) = create_auction_house(&mut context, 100, false)
+        .await
+        .expect("Failed to create Auction House");
+
+    let (_, config_tx) = update_auctioneer_config(
+        &mut context,
+        &authority,
+        &auction_house,
+        Some(AUCTIONEER_FEE_BASIS_POINTS),
+        &authority.pubkey(),
+    );
+    let tx_error = context
+        .banks_client
+        .process_transaction(config_tx)
+        .await
+        .unwrap_err();
+
+    assert_error(tx_error, AuctioneerError::InvalidAuctioneerFee.into());
+}
+
+#[tokio::test]
+async fn init_auctioneer_config_previously_authorized_success() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+
+    let (authority, auction_house, auction_house_data) =
+        create_auction_house(&mut context, 100, false)
+            .await
+            .expect("Failed to create Auction House");
+
+    // A house authorized before the config existed has none
+    let (auctioneer_config, _) = find_auctioneer_config_address(&auction_house);
+    context.set_account(&auctioneer_config, &AccountSharedData::default());
+
+    let (_, init_tx) = init_auctioneer_config(
+        &mut context,
+        &authority,
+        &auction_house,
+        0,
+        &authority.pubkey(),
+    );
+    context
+        .banks_client
+        .process_transaction(init_tx)
+        .await
+        .unwrap();
+
+    let auctioneer_config_account = context
+        .banks_client
+        .get_account(auctioneer_config)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    let auctioneer_config_data =
+        AuctioneerConfig::try_deserialize(&mut auctioneer_config_account.as_ref()).unwrap();
+    assert_eq!(auctioneer_config_data.auction_house, auction_house);
+    assert_eq!(auctioneer_config_data.fee_basis_points, 0);
+    assert_eq!(auctioneer_config_data.fee_destination, authority.pubkey());
+
+    // The house settles again once the config is back
+    let (token, sell_accounts, buy_accounts, buyer) =
+        setup_won_auction(&mut context, &auction_house, &auction_house_data).await;
+    let (_, execute_sale_tx) = execute_sale(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        None,
+        &sell_accounts,
+        &buy_accounts,
+        ONE_SOL,
+        &buyer,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(execute_sale_tx)
+        .await
+        .unwrap();
+}
@@ -0,0 +1,728 @@
+use anchor_client::solana_sdk::{signature::Keypair, signer::Signer};
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::{prelude::Pubkey, AccountDeserialize};
+use anchor_spl::token::spl_token;
+use auction_house::{pda::find_escrow_ledger_address, AuctionHouse, EscrowLedger};
+use auctioneer::batch_sell::config::{BatchBidState, BatchListingConfig, BidBook};
+use auctioneer::constants::BATCH_SETTLEMENT_PERIOD;
+use auctioneer::errors::AuctioneerError;
+use solana_program_test::{tokio, ProgramTestContext};
+use std::time::SystemTime;
+
+mod utils;
+use utils::setup_functions::*;
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_secs() as i64
+}
+
+#[tokio::test]
+async fn batch_auction_uniform_price_success() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+
+    let (_, auction_house, auction_house_data) = create_auction_house(&mut context, 100, false)
+        .await
+        .expect("Failed to create Auction House");
+
+    let token_size = 3;
+    let token = create_sft(&mut context, None, token_size)
+        .await
+        .expect("Failed to create SFT");
+
+    let start_time = now() - 60;
+    let end_time = now() + 60;
+
+    // Sell
+
+    let (sell_accounts, sell_tx) = batch_sell(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        token_size,
+        start_time,
+        end_time,
+        ONE_SOL / 2,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .expect("Failed to sell SFT");
+
+    // Bid, the lowest bid is left out once the top units are filled
+
+    let buyer1 = Keypair::new();
+    let buyer2 = Keypair::new();
+    let buyer3 = Keypair::new();
+    let bids = [
+        (&buyer1, 2 * ONE_SOL, 2),
+        (&buyer2, ONE_SOL, 2),
+        (&buyer3, ONE_SOL / 2, 1),
+    ];
+    for (buyer, price, quantity) in bids {
+        airdrop(&mut context, &buyer.pubkey(), 10 * ONE_SOL)
+            .await
+            .unwrap();
+
+        let (_, deposit_tx) = deposit(
+            &mut context,
+            &auction_house,
+            &auction_house_data,
+            buyer,
+            price * quantity,
+        );
+        context
+            .banks_client
+            .process_transaction(deposit_tx)
+            .await
+            .unwrap();
+
+        let (_, bid_tx) = batch_bid(
+            &mut context,
+            &auction_house,
+            &auction_house_data,
+            &token,
+            token_size,
+            buyer,
+            price,
+            quantity,
+        );
+        context
+            .banks_client
+            .process_transaction(bid_tx)
+            .await
+            .unwrap();
+    }
+
+    // Clear
+
+    warp_to_timestamp(&mut context, end_time + 1).await;
+
+    let (_, clear_tx) =
+        clear_batch_auction(&mut context, &sell_accounts.batch_listing_config, &buyer3);
+    context
+        .banks_client
+        .process_transaction(clear_tx)
+        .await
+        .unwrap();
+
+    let batch_listing_config_account = context
+        .banks_client
+        .get_account(sell_accounts.batch_listing_config)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    let batch_listing_config_data =
+        BatchListingConfig::try_deserialize(&mut batch_listing_config_account.as_ref()).unwrap();
+
+    assert!(batch_listing_config_data.cleared);
+    assert_eq!(batch_listing_config_data.clearing_price, ONE_SOL);
+
+    // Settle, winners pay the clearing price for their filled units
+
+    let mut settle_accounts = Vec::new();
+    for (buyer, filled) in [(&buyer1, 2), (&buyer2, 1), (&buyer3, 0)] {
+        let (accounts, settle_tx) = settle_batch_bid(
+            &mut context,
+            &auction_house,
+            &auction_house_data,
+            &token,
+            token_size,
+            buyer,
+            ONE_SOL * filled,
+            filled,
+        );
+        context
+            .banks_client
+            .process_transaction(settle_tx)
+            .await
+            .unwrap();
+        settle_accounts.push((buyer, accounts, filled));
+    }
+
+    // Execute
+
+    for (buyer, accounts, filled) in settle_accounts.iter().take(2) {
+        let (execute_sale_accounts, execute_sale_tx) = execute_batch_sale(
+            &mut context,
+            &auction_house,
+            &auction_house_data,
+            &token,
+            &sell_accounts,
+            accounts,
+            token_size,
+            buyer,
+        );
+        context
+            .banks_client
+            .process_transaction(execute_sale_tx)
+            .await
+            .unwrap();
+
+        let buyer_token = spl_token::state::Account::unpack_from_slice(
+            context
+                .banks_client
+                .get_account(execute_sale_accounts.buyer_receipt_token_account)
+                .await
+                .unwrap()
+                .unwrap()
+                .data
+                .as_slice(),
+        )
+        .unwrap();
+        assert_eq!(buyer_token.amount, *filled);
+    }
+
+    let bid_book_account = context
+        .banks_client
+        .get_account(sell_accounts.bid_book)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    let bid_book_data = BidBook::try_deserialize(&mut bid_book_account.as_ref()).unwrap();
+
+    assert_eq!(bid_book_data.bids[0].state, BatchBidState::Executed);
+    assert_eq!(bid_book_data.bids[1].state, BatchBidState::Executed);
+    assert_eq!(bid_book_data.bids[2].bidder, buyer3.pubkey());
+    assert_eq!(bid_book_data.bids[2].state, BatchBidState::Released);
+    assert_eq!(bid_book_data.bids[2].locked_amount, 0);
+
+    let seller_token = spl_token::state::Account::unpack_from_slice(
+        context
+            .banks_client
+            .get_account(token.ata)
+            .await
+            .unwrap()
+            .unwrap()
+            .data
+            .as_slice(),
+    )
+    .unwrap();
+    assert_eq!(seller_token.amount, 0);
+}
+
+#[tokio::test]
+async fn failure_batch_sell_single_unit() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+
+    let (_, auction_house, auction_house_data) = create_auction_house(&mut context, 100, false)
+        .await
+        .expect("Failed to create Auction House");
+
+    let token = create_nft(&mut context, None)
+        .await
+        .expect("Failed to create NFT");
+
+    let (_, sell_tx) = batch_sell(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        1,
+        now() - 60,
+        now() + 60,
+        ONE_SOL,
+    );
+    let tx_error = context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap_err();
+
+    assert_error(tx_error, AuctioneerError::InvalidBatchSize.into());
+}
+
+#[tokio::test]
+async fn failure_batch_bid_over_listing_size() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+
+    let (_, auction_house, auction_house_data) = create_auction_house(&mut context, 100, false)
+        .await
+        .expect("Failed to create Auction House");
+
+    let token_size = 2;
+    let token = create_sft(&mut context, None, token_size)
+        .await
+        .expect("Failed to create SFT");
+
+    let (_, sell_tx) = batch_sell(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        token_size,
+        now() - 60,
+        now() + 60,
+        ONE_SOL,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .expect("Failed to sell SFT");
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), 10 * ONE_SOL)
+        .await
+        .unwrap();
+
+    let (_, bid_tx) = batch_bid(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        token_size,
+        &buyer,
+        ONE_SOL,
+        token_size + 1,
+    );
+    let tx_error = context
+        .banks_client
+        .process_transaction(bid_tx)
+        .await
+        .unwrap_err();
+
+    assert_error(tx_error, AuctioneerError::InvalidBidQuantity.into());
+}
+
+async fn place_batch_bid(
+    context: &mut ProgramTestContext,
+    auction_house: &Pubkey,
+    auction_house_data: &AuctionHouse,
+    token: &NFT,
+    token_size: u64,
+    buyer: &Keypair,
+    price: u64,
+    quantity: u64,
+) {
+    airdrop(context, &buyer.pubkey(), 10 * ONE_SOL)
+        .await
+        .unwrap();
+
+    let (_, deposit_tx) = deposit(
+        context,
+        auction_house,
+        auction_house_data,
+        buyer,
+        price * quantity,
+    );
+    context
+        .banks_client
+        .process_transaction(deposit_tx)
+        .await
+        .unwrap();
+
+    let (_, bid_tx) = batch_bid(
+        context,
+        auction_house,
+        auction_house_data,
+        token,
+        token_size,
+        buyer,
+        price,
+        quantity,
+    );
+    context
+        .banks_client
+        .process_transaction(bid_tx)
+        .await
+        .unwrap();
+}
+
+async fn get_locked_amount(
+    context: &mut ProgramTestContext,
+    auction_house: &Pubkey,
+    wallet: &Pubkey,
+) -> u64 {
+    let (escrow_ledger, _) = find_escrow_ledger_address(auction_house, wallet);
+    let escrow_ledger_account = context
+        .banks_client
+        .get_account(escrow_ledger)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+
+    EscrowLedger::try_deserialize(&mut escrow_ledger_account.as_ref())
+        .unwrap()
+        .locked_amount
+}
+
+#[tokio::test]
+async fn batch_auction_release_bids_and_cancel_success() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+
+    let (_, auction_house, auction_house_data) = create_auction_house(&mut context, 100, false)
+        .await
+        .expect("Failed to create Auction House");
+
+    let token_size = 2;
+    let token = create_sft(&mut context, None, token_size)
+        .await
+        .expect("Failed to create SFT");
+
+    let start_time = now() - 60;
+    let end_time = now() + 60;
+
+    let (sell_accounts, sell_tx) = batch_sell(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        token_size,
+        start_time,
+        end_time,
+        ONE_SOL / 2,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .expect("Failed to sell SFT");
+
+    // The winner takes both units, the second bid loses
+
+    let winner = Keypair::new();
+    let loser = Keypair::new();
+    place_batch_bid(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        token_size,
+        &winner,
+        ONE_SOL,
+        2,
+    )
+    .await;
+    place_batch_bid(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        token_size,
+        &loser,
+        ONE_SOL / 2,
+        1,
+    )
+    .await;
+
+    warp_to_timestamp(&mut context, end_time + 1).await;
+
+    let cranker = Keypair::new();
+    airdrop(&mut context, &cranker.pubkey(), ONE_SOL)
+        .await
+        .unwrap();
+
+    let (_, clear_tx) =
+        clear_batch_auction(&mut context, &sell_accounts.batch_listing_config, &cranker);
+    context
+        .banks_client
+        .process_transaction(clear_tx)
+        .await
+        .unwrap();
+
+    // Anyone can release the losing bid
+
+    let (_, release_tx) = release_batch_bid(
+        &mut context,
+        &auction_house,
+        &token,
+        token_size,
+        &loser.pubkey(),
+        &sell_accounts,
+        &cranker,
+    );
+    context
+        .banks_client
+        .process_transaction(release_tx)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        get_locked_amount(&mut context, &auction_house, &loser.pubkey()).await,
+        0
+    );
+
+    // The winner keeps the settlement period to settle
+
+    let (_, release_tx) = release_batch_bid(
+        &mut context,
+        &auction_house,
+        &token,
+        token_size,
+        &winner.pubkey(),
+        &sell_accounts,
+        &cranker,
+    );
+    let tx_error = context
+        .banks_client
+        .process_transaction(release_tx)
+        .await
+        .unwrap_err();
+
+    assert_error(tx_error, AuctioneerError::WinnerCanStillSettle.into());
+
+    // Past the deadline the seller reclaims the units of the winner who did not settle
+
+    warp_to_timestamp(&mut context, end_time + BATCH_SETTLEMENT_PERIOD + 1).await;
+
+    let (_, release_tx) = release_batch_bid(
+        &mut context,
+        &auction_house,
+        &token,
+        token_size,
+        &winner.pubkey(),
+        &sell_accounts,
+        &token.owner,
+    );
+    context
+        .banks_client
+        .process_transaction(release_tx)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        get_locked_amount(&mut context, &auction_house, &winner.pubkey()).await,
+        0
+    );
+
+    let bid_book_account = context
+        .banks_client
+        .get_account(sell_accounts.bid_book)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    let bid_book_data = BidBook::try_deserialize(&mut bid_book_account.as_ref()).unwrap();
+
+    assert!(bid_book_data
+        .bids
+        .iter()
+        .all(|bid| bid.state == BatchBidState::Released && bid.locked_amount == 0));
+
+    // With every bid released the listing can be cancelled
+
+    let (_, cancel_tx) = cancel_batch_listing(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        token_size,
+        &sell_accounts,
+    );
+    context
+        .banks_client
+        .process_transaction(cancel_tx)
+        .await
+        .unwrap();
+
+    for closed_account in [
+        sell_accounts.batch_listing_config,
+        sell_accounts.bid_book,
+        sell_accounts.seller_trade_state,
+    ] {
+        assert!(context
+            .banks_client
+            .get_account(closed_account)
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
+
+#[tokio::test]
+async fn failure_cancel_batch_listing_open_bids() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+
+    let (_, auction_house, auction_house_data) = create_auction_house(&mut context, 100, false)
+        .await
+        .expect("Failed to create Auction House");
+
+    let token_size = 2;
+    let token = create_sft(&mut context, None, token_size)
+        .await
+        .expect("Failed to create SFT");
+
+    let (sell_accounts, sell_tx) = batch_sell(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        token_size,
+        now() - 60,
+        now() + 60,
+        ONE_SOL,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .expect("Failed to sell SFT");
+
+    let buyer = Keypair::new();
+    place_batch_bid(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        token_size,
+        &buyer,
+        ONE_SOL,
+        1,
+    )
+    .await;
+
+    let (_, cancel_tx) = cancel_batch_listing(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        token_size,
+        &sell_accounts,
+    );
+    let tx_error = context
+        .banks_client
+        .process_transaction(cancel_tx)
+        .await
+        .unwrap_err();
+
+    assert_error(tx_error, AuctioneerError::BatchBidsOutstanding.into());
+}
+
+#[tokio::test]
+async fn execute_batch_sale_auctioneer_fee_success() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+
+    let (authority, auction_house, auction_house_data) =
+        create_auction_house(&mut context, 100, false)
+            .await
+            .expect("Failed to create Auction House");
+
+    // The house lets the auctioneer take 2% of each batch sale
+    let update_tx = update_auction_house(
+        &mut context,
+        &authority,
+        &auction_house,
+        &auction_house_data,
+        None,
+        None,
+        None,
+        Some(200),
+    );
+    context
+        .banks_client
+        .process_transaction(update_tx)
+        .await
+        .unwrap();
+    let (_, config_tx) = update_auctioneer_config(
+        &mut context,
+        &authority,
+        &auction_house,
+        Some(200),
+        &authority.pubkey(),
+    );
+    context
+        .banks_client
+        .process_transaction(config_tx)
+        .await
+        .unwrap();
+
+    let token_size = 2;
+    let token = create_sft(&mut context, None, token_size)
+        .await
+        .expect("Failed to create SFT");
+
+    let end_time = now() + 60;
+    let (sell_accounts, sell_tx) = batch_sell(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        token_size,
+        now() - 60,
+        end_time,
+        ONE_SOL,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .expect("Failed to sell SFT");
+
+    let buyer = Keypair::new();
+    place_batch_bid(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        token_size,
+        &buyer,
+        ONE_SOL,
+        token_size,
+    )
+    .await;
+
+    warp_to_timestamp(&mut context, end_time + 1).await;
+
+    let (_, clear_tx) =
+        clear_batch_auction(&mut context, &sell_accounts.batch_listing_config, &buyer);
+    context
+        .banks_client
+        .process_transaction(clear_tx)
+        .await
+        .unwrap();
+
+    let (settle_accounts, settle_tx) = settle_batch_bid(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        token_size,
+        &buyer,
+        ONE_SOL * token_size,
+        token_size,
+    );
+    context
+        .banks_client
+        .process_transaction(settle_tx)
+        .await
+        .unwrap();
+
+    let fee_destination_before = context
+        .banks_client
+        .get_balance(authority.pubkey())
+        .await
+        .unwrap();
+
+    let (_, execute_sale_tx) = execute_batch_sale(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &sell_accounts,
+        &settle_accounts,
+        token_size,
+        &buyer,
+    );
+    context
+        .banks_client
+        .process_transaction(execute_sale_tx)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        context
+            .banks_client
+            .get_balance(authority.pubkey())
+            .await
+            .unwrap(),
+        fee_destination_before + ONE_SOL * token_size / 50
+    );
+}
@@ -1,7 +1,10 @@
 use anchor_client::solana_sdk::{signature::Keypair, signer::Signer};
-use anchor_lang::AccountDeserialize;
+use anchor_lang::{prelude::Pubkey, AccountDeserialize};
+use auction_house::{pda::find_escrow_ledger_address, EscrowLedger};
 use auctioneer::errors::AuctioneerError;
-use auctioneer::sell::config::ListingConfig;
+use auctioneer::sell::config::{
+    BidIncrementMode, BidIncrementParams, BidIncrementTier, DutchAuctionParams, ListingConfig,
+};
 use solana_program_test::tokio;
 use std::time::SystemTime;
 
@@ -77,7 +80,8 @@ async fn buy_success() {
         &token,
         &buyer,
         bid_amount,
-    );
+    )
+    .await;
     context
         .banks_client
         .process_transaction(buy_tx)
@@ -167,7 +171,8 @@ async fn multiple_buy_success() {
         &token,
         &buyer1,
         bid_amount1,
-    );
+    )
+    .await;
     context
         .banks_client
         .process_transaction(buy_tx1)
@@ -203,7 +208,8 @@ async fn multiple_buy_success() {
         &token,
         &buyer2,
         bid_amount2,
-    );
+    )
+    .await;
     context
         .banks_client
         .process_transaction(buy_tx2)
@@ -293,7 +299,8 @@ async fn failure_buy_below_reserve_price() {
         &token,
         &buyer,
         bid_amount,
-    );
+    )
+    .await;
 
     let tx_error = context
         .banks_client
@@ -373,7 +380,8 @@ async fn failure_multiple_buy_increment() {
         &token,
         &buyer1,
         bid_amount1,
-    );
+    )
+    .await;
     context
         .banks_client
         .process_transaction(buy_tx1)
@@ -409,7 +417,8 @@ async fn failure_multiple_buy_increment() {
         &token,
         &buyer2,
         bid_amount2,
-    );
+    )
+    .await;
 
     let tx_error = context
         .banks_client
@@ -505,7 +514,8 @@ async fn multiple_buy_time_ext_success() {
         &token,
         &buyer1,
         bid_amount1,
-    );
+    )
+    .await;
     context
         .banks_client
         .process_transaction(buy_tx1)
@@ -557,7 +567,8 @@ async fn multiple_buy_time_ext_success() {
         &token,
         &buyer2,
         bid_amount2,
-    );
+    )
+    .await;
     context
         .banks_client
         .process_transaction(buy_tx2)
@@ -578,3 +589,697 @@ async fn multiple_buy_time_ext_success() {
     // Assert new expanded time before auction ending
     assert_eq!(listing_config_data2.end_time, end_time0 + 60 + 60);
 }
+
+#[tokio::test]
+async fn dutch_buy_success() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+
+    let (_, auction_house, auction_house_data) = create_auction_house(&mut context, 100, false)
+        .await
+        .expect("Failed to create Auction House");
+
+    let token = create_nft(&mut context, None)
+        .await
+        .expect("Failed to create NFT");
+
+    // Sell
+
+    let (sell_accounts, sell_tx) = sell_dutch(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        (SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_secs()
+            - 60) as i64,
+        (SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_secs()
+            + 60) as i64,
+        DutchAuctionParams {
+            start_price: 3 * ONE_SOL,
+            floor_price: ONE_SOL,
+            price_decay_interval: 3600,
+            price_decay_amount: ONE_SOL,
+        },
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .expect("Failed to sell NFT");
+
+    // Buy 1
+
+    let buyer1 = Keypair::new();
+    airdrop(&mut context, &buyer1.pubkey(), 10 * ONE_SOL)
+        .await
+        .unwrap();
+
+    let (_, deposit_tx1) = deposit(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &buyer1,
+        5 * ONE_SOL,
+    );
+    context
+        .banks_client
+        .process_transaction(deposit_tx1)
+        .await
+        .unwrap();
+
+    let bid_amount1 = 3 * ONE_SOL; // Matches the current price
+
+    let (_, buy_tx1) = buy(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer1,
+        bid_amount1,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(buy_tx1)
+        .await
+        .unwrap();
+
+    let listing_config_account = context
+        .banks_client
+        .get_account(sell_accounts.listing_config)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+
+    let listing_config_data =
+        ListingConfig::try_deserialize(&mut listing_config_account.as_ref()).unwrap();
+
+    assert_eq!(listing_config_data.highest_bid.amount, bid_amount1);
+
+    // Buy 2
+
+    let buyer2 = Keypair::new();
+    airdrop(&mut context, &buyer2.pubkey(), 10 * ONE_SOL)
+        .await
+        .unwrap();
+
+    let (_, deposit_tx2) = deposit(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &buyer2,
+        5 * ONE_SOL,
+    );
+    context
+        .banks_client
+        .process_transaction(deposit_tx2)
+        .await
+        .unwrap();
+
+    let bid_amount2 = 4 * ONE_SOL; // The first bid has already won the auction
+
+    let (_, buy_tx2) = buy(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer2,
+        bid_amount2,
+    )
+    .await;
+
+    let tx_error = context
+        .banks_client
+        .process_transaction(buy_tx2)
+        .await
+        .unwrap_err();
+
+    assert_error(tx_error, AuctioneerError::AuctionAlreadySold.into());
+}
+
+#[tokio::test]
+async fn failure_dutch_buy_below_current_price() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+
+    let (_, auction_house, auction_house_data) = create_auction_house(&mut context, 100, false)
+        .await
+        .expect("Failed to create Auction House");
+
+    let token = create_nft(&mut context, None)
+        .await
+        .expect("Failed to create NFT");
+
+    // Sell
+
+    let (_, sell_tx) = sell_dutch(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        (SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_secs()
+            - 60) as i64,
+        (SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_secs()
+            + 60) as i64,
+        DutchAuctionParams {
+            start_price: 3 * ONE_SOL,
+            floor_price: ONE_SOL,
+            price_decay_interval: 3600,
+            price_decay_amount: ONE_SOL,
+        },
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .expect("Failed to sell NFT");
+
+    // Buy
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), 10 * ONE_SOL)
+        .await
+        .unwrap();
+
+    let (_, deposit_tx) = deposit(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &buyer,
+        5 * ONE_SOL,
+    );
+    context
+        .banks_client
+        .process_transaction(deposit_tx)
+        .await
+        .unwrap();
+
+    let bid_amount = 2 * ONE_SOL; // The price has not decayed yet
+
+    let (_, buy_tx) = buy(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer,
+        bid_amount,
+    )
+    .await;
+
+    let tx_error = context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap_err();
+
+    assert_error(tx_error, AuctioneerError::BelowDutchAuctionPrice.into());
+}
+
+#[tokio::test]
+async fn failure_buy_after_buy_now_price_reached() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+
+    let (_, auction_house, auction_house_data) = create_auction_house(&mut context, 100, false)
+        .await
+        .expect("Failed to create Auction House");
+
+    let token = create_nft(&mut context, None)
+        .await
+        .expect("Failed to create NFT");
+
+    // Sell
+
+    let (_, sell_tx) = sell_with_params(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        (SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_secs()
+            - 60) as i64,
+        (SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_secs()
+            + 60) as i64,
+        None,
+        None,
+        None,
+        None,
+        None,
+        Some(2 * ONE_SOL), // Buy now price
+        None,
+        None,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .expect("Failed to sell NFT");
+
+    // Buy 1
+
+    let buyer1 = Keypair::new();
+    airdrop(&mut context, &buyer1.pubkey(), 10 * ONE_SOL)
+        .await
+        .unwrap();
+
+    let (_, deposit_tx1) = deposit(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &buyer1,
+        5 * ONE_SOL,
+    );
+    context
+        .banks_client
+        .process_transaction(deposit_tx1)
+        .await
+        .unwrap();
+
+    let bid_amount1 = 2 * ONE_SOL; // Reaches the buy now price
+
+    let (_, buy_tx1) = buy(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer1,
+        bid_amount1,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(buy_tx1)
+        .await
+        .unwrap();
+
+    // Buy 2
+
+    let buyer2 = Keypair::new();
+    airdrop(&mut context, &buyer2.pubkey(), 10 * ONE_SOL)
+        .await
+        .unwrap();
+
+    let (_, deposit_tx2) = deposit(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &buyer2,
+        5 * ONE_SOL,
+    );
+    context
+        .banks_client
+        .process_transaction(deposit_tx2)
+        .await
+        .unwrap();
+
+    let bid_amount2 = 3 * ONE_SOL;
+
+    let (_, buy_tx2) = buy(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer2,
+        bid_amount2,
+    )
+    .await;
+
+    let tx_error = context
+        .banks_client
+        .process_transaction(buy_tx2)
+        .await
+        .unwrap_err();
+
+    assert_error(tx_error, AuctioneerError::AuctionAlreadySold.into());
+}
+
+#[tokio::test]
+async fn failure_multiple_buy_bps_increment() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+
+    let (_, auction_house, auction_house_data) = create_auction_house(&mut context, 100, false)
+        .await
+        .expect("Failed to create Auction House");
+
+    let token = create_nft(&mut context, None)
+        .await
+        .expect("Failed to create NFT");
+
+    // Sell
+
+    let (_, sell_tx) = sell_with_params(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        (SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_secs()
+            - 60) as i64,
+        (SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_secs()
+            + 60) as i64,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        Some(BidIncrementParams {
+            mode: BidIncrementMode::BasisPoints,
+            basis_points: 1000, // 10% of the highest bid
+            tiers: vec![],
+        }),
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .expect("Failed to sell NFT");
+
+    // Buy 1
+
+    let buyer1 = Keypair::new();
+    airdrop(&mut context, &buyer1.pubkey(), 10 * ONE_SOL)
+        .await
+        .unwrap();
+
+    let (_, deposit_tx1) = deposit(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &buyer1,
+        5 * ONE_SOL,
+    );
+    context
+        .banks_client
+        .process_transaction(deposit_tx1)
+        .await
+        .unwrap();
+
+    let bid_amount1 = 3 * ONE_SOL;
+
+    let (_, buy_tx1) = buy(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer1,
+        bid_amount1,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(buy_tx1)
+        .await
+        .unwrap();
+
+    // Buy 2
+
+    let buyer2 = Keypair::new();
+    airdrop(&mut context, &buyer2.pubkey(), 10 * ONE_SOL)
+        .await
+        .unwrap();
+
+    let (_, deposit_tx2) = deposit(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &buyer2,
+        5 * ONE_SOL,
+    );
+    context
+        .banks_client
+        .process_transaction(deposit_tx2)
+        .await
+        .unwrap();
+
+    let bid_amount2 = 3 * ONE_SOL + ONE_SOL / 5; // Not passes 10% bid increment
+
+    let (_, buy_tx2) = buy(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer2,
+        bid_amount2,
+    )
+    .await;
+
+    let tx_error = context
+        .banks_client
+        .process_transaction(buy_tx2)
+        .await
+        .unwrap_err();
+
+    assert_error(tx_error, AuctioneerError::BelowBidIncrement.into());
+}
+
+#[tokio::test]
+async fn multiple_buy_tiered_increment_success() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+
+    let (_, auction_house, auction_house_data) = create_auction_house(&mut context, 100, false)
+        .await
+        .expect("Failed to create Auction House");
+
+    let token = create_nft(&mut context, None)
+        .await
+        .expect("Failed to create NFT");
+
+    // Sell
+
+    let (_, sell_tx) = sell_with_params(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        (SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_secs()
+            - 60) as i64,
+        (SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_secs()
+            + 60) as i64,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        Some(BidIncrementParams {
+            mode: BidIncrementMode::Tiered,
+            basis_points: 0,
+            tiers: vec![
+                BidIncrementTier {
+                    min_price: 0,
+                    increment: ONE_SOL / 10,
+                },
+                BidIncrementTier {
+                    min_price: 2 * ONE_SOL,
+                    increment: ONE_SOL,
+                },
+            ],
+        }),
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .expect("Failed to sell NFT");
+
+    // Buy 1
+
+    let buyer1 = Keypair::new();
+    airdrop(&mut context, &buyer1.pubkey(), 10 * ONE_SOL)
+        .await
+        .unwrap();
+
+    let (_, deposit_tx1) = deposit(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &buyer1,
+        5 * ONE_SOL,
+    );
+    context
+        .banks_client
+        .process_transaction(deposit_tx1)
+        .await
+        .unwrap();
+
+    let bid_amount1 = 3 * ONE_SOL;
+
+    let (_, buy_tx1) = buy(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer1,
+        bid_amount1,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(buy_tx1)
+        .await
+        .unwrap();
+
+    // Buy 2
+
+    let buyer2 = Keypair::new();
+    airdrop(&mut context, &buyer2.pubkey(), 10 * ONE_SOL)
+        .await
+        .unwrap();
+
+    let (_, deposit_tx2) = deposit(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &buyer2,
+        5 * ONE_SOL,
+    );
+    context
+        .banks_client
+        .process_transaction(deposit_tx2)
+        .await
+        .unwrap();
+
+    let bid_amount2 = 4 * ONE_SOL; // Passes the 2 SOL price band increment
+
+    let (_, buy_tx2) = buy(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer2,
+        bid_amount2,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(buy_tx2)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn buy_second_listing_tops_up_locked_escrow_success() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+
+    let (_, auction_house, auction_house_data) = create_auction_house(&mut context, 100, false)
+        .await
+        .expect("Failed to create Auction House");
+
+    let start_time = (SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_secs()
+        - 60) as i64;
+    let end_time = start_time + 120;
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), 10 * ONE_SOL)
+        .await
+        .unwrap();
+
+    let (_, deposit_tx) = deposit(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &buyer,
+        ONE_SOL,
+    );
+    context
+        .banks_client
+        .process_transaction(deposit_tx)
+        .await
+        .unwrap();
+
+    let mut escrow_payment_account = Pubkey::default();
+    for _ in 0..2 {
+        let token = create_nft(&mut context, None)
+            .await
+            .expect("Failed to create NFT");
+
+        let (_, sell_tx) = sell(
+            &mut context,
+            &auction_house,
+            &auction_house_data,
+            &token,
+            start_time,
+            end_time,
+            None,
+            None,
+            None,
+            None,
+        );
+        context
+            .banks_client
+            .process_transaction(sell_tx)
+            .await
+            .expect("Failed to sell NFT");
+
+        // The deposit only covers the first bid, its lock can't back the second one
+        let (buy_accounts, buy_tx) = buy(
+            &mut context,
+            &auction_house,
+            &auction_house_data,
+            &token,
+            &buyer,
+            ONE_SOL,
+        )
+        .await;
+        context
+            .banks_client
+            .process_transaction(buy_tx)
+            .await
+            .unwrap();
+        escrow_payment_account = buy_accounts.escrow_payment_account;
+    }
+
+    let rent = context.banks_client.get_rent().await.unwrap();
+    assert_eq!(
+        context
+            .banks_client
+            .get_balance(escrow_payment_account)
+            .await
+            .unwrap(),
+        2 * ONE_SOL + rent.minimum_balance(0)
+    );
+
+    let (escrow_ledger, _) = find_escrow_ledger_address(&auction_house, &buyer.pubkey());
+    let escrow_ledger_account = context
+        .banks_client
+        .get_account(escrow_ledger)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    let escrow_ledger_data =
+        EscrowLedger::try_deserialize(&mut escrow_ledger_account.as_ref()).unwrap();
+    assert_eq!(escrow_ledger_data.locked_amount, 2 * ONE_SOL);
+}
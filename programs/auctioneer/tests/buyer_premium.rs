@@ -0,0 +1,455 @@
+use anchor_client::solana_sdk::{signature::Keypair, signer::Signer, transaction::Transaction};
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::{system_program, sysvar};
+use anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas};
+use anchor_spl::{associated_token, token::spl_token};
+use auction_house::{errors::AuctionHouseError, AuctionHouse};
+use auctioneer::commit_bid::config::BidCommitment;
+use auctioneer::errors::AuctioneerError;
+use auctioneer::execute_sale::config::AuctionResult;
+use auctioneer::pda::*;
+use auctioneer::sell::config::SealedBidParams;
+use solana_program_test::{tokio, ProgramTestContext};
+use std::time::SystemTime;
+
+mod utils;
+use utils::setup_functions::*;
+
+// 5% of the price on top of the winning bid
+const BUYER_PREMIUM_BASIS_POINTS: u16 = 500;
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_secs() as i64
+}
+
+fn set_buyer_premium(
+    context: &mut ProgramTestContext,
+    authority: &Keypair,
+    auction_house: &Pubkey,
+    auction_house_data: &AuctionHouse,
+    buyer_premium_basis_points: u16,
+) -> Transaction {
+    let data = auction_house::instruction::UpdateAuctionHouse {
+        seller_fee_basis_points: None,
+        can_change_sale_price: None,
+        min_auction_duration: None,
+        max_auction_duration: None,
+        max_time_extension: None,
+        royalty_policy: None,
+        referral_basis_points: None,
+        buyer_premium_basis_points: Some(buyer_premium_basis_points),
+        max_auctioneer_fee_basis_points: None,
+    };
+
+    let accounts = auction_house::accounts::UpdateAuctionHouse {
+        treasury_mint: auction_house_data.treasury_mint,
+        payer: authority.pubkey(),
+        authority: authority.pubkey(),
+        new_authority: authority.pubkey(),
+        fee_withdrawal_destination: auction_house_data.fee_withdrawal_destination,
+        treasury_withdrawal_destination: auction_house_data.treasury_withdrawal_destination,
+        treasury_withdrawal_destination_owner: auction_house_data.treasury_withdrawal_destination,
+        auction_house: *auction_house,
+        token_program: spl_token::id(),
+        system_program: system_program::id(),
+        associated_token_program: associated_token::ID,
+        rent: sysvar::rent::id(),
+    };
+
+    let ix = Instruction {
+        program_id: auction_house::id(),
+        data: data.data(),
+        accounts: accounts.to_account_metas(None),
+    };
+
+    Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&authority.pubkey()),
+        &[authority],
+        context.last_blockhash,
+    )
+}
+
+// Auction house charging the buyer's premium, with a funded bidder
+async fn setup_premium_auction_house(
+    context: &mut ProgramTestContext,
+) -> (Keypair, Pubkey, AuctionHouse, Keypair) {
+    let (authority, auction_house, auction_house_data) = create_auction_house(context, 100, false)
+        .await
+        .expect("Failed to create Auction House");
+
+    let premium_tx = set_buyer_premium(
+        context,
+        &authority,
+        &auction_house,
+        &auction_house_data,
+        BUYER_PREMIUM_BASIS_POINTS,
+    );
+    context
+        .banks_client
+        .process_transaction(premium_tx)
+        .await
+        .unwrap();
+    let auction_house_data = get_auction_house(context, &auction_house).await;
+
+    let buyer = Keypair::new();
+    airdrop(context, &buyer.pubkey(), 10 * ONE_SOL)
+        .await
+        .unwrap();
+
+    (authority, auction_house, auction_house_data, buyer)
+}
+
+#[tokio::test]
+async fn execute_sale_buyer_premium_success() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+    let (_, auction_house, auction_house_data, buyer) =
+        setup_premium_auction_house(&mut context).await;
+
+    let token = create_nft(&mut context, None)
+        .await
+        .expect("Failed to create NFT");
+
+    let end_time = now() + 60;
+    let (sell_accounts, sell_tx) = sell(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        now() - 60,
+        end_time,
+        None,
+        None,
+        None,
+        None,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    let (_, deposit_tx) = deposit(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &buyer,
+        5 * ONE_SOL,
+    );
+    context
+        .banks_client
+        .process_transaction(deposit_tx)
+        .await
+        .unwrap();
+
+    let (buy_accounts, buy_tx) = buy(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer,
+        ONE_SOL,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    warp_to_timestamp(&mut context, end_time + 1).await;
+
+    let treasury_before = context
+        .banks_client
+        .get_balance(auction_house_data.auction_house_treasury)
+        .await
+        .unwrap();
+
+    let (_, execute_sale_tx) = execute_sale(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        None,
+        &sell_accounts,
+        &buy_accounts,
+        ONE_SOL,
+        &buyer,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(execute_sale_tx)
+        .await
+        .unwrap();
+
+    // The treasury collects the premium on top of the house fee
+    let treasury_after = context
+        .banks_client
+        .get_balance(auction_house_data.auction_house_treasury)
+        .await
+        .unwrap();
+    assert_eq!(
+        treasury_after - treasury_before,
+        ONE_SOL / 100 + ONE_SOL / 20
+    );
+
+    let (auction_result, _) = find_auction_result_address(
+        &sell_accounts.listing_config,
+        &buy_accounts.buyer_trade_state,
+    );
+    let auction_result_account = context
+        .banks_client
+        .get_account(auction_result)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    let auction_result_data =
+        AuctionResult::try_deserialize(&mut auction_result_account.as_ref()).unwrap();
+    assert_eq!(auction_result_data.price, ONE_SOL);
+    assert_eq!(auction_result_data.buyer_premium, ONE_SOL / 20);
+    assert_eq!(auction_result_data.auctioneer_fee, 0);
+}
+
+#[tokio::test]
+async fn failure_buy_escrow_without_premium() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+    let (_, auction_house, auction_house_data, buyer) =
+        setup_premium_auction_house(&mut context).await;
+
+    let token = create_nft(&mut context, None)
+        .await
+        .expect("Failed to create NFT");
+
+    let (_, sell_tx) = sell(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        now() - 60,
+        now() + 60,
+        None,
+        None,
+        None,
+        None,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    // The escrow covers the bid but not the premium its winner would owe
+    let (_, deposit_tx) = deposit(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &buyer,
+        ONE_SOL,
+    );
+    context
+        .banks_client
+        .process_transaction(deposit_tx)
+        .await
+        .unwrap();
+
+    let (_, buy_tx) = buy(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer,
+        ONE_SOL,
+    )
+    .await;
+    let tx_error = context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap_err();
+
+    assert_error(tx_error, AuctionHouseError::InsufficientFunds.into());
+}
+
+#[tokio::test]
+async fn commit_bid_deposits_buyer_premium_success() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+    let (_, auction_house, auction_house_data, buyer) =
+        setup_premium_auction_house(&mut context).await;
+
+    let token = create_nft(&mut context, None)
+        .await
+        .expect("Failed to create NFT");
+
+    let end_time = now() + 60;
+    let (sell_accounts, sell_tx) = sell_sealed(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        now() - 60,
+        end_time,
+        None,
+        SealedBidParams {
+            reveal_end_time: end_time + 60,
+            second_price: false,
+        },
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    let salt = [1; 32];
+    let (commit_accounts, commit_tx) = commit_bid(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer,
+        2 * ONE_SOL,
+        salt,
+        2 * ONE_SOL,
+    );
+    context
+        .banks_client
+        .process_transaction(commit_tx)
+        .await
+        .unwrap();
+
+    // The deposit backs a 2 SOL bid and the premium on top of it
+    let bid_commitment_account = context
+        .banks_client
+        .get_account(commit_accounts.bid_commitment)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    let bid_commitment_data =
+        BidCommitment::try_deserialize(&mut bid_commitment_account.as_ref()).unwrap();
+    assert_eq!(bid_commitment_data.deposit_amount, 2 * ONE_SOL);
+    assert_eq!(
+        bid_commitment_data.escrow_amount,
+        2 * ONE_SOL + ONE_SOL / 10
+    );
+
+    warp_to_timestamp(&mut context, end_time + 1).await;
+
+    let (_, reveal_tx) = reveal_bid(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer,
+        2 * ONE_SOL,
+        salt,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(reveal_tx)
+        .await
+        .unwrap();
+
+    let listing_config_account = context
+        .banks_client
+        .get_account(sell_accounts.listing_config)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    let listing_config_data = auctioneer::sell::config::ListingConfig::try_deserialize(
+        &mut listing_config_account.as_ref(),
+    )
+    .unwrap();
+    assert_eq!(listing_config_data.highest_bidder, buyer.pubkey());
+    assert_eq!(listing_config_data.escrow_locked_amount, 2 * ONE_SOL);
+}
+
+#[tokio::test]
+async fn failure_reveal_bid_premium_raised_after_commit() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+    let (authority, auction_house, auction_house_data, buyer) =
+        setup_premium_auction_house(&mut context).await;
+
+    let token = create_nft(&mut context, None)
+        .await
+        .expect("Failed to create NFT");
+
+    let end_time = now() + 60;
+    let (_, sell_tx) = sell_sealed(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        now() - 60,
+        end_time,
+        None,
+        SealedBidParams {
+            reveal_end_time: end_time + 60,
+            second_price: false,
+        },
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    let salt = [1; 32];
+    let (_, commit_tx) = commit_bid(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer,
+        2 * ONE_SOL,
+        salt,
+        2 * ONE_SOL,
+    );
+    context
+        .banks_client
+        .process_transaction(commit_tx)
+        .await
+        .unwrap();
+
+    // Doubling the premium leaves the deposit short of the bid it was meant to back
+    let premium_tx = set_buyer_premium(
+        &mut context,
+        &authority,
+        &auction_house,
+        &auction_house_data,
+        2 * BUYER_PREMIUM_BASIS_POINTS,
+    );
+    context
+        .banks_client
+        .process_transaction(premium_tx)
+        .await
+        .unwrap();
+
+    warp_to_timestamp(&mut context, end_time + 1).await;
+
+    let (_, reveal_tx) = reveal_bid(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer,
+        2 * ONE_SOL,
+        salt,
+    )
+    .await;
+    let tx_error = context
+        .banks_client
+        .process_transaction(reveal_tx)
+        .await
+        .unwrap_err();
+
+    assert_error(tx_error, AuctioneerError::BidNotBackedByDeposit.into());
+}
@@ -52,7 +52,8 @@ async fn cancel_sell_success() {
         &auction_house_data,
         &token,
         &sell_accounts.seller_trade_state,
-    );
+    )
+    .await;
     context
         .banks_client
         .process_transaction(cancel_sell_tx)
@@ -138,7 +139,8 @@ async fn failure_cancel_buy_highest_bid() {
         &token,
         &buyer,
         bid_amount,
-    );
+    )
+    .await;
     context
         .banks_client
         .process_transaction(buy_tx)
@@ -174,7 +176,8 @@ async fn failure_cancel_buy_highest_bid() {
         &token,
         &buyer2,
         bid_amount2,
-    );
+    )
+    .await;
     context
         .banks_client
         .process_transaction(buy_tx2)
@@ -191,7 +194,8 @@ async fn failure_cancel_buy_highest_bid() {
         &buyer2,
         &buy_accounts2.buyer_trade_state,
         bid_amount2,
-    );
+    )
+    .await;
 
     let tx_error = context
         .banks_client
@@ -1,5 +1,7 @@
 use anchor_client::solana_sdk::{signature::Keypair, signer::Signer};
+use auction_house::errors::AuctionHouseError;
 use solana_program_test::tokio;
+use std::time::SystemTime;
 
 mod utils;
 use utils::setup_functions::*;
@@ -44,7 +46,8 @@ async fn close_escrow_account_success() {
 
     assert_eq!(escrow_payment_account.lamports, ONE_SOL + rent_exempt_min);
 
-    let (_, close_escrow_account_tx) = close_escrow_account(&mut context, &auction_house, &buyer);
+    let (_, close_escrow_account_tx) =
+        close_escrow_account(&mut context, &auction_house, &auction_house_data, &buyer);
     context
         .banks_client
         .process_transaction(close_escrow_account_tx)
@@ -59,3 +62,87 @@ async fn close_escrow_account_success() {
 
     assert!(closed_escrow_payment_account.is_none());
 }
+
+#[tokio::test]
+async fn failure_close_escrow_account_open_bid() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+
+    let (_, auction_house, auction_house_data) = create_auction_house(&mut context, 100, false)
+        .await
+        .expect("Failed to create Auction House");
+
+    let token = create_nft(&mut context, None)
+        .await
+        .expect("Failed to create NFT");
+
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_secs() as i64;
+    let (_, sell_tx) = sell(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        now - 60,
+        now + 60,
+        None,
+        None,
+        None,
+        None,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .expect("Failed to sell NFT");
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), 3 * ONE_SOL)
+        .await
+        .unwrap();
+    let (_, buy_tx) = buy(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer,
+        ONE_SOL,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    // Outbid, so only the open trade state keeps the escrow alive
+    let buyer2 = Keypair::new();
+    airdrop(&mut context, &buyer2.pubkey(), 3 * ONE_SOL)
+        .await
+        .unwrap();
+    let (_, buy_tx2) = buy(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer2,
+        2 * ONE_SOL,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(buy_tx2)
+        .await
+        .unwrap();
+
+    let (_, close_escrow_account_tx) =
+        close_escrow_account(&mut context, &auction_house, &auction_house_data, &buyer);
+    let tx_error = context
+        .banks_client
+        .process_transaction(close_escrow_account_tx)
+        .await
+        .unwrap_err();
+
+    assert_error(tx_error, AuctionHouseError::EscrowHasOpenBids.into());
+}
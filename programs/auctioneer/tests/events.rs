@@ -0,0 +1,255 @@
+use anchor_client::solana_sdk::{signature::Keypair, signer::Signer};
+use anchor_lang::prelude::Pubkey;
+use auction_house::AuctionHouse;
+use auctioneer::events::{BidPlaced, ListingCancelled, ListingCreated, Outbid, TimeExtended};
+use auctioneer::sell::config::AuctionType;
+use solana_program_test::{tokio, ProgramTestContext};
+use std::time::SystemTime;
+
+mod utils;
+use utils::setup_functions::*;
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_secs() as i64
+}
+
+async fn fund_buyer(
+    context: &mut ProgramTestContext,
+    auction_house: &Pubkey,
+    auction_house_data: &AuctionHouse,
+) -> Keypair {
+    let buyer = Keypair::new();
+    airdrop(context, &buyer.pubkey(), 10 * ONE_SOL)
+        .await
+        .unwrap();
+
+    let (_, deposit_tx) = deposit(
+        context,
+        auction_house,
+        auction_house_data,
+        &buyer,
+        5 * ONE_SOL,
+    );
+    context
+        .banks_client
+        .process_transaction(deposit_tx)
+        .await
+        .unwrap();
+
+    buyer
+}
+
+#[tokio::test]
+async fn listing_events_success() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+
+    let (_, auction_house, auction_house_data) = create_auction_house(&mut context, 100, false)
+        .await
+        .expect("Failed to create Auction House");
+
+    let token = create_nft(&mut context, None)
+        .await
+        .expect("Failed to create NFT");
+
+    let start_time = now() - 60;
+    let end_time = now() + 60;
+
+    // Sell
+
+    let (sell_accounts, sell_tx) = sell(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        start_time,
+        end_time,
+        Some(ONE_SOL),
+        None,
+        None,
+        None,
+    );
+    let events: Vec<ListingCreated> = process_transaction_events(&mut context, sell_tx).await;
+
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].listing_config, sell_accounts.listing_config);
+    assert_eq!(events[0].auction_house, auction_house);
+    assert_eq!(events[0].seller, token.owner.pubkey());
+    assert_eq!(events[0].token_mint, token.mint.pubkey());
+    assert_eq!(events[0].auction_type, AuctionType::English);
+    assert_eq!(events[0].start_time, start_time);
+    assert_eq!(events[0].end_time, end_time);
+    assert_eq!(events[0].reserve_price, ONE_SOL);
+
+    // Buy
+
+    let buyer1 = fund_buyer(&mut context, &auction_house, &auction_house_data).await;
+    let (_, buy_tx) = buy(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer1,
+        ONE_SOL,
+    )
+    .await;
+    let events: Vec<BidPlaced> = process_transaction_events(&mut context, buy_tx).await;
+
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].listing_config, sell_accounts.listing_config);
+    assert_eq!(events[0].bidder, buyer1.pubkey());
+    assert_eq!(events[0].amount, ONE_SOL);
+    assert!(!events[0].is_proxy);
+
+    // Outbid
+
+    let buyer2 = fund_buyer(&mut context, &auction_house, &auction_house_data).await;
+    let (_, buy_tx) = buy(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer2,
+        2 * ONE_SOL,
+    )
+    .await;
+    let events: Vec<Outbid> = process_transaction_events(&mut context, buy_tx).await;
+
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].bidder, buyer1.pubkey());
+    assert_eq!(events[0].highest_bidder, buyer2.pubkey());
+    assert_eq!(events[0].highest_bid, 2 * ONE_SOL);
+}
+
+#[tokio::test]
+async fn listing_cancelled_event_success() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+
+    let (_, auction_house, auction_house_data) = create_auction_house(&mut context, 100, false)
+        .await
+        .expect("Failed to create Auction House");
+
+    let token = create_nft(&mut context, None)
+        .await
+        .expect("Failed to create NFT");
+
+    // Sell
+
+    let (sell_accounts, sell_tx) = sell(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        now() - 60,
+        now() + 60,
+        None,
+        None,
+        None,
+        None,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .expect("Failed to sell NFT");
+
+    // Cancel
+
+    let (_, cancel_sell_tx) = cancel_sell(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &sell_accounts.seller_trade_state,
+    )
+    .await;
+    let events: Vec<ListingCancelled> =
+        process_transaction_events(&mut context, cancel_sell_tx).await;
+
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].listing_config, sell_accounts.listing_config);
+    assert_eq!(events[0].seller, token.owner.pubkey());
+}
+
+#[tokio::test]
+async fn time_extended_event_success() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+
+    let (authority, auction_house, auction_house_data) =
+        create_auction_house(&mut context, 100, false)
+            .await
+            .expect("Failed to create Auction House");
+
+    // Cap the time extensions of a listing at 90 seconds
+
+    let update_tx = update_auction_house(
+        &mut context,
+        &authority,
+        &auction_house,
+        &auction_house_data,
+        None,
+        None,
+        Some(90),
+        None,
+    );
+    context
+        .banks_client
+        .process_transaction(update_tx)
+        .await
+        .unwrap();
+
+    let token = create_nft(&mut context, None)
+        .await
+        .expect("Failed to create NFT");
+
+    let end_time = now() + 60;
+
+    // Sell, every bid extends the auction by 60 seconds
+
+    let (sell_accounts, sell_tx) = sell(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        now() - 60,
+        end_time,
+        None,
+        None,
+        Some(120),
+        Some(60),
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .expect("Failed to sell NFT");
+
+    // The first bid extends the end time, the second one up to the cap and the third one
+    // leaves it where it is
+
+    let expected_end_times = [Some(end_time + 60), Some(end_time + 90), None];
+    for (i, expected_end_time) in expected_end_times.into_iter().enumerate() {
+        let buyer = fund_buyer(&mut context, &auction_house, &auction_house_data).await;
+        let (_, buy_tx) = buy(
+            &mut context,
+            &auction_house,
+            &auction_house_data,
+            &token,
+            &buyer,
+            (i as u64 + 1) * ONE_SOL,
+        )
+        .await;
+        let events: Vec<TimeExtended> = process_transaction_events(&mut context, buy_tx).await;
+
+        match expected_end_time {
+            Some(expected_end_time) => {
+                assert_eq!(events.len(), 1);
+                assert_eq!(events[0].listing_config, sell_accounts.listing_config);
+                assert_eq!(events[0].end_time, expected_end_time);
+            }
+            None => assert!(events.is_empty()),
+        }
+    }
+}
@@ -2,7 +2,10 @@ use anchor_client::solana_sdk::{signature::Keypair, signer::Signer};
 use anchor_lang::prelude::Pubkey;
 use anchor_lang::solana_program::program_pack::Pack;
 use anchor_spl::token::spl_token;
+use auctioneer::constants::SETTLEMENT_BOUNTY;
 use auctioneer::errors::AuctioneerError;
+use auctioneer::execute_sale::config::AUCTION_RESULT_SIZE;
+use auctioneer::sell::config::DutchAuctionParams;
 use nft_minter::utils::Creator;
 use solana_program_test::tokio;
 use std::time::SystemTime;
@@ -80,7 +83,8 @@ async fn execute_sale_success() {
         &token,
         &buyer,
         bid_amount,
-    );
+    )
+    .await;
     context
         .banks_client
         .process_transaction(buy_tx)
@@ -182,7 +186,8 @@ async fn execute_sale_multiple_buy_success() {
         &token,
         &buyer,
         bid_amount,
-    );
+    )
+    .await;
     context
         .banks_client
         .process_transaction(buy_tx)
@@ -218,7 +223,8 @@ async fn execute_sale_multiple_buy_success() {
         &token,
         &buyer2,
         bid_amount2,
-    );
+    )
+    .await;
     context
         .banks_client
         .process_transaction(buy_tx2)
@@ -320,7 +326,8 @@ async fn failure_execute_sale_multiple_buy_not_highest_bid() {
         &token,
         &buyer,
         bid_amount,
-    );
+    )
+    .await;
     context
         .banks_client
         .process_transaction(buy_tx)
@@ -356,7 +363,8 @@ async fn failure_execute_sale_multiple_buy_not_highest_bid() {
         &token,
         &buyer2,
         bid_amount2,
-    );
+    )
+    .await;
     context
         .banks_client
         .process_transaction(buy_tx2)
@@ -482,7 +490,8 @@ async fn execute_sale_with_metadata_creators_success() {
         &token,
         &buyer,
         bid_amount,
-    );
+    )
+    .await;
     context
         .banks_client
         .process_transaction(buy_tx)
@@ -586,3 +595,392 @@ async fn execute_sale_with_metadata_creators_success() {
     assert!(seller_before.lamports < seller_after.lamports);
     assert_eq!(buyer_token_after.amount, 1);
 }
+
+#[tokio::test]
+async fn execute_sale_dutch_auction_success() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+
+    let (_, auction_house, auction_house_data) = create_auction_house(&mut context, 100, false)
+        .await
+        .expect("Failed to create Auction House");
+
+    let token = create_nft(&mut context, None)
+        .await
+        .expect("Failed to create NFT");
+
+    // Sell
+
+    let (sell_accounts, sell_tx) = sell_dutch(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        (SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_secs()
+            - 60) as i64,
+        (SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_secs()
+            + 60) as i64,
+        DutchAuctionParams {
+            start_price: 2 * ONE_SOL,
+            floor_price: ONE_SOL,
+            price_decay_interval: 3600,
+            price_decay_amount: ONE_SOL,
+        },
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .expect("Failed to sell NFT");
+
+    // Buy
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), 10 * ONE_SOL)
+        .await
+        .unwrap();
+
+    let (_, deposit_tx) = deposit(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &buyer,
+        5 * ONE_SOL,
+    );
+    context
+        .banks_client
+        .process_transaction(deposit_tx)
+        .await
+        .unwrap();
+
+    let bid_amount = 2 * ONE_SOL;
+
+    let (buy_accounts, buy_tx) = buy(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer,
+        bid_amount,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    // Execute sale before the end time, the Dutch auction is already sold
+
+    let (_, execute_sale_tx) = execute_sale(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        None,
+        &sell_accounts,
+        &buy_accounts,
+        bid_amount,
+        &buyer,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(execute_sale_tx)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn execute_sale_buy_now_price_success() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+
+    let (_, auction_house, auction_house_data) = create_auction_house(&mut context, 100, false)
+        .await
+        .expect("Failed to create Auction House");
+
+    let token = create_nft(&mut context, None)
+        .await
+        .expect("Failed to create NFT");
+
+    // Sell
+
+    let (sell_accounts, sell_tx) = sell_with_params(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        (SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_secs()
+            - 60) as i64,
+        (SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_secs()
+            + 60) as i64,
+        None,
+        None,
+        None,
+        None,
+        None,
+        Some(2 * ONE_SOL), // Buy now price
+        None,
+        None,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .expect("Failed to sell NFT");
+
+    // Buy
+
+    let buyer1 = Keypair::new();
+    airdrop(&mut context, &buyer1.pubkey(), 10 * ONE_SOL)
+        .await
+        .unwrap();
+
+    let (_, deposit_tx1) = deposit(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &buyer1,
+        5 * ONE_SOL,
+    );
+    context
+        .banks_client
+        .process_transaction(deposit_tx1)
+        .await
+        .unwrap();
+
+    let bid_amount1 = 2 * ONE_SOL; // Reaches the buy now price
+
+    let (buy_accounts1, buy_tx1) = buy(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer1,
+        bid_amount1,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(buy_tx1)
+        .await
+        .unwrap();
+
+    // Execute sale before the end time, the buy now price ended the auction
+
+    let (_, execute_sale_tx) = execute_sale(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        None,
+        &sell_accounts,
+        &buy_accounts1,
+        bid_amount1,
+        &buyer1,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(execute_sale_tx)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn execute_sale_cranked_by_third_party_success() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+
+    let (_, auction_house, auction_house_data) = create_auction_house(&mut context, 100, false)
+        .await
+        .expect("Failed to create Auction House");
+
+    let token = create_nft(&mut context, None)
+        .await
+        .expect("Failed to create NFT");
+
+    let end_time = (SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_secs()
+        + 60) as i64;
+
+    // Sell
+
+    let (sell_accounts, sell_tx) = sell(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        end_time - 120,
+        end_time,
+        None,
+        None,
+        None,
+        None,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .expect("Failed to sell NFT");
+
+    // Buy
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), 10 * ONE_SOL)
+        .await
+        .unwrap();
+
+    let (_, deposit_tx) = deposit(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &buyer,
+        5 * ONE_SOL,
+    );
+    context
+        .banks_client
+        .process_transaction(deposit_tx)
+        .await
+        .unwrap();
+
+    let bid_amount = ONE_SOL;
+
+    let (buy_accounts, buy_tx) = buy(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer,
+        bid_amount,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    // Execute sale, neither the buyer nor the seller signs
+
+    warp_to_timestamp(&mut context, end_time + 1).await;
+
+    let fee_account_balance_before = context
+        .banks_client
+        .get_balance(auction_house_data.auction_house_fee_account)
+        .await
+        .unwrap();
+
+    // Buyer and seller get back the rent of everything they opened for the sale
+    let buyer_balance_before = context
+        .banks_client
+        .get_balance(buyer.pubkey())
+        .await
+        .unwrap();
+    let seller_balance_before = context
+        .banks_client
+        .get_balance(token.owner.pubkey())
+        .await
+        .unwrap();
+    let buyer_rent = context
+        .banks_client
+        .get_balance(buy_accounts.buyer_trade_state)
+        .await
+        .unwrap();
+    let mut seller_rent = 0;
+    for account in [
+        sell_accounts.listing_config,
+        sell_accounts.seller_trade_state,
+        sell_accounts.free_seller_trade_state,
+    ] {
+        seller_rent += context.banks_client.get_balance(account).await.unwrap();
+    }
+
+    let cranker = Keypair::new();
+    airdrop(&mut context, &cranker.pubkey(), ONE_SOL)
+        .await
+        .unwrap();
+
+    let (execute_sale_accounts, execute_sale_tx) = execute_sale(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        None,
+        &sell_accounts,
+        &buy_accounts,
+        bid_amount,
+        &cranker,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(execute_sale_tx)
+        .await
+        .unwrap();
+
+    let buyer_token_after = spl_token::state::Account::unpack_from_slice(
+        context
+            .banks_client
+            .get_account(execute_sale_accounts.buyer_receipt_token_account)
+            .await
+            .unwrap()
+            .unwrap()
+            .data
+            .as_slice(),
+    )
+    .unwrap();
+    assert_eq!(buyer_token_after.amount, 1);
+
+    // The cranker pays for the buyer token account, the house fee account is left untouched
+    let fee_account_balance_after = context
+        .banks_client
+        .get_balance(auction_house_data.auction_house_fee_account)
+        .await
+        .unwrap();
+    assert_eq!(fee_account_balance_after, fee_account_balance_before);
+
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let token_account_rent = rent.minimum_balance(spl_token::state::Account::LEN);
+    let cranker_balance = context
+        .banks_client
+        .get_balance(cranker.pubkey())
+        .await
+        .unwrap();
+    assert_eq!(
+        cranker_balance,
+        ONE_SOL - token_account_rent + SETTLEMENT_BOUNTY - 5000
+    );
+
+    // The bounty and the auction result record come out of the listing rent
+    assert_eq!(
+        context
+            .banks_client
+            .get_balance(buyer.pubkey())
+            .await
+            .unwrap(),
+        buyer_balance_before + buyer_rent
+    );
+    assert_eq!(
+        context
+            .banks_client
+            .get_balance(token.owner.pubkey())
+            .await
+            .unwrap(),
+        seller_balance_before + bid_amount - bid_amount / 100 + seller_rent
+            - rent.minimum_balance(AUCTION_RESULT_SIZE)
+            - SETTLEMENT_BOUNTY
+    );
+}
@@ -0,0 +1,244 @@
+use anchor_client::solana_sdk::{signature::Keypair, signer::Signer};
+use anchor_lang::AccountDeserialize;
+use auctioneer::errors::AuctioneerError;
+use auctioneer::sell::config::{
+    AuctionType, ListingConfig, ListingConfigVersion, LISTING_CONFIG_SIZE,
+};
+use solana_program_test::tokio;
+use std::time::SystemTime;
+
+mod utils;
+use utils::setup_functions::*;
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_secs() as i64
+}
+
+#[tokio::test]
+async fn migrate_listing_config_success() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+
+    let (_, auction_house, auction_house_data) = create_auction_house(&mut context, 100, false)
+        .await
+        .expect("Failed to create Auction House");
+
+    let token = create_nft(&mut context, None)
+        .await
+        .expect("Failed to create NFT");
+
+    // Sell
+
+    let (sell_accounts, sell_tx) = sell(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        now() - 60,
+        now() + 60,
+        None,
+        None,
+        None,
+        None,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .expect("Failed to sell NFT");
+
+    // Buy
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), 10 * ONE_SOL)
+        .await
+        .unwrap();
+
+    let (_, deposit_tx) = deposit(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &buyer,
+        5 * ONE_SOL,
+    );
+    context
+        .banks_client
+        .process_transaction(deposit_tx)
+        .await
+        .unwrap();
+
+    let (buy_accounts, buy_tx) = buy(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer,
+        ONE_SOL,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    // Migrate
+
+    set_legacy_listing_config(&mut context, &sell_accounts.listing_config).await;
+
+    let payer = Keypair::new();
+    airdrop(&mut context, &payer.pubkey(), ONE_SOL)
+        .await
+        .unwrap();
+
+    let (_, migrate_tx) = migrate_listing_config(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer.pubkey(),
+        &payer,
+    );
+    context
+        .banks_client
+        .process_transaction(migrate_tx)
+        .await
+        .unwrap();
+
+    let listing_config_account = context
+        .banks_client
+        .get_account(sell_accounts.listing_config)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    assert_eq!(listing_config_account.len(), LISTING_CONFIG_SIZE);
+
+    let listing_config_data =
+        ListingConfig::try_deserialize(&mut listing_config_account.as_ref()).unwrap();
+    assert!(matches!(
+        listing_config_data.version,
+        ListingConfigVersion::V1
+    ));
+    assert_eq!(listing_config_data.auction_type, AuctionType::English);
+    assert_eq!(listing_config_data.highest_bid.amount, ONE_SOL);
+    assert_eq!(
+        listing_config_data.highest_bid.buyer_trade_state,
+        buy_accounts.buyer_trade_state
+    );
+    assert_eq!(listing_config_data.highest_bidder, buyer.pubkey());
+
+    // Migrating twice fails
+
+    let (_, migrate_tx) = migrate_listing_config(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer.pubkey(),
+        &payer,
+    );
+    let tx_error = context
+        .banks_client
+        .process_transaction(migrate_tx)
+        .await
+        .unwrap_err();
+    assert_error(
+        tx_error,
+        AuctioneerError::ListingConfigAlreadyMigrated.into(),
+    );
+}
+
+#[tokio::test]
+async fn migrate_listing_config_wrong_highest_bidder() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+
+    let (_, auction_house, auction_house_data) = create_auction_house(&mut context, 100, false)
+        .await
+        .expect("Failed to create Auction House");
+
+    let token = create_nft(&mut context, None)
+        .await
+        .expect("Failed to create NFT");
+
+    // Sell
+
+    let (sell_accounts, sell_tx) = sell(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        now() - 60,
+        now() + 60,
+        None,
+        None,
+        None,
+        None,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .expect("Failed to sell NFT");
+
+    // Buy
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), 10 * ONE_SOL)
+        .await
+        .unwrap();
+
+    let (_, deposit_tx) = deposit(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &buyer,
+        5 * ONE_SOL,
+    );
+    context
+        .banks_client
+        .process_transaction(deposit_tx)
+        .await
+        .unwrap();
+
+    let (_, buy_tx) = buy(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer,
+        ONE_SOL,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    // Migrate
+
+    set_legacy_listing_config(&mut context, &sell_accounts.listing_config).await;
+
+    let payer = Keypair::new();
+    airdrop(&mut context, &payer.pubkey(), ONE_SOL)
+        .await
+        .unwrap();
+
+    let (_, migrate_tx) = migrate_listing_config(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &payer.pubkey(),
+        &payer,
+    );
+    let tx_error = context
+        .banks_client
+        .process_transaction(migrate_tx)
+        .await
+        .unwrap_err();
+    assert_error(tx_error, AuctioneerError::InvalidHighestBidder.into());
+}
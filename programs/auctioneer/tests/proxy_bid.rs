@@ -0,0 +1,419 @@
+use anchor_client::solana_sdk::{signature::Keypair, signer::Signer};
+use anchor_lang::{prelude::Pubkey, AccountDeserialize};
+use auction_house::AuctionHouse;
+use auctioneer::errors::AuctioneerError;
+use auctioneer::sell::config::ListingConfig;
+use solana_program_test::{tokio, ProgramTestContext};
+use std::time::SystemTime;
+
+mod utils;
+use utils::setup_functions::*;
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_secs() as i64
+}
+
+async fn fund_buyer(
+    context: &mut ProgramTestContext,
+    auction_house: &Pubkey,
+    auction_house_data: &AuctionHouse,
+    amount: u64,
+) -> Keypair {
+    let buyer = Keypair::new();
+    airdrop(context, &buyer.pubkey(), 10 * ONE_SOL)
+        .await
+        .unwrap();
+
+    let (_, deposit_tx) = deposit(context, auction_house, auction_house_data, &buyer, amount);
+    context
+        .banks_client
+        .process_transaction(deposit_tx)
+        .await
+        .unwrap();
+
+    buyer
+}
+
+async fn get_listing_config(
+    context: &mut ProgramTestContext,
+    listing_config: Pubkey,
+) -> ListingConfig {
+    let listing_config_account = context
+        .banks_client
+        .get_account(listing_config)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+
+    ListingConfig::try_deserialize(&mut listing_config_account.as_ref()).unwrap()
+}
+
+#[tokio::test]
+async fn proxy_bid_success() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+
+    let (_, auction_house, auction_house_data) = create_auction_house(&mut context, 100, false)
+        .await
+        .expect("Failed to create Auction House");
+
+    let token = create_nft(&mut context, None)
+        .await
+        .expect("Failed to create NFT");
+
+    let end_time = now() + 60;
+
+    // Sell
+
+    let (sell_accounts, sell_tx) = sell(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        now() - 60,
+        end_time,
+        Some(ONE_SOL),
+        Some(ONE_SOL / 10),
+        None,
+        None,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .expect("Failed to sell NFT");
+
+    // Proxy bid, the standing bid opens at the reserve price
+
+    let buyer1 = fund_buyer(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        5 * ONE_SOL,
+    )
+    .await;
+    let (_, proxy_bid_tx) = proxy_bid(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer1,
+        3 * ONE_SOL,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(proxy_bid_tx)
+        .await
+        .unwrap();
+
+    let listing_config_data = get_listing_config(&mut context, sell_accounts.listing_config).await;
+    assert_eq!(listing_config_data.highest_bidder, buyer1.pubkey());
+    assert_eq!(listing_config_data.highest_bid.amount, ONE_SOL);
+    assert_eq!(listing_config_data.proxy_max_amount, 3 * ONE_SOL);
+
+    // Competing bid below the maximum, the proxy bid is raised by the increment
+
+    let buyer2 = fund_buyer(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        5 * ONE_SOL,
+    )
+    .await;
+    let (_, buy_tx) = buy(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer2,
+        2 * ONE_SOL,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    let listing_config_data = get_listing_config(&mut context, sell_accounts.listing_config).await;
+    assert_eq!(listing_config_data.highest_bidder, buyer1.pubkey());
+    assert_eq!(
+        listing_config_data.highest_bid.amount,
+        2 * ONE_SOL + ONE_SOL / 10
+    );
+
+    // Anyone settles at the standing bid, without the winner signing
+
+    warp_to_timestamp(&mut context, end_time + 1).await;
+
+    let cranker = Keypair::new();
+    airdrop(&mut context, &cranker.pubkey(), ONE_SOL)
+        .await
+        .unwrap();
+
+    let winning_bid = 2 * ONE_SOL + ONE_SOL / 10;
+    let (settle_accounts, settle_tx) = settle_proxy_bid(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer1.pubkey(),
+        &cranker,
+        winning_bid,
+    );
+    context
+        .banks_client
+        .process_transaction(settle_tx)
+        .await
+        .unwrap();
+
+    let (_, execute_sale_tx) = execute_sale(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        None,
+        &sell_accounts,
+        &settle_accounts,
+        winning_bid,
+        &buyer1,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(execute_sale_tx)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn proxy_bid_outbid_success() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+
+    let (_, auction_house, auction_house_data) = create_auction_house(&mut context, 100, false)
+        .await
+        .expect("Failed to create Auction House");
+
+    let token = create_nft(&mut context, None)
+        .await
+        .expect("Failed to create NFT");
+
+    // Sell
+
+    let (sell_accounts, sell_tx) = sell(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        now() - 60,
+        now() + 60,
+        Some(ONE_SOL),
+        Some(ONE_SOL / 10),
+        None,
+        None,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .expect("Failed to sell NFT");
+
+    // Proxy bid
+
+    let buyer1 = fund_buyer(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        5 * ONE_SOL,
+    )
+    .await;
+    let (_, proxy_bid_tx) = proxy_bid(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer1,
+        2 * ONE_SOL,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(proxy_bid_tx)
+        .await
+        .unwrap();
+
+    // Competing bid above the maximum
+
+    let buyer2 = fund_buyer(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        5 * ONE_SOL,
+    )
+    .await;
+    let (buy_accounts, buy_tx) = buy(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer2,
+        3 * ONE_SOL,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    let listing_config_data = get_listing_config(&mut context, sell_accounts.listing_config).await;
+    assert_eq!(listing_config_data.highest_bidder, buyer2.pubkey());
+    assert_eq!(listing_config_data.highest_bid.amount, 3 * ONE_SOL);
+    assert_eq!(
+        listing_config_data.highest_bid.buyer_trade_state,
+        buy_accounts.buyer_trade_state
+    );
+    assert_eq!(listing_config_data.proxy_max_amount, 0);
+}
+
+#[tokio::test]
+async fn failure_proxy_bid_insufficient_escrow() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+
+    let (_, auction_house, auction_house_data) = create_auction_house(&mut context, 100, false)
+        .await
+        .expect("Failed to create Auction House");
+
+    let token = create_nft(&mut context, None)
+        .await
+        .expect("Failed to create NFT");
+
+    // Sell
+
+    let (_, sell_tx) = sell(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        now() - 60,
+        now() + 60,
+        None,
+        None,
+        None,
+        None,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .expect("Failed to sell NFT");
+
+    // Proxy bid over the escrow balance
+
+    let buyer = fund_buyer(&mut context, &auction_house, &auction_house_data, ONE_SOL).await;
+    let (_, proxy_bid_tx) = proxy_bid(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer,
+        2 * ONE_SOL,
+    )
+    .await;
+    let tx_error = context
+        .banks_client
+        .process_transaction(proxy_bid_tx)
+        .await
+        .unwrap_err();
+
+    assert_error(tx_error, AuctioneerError::InsufficientProxyBidEscrow.into());
+}
+
+#[tokio::test]
+async fn failure_settle_proxy_bid_not_winner() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+
+    let (_, auction_house, auction_house_data) = create_auction_house(&mut context, 100, false)
+        .await
+        .expect("Failed to create Auction House");
+
+    let token = create_nft(&mut context, None)
+        .await
+        .expect("Failed to create NFT");
+
+    let end_time = now() + 60;
+
+    // Sell
+
+    let (_, sell_tx) = sell(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        now() - 60,
+        end_time,
+        None,
+        None,
+        None,
+        None,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .expect("Failed to sell NFT");
+
+    // Proxy bid
+
+    let buyer = fund_buyer(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        5 * ONE_SOL,
+    )
+    .await;
+    let (_, proxy_bid_tx) = proxy_bid(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer,
+        3 * ONE_SOL,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(proxy_bid_tx)
+        .await
+        .unwrap();
+
+    // Settle for a wallet that did not win
+
+    warp_to_timestamp(&mut context, end_time + 1).await;
+
+    let cranker = Keypair::new();
+    airdrop(&mut context, &cranker.pubkey(), ONE_SOL)
+        .await
+        .unwrap();
+
+    let (_, settle_tx) = settle_proxy_bid(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &cranker.pubkey(),
+        &cranker,
+        ONE_SOL,
+    );
+    let tx_error = context
+        .banks_client
+        .process_transaction(settle_tx)
+        .await
+        .unwrap_err();
+
+    assert_error(tx_error, AuctioneerError::NotWinningBidder.into());
+}
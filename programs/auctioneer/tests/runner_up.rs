@@ -0,0 +1,319 @@
+use anchor_client::solana_sdk::{account::AccountSharedData, signature::Keypair, signer::Signer};
+use anchor_lang::{prelude::Pubkey, AccountDeserialize};
+use auction_house::{pda::find_escrow_payment_account_address, AuctionHouse};
+use auctioneer::constants::RUNNER_UP_GRACE_PERIOD;
+use auctioneer::errors::AuctioneerError;
+use auctioneer::sell::config::ListingConfig;
+use solana_program_test::{tokio, ProgramTestContext};
+use std::time::SystemTime;
+
+mod utils;
+use utils::setup_functions::*;
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_secs() as i64
+}
+
+async fn place_bid(
+    context: &mut ProgramTestContext,
+    auction_house: &Pubkey,
+    auction_house_data: &AuctionHouse,
+    token: &NFT,
+    amount: u64,
+) -> Keypair {
+    let buyer = Keypair::new();
+    airdrop(context, &buyer.pubkey(), 10 * ONE_SOL)
+        .await
+        .unwrap();
+
+    let (_, deposit_tx) = deposit(context, auction_house, auction_house_data, &buyer, amount);
+    context
+        .banks_client
+        .process_transaction(deposit_tx)
+        .await
+        .unwrap();
+
+    let (_, buy_tx) = buy(
+        context,
+        auction_house,
+        auction_house_data,
+        token,
+        &buyer,
+        amount,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    buyer
+}
+
+#[tokio::test]
+async fn promote_runner_up_after_grace_period_success() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+
+    let (_, auction_house, auction_house_data) = create_auction_house(&mut context, 100, false)
+        .await
+        .expect("Failed to create Auction House");
+
+    let token = create_nft(&mut context, None)
+        .await
+        .expect("Failed to create NFT");
+
+    let end_time = now() + 60;
+
+    // Sell
+
+    let (sell_accounts, sell_tx) = sell(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        now() - 60,
+        end_time,
+        None,
+        None,
+        None,
+        None,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .expect("Failed to sell NFT");
+
+    // Buy
+
+    let buyer1 = place_bid(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        ONE_SOL,
+    )
+    .await;
+    let buyer2 = place_bid(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        2 * ONE_SOL,
+    )
+    .await;
+
+    // Promote once the winner let the grace period pass
+
+    warp_to_timestamp(&mut context, end_time + RUNNER_UP_GRACE_PERIOD + 1).await;
+
+    let (_, promote_tx) = promote_runner_up(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer2.pubkey(),
+        &[buyer1.pubkey()],
+        &buyer1,
+    );
+    context
+        .banks_client
+        .process_transaction(promote_tx)
+        .await
+        .unwrap();
+
+    let listing_config_account = context
+        .banks_client
+        .get_account(sell_accounts.listing_config)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    let listing_config_data =
+        ListingConfig::try_deserialize(&mut listing_config_account.as_ref()).unwrap();
+
+    assert_eq!(listing_config_data.highest_bidder, buyer1.pubkey());
+    assert_eq!(listing_config_data.highest_bid.amount, ONE_SOL);
+    assert!(listing_config_data.runner_up_bids.is_empty());
+}
+
+#[tokio::test]
+async fn failure_promote_runner_up_funded_winner() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+
+    let (_, auction_house, auction_house_data) = create_auction_house(&mut context, 100, false)
+        .await
+        .expect("Failed to create Auction House");
+
+    let token = create_nft(&mut context, None)
+        .await
+        .expect("Failed to create NFT");
+
+    let end_time = now() + 60;
+
+    // Sell
+
+    let (_, sell_tx) = sell(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        now() - 60,
+        end_time,
+        None,
+        None,
+        None,
+        None,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .expect("Failed to sell NFT");
+
+    // Buy
+
+    let buyer1 = place_bid(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        ONE_SOL,
+    )
+    .await;
+    let buyer2 = place_bid(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        2 * ONE_SOL,
+    )
+    .await;
+
+    // Promote within the grace period
+
+    warp_to_timestamp(&mut context, end_time + 1).await;
+
+    let (_, promote_tx) = promote_runner_up(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer2.pubkey(),
+        &[buyer1.pubkey()],
+        &buyer1,
+    );
+    let tx_error = context
+        .banks_client
+        .process_transaction(promote_tx)
+        .await
+        .unwrap_err();
+
+    assert_error(tx_error, AuctioneerError::WinnerCanStillSettle.into());
+}
+
+#[tokio::test]
+async fn promote_runner_up_winner_escrow_down_to_rent_success() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+
+    let (_, auction_house, auction_house_data) = create_auction_house(&mut context, 100, false)
+        .await
+        .expect("Failed to create Auction House");
+
+    let token = create_nft(&mut context, None)
+        .await
+        .expect("Failed to create NFT");
+
+    let end_time = now() + 60;
+
+    // Sell
+
+    let (sell_accounts, sell_tx) = sell(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        now() - 60,
+        end_time,
+        None,
+        None,
+        None,
+        None,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .expect("Failed to sell NFT");
+
+    // Buy
+
+    let buyer1 = place_bid(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        ONE_SOL,
+    )
+    .await;
+    let buyer2 = place_bid(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        2 * ONE_SOL,
+    )
+    .await;
+
+    // The winner's escrow holds the bid amount, but part of it is the rent exempt minimum
+
+    let (winner_escrow_payment_account, _) =
+        find_escrow_payment_account_address(&auction_house, &buyer2.pubkey());
+    let mut winner_escrow = context
+        .banks_client
+        .get_account(winner_escrow_payment_account)
+        .await
+        .unwrap()
+        .unwrap();
+    winner_escrow.lamports = 2 * ONE_SOL;
+    context.set_account(
+        &winner_escrow_payment_account,
+        &AccountSharedData::from(winner_escrow),
+    );
+
+    // Promote within the grace period
+
+    warp_to_timestamp(&mut context, end_time + 1).await;
+
+    let (_, promote_tx) = promote_runner_up(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer2.pubkey(),
+        &[buyer1.pubkey()],
+        &buyer1,
+    );
+    context
+        .banks_client
+        .process_transaction(promote_tx)
+        .await
+        .unwrap();
+
+    let listing_config_account = context
+        .banks_client
+        .get_account(sell_accounts.listing_config)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    let listing_config_data =
+        ListingConfig::try_deserialize(&mut listing_config_account.as_ref()).unwrap();
+
+    assert_eq!(listing_config_data.highest_bidder, buyer1.pubkey());
+    assert_eq!(listing_config_data.highest_bid.amount, ONE_SOL);
+}
@@ -0,0 +1,585 @@
+use anchor_client::solana_sdk::{signature::Keypair, signer::Signer};
+use anchor_lang::{prelude::Pubkey, AccountDeserialize};
+use auction_house::errors::AuctionHouseError;
+use auctioneer::errors::AuctioneerError;
+use auctioneer::sell::config::{ListingConfig, SealedBidParams};
+use solana_program_test::{tokio, ProgramTestContext};
+use std::time::SystemTime;
+
+mod utils;
+use utils::setup_functions::*;
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_secs() as i64
+}
+
+async fn get_listing_config(
+    context: &mut ProgramTestContext,
+    listing_config: Pubkey,
+) -> ListingConfig {
+    let listing_config_account = context
+        .banks_client
+        .get_account(listing_config)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+
+    ListingConfig::try_deserialize(&mut listing_config_account.as_ref()).unwrap()
+}
+
+#[tokio::test]
+async fn sealed_second_price_success() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+
+    let (_, auction_house, auction_house_data) = create_auction_house(&mut context, 100, false)
+        .await
+        .expect("Failed to create Auction House");
+
+    let token = create_nft(&mut context, None)
+        .await
+        .expect("Failed to create NFT");
+
+    let start_time = now() - 60;
+    let end_time = now() + 60;
+    let reveal_end_time = end_time + 60;
+
+    // Sell
+
+    let (sell_accounts, sell_tx) = sell_sealed(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        start_time,
+        end_time,
+        None,
+        SealedBidParams {
+            reveal_end_time,
+            second_price: true,
+        },
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .expect("Failed to sell NFT");
+
+    // Commit
+
+    let buyer1 = Keypair::new();
+    let buyer2 = Keypair::new();
+    let salt1 = [1; 32];
+    let salt2 = [2; 32];
+    for (buyer, amount, salt) in [(&buyer1, 2 * ONE_SOL, salt1), (&buyer2, ONE_SOL, salt2)] {
+        airdrop(&mut context, &buyer.pubkey(), 10 * ONE_SOL)
+            .await
+            .unwrap();
+
+        let (_, commit_tx) = commit_bid(
+            &mut context,
+            &auction_house,
+            &auction_house_data,
+            &token,
+            buyer,
+            amount,
+            salt,
+            5 * ONE_SOL,
+        );
+        context
+            .banks_client
+            .process_transaction(commit_tx)
+            .await
+            .unwrap();
+    }
+
+    // Reveal
+
+    warp_to_timestamp(&mut context, end_time + 1).await;
+
+    for (buyer, amount, salt) in [(&buyer1, 2 * ONE_SOL, salt1), (&buyer2, ONE_SOL, salt2)] {
+        let (_, reveal_tx) = reveal_bid(
+            &mut context,
+            &auction_house,
+            &auction_house_data,
+            &token,
+            buyer,
+            amount,
+            salt,
+        )
+        .await;
+        context
+            .banks_client
+            .process_transaction(reveal_tx)
+            .await
+            .unwrap();
+    }
+
+    let listing_config_account = context
+        .banks_client
+        .get_account(sell_accounts.listing_config)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    let listing_config_data =
+        ListingConfig::try_deserialize(&mut listing_config_account.as_ref()).unwrap();
+
+    assert_eq!(listing_config_data.highest_bidder, buyer1.pubkey());
+    assert_eq!(listing_config_data.highest_bid.amount, 2 * ONE_SOL);
+    assert_eq!(listing_config_data.second_highest_amount, ONE_SOL);
+
+    // Anyone settles at the second highest price
+
+    warp_to_timestamp(&mut context, reveal_end_time + 1).await;
+
+    let cranker = Keypair::new();
+    airdrop(&mut context, &cranker.pubkey(), ONE_SOL)
+        .await
+        .unwrap();
+
+    let (settle_accounts, settle_tx) = settle_sealed_bid(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer1.pubkey(),
+        &cranker,
+        ONE_SOL,
+    );
+    context
+        .banks_client
+        .process_transaction(settle_tx)
+        .await
+        .unwrap();
+
+    let (_, execute_sale_tx) = execute_sale(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        None,
+        &sell_accounts,
+        &settle_accounts,
+        ONE_SOL,
+        &buyer1,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(execute_sale_tx)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn failure_buy_sealed_bid_auction() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+
+    let (_, auction_house, auction_house_data) = create_auction_house(&mut context, 100, false)
+        .await
+        .expect("Failed to create Auction House");
+
+    let token = create_nft(&mut context, None)
+        .await
+        .expect("Failed to create NFT");
+
+    // Sell
+
+    let (_, sell_tx) = sell_sealed(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        now() - 60,
+        now() + 60,
+        None,
+        SealedBidParams {
+            reveal_end_time: now() + 120,
+            second_price: false,
+        },
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .expect("Failed to sell NFT");
+
+    // Buy
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), 10 * ONE_SOL)
+        .await
+        .unwrap();
+
+    let (_, deposit_tx) = deposit(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &buyer,
+        5 * ONE_SOL,
+    );
+    context
+        .banks_client
+        .process_transaction(deposit_tx)
+        .await
+        .unwrap();
+
+    let (_, buy_tx) = buy(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer,
+        ONE_SOL,
+    )
+    .await;
+    let tx_error = context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap_err();
+
+    assert_error(tx_error, AuctioneerError::BidMustBeSealed.into());
+}
+
+#[tokio::test]
+async fn failure_reveal_invalid_bid() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+
+    let (_, auction_house, auction_house_data) = create_auction_house(&mut context, 100, false)
+        .await
+        .expect("Failed to create Auction House");
+
+    let token = create_nft(&mut context, None)
+        .await
+        .expect("Failed to create NFT");
+
+    let end_time = now() + 60;
+
+    // Sell
+
+    let (_, sell_tx) = sell_sealed(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        now() - 60,
+        end_time,
+        None,
+        SealedBidParams {
+            reveal_end_time: end_time + 60,
+            second_price: false,
+        },
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .expect("Failed to sell NFT");
+
+    // Commit
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), 10 * ONE_SOL)
+        .await
+        .unwrap();
+
+    let salt = [7; 32];
+    let (_, commit_tx) = commit_bid(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer,
+        ONE_SOL,
+        salt,
+        5 * ONE_SOL,
+    );
+    context
+        .banks_client
+        .process_transaction(commit_tx)
+        .await
+        .unwrap();
+
+    // Reveal an amount different from the committed one
+
+    warp_to_timestamp(&mut context, end_time + 1).await;
+
+    let (_, reveal_tx) = reveal_bid(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer,
+        2 * ONE_SOL,
+        salt,
+    )
+    .await;
+    let tx_error = context
+        .banks_client
+        .process_transaction(reveal_tx)
+        .await
+        .unwrap_err();
+
+    assert_error(tx_error, AuctioneerError::InvalidBidReveal.into());
+}
+
+#[tokio::test]
+async fn sealed_bid_deposit_locked_until_revealed() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+
+    let (_, auction_house, auction_house_data) = create_auction_house(&mut context, 100, false)
+        .await
+        .expect("Failed to create Auction House");
+
+    let token = create_nft(&mut context, None)
+        .await
+        .expect("Failed to create NFT");
+
+    let end_time = now() + 60;
+    let reveal_end_time = end_time + 60;
+
+    // Sell
+
+    let (sell_accounts, sell_tx) = sell_sealed(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        now() - 60,
+        end_time,
+        None,
+        SealedBidParams {
+            reveal_end_time,
+            second_price: false,
+        },
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .expect("Failed to sell NFT");
+
+    // Commit
+
+    let buyer1 = Keypair::new();
+    let buyer2 = Keypair::new();
+    let salt1 = [1; 32];
+    let salt2 = [2; 32];
+    for (buyer, amount, salt) in [(&buyer1, 2 * ONE_SOL, salt1), (&buyer2, ONE_SOL, salt2)] {
+        airdrop(&mut context, &buyer.pubkey(), 10 * ONE_SOL)
+            .await
+            .unwrap();
+
+        let (_, commit_tx) = commit_bid(
+            &mut context,
+            &auction_house,
+            &auction_house_data,
+            &token,
+            buyer,
+            amount,
+            salt,
+            5 * ONE_SOL,
+        );
+        context
+            .banks_client
+            .process_transaction(commit_tx)
+            .await
+            .unwrap();
+    }
+
+    let listing_config_data = get_listing_config(&mut context, sell_accounts.listing_config).await;
+    assert_eq!(listing_config_data.bid_commitments, 2);
+
+    // The committed deposit can not be withdrawn
+
+    let (_, withdraw_tx) = withdraw(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &buyer2,
+        ONE_SOL,
+    );
+    let tx_error = context
+        .banks_client
+        .process_transaction(withdraw_tx)
+        .await
+        .unwrap_err();
+    assert_error(tx_error, AuctionHouseError::EscrowFundsLocked.into());
+
+    // Reveal
+
+    warp_to_timestamp(&mut context, end_time + 1).await;
+
+    for (buyer, amount, salt) in [(&buyer1, 2 * ONE_SOL, salt1), (&buyer2, ONE_SOL, salt2)] {
+        let (_, reveal_tx) = reveal_bid(
+            &mut context,
+            &auction_house,
+            &auction_house_data,
+            &token,
+            buyer,
+            amount,
+            salt,
+        )
+        .await;
+        context
+            .banks_client
+            .process_transaction(reveal_tx)
+            .await
+            .unwrap();
+    }
+
+    let listing_config_data = get_listing_config(&mut context, sell_accounts.listing_config).await;
+    assert_eq!(listing_config_data.bid_commitments, 0);
+    assert_eq!(listing_config_data.escrow_locked_amount, 2 * ONE_SOL);
+
+    // The losing deposit is released, the winning bid stays locked
+
+    let (_, withdraw_tx) = withdraw(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &buyer2,
+        5 * ONE_SOL,
+    );
+    context
+        .banks_client
+        .process_transaction(withdraw_tx)
+        .await
+        .unwrap();
+
+    let (_, withdraw_tx) = withdraw(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &buyer1,
+        4 * ONE_SOL,
+    );
+    let tx_error = context
+        .banks_client
+        .process_transaction(withdraw_tx)
+        .await
+        .unwrap_err();
+    assert_error(tx_error, AuctionHouseError::EscrowFundsLocked.into());
+}
+
+#[tokio::test]
+async fn cancel_bid_commitment_success() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+
+    let (_, auction_house, auction_house_data) = create_auction_house(&mut context, 100, false)
+        .await
+        .expect("Failed to create Auction House");
+
+    let token = create_nft(&mut context, None)
+        .await
+        .expect("Failed to create NFT");
+
+    let end_time = now() + 60;
+    let reveal_end_time = end_time + 60;
+
+    // Sell
+
+    let (sell_accounts, sell_tx) = sell_sealed(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        now() - 60,
+        end_time,
+        None,
+        SealedBidParams {
+            reveal_end_time,
+            second_price: false,
+        },
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .expect("Failed to sell NFT");
+
+    // Commit
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), 10 * ONE_SOL)
+        .await
+        .unwrap();
+
+    let (commit_accounts, commit_tx) = commit_bid(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer,
+        ONE_SOL,
+        [3; 32],
+        5 * ONE_SOL,
+    );
+    context
+        .banks_client
+        .process_transaction(commit_tx)
+        .await
+        .unwrap();
+
+    // The commitment can not be cancelled while it can still be revealed
+
+    warp_to_timestamp(&mut context, end_time + 1).await;
+
+    let (_, cancel_tx) = cancel_bid_commitment(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer,
+    );
+    let tx_error = context
+        .banks_client
+        .process_transaction(cancel_tx)
+        .await
+        .unwrap_err();
+    assert_error(tx_error, AuctioneerError::RevealPeriodActive.into());
+
+    // Cancel the unrevealed commitment
+
+    warp_to_timestamp(&mut context, reveal_end_time + 1).await;
+
+    let (_, cancel_tx) = cancel_bid_commitment(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer,
+    );
+    context
+        .banks_client
+        .process_transaction(cancel_tx)
+        .await
+        .unwrap();
+
+    assert!(context
+        .banks_client
+        .get_account(commit_accounts.bid_commitment)
+        .await
+        .unwrap()
+        .is_none());
+    let listing_config_data = get_listing_config(&mut context, sell_accounts.listing_config).await;
+    assert_eq!(listing_config_data.bid_commitments, 0);
+
+    let (_, withdraw_tx) = withdraw(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &buyer,
+        5 * ONE_SOL,
+    );
+    context
+        .banks_client
+        .process_transaction(withdraw_tx)
+        .await
+        .unwrap();
+}
@@ -0,0 +1,313 @@
+use anchor_client::solana_sdk::{signature::Keypair, signer::Signer};
+use anchor_lang::AccountDeserialize;
+use auctioneer::errors::AuctioneerError;
+use auctioneer::sell::config::{ListingConfig, SealedBidParams};
+use solana_program_test::tokio;
+use std::time::SystemTime;
+
+mod utils;
+use utils::setup_functions::*;
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_secs() as i64
+}
+
+#[tokio::test]
+async fn update_listing_success() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+
+    let (_, auction_house, auction_house_data) = create_auction_house(&mut context, 100, false)
+        .await
+        .expect("Failed to create Auction House");
+
+    let token = create_nft(&mut context, None)
+        .await
+        .expect("Failed to create NFT");
+
+    // Sell
+
+    let (sell_accounts, sell_tx) = sell(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        now() - 60,
+        now() + 60,
+        None,
+        None,
+        None,
+        None,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .expect("Failed to sell NFT");
+
+    // Update
+
+    let end_time = now() + 3600;
+    let (_, update_tx) = update_listing(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        None,
+        Some(end_time),
+        Some(ONE_SOL),
+        Some(ONE_SOL / 10),
+        Some(60),
+        Some(30),
+    );
+    context
+        .banks_client
+        .process_transaction(update_tx)
+        .await
+        .unwrap();
+
+    let listing_config_account = context
+        .banks_client
+        .get_account(sell_accounts.listing_config)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    let listing_config_data =
+        ListingConfig::try_deserialize(&mut listing_config_account.as_ref()).unwrap();
+
+    assert_eq!(listing_config_data.end_time, end_time);
+    assert_eq!(listing_config_data.reserve_price, ONE_SOL);
+    assert_eq!(listing_config_data.min_bid_increment, ONE_SOL / 10);
+    assert_eq!(listing_config_data.time_ext_period, 60);
+    assert_eq!(listing_config_data.time_ext_delta, 30);
+}
+
+#[tokio::test]
+async fn failure_update_listing_end_before_start() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+
+    let (_, auction_house, auction_house_data) = create_auction_house(&mut context, 100, false)
+        .await
+        .expect("Failed to create Auction House");
+
+    let token = create_nft(&mut context, None)
+        .await
+        .expect("Failed to create NFT");
+
+    // Sell
+
+    let start_time = now() - 60;
+    let (_, sell_tx) = sell(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        start_time,
+        now() + 60,
+        None,
+        None,
+        None,
+        None,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .expect("Failed to sell NFT");
+
+    // Update
+
+    let (_, update_tx) = update_listing(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        None,
+        Some(start_time - 1),
+        None,
+        None,
+        None,
+        None,
+    );
+    let tx_error = context
+        .banks_client
+        .process_transaction(update_tx)
+        .await
+        .unwrap_err();
+
+    assert_error(tx_error, AuctioneerError::EndBeforeStart.into());
+}
+
+#[tokio::test]
+async fn failure_update_listing_after_bid() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+
+    let (_, auction_house, auction_house_data) = create_auction_house(&mut context, 100, false)
+        .await
+        .expect("Failed to create Auction House");
+
+    let token = create_nft(&mut context, None)
+        .await
+        .expect("Failed to create NFT");
+
+    // Sell
+
+    let (_, sell_tx) = sell(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        now() - 60,
+        now() + 60,
+        None,
+        None,
+        None,
+        None,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .expect("Failed to sell NFT");
+
+    // Buy
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), 10 * ONE_SOL)
+        .await
+        .unwrap();
+
+    let (_, deposit_tx) = deposit(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &buyer,
+        5 * ONE_SOL,
+    );
+    context
+        .banks_client
+        .process_transaction(deposit_tx)
+        .await
+        .unwrap();
+
+    let (_, buy_tx) = buy(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer,
+        ONE_SOL,
+    )
+    .await;
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    // Update
+
+    let (_, update_tx) = update_listing(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        None,
+        None,
+        Some(2 * ONE_SOL),
+        None,
+        None,
+        None,
+    );
+    let tx_error = context
+        .banks_client
+        .process_transaction(update_tx)
+        .await
+        .unwrap_err();
+
+    assert_error(tx_error, AuctioneerError::ListingHasBids.into());
+}
+
+#[tokio::test]
+async fn failure_update_listing_with_bid_commitments() {
+    let mut context = auctioneer_program_test().start_with_context().await;
+
+    let (_, auction_house, auction_house_data) = create_auction_house(&mut context, 100, false)
+        .await
+        .expect("Failed to create Auction House");
+
+    let token = create_nft(&mut context, None)
+        .await
+        .expect("Failed to create NFT");
+
+    let end_time = now() + 60;
+
+    // Sell
+
+    let (_, sell_tx) = sell_sealed(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        now() - 60,
+        end_time,
+        None,
+        SealedBidParams {
+            reveal_end_time: end_time + 60,
+            second_price: false,
+        },
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .expect("Failed to sell NFT");
+
+    // Commit
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), 10 * ONE_SOL)
+        .await
+        .unwrap();
+
+    let (_, commit_tx) = commit_bid(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        &buyer,
+        ONE_SOL,
+        [1; 32],
+        5 * ONE_SOL,
+    );
+    context
+        .banks_client
+        .process_transaction(commit_tx)
+        .await
+        .unwrap();
+
+    // Raise the reserve price under the committed bid
+
+    let (_, update_tx) = update_listing(
+        &mut context,
+        &auction_house,
+        &auction_house_data,
+        &token,
+        None,
+        None,
+        Some(2 * ONE_SOL),
+        None,
+        None,
+        None,
+    );
+    let tx_error = context
+        .banks_client
+        .process_transaction(update_tx)
+        .await
+        .unwrap_err();
+
+    assert_error(tx_error, AuctioneerError::ListingHasBidCommitments.into());
+}
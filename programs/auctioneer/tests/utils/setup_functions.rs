@@ -1,17 +1,25 @@
 #![allow(dead_code)]
 
+use anchor_client::solana_sdk::account::{Account, AccountSharedData};
 use anchor_client::solana_sdk::transaction::{Transaction, TransactionError};
 use anchor_client::solana_sdk::{signature::Keypair, signer::Signer};
 use anchor_lang::prelude::AccountMeta;
 use anchor_lang::solana_program::instruction::{Instruction, InstructionError};
-use anchor_lang::solana_program::{system_instruction, system_program, sysvar};
+use anchor_lang::solana_program::{clock::Clock, system_instruction, system_program, sysvar};
 use anchor_lang::{prelude::Pubkey, AccountDeserialize};
-use anchor_lang::{InstructionData, ToAccountMetas};
+use anchor_lang::{
+    AnchorDeserialize, AnchorSerialize, Discriminator, Event, InstructionData, ToAccountMetas,
+};
 use anchor_spl::associated_token::{self, get_associated_token_address};
 use anchor_spl::token::spl_token;
 use auction_house::pda::*;
 use auction_house::AuctionHouse;
 use auctioneer::pda::*;
+use auctioneer::sell::config::{
+    Bid, BidIncrementParams, DutchAuctionParams, ListingConfig, ListingConfigV0,
+    ListingConfigVersion, SealedBidParams, LISTING_CONFIG_V0_SIZE,
+};
+use auctioneer::utils::compute_bid_commitment;
 use nft_minter::pda::*;
 use nft_minter::utils::{token_metadata_program_id, Creator};
 use solana_program_test::{BanksClientError, ProgramTest, ProgramTestContext};
@@ -32,6 +40,30 @@ pub fn assert_error(error: BanksClientError, expected_error: u32) {
     }
 }
 
+// Processes the transaction and decodes the events of type T it emitted
+pub async fn process_transaction_events<T: Event>(
+    context: &mut ProgramTestContext,
+    tx: Transaction,
+) -> Vec<T> {
+    let result = context
+        .banks_client
+        .process_transaction_with_metadata(tx)
+        .await
+        .unwrap();
+    result.result.unwrap();
+
+    result
+        .metadata
+        .unwrap()
+        .log_messages
+        .iter()
+        .filter_map(|log| log.strip_prefix("Program data: "))
+        .filter_map(|data| base64::decode(data).ok())
+        .filter(|data| data.starts_with(&T::discriminator()))
+        .map(|data| T::try_from_slice(&data[8..]).unwrap())
+        .collect()
+}
+
 pub async fn airdrop(
     context: &mut ProgramTestContext,
     receiver: &Pubkey,
@@ -144,6 +176,84 @@ pub async fn create_nft(
     })
 }
 
+// Semi-fungible token without a master edition, so more than one unit can be minted
+pub async fn create_sft(
+    context: &mut ProgramTestContext,
+    metadata_creators: Option<Vec<Creator>>,
+    supply: u64,
+) -> Result<NFT, BanksClientError> {
+    let mint = Keypair::new();
+    let owner = Keypair::new();
+    airdrop(context, &owner.pubkey(), 10 * ONE_SOL)
+        .await
+        .unwrap();
+
+    let ata = get_associated_token_address(&owner.pubkey(), &mint.pubkey());
+    let (metadata, _) = find_metadata_account(&mint.pubkey());
+    let (master_edition, _) = find_master_edition_account(&mint.pubkey());
+
+    // CreateToken
+    let create_token_ix = Instruction {
+        program_id: nft_minter::id(),
+        data: nft_minter::instruction::CreateToken {
+            name: "Solana Course SFT".to_string(),
+            symbol: "SOLC".to_string(),
+            uri: "https://raw.githubusercontent.com/arsenijkovalov/nft-assets/main/assets/nft.json"
+                .to_string(),
+            creators: metadata_creators,
+            seller_fee_basis_points: 10,
+            is_mutable: false,
+        }
+        .data(),
+        accounts: nft_minter::accounts::CreateToken {
+            payer: owner.pubkey(),
+            mint_account: mint.pubkey(),
+            mint_authority: owner.pubkey(),
+            update_authority: owner.pubkey(),
+            metadata_account: metadata,
+            token_metadata_program: token_metadata_program_id(),
+            system_program: system_program::id(),
+            token_program: anchor_spl::token::ID,
+            rent: sysvar::rent::id(),
+        }
+        .to_account_metas(None),
+    };
+
+    let create_ata_ix =
+        associated_token::spl_associated_token_account::instruction::create_associated_token_account(
+            &owner.pubkey(),
+            &owner.pubkey(),
+            &mint.pubkey(),
+            &spl_token::id(),
+        );
+
+    let mint_to_ix = spl_token::instruction::mint_to(
+        &spl_token::id(),
+        &mint.pubkey(),
+        &ata,
+        &owner.pubkey(),
+        &[],
+        supply,
+    )
+    .unwrap();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[create_token_ix, create_ata_ix, mint_to_ix],
+        Some(&owner.pubkey()),
+        &[&mint, &owner],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await?;
+
+    Ok(NFT {
+        mint,
+        owner,
+        ata,
+        metadata,
+        master_edition,
+    })
+}
+
 pub fn auctioneer_program_test() -> ProgramTest {
     let mut program = ProgramTest::new("auctioneer", auctioneer::id(), None);
     program.add_program("auction_house", auction_house::id(), None);
@@ -228,11 +338,29 @@ pub async fn create_auction_house(
         .to_account_metas(None),
     };
 
+    // InitAuctioneerConfig
+    let init_auctioneer_config_ix = Instruction {
+        program_id: auctioneer::id(),
+        data: auctioneer::instruction::InitAuctioneerConfig {
+            fee_basis_points: 0,
+        }
+        .data(),
+        accounts: auctioneer::accounts::AuctioneerInitConfig {
+            wallet: authority.pubkey(),
+            auction_house,
+            auctioneer_config: find_auctioneer_config_address(&auction_house).0,
+            fee_destination: authority.pubkey(),
+            system_program: system_program::id(),
+        }
+        .to_account_metas(None),
+    };
+
     let tx = Transaction::new_signed_with_payer(
         &[
             create_auction_house_ix,
             delegate_auctioneer_ix,
             auctioneer_authorize_ix,
+            init_auctioneer_config_ix,
         ],
         Some(&authority.pubkey()),
         &[&authority],
@@ -252,6 +380,138 @@ pub async fn create_auction_house(
     Ok((authority, auction_house, auction_house_data))
 }
 
+pub fn update_auction_house(
+    context: &mut ProgramTestContext,
+    authority: &Keypair,
+    auction_house: &Pubkey,
+    auction_house_data: &AuctionHouse,
+    min_auction_duration: Option<i64>,
+    max_auction_duration: Option<i64>,
+    max_time_extension: Option<i64>,
+    max_auctioneer_fee_basis_points: Option<u16>,
+) -> Transaction {
+    let data = auction_house::instruction::UpdateAuctionHouse {
+        seller_fee_basis_points: None,
+        can_change_sale_price: None,
+        min_auction_duration,
+        max_auction_duration,
+        max_time_extension,
+        royalty_policy: None,
+        referral_basis_points: None,
+        buyer_premium_basis_points: None,
+        max_auctioneer_fee_basis_points,
+    };
+
+    let accounts = auction_house::accounts::UpdateAuctionHouse {
+        treasury_mint: auction_house_data.treasury_mint,
+        payer: authority.pubkey(),
+        authority: authority.pubkey(),
+        new_authority: authority.pubkey(),
+        fee_withdrawal_destination: auction_house_data.fee_withdrawal_destination,
+        treasury_withdrawal_destination: auction_house_data.treasury_withdrawal_destination,
+        treasury_withdrawal_destination_owner: auction_house_data.treasury_withdrawal_destination,
+        auction_house: *auction_house,
+        token_program: spl_token::id(),
+        system_program: system_program::id(),
+        associated_token_program: associated_token::ID,
+        rent: sysvar::rent::id(),
+    };
+
+    let ix = Instruction {
+        program_id: auction_house::id(),
+        data: data.data(),
+        accounts: accounts.to_account_metas(None),
+    };
+
+    Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&authority.pubkey()),
+        &[authority],
+        context.last_blockhash,
+    )
+}
+
+pub fn init_auctioneer_config(
+    context: &mut ProgramTestContext,
+    authority: &Keypair,
+    auction_house: &Pubkey,
+    fee_basis_points: u16,
+    fee_destination: &Pubkey,
+) -> (auctioneer::accounts::AuctioneerInitConfig, Transaction) {
+    let data = auctioneer::instruction::InitAuctioneerConfig { fee_basis_points };
+
+    let accounts = auctioneer::accounts::AuctioneerInitConfig {
+        wallet: authority.pubkey(),
+        auction_house: *auction_house,
+        auctioneer_config: find_auctioneer_config_address(auction_house).0,
+        fee_destination: *fee_destination,
+        system_program: system_program::id(),
+    };
+
+    let ix = Instruction {
+        program_id: auctioneer::id(),
+        data: data.data(),
+        accounts: accounts.to_account_metas(None),
+    };
+
+    (
+        accounts,
+        Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&authority.pubkey()),
+            &[authority],
+            context.last_blockhash,
+        ),
+    )
+}
+
+pub fn update_auctioneer_config(
+    context: &mut ProgramTestContext,
+    authority: &Keypair,
+    auction_house: &Pubkey,
+    fee_basis_points: Option<u16>,
+    fee_destination: &Pubkey,
+) -> (auctioneer::accounts::AuctioneerUpdateConfig, Transaction) {
+    let data = auctioneer::instruction::UpdateAuctioneerConfig { fee_basis_points };
+
+    let accounts = auctioneer::accounts::AuctioneerUpdateConfig {
+        wallet: authority.pubkey(),
+        auction_house: *auction_house,
+        auctioneer_config: find_auctioneer_config_address(auction_house).0,
+        fee_destination: *fee_destination,
+    };
+
+    let ix = Instruction {
+        program_id: auctioneer::id(),
+        data: data.data(),
+        accounts: accounts.to_account_metas(None),
+    };
+
+    (
+        accounts,
+        Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&authority.pubkey()),
+            &[authority],
+            context.last_blockhash,
+        ),
+    )
+}
+
+pub async fn get_auction_house(
+    context: &mut ProgramTestContext,
+    auction_house: &Pubkey,
+) -> AuctionHouse {
+    let auction_house_account = context
+        .banks_client
+        .get_account(*auction_house)
+        .await
+        .unwrap()
+        .expect("Auction House account not found");
+
+    AuctionHouse::try_deserialize(&mut auction_house_account.data.as_ref()).unwrap()
+}
+
 pub fn sell(
     context: &mut ProgramTestContext,
     auction_house: &Pubkey,
@@ -263,6 +523,95 @@ pub fn sell(
     min_bid_increment: Option<u64>,
     time_ext_period: Option<u32>,
     time_ext_delta: Option<u32>,
+) -> (auctioneer::accounts::AuctioneerSell, Transaction) {
+    sell_with_params(
+        context,
+        auction_house,
+        auction_house_data,
+        token,
+        start_time,
+        end_time,
+        reserve_price,
+        min_bid_increment,
+        time_ext_period,
+        time_ext_delta,
+        None,
+        None,
+        None,
+        None,
+    )
+}
+
+pub fn sell_dutch(
+    context: &mut ProgramTestContext,
+    auction_house: &Pubkey,
+    auction_house_data: &AuctionHouse,
+    token: &NFT,
+    start_time: i64,
+    end_time: i64,
+    dutch_auction: DutchAuctionParams,
+) -> (auctioneer::accounts::AuctioneerSell, Transaction) {
+    sell_with_params(
+        context,
+        auction_house,
+        auction_house_data,
+        token,
+        start_time,
+        end_time,
+        None,
+        None,
+        None,
+        None,
+        Some(dutch_auction),
+        None,
+        None,
+        None,
+    )
+}
+
+pub fn sell_sealed(
+    context: &mut ProgramTestContext,
+    auction_house: &Pubkey,
+    auction_house_data: &AuctionHouse,
+    token: &NFT,
+    start_time: i64,
+    end_time: i64,
+    reserve_price: Option<u64>,
+    sealed_bid: SealedBidParams,
+) -> (auctioneer::accounts::AuctioneerSell, Transaction) {
+    sell_with_params(
+        context,
+        auction_house,
+        auction_house_data,
+        token,
+        start_time,
+        end_time,
+        reserve_price,
+        None,
+        None,
+        None,
+        None,
+        None,
+        Some(sealed_bid),
+        None,
+    )
+}
+
+pub fn sell_with_params(
+    context: &mut ProgramTestContext,
+    auction_house: &Pubkey,
+    auction_house_data: &AuctionHouse,
+    token: &NFT,
+    start_time: i64,
+    end_time: i64,
+    reserve_price: Option<u64>,
+    min_bid_increment: Option<u64>,
+    time_ext_period: Option<u32>,
+    time_ext_delta: Option<u32>,
+    dutch_auction: Option<DutchAuctionParams>,
+    buy_now_price: Option<u64>,
+    sealed_bid: Option<SealedBidParams>,
+    bid_increment: Option<BidIncrementParams>,
 ) -> (auctioneer::accounts::AuctioneerSell, Transaction) {
     let (seller_trade_state, seller_trade_state_bump) = find_auctioneer_trade_state_address(
         &token.owner.pubkey(),
@@ -311,6 +660,10 @@ pub fn sell(
         min_bid_increment,
         time_ext_period,
         time_ext_delta,
+        dutch_auction,
+        buy_now_price,
+        sealed_bid,
+        bid_increment,
     };
 
     let accounts = auctioneer::accounts::AuctioneerSell {
@@ -349,14 +702,18 @@ pub fn sell(
     )
 }
 
-pub fn buy(
+pub fn update_listing(
     context: &mut ProgramTestContext,
     auction_house: &Pubkey,
     auction_house_data: &AuctionHouse,
     token: &NFT,
-    buyer: &Keypair,
-    buyer_price: u64, // Bid amount
-) -> (auctioneer::accounts::AuctioneerBuy, Transaction) {
+    start_time: Option<i64>,
+    end_time: Option<i64>,
+    reserve_price: Option<u64>,
+    min_bid_increment: Option<u64>,
+    time_ext_period: Option<u32>,
+    time_ext_delta: Option<u32>,
+) -> (auctioneer::accounts::AuctioneerUpdateListing, Transaction) {
     let (listing_config, _) = find_listing_config_address(
         &token.owner.pubkey(),
         auction_house,
@@ -365,49 +722,22 @@ pub fn buy(
         &token.mint.pubkey(),
         1,
     );
-    let (escrow_payment_account, escrow_payment_account_bump) =
-        find_escrow_payment_account_address(auction_house, &buyer.pubkey());
-    let (auctioneer_authority, auctioneer_authority_bump) =
-        find_auctioneer_authority_address(auction_house);
-    let (auctioneer, _) = find_auctioneer_address(auction_house, &auctioneer_authority);
-    let (buyer_trade_state, buyer_trade_state_bump) = find_trade_state_address(
-        &buyer.pubkey(),
-        auction_house,
-        &token.ata,
-        &auction_house_data.treasury_mint,
-        &token.mint.pubkey(),
-        buyer_price,
-        1,
-    );
 
-    let data = auctioneer::instruction::Buy {
-        trade_state_bump: buyer_trade_state_bump,
-        escrow_payment_bump: escrow_payment_account_bump,
-        auctioneer_authority_bump: auctioneer_authority_bump,
+    let data = auctioneer::instruction::UpdateListing {
         token_size: 1,
-        buyer_price,
+        start_time,
+        end_time,
+        reserve_price,
+        min_bid_increment,
+        time_ext_period,
+        time_ext_delta,
     };
 
-    let accounts = auctioneer::accounts::AuctioneerBuy {
-        auction_house_program: auction_house::id(),
+    let accounts = auctioneer::accounts::AuctioneerUpdateListing {
         listing_config,
-        seller: token.owner.pubkey(),
-        wallet: buyer.pubkey(),
+        wallet: token.owner.pubkey(),
         token_account: token.ata,
-        metadata: token.metadata,
-        authority: auction_house_data.authority,
         auction_house: *auction_house,
-        auction_house_fee_account: auction_house_data.auction_house_fee_account,
-        buyer_trade_state,
-        token_program: spl_token::id(),
-        treasury_mint: auction_house_data.treasury_mint,
-        payment_account: buyer.pubkey(),
-        transfer_authority: buyer.pubkey(),
-        system_program: system_program::id(),
-        rent: sysvar::rent::id(),
-        escrow_payment_account,
-        auctioneer_authority,
-        auctioneer,
     };
 
     let ix = Instruction {
@@ -420,143 +750,176 @@ pub fn buy(
         accounts,
         Transaction::new_signed_with_payer(
             &[ix],
-            Some(&buyer.pubkey()),
-            &[buyer],
+            Some(&token.owner.pubkey()),
+            &[&token.owner],
             context.last_blockhash,
         ),
     )
 }
 
-pub async fn execute_sale(
+// Rewrites a listing config in the layout listings had before V1
+pub async fn set_legacy_listing_config(context: &mut ProgramTestContext, listing_config: &Pubkey) {
+    let account = context
+        .banks_client
+        .get_account(*listing_config)
+        .await
+        .expect("Failed to get listing config")
+        .expect("Listing config not found");
+    let listing_config_data = ListingConfig::try_deserialize(&mut account.data.as_ref()).unwrap();
+
+    let legacy_listing_config = ListingConfigV0 {
+        version: ListingConfigVersion::V0,
+        start_time: listing_config_data.start_time,
+        end_time: listing_config_data.end_time,
+        highest_bid: Bid {
+            version: ListingConfigVersion::V0,
+            ..listing_config_data.highest_bid
+        },
+        bump: listing_config_data.bump,
+        reserve_price: listing_config_data.reserve_price,
+        min_bid_increment: listing_config_data.min_bid_increment,
+        time_ext_period: listing_config_data.time_ext_period,
+        time_ext_delta: listing_config_data.time_ext_delta,
+    };
+    let mut data = ListingConfig::discriminator().to_vec();
+    legacy_listing_config.serialize(&mut data).unwrap();
+    assert_eq!(data.len(), LISTING_CONFIG_V0_SIZE);
+
+    let rent = context.banks_client.get_rent().await.unwrap();
+    context.set_account(
+        listing_config,
+        &AccountSharedData::from(Account {
+            lamports: rent.minimum_balance(data.len()),
+            data,
+            owner: auctioneer::id(),
+            executable: false,
+            rent_epoch: account.rent_epoch,
+        }),
+    );
+}
+
+pub fn migrate_listing_config(
     context: &mut ProgramTestContext,
     auction_house: &Pubkey,
     auction_house_data: &AuctionHouse,
     token: &NFT,
-    metadata_creators: Option<Vec<Creator>>,
-    sell_accounts: &auctioneer::accounts::AuctioneerSell,
-    buy_accounts: &auctioneer::accounts::AuctioneerBuy,
-    highest_bid: u64,
-    signer_payer: &Keypair,
-) -> (auctioneer::accounts::AuctioneerExecuteSale, Transaction) {
-    if signer_payer.pubkey().eq(&auction_house_data.authority) {
-        airdrop(
-            context,
-            &auction_house_data.auction_house_fee_account,
-            10 * ONE_SOL,
-        )
-        .await
-        .expect("Failed to airdrop SOLs to Auction House fee account");
-    }
-
-    let (auctioneer_authority, auctioneer_authority_bump) =
-        find_auctioneer_authority_address(auction_house);
-    let (auctioneer, _) = find_auctioneer_address(auction_house, &auctioneer_authority);
-    let buyer_receipt_token_account =
-        get_associated_token_address(&buy_accounts.wallet, &token.mint.pubkey());
-    let (_, escrow_payment_account_bump) =
-        find_escrow_payment_account_address(&auction_house, &buy_accounts.wallet);
-    let (_, program_as_signer_bump) = find_program_as_signer_address();
-
-    let (_, free_seller_trade_state_bump) = find_trade_state_address(
+    highest_bidder: &Pubkey,
+    payer: &Keypair,
+) -> (
+    auctioneer::accounts::AuctioneerMigrateListingConfig,
+    Transaction,
+) {
+    let (listing_config, _) = find_listing_config_address(
         &token.owner.pubkey(),
         auction_house,
-        &sell_accounts.token_account,
+        &token.ata,
         &auction_house_data.treasury_mint,
         &token.mint.pubkey(),
-        0,
         1,
     );
 
-    let data = auctioneer::instruction::ExecuteSale {
-        escrow_payment_bump: escrow_payment_account_bump,
-        free_trade_state_bump: free_seller_trade_state_bump,
-        program_as_signer_bump,
-        auctioneer_authority_bump: auctioneer_authority_bump,
-        token_size: 1,
-        buyer_price: highest_bid,
-    };
+    let data = auctioneer::instruction::MigrateListingConfig { token_size: 1 };
 
-    let accounts = auctioneer::accounts::AuctioneerExecuteSale {
-        auction_house_program: auction_house::id(),
-        listing_config: sell_accounts.listing_config,
-        buyer: buy_accounts.wallet,
-        seller: sell_accounts.wallet,
-        authority: auction_house_data.authority,
+    let accounts = auctioneer::accounts::AuctioneerMigrateListingConfig {
+        listing_config,
+        seller: token.owner.pubkey(),
+        token_account: token.ata,
         auction_house: *auction_house,
-        metadata: token.metadata,
-        token_account: sell_accounts.token_account,
-        seller_trade_state: sell_accounts.seller_trade_state,
-        buyer_trade_state: buy_accounts.buyer_trade_state,
-        token_program: spl_token::id(),
-        free_trade_state: sell_accounts.free_seller_trade_state,
-        seller_payment_receipt_account: token.owner.pubkey(),
-        buyer_receipt_token_account,
-        escrow_payment_account: buy_accounts.escrow_payment_account,
-        token_mint: token.mint.pubkey(),
-        auction_house_fee_account: auction_house_data.auction_house_fee_account,
-        auction_house_treasury: auction_house_data.auction_house_treasury,
-        treasury_mint: auction_house_data.treasury_mint,
-        program_as_signer: sell_accounts.program_as_signer,
+        highest_bidder: *highest_bidder,
+        payer: payer.pubkey(),
         system_program: system_program::id(),
-        associated_token_program: anchor_spl::associated_token::ID,
-        rent: sysvar::rent::id(),
-        auctioneer_authority,
-        auctioneer,
     };
 
-    let mut account_metas = accounts.to_account_metas(None);
-    if let Some(creators) = metadata_creators {
-        for creator in &creators {
-            account_metas.push(AccountMeta {
-                pubkey: creator.address,
-                is_signer: false,
-                is_writable: true,
-            });
-        }
-    }
-
     let ix = Instruction {
         program_id: auctioneer::id(),
         data: data.data(),
-        accounts: account_metas,
+        accounts: accounts.to_account_metas(None),
     };
 
     (
         accounts,
         Transaction::new_signed_with_payer(
             &[ix],
-            Some(&signer_payer.pubkey()),
-            &[signer_payer],
+            Some(&payer.pubkey()),
+            &[payer],
             context.last_blockhash,
         ),
     )
 }
 
-pub fn deposit(
+pub async fn get_highest_bidder(
+    context: &mut ProgramTestContext,
+    listing_config: &Pubkey,
+) -> Pubkey {
+    match context
+        .banks_client
+        .get_account(*listing_config)
+        .await
+        .expect("Failed to get listing config")
+    {
+        Some(account) => {
+            ListingConfig::try_deserialize(&mut account.data.as_ref())
+                .unwrap()
+                .highest_bidder
+        }
+        None => Pubkey::default(),
+    }
+}
+
+pub async fn buy(
     context: &mut ProgramTestContext,
     auction_house: &Pubkey,
     auction_house_data: &AuctionHouse,
+    token: &NFT,
     buyer: &Keypair,
-    amount: u64,
-) -> (auctioneer::accounts::AuctioneerDeposit, Transaction) {
+    buyer_price: u64, // Bid amount
+) -> (auctioneer::accounts::AuctioneerBuy, Transaction) {
+    let (listing_config, _) = find_listing_config_address(
+        &token.owner.pubkey(),
+        auction_house,
+        &token.ata,
+        &auction_house_data.treasury_mint,
+        &token.mint.pubkey(),
+        1,
+    );
     let (escrow_payment_account, escrow_payment_account_bump) =
         find_escrow_payment_account_address(auction_house, &buyer.pubkey());
+    let (escrow_ledger, _) = find_escrow_ledger_address(auction_house, &buyer.pubkey());
+    let highest_bidder = get_highest_bidder(context, &listing_config).await;
+    let (highest_bidder_escrow_ledger, _) =
+        find_escrow_ledger_address(auction_house, &highest_bidder);
     let (auctioneer_authority, auctioneer_authority_bump) =
         find_auctioneer_authority_address(auction_house);
     let (auctioneer, _) = find_auctioneer_address(auction_house, &auctioneer_authority);
+    let (buyer_trade_state, buyer_trade_state_bump) = find_trade_state_address(
+        &buyer.pubkey(),
+        auction_house,
+        &token.ata,
+        &auction_house_data.treasury_mint,
+        &token.mint.pubkey(),
+        buyer_price,
+        1,
+    );
 
-    let data = auctioneer::instruction::Deposit {
-        amount,
+    let data = auctioneer::instruction::Buy {
+        trade_state_bump: buyer_trade_state_bump,
         escrow_payment_bump: escrow_payment_account_bump,
         auctioneer_authority_bump: auctioneer_authority_bump,
+        token_size: 1,
+        buyer_price,
     };
 
-    let accounts = auctioneer::accounts::AuctioneerDeposit {
+    let accounts = auctioneer::accounts::AuctioneerBuy {
         auction_house_program: auction_house::id(),
+        listing_config,
+        seller: token.owner.pubkey(),
         wallet: buyer.pubkey(),
+        token_account: token.ata,
+        metadata: token.metadata,
         authority: auction_house_data.authority,
         auction_house: *auction_house,
         auction_house_fee_account: auction_house_data.auction_house_fee_account,
+        buyer_trade_state,
         token_program: spl_token::id(),
         treasury_mint: auction_house_data.treasury_mint,
         payment_account: buyer.pubkey(),
@@ -564,6 +927,8 @@ pub fn deposit(
         system_program: system_program::id(),
         rent: sysvar::rent::id(),
         escrow_payment_account,
+        escrow_ledger,
+        highest_bidder_escrow_ledger,
         auctioneer_authority,
         auctioneer,
     };
@@ -585,40 +950,52 @@ pub fn deposit(
     )
 }
 
-pub fn withdraw(
+pub async fn proxy_bid(
     context: &mut ProgramTestContext,
     auction_house: &Pubkey,
     auction_house_data: &AuctionHouse,
+    token: &NFT,
     buyer: &Keypair,
-    amount: u64,
-) -> (auctioneer::accounts::AuctioneerWithdraw, Transaction) {
+    max_amount: u64,
+) -> (auctioneer::accounts::AuctioneerProxyBid, Transaction) {
+    let (listing_config, _) = find_listing_config_address(
+        &token.owner.pubkey(),
+        auction_house,
+        &token.ata,
+        &auction_house_data.treasury_mint,
+        &token.mint.pubkey(),
+        1,
+    );
     let (escrow_payment_account, escrow_payment_account_bump) =
         find_escrow_payment_account_address(auction_house, &buyer.pubkey());
-    let (auctioneer_authority, auctioneer_authority_bump) =
-        find_auctioneer_authority_address(auction_house);
+    let (escrow_ledger, _) = find_escrow_ledger_address(auction_house, &buyer.pubkey());
+    let highest_bidder = get_highest_bidder(context, &listing_config).await;
+    let (highest_bidder_escrow_ledger, _) =
+        find_escrow_ledger_address(auction_house, &highest_bidder);
+    let (auctioneer_authority, _) = find_auctioneer_authority_address(auction_house);
     let (auctioneer, _) = find_auctioneer_address(auction_house, &auctioneer_authority);
 
-    let data = auctioneer::instruction::Withdraw {
+    let data = auctioneer::instruction::ProxyBid {
         escrow_payment_bump: escrow_payment_account_bump,
-        auctioneer_authority_bump,
-        amount,
+        token_size: 1,
+        max_amount,
     };
 
-    let accounts = auctioneer::accounts::AuctioneerWithdraw {
+    let accounts = auctioneer::accounts::AuctioneerProxyBid {
         auction_house_program: auction_house::id(),
+        listing_config,
+        seller: token.owner.pubkey(),
         wallet: buyer.pubkey(),
+        token_account: token.ata,
         escrow_payment_account,
-        receipt_account: buyer.pubkey(),
+        escrow_ledger,
+        highest_bidder_escrow_ledger,
         treasury_mint: auction_house_data.treasury_mint,
-        authority: auction_house_data.authority,
         auction_house: *auction_house,
-        auction_house_fee_account: auction_house_data.auction_house_fee_account,
-        token_program: spl_token::id(),
-        system_program: system_program::id(),
-        associated_token_program: associated_token::ID,
-        rent: sysvar::rent::id(),
         auctioneer_authority,
         auctioneer,
+        system_program: system_program::id(),
+        rent: sysvar::rent::id(),
     };
 
     let ix = Instruction {
@@ -638,15 +1015,15 @@ pub fn withdraw(
     )
 }
 
-fn cancel(
+pub fn promote_runner_up(
     context: &mut ProgramTestContext,
     auction_house: &Pubkey,
     auction_house_data: &AuctionHouse,
     token: &NFT,
-    wallet: &Keypair,
-    trade_state: &Pubkey,
-    buyer_price: u64,
-) -> (auctioneer::accounts::AuctioneerCancel, Transaction) {
+    winner: &Pubkey,
+    runner_ups: &[Pubkey],
+    payer: &Keypair,
+) -> (auctioneer::accounts::AuctioneerPromoteRunnerUp, Transaction) {
     let (listing_config, _) = find_listing_config_address(
         &token.owner.pubkey(),
         auction_house,
@@ -655,108 +1032,1079 @@ fn cancel(
         &token.mint.pubkey(),
         1,
     );
-    let (auctioneer_authority, auctioneer_authority_bump) =
-        find_auctioneer_authority_address(auction_house);
+    let (winner_escrow_payment_account, _) =
+        find_escrow_payment_account_address(auction_house, winner);
+    let (winner_escrow_ledger, _) = find_escrow_ledger_address(auction_house, winner);
+    let (auctioneer_authority, _) = find_auctioneer_authority_address(auction_house);
     let (auctioneer, _) = find_auctioneer_address(auction_house, &auctioneer_authority);
 
-    let data = auctioneer::instruction::Cancel {
-        auctioneer_authority_bump,
-        buyer_price,
-        token_size: 1,
-    };
+    let data = auctioneer::instruction::PromoteRunnerUp { token_size: 1 };
 
-    let accounts = auctioneer::accounts::AuctioneerCancel {
+    let accounts = auctioneer::accounts::AuctioneerPromoteRunnerUp {
         auction_house_program: auction_house::id(),
         listing_config,
         seller: token.owner.pubkey(),
-        wallet: wallet.pubkey(),
         token_account: token.ata,
-        token_mint: token.mint.pubkey(),
-        authority: auction_house_data.authority,
+        winner_escrow_payment_account,
+        winner_escrow_ledger,
+        treasury_mint: auction_house_data.treasury_mint,
         auction_house: *auction_house,
-        auction_house_fee_account: auction_house_data.auction_house_fee_account,
-        trade_state: *trade_state,
         auctioneer_authority,
         auctioneer,
-        token_program: spl_token::id(),
     };
 
+    let mut account_metas = accounts.to_account_metas(None);
+    for runner_up in runner_ups {
+        let (escrow_payment_account, _) =
+            find_escrow_payment_account_address(auction_house, runner_up);
+        account_metas.push(AccountMeta::new_readonly(escrow_payment_account, false));
+    }
+
     let ix = Instruction {
         program_id: auctioneer::id(),
         data: data.data(),
-        accounts: accounts.to_account_metas(None),
+        accounts: account_metas,
     };
 
     (
         accounts,
         Transaction::new_signed_with_payer(
             &[ix],
-            Some(&wallet.pubkey()),
-            &[wallet],
+            Some(&payer.pubkey()),
+            &[payer],
             context.last_blockhash,
         ),
     )
 }
 
-pub fn cancel_sell(
-    context: &mut ProgramTestContext,
-    auction_house: &Pubkey,
-    auction_house_data: &AuctionHouse,
-    token: &NFT,
-    seller_trade_state: &Pubkey,
-) -> (auctioneer::accounts::AuctioneerCancel, Transaction) {
-    cancel(
-        context,
-        auction_house,
-        auction_house_data,
-        token,
-        &token.owner,
-        seller_trade_state,
-        u64::MAX,
-    )
+pub async fn warp_to_timestamp(context: &mut ProgramTestContext, unix_timestamp: i64) {
+    let mut clock: Clock = context
+        .banks_client
+        .get_sysvar()
+        .await
+        .expect("Failed to get clock");
+    clock.unix_timestamp = unix_timestamp;
+    context.set_sysvar(&clock);
 }
 
-pub fn cancel_buy(
+pub fn commit_bid(
     context: &mut ProgramTestContext,
     auction_house: &Pubkey,
     auction_house_data: &AuctionHouse,
     token: &NFT,
     buyer: &Keypair,
-    buyer_trade_state: &Pubkey,
-    buyer_price: u64, // Bid amount
-) -> (auctioneer::accounts::AuctioneerCancel, Transaction) {
-    cancel(
-        context,
+    amount: u64, // Sealed bid amount
+    salt: [u8; 32],
+    deposit_amount: u64,
+) -> (auctioneer::accounts::AuctioneerCommitBid, Transaction) {
+    let (listing_config, _) = find_listing_config_address(
+        &token.owner.pubkey(),
         auction_house,
-        auction_house_data,
-        token,
-        buyer,
-        buyer_trade_state,
-        buyer_price,
-    )
-}
-
-pub fn close_escrow_account(
-    context: &mut ProgramTestContext,
-    auction_house: &Pubkey,
-    buyer: &Keypair,
-) -> (auction_house::accounts::CloseEscrowAccount, Transaction) {
+        &token.ata,
+        &auction_house_data.treasury_mint,
+        &token.mint.pubkey(),
+        1,
+    );
+    let (bid_commitment, _) = find_bid_commitment_address(&listing_config, &buyer.pubkey());
     let (escrow_payment_account, escrow_payment_account_bump) =
         find_escrow_payment_account_address(auction_house, &buyer.pubkey());
+    let (escrow_ledger, _) = find_escrow_ledger_address(auction_house, &buyer.pubkey());
+    let (auctioneer_authority, auctioneer_authority_bump) =
+        find_auctioneer_authority_address(auction_house);
+    let (auctioneer, _) = find_auctioneer_address(auction_house, &auctioneer_authority);
 
-    let data = auction_house::instruction::CloseEscrowAccount {
+    let data = auctioneer::instruction::CommitBid {
         escrow_payment_bump: escrow_payment_account_bump,
+        auctioneer_authority_bump,
+        token_size: 1,
+        commitment: compute_bid_commitment(amount, &salt, &buyer.pubkey()),
+        deposit_amount,
     };
 
-    let accounts = auction_house::accounts::CloseEscrowAccount {
+    let accounts = auctioneer::accounts::AuctioneerCommitBid {
+        auction_house_program: auction_house::id(),
+        listing_config,
+        seller: token.owner.pubkey(),
+        bid_commitment,
         wallet: buyer.pubkey(),
-        escrow_payment_account,
+        payment_account: buyer.pubkey(),
+        transfer_authority: buyer.pubkey(),
+        token_account: token.ata,
+        escrow_payment_account,
+        escrow_ledger,
+        treasury_mint: auction_house_data.treasury_mint,
+        authority: auction_house_data.authority,
+        auction_house: *auction_house,
+        auction_house_fee_account: auction_house_data.auction_house_fee_account,
+        auctioneer_authority,
+        auctioneer,
+        token_program: spl_token::id(),
+        system_program: system_program::id(),
+        rent: sysvar::rent::id(),
+    };
+
+    let ix = Instruction {
+        program_id: auctioneer::id(),
+        data: data.data(),
+        accounts: accounts.to_account_metas(None),
+    };
+
+    (
+        accounts,
+        Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&buyer.pubkey()),
+            &[buyer],
+            context.last_blockhash,
+        ),
+    )
+}
+
+pub async fn reveal_bid(
+    context: &mut ProgramTestContext,
+    auction_house: &Pubkey,
+    auction_house_data: &AuctionHouse,
+    token: &NFT,
+    buyer: &Keypair,
+    amount: u64,
+    salt: [u8; 32],
+) -> (auctioneer::accounts::AuctioneerRevealBid, Transaction) {
+    let (listing_config, _) = find_listing_config_address(
+        &token.owner.pubkey(),
+        auction_house,
+        &token.ata,
+        &auction_house_data.treasury_mint,
+        &token.mint.pubkey(),
+        1,
+    );
+    let (bid_commitment, _) = find_bid_commitment_address(&listing_config, &buyer.pubkey());
+    let (escrow_ledger, _) = find_escrow_ledger_address(auction_house, &buyer.pubkey());
+    let highest_bidder = get_highest_bidder(context, &listing_config).await;
+    let (highest_bidder_escrow_ledger, _) =
+        find_escrow_ledger_address(auction_house, &highest_bidder);
+    let (auctioneer_authority, auctioneer_authority_bump) =
+        find_auctioneer_authority_address(auction_house);
+    let (auctioneer, _) = find_auctioneer_address(auction_house, &auctioneer_authority);
+
+    let data = auctioneer::instruction::RevealBid {
+        auctioneer_authority_bump,
+        token_size: 1,
+        amount,
+        salt,
+    };
+
+    let accounts = auctioneer::accounts::AuctioneerRevealBid {
+        auction_house_program: auction_house::id(),
+        listing_config,
+        seller: token.owner.pubkey(),
+        bid_commitment,
+        wallet: buyer.pubkey(),
+        token_account: token.ata,
+        escrow_ledger,
+        highest_bidder_escrow_ledger,
+        auction_house: *auction_house,
+        auctioneer_authority,
+        auctioneer,
+    };
+
+    let ix = Instruction {
+        program_id: auctioneer::id(),
+        data: data.data(),
+        accounts: accounts.to_account_metas(None),
+    };
+
+    (
+        accounts,
+        Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&buyer.pubkey()),
+            &[buyer],
+            context.last_blockhash,
+        ),
+    )
+}
+
+pub fn cancel_bid_commitment(
+    context: &mut ProgramTestContext,
+    auction_house: &Pubkey,
+    auction_house_data: &AuctionHouse,
+    token: &NFT,
+    buyer: &Keypair,
+) -> (
+    auctioneer::accounts::AuctioneerCancelBidCommitment,
+    Transaction,
+) {
+    let (listing_config, _) = find_listing_config_address(
+        &token.owner.pubkey(),
+        auction_house,
+        &token.ata,
+        &auction_house_data.treasury_mint,
+        &token.mint.pubkey(),
+        1,
+    );
+    let (bid_commitment, _) = find_bid_commitment_address(&listing_config, &buyer.pubkey());
+    let (escrow_ledger, _) = find_escrow_ledger_address(auction_house, &buyer.pubkey());
+    let (auctioneer_authority, auctioneer_authority_bump) =
+        find_auctioneer_authority_address(auction_house);
+    let (auctioneer, _) = find_auctioneer_address(auction_house, &auctioneer_authority);
+
+    let data = auctioneer::instruction::CancelBidCommitment {
+        auctioneer_authority_bump,
+    };
+
+    let accounts = auctioneer::accounts::AuctioneerCancelBidCommitment {
+        auction_house_program: auction_house::id(),
+        listing_config,
+        bid_commitment,
+        wallet: buyer.pubkey(),
+        escrow_ledger,
+        auction_house: *auction_house,
+        auctioneer_authority,
+        auctioneer,
+    };
+
+    let ix = Instruction {
+        program_id: auctioneer::id(),
+        data: data.data(),
+        accounts: accounts.to_account_metas(None),
+    };
+
+    (
+        accounts,
+        Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&buyer.pubkey()),
+            &[buyer],
+            context.last_blockhash,
+        ),
+    )
+}
+
+// Settling uses the same accounts as a buy, they are returned as such so they can be
+// passed to execute_sale.
+pub fn settle_sealed_bid(
+    context: &mut ProgramTestContext,
+    auction_house: &Pubkey,
+    auction_house_data: &AuctionHouse,
+    token: &NFT,
+    buyer: &Pubkey,
+    payer: &Keypair,
+    buyer_price: u64, // Settlement price
+) -> (auctioneer::accounts::AuctioneerBuy, Transaction) {
+    let (listing_config, _) = find_listing_config_address(
+        &token.owner.pubkey(),
+        auction_house,
+        &token.ata,
+        &auction_house_data.treasury_mint,
+        &token.mint.pubkey(),
+        1,
+    );
+    let (escrow_payment_account, _) = find_escrow_payment_account_address(auction_house, buyer);
+    let (auctioneer_authority, auctioneer_authority_bump) =
+        find_auctioneer_authority_address(auction_house);
+    let (auctioneer, _) = find_auctioneer_address(auction_house, &auctioneer_authority);
+    let (buyer_trade_state, buyer_trade_state_bump) = find_trade_state_address(
+        buyer,
+        auction_house,
+        &token.ata,
+        &auction_house_data.treasury_mint,
+        &token.mint.pubkey(),
+        buyer_price,
+        1,
+    );
+
+    let (escrow_ledger, _) = find_escrow_ledger_address(auction_house, buyer);
+
+    let data = auctioneer::instruction::SettleSealedBid {
+        trade_state_bump: buyer_trade_state_bump,
+        auctioneer_authority_bump,
+        token_size: 1,
+    };
+
+    let accounts = auctioneer::accounts::AuctioneerSettleSealedBid {
+        auction_house_program: auction_house::id(),
+        listing_config,
+        seller: token.owner.pubkey(),
+        wallet: *buyer,
+        token_account: token.ata,
+        metadata: token.metadata,
+        escrow_ledger,
+        auction_house: *auction_house,
+        buyer_trade_state,
+        auctioneer_authority,
+        auctioneer,
+        payer: payer.pubkey(),
+        system_program: system_program::id(),
+        rent: sysvar::rent::id(),
+    };
+
+    let ix = Instruction {
+        program_id: auctioneer::id(),
+        data: data.data(),
+        accounts: accounts.to_account_metas(None),
+    };
+
+    (
+        auctioneer::accounts::AuctioneerBuy {
+            auction_house_program: accounts.auction_house_program,
+            listing_config: accounts.listing_config,
+            seller: accounts.seller,
+            wallet: accounts.wallet,
+            payment_account: accounts.wallet,
+            transfer_authority: accounts.wallet,
+            treasury_mint: auction_house_data.treasury_mint,
+            token_account: accounts.token_account,
+            metadata: accounts.metadata,
+            escrow_payment_account,
+            escrow_ledger: accounts.escrow_ledger,
+            highest_bidder_escrow_ledger: accounts.escrow_ledger,
+            authority: auction_house_data.authority,
+            auction_house: accounts.auction_house,
+            auction_house_fee_account: auction_house_data.auction_house_fee_account,
+            buyer_trade_state: accounts.buyer_trade_state,
+            auctioneer_authority: accounts.auctioneer_authority,
+            auctioneer: accounts.auctioneer,
+            token_program: spl_token::id(),
+            system_program: accounts.system_program,
+            rent: accounts.rent,
+        },
+        Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&payer.pubkey()),
+            &[payer],
+            context.last_blockhash,
+        ),
+    )
+}
+
+pub fn settle_proxy_bid(
+    context: &mut ProgramTestContext,
+    auction_house: &Pubkey,
+    auction_house_data: &AuctionHouse,
+    token: &NFT,
+    buyer: &Pubkey,
+    payer: &Keypair,
+    buyer_price: u64, // Settlement price
+) -> (auctioneer::accounts::AuctioneerBuy, Transaction) {
+    let (listing_config, _) = find_listing_config_address(
+        &token.owner.pubkey(),
+        auction_house,
+        &token.ata,
+        &auction_house_data.treasury_mint,
+        &token.mint.pubkey(),
+        1,
+    );
+    let (escrow_payment_account, _) = find_escrow_payment_account_address(auction_house, buyer);
+    let (auctioneer_authority, auctioneer_authority_bump) =
+        find_auctioneer_authority_address(auction_house);
+    let (auctioneer, _) = find_auctioneer_address(auction_house, &auctioneer_authority);
+    let (buyer_trade_state, buyer_trade_state_bump) = find_trade_state_address(
+        buyer,
+        auction_house,
+        &token.ata,
+        &auction_house_data.treasury_mint,
+        &token.mint.pubkey(),
+        buyer_price,
+        1,
+    );
+
+    let (escrow_ledger, _) = find_escrow_ledger_address(auction_house, buyer);
+
+    let data = auctioneer::instruction::SettleProxyBid {
+        trade_state_bump: buyer_trade_state_bump,
+        auctioneer_authority_bump,
+        token_size: 1,
+    };
+
+    let accounts = auctioneer::accounts::AuctioneerSettleProxyBid {
+        auction_house_program: auction_house::id(),
+        listing_config,
+        seller: token.owner.pubkey(),
+        wallet: *buyer,
+        token_account: token.ata,
+        metadata: token.metadata,
+        escrow_ledger,
+        auction_house: *auction_house,
+        buyer_trade_state,
+        auctioneer_authority,
+        auctioneer,
+        payer: payer.pubkey(),
+        system_program: system_program::id(),
+        rent: sysvar::rent::id(),
+    };
+
+    let ix = Instruction {
+        program_id: auctioneer::id(),
+        data: data.data(),
+        accounts: accounts.to_account_metas(None),
+    };
+
+    (
+        auctioneer::accounts::AuctioneerBuy {
+            auction_house_program: accounts.auction_house_program,
+            listing_config: accounts.listing_config,
+            seller: accounts.seller,
+            wallet: accounts.wallet,
+            payment_account: accounts.wallet,
+            transfer_authority: accounts.wallet,
+            treasury_mint: auction_house_data.treasury_mint,
+            token_account: accounts.token_account,
+            metadata: accounts.metadata,
+            escrow_payment_account,
+            escrow_ledger: accounts.escrow_ledger,
+            highest_bidder_escrow_ledger: accounts.escrow_ledger,
+            authority: auction_house_data.authority,
+            auction_house: accounts.auction_house,
+            auction_house_fee_account: auction_house_data.auction_house_fee_account,
+            buyer_trade_state: accounts.buyer_trade_state,
+            auctioneer_authority: accounts.auctioneer_authority,
+            auctioneer: accounts.auctioneer,
+            token_program: spl_token::id(),
+            system_program: accounts.system_program,
+            rent: accounts.rent,
+        },
+        Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&payer.pubkey()),
+            &[payer],
+            context.last_blockhash,
+        ),
+    )
+}
+
+pub async fn execute_sale(
+    context: &mut ProgramTestContext,
+    auction_house: &Pubkey,
+    auction_house_data: &AuctionHouse,
+    token: &NFT,
+    metadata_creators: Option<Vec<Creator>>,
+    sell_accounts: &auctioneer::accounts::AuctioneerSell,
+    buy_accounts: &auctioneer::accounts::AuctioneerBuy,
+    highest_bid: u64,
+    signer_payer: &Keypair,
+) -> (auctioneer::accounts::AuctioneerExecuteSale, Transaction) {
+    if signer_payer.pubkey().eq(&auction_house_data.authority) {
+        airdrop(
+            context,
+            &auction_house_data.auction_house_fee_account,
+            10 * ONE_SOL,
+        )
+        .await
+        .expect("Failed to airdrop SOLs to Auction House fee account");
+    }
+
+    let (auctioneer_authority, auctioneer_authority_bump) =
+        find_auctioneer_authority_address(auction_house);
+    let (auctioneer, _) = find_auctioneer_address(auction_house, &auctioneer_authority);
+    let buyer_receipt_token_account =
+        get_associated_token_address(&buy_accounts.wallet, &token.mint.pubkey());
+    let (_, escrow_payment_account_bump) =
+        find_escrow_payment_account_address(&auction_house, &buy_accounts.wallet);
+    let (_, program_as_signer_bump) = find_program_as_signer_address();
+
+    let (_, free_seller_trade_state_bump) = find_trade_state_address(
+        &token.owner.pubkey(),
+        auction_house,
+        &sell_accounts.token_account,
+        &auction_house_data.treasury_mint,
+        &token.mint.pubkey(),
+        0,
+        1,
+    );
+
+    let data = auctioneer::instruction::ExecuteSale {
+        escrow_payment_bump: escrow_payment_account_bump,
+        free_trade_state_bump: free_seller_trade_state_bump,
+        program_as_signer_bump,
+        auctioneer_authority_bump: auctioneer_authority_bump,
+        token_size: 1,
+        buyer_price: highest_bid,
+    };
+
+    let (auction_result, _) = find_auction_result_address(
+        &sell_accounts.listing_config,
+        &buy_accounts.buyer_trade_state,
+    );
+    let (escrow_ledger, _) = find_escrow_ledger_address(auction_house, &buy_accounts.wallet);
+
+    let accounts = auctioneer::accounts::AuctioneerExecuteSale {
+        auction_house_program: auction_house::id(),
+        listing_config: sell_accounts.listing_config,
+        auction_result,
+        buyer: buy_accounts.wallet,
+        seller: sell_accounts.wallet,
+        authority: auction_house_data.authority,
+        auction_house: *auction_house,
+        metadata: token.metadata,
+        token_account: sell_accounts.token_account,
+        seller_trade_state: sell_accounts.seller_trade_state,
+        buyer_trade_state: buy_accounts.buyer_trade_state,
+        token_program: spl_token::id(),
+        treasury_token_program: spl_token::id(),
+        free_trade_state: sell_accounts.free_seller_trade_state,
+        proceeds_split: find_proceeds_split_address(&sell_accounts.seller_trade_state).0,
+        referral: find_referral_address(&sell_accounts.seller_trade_state).0,
+        seller_payment_receipt_account: token.owner.pubkey(),
+        buyer_receipt_token_account,
+        escrow_payment_account: buy_accounts.escrow_payment_account,
+        escrow_ledger,
+        token_mint: token.mint.pubkey(),
+        auction_house_fee_account: auction_house_data.auction_house_fee_account,
+        auction_house_treasury: auction_house_data.auction_house_treasury,
+        treasury_mint: auction_house_data.treasury_mint,
+        program_as_signer: sell_accounts.program_as_signer,
+        system_program: system_program::id(),
+        associated_token_program: anchor_spl::associated_token::ID,
+        rent: sysvar::rent::id(),
+        auctioneer_authority,
+        auctioneer,
+        cranker: Some(signer_payer.pubkey()),
+        referrer: None,
+        referrer_token_account: None,
+        auctioneer_config: find_auctioneer_config_address(auction_house).0,
+        auctioneer_fee_destination: auction_house_data.authority,
+    };
+
+    let mut account_metas = accounts.to_account_metas(None);
+    if let Some(creators) = metadata_creators {
+        for creator in &creators {
+            account_metas.push(AccountMeta {
+                pubkey: creator.address,
+                is_signer: false,
+                is_writable: true,
+            });
+        }
+    }
+
+    let ix = Instruction {
+        program_id: auctioneer::id(),
+        data: data.data(),
+        accounts: account_metas,
+    };
+
+    (
+        accounts,
+        Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&signer_payer.pubkey()),
+            &[signer_payer],
+            context.last_blockhash,
+        ),
+    )
+}
+
+pub fn deposit(
+    context: &mut ProgramTestContext,
+    auction_house: &Pubkey,
+    auction_house_data: &AuctionHouse,
+    buyer: &Keypair,
+    amount: u64,
+) -> (auctioneer::accounts::AuctioneerDeposit, Transaction) {
+    let (escrow_payment_account, escrow_payment_account_bump) =
+        find_escrow_payment_account_address(auction_house, &buyer.pubkey());
+    let (auctioneer_authority, auctioneer_authority_bump) =
+        find_auctioneer_authority_address(auction_house);
+    let (auctioneer, _) = find_auctioneer_address(auction_house, &auctioneer_authority);
+
+    let data = auctioneer::instruction::Deposit {
+        amount,
+        escrow_payment_bump: escrow_payment_account_bump,
+        auctioneer_authority_bump: auctioneer_authority_bump,
+    };
+
+    let accounts = auctioneer::accounts::AuctioneerDeposit {
+        auction_house_program: auction_house::id(),
+        wallet: buyer.pubkey(),
+        authority: auction_house_data.authority,
+        auction_house: *auction_house,
+        auction_house_fee_account: auction_house_data.auction_house_fee_account,
+        token_program: spl_token::id(),
+        treasury_mint: auction_house_data.treasury_mint,
+        payment_account: buyer.pubkey(),
+        transfer_authority: buyer.pubkey(),
+        system_program: system_program::id(),
+        rent: sysvar::rent::id(),
+        escrow_payment_account,
+        auctioneer_authority,
+        auctioneer,
+    };
+
+    let ix = Instruction {
+        program_id: auctioneer::id(),
+        data: data.data(),
+        accounts: accounts.to_account_metas(None),
+    };
+
+    (
+        accounts,
+        Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&buyer.pubkey()),
+            &[buyer],
+            context.last_blockhash,
+        ),
+    )
+}
+
+pub fn withdraw(
+    context: &mut ProgramTestContext,
+    auction_house: &Pubkey,
+    auction_house_data: &AuctionHouse,
+    buyer: &Keypair,
+    amount: u64,
+) -> (auctioneer::accounts::AuctioneerWithdraw, Transaction) {
+    let (escrow_payment_account, escrow_payment_account_bump) =
+        find_escrow_payment_account_address(auction_house, &buyer.pubkey());
+    let (auctioneer_authority, auctioneer_authority_bump) =
+        find_auctioneer_authority_address(auction_house);
+    let (auctioneer, _) = find_auctioneer_address(auction_house, &auctioneer_authority);
+
+    let (escrow_ledger, _) = find_escrow_ledger_address(auction_house, &buyer.pubkey());
+
+    let data = auctioneer::instruction::Withdraw {
+        escrow_payment_bump: escrow_payment_account_bump,
+        auctioneer_authority_bump,
+        amount,
+    };
+
+    let accounts = auctioneer::accounts::AuctioneerWithdraw {
+        auction_house_program: auction_house::id(),
+        wallet: buyer.pubkey(),
+        escrow_payment_account,
+        escrow_ledger,
+        receipt_account: buyer.pubkey(),
+        treasury_mint: auction_house_data.treasury_mint,
+        authority: auction_house_data.authority,
+        auction_house: *auction_house,
+        auction_house_fee_account: auction_house_data.auction_house_fee_account,
+        token_program: spl_token::id(),
+        system_program: system_program::id(),
+        associated_token_program: associated_token::ID,
+        rent: sysvar::rent::id(),
+        auctioneer_authority,
+        auctioneer,
+    };
+
+    let ix = Instruction {
+        program_id: auctioneer::id(),
+        data: data.data(),
+        accounts: accounts.to_account_metas(None),
+    };
+
+    (
+        accounts,
+        Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&buyer.pubkey()),
+            &[buyer],
+            context.last_blockhash,
+        ),
+    )
+}
+
+async fn cancel(
+    context: &mut ProgramTestContext,
+    auction_house: &Pubkey,
+    auction_house_data: &AuctionHouse,
+    token: &NFT,
+    wallet: &Keypair,
+    trade_state: &Pubkey,
+    buyer_price: u64,
+) -> (auctioneer::accounts::AuctioneerCancel, Transaction) {
+    let (listing_config, _) = find_listing_config_address(
+        &token.owner.pubkey(),
+        auction_house,
+        &token.ata,
+        &auction_house_data.treasury_mint,
+        &token.mint.pubkey(),
+        1,
+    );
+    let (escrow_ledger, _) = find_escrow_ledger_address(auction_house, &wallet.pubkey());
+    let highest_bidder = get_highest_bidder(context, &listing_config).await;
+    let (highest_bidder_escrow_ledger, _) =
+        find_escrow_ledger_address(auction_house, &highest_bidder);
+    let (auctioneer_authority, auctioneer_authority_bump) =
+        find_auctioneer_authority_address(auction_house);
+    let (auctioneer, _) = find_auctioneer_address(auction_house, &auctioneer_authority);
+
+    let data = auctioneer::instruction::Cancel {
+        auctioneer_authority_bump,
+        buyer_price,
+        token_size: 1,
+    };
+
+    let accounts = auctioneer::accounts::AuctioneerCancel {
+        auction_house_program: auction_house::id(),
+        listing_config,
+        seller: token.owner.pubkey(),
+        wallet: wallet.pubkey(),
+        token_account: token.ata,
+        token_mint: token.mint.pubkey(),
+        authority: auction_house_data.authority,
+        auction_house: *auction_house,
+        auction_house_fee_account: auction_house_data.auction_house_fee_account,
+        trade_state: *trade_state,
+        proceeds_split: find_proceeds_split_address(trade_state).0,
+        referral: find_referral_address(trade_state).0,
+        escrow_ledger,
+        highest_bidder_escrow_ledger,
+        auctioneer_authority,
+        auctioneer,
+        token_program: spl_token::id(),
+    };
+
+    let ix = Instruction {
+        program_id: auctioneer::id(),
+        data: data.data(),
+        accounts: accounts.to_account_metas(None),
+    };
+
+    (
+        accounts,
+        Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&wallet.pubkey()),
+            &[wallet],
+            context.last_blockhash,
+        ),
+    )
+}
+
+pub async fn cancel_sell(
+    context: &mut ProgramTestContext,
+    auction_house: &Pubkey,
+    auction_house_data: &AuctionHouse,
+    token: &NFT,
+    seller_trade_state: &Pubkey,
+) -> (auctioneer::accounts::AuctioneerCancel, Transaction) {
+    cancel(
+        context,
+        auction_house,
+        auction_house_data,
+        token,
+        &token.owner,
+        seller_trade_state,
+        u64::MAX,
+    )
+    .await
+}
+
+pub async fn cancel_buy(
+    context: &mut ProgramTestContext,
+    auction_house: &Pubkey,
+    auction_house_data: &AuctionHouse,
+    token: &NFT,
+    buyer: &Keypair,
+    buyer_trade_state: &Pubkey,
+    buyer_price: u64, // Bid amount
+) -> (auctioneer::accounts::AuctioneerCancel, Transaction) {
+    cancel(
+        context,
+        auction_house,
+        auction_house_data,
+        token,
+        buyer,
+        buyer_trade_state,
+        buyer_price,
+    )
+    .await
+}
+
+pub fn close_auction_result(
+    context: &mut ProgramTestContext,
+    listing_config: &Pubkey,
+    buyer_trade_state: &Pubkey,
+    seller: &Keypair,
+) -> (
+    auctioneer::accounts::AuctioneerCloseAuctionResult,
+    Transaction,
+) {
+    let (auction_result, _) = find_auction_result_address(listing_config, buyer_trade_state);
+
+    let data = auctioneer::instruction::CloseAuctionResult {};
+
+    let accounts = auctioneer::accounts::AuctioneerCloseAuctionResult {
+        auction_result,
+        seller: seller.pubkey(),
+    };
+
+    let ix = Instruction {
+        program_id: auctioneer::id(),
+        data: data.data(),
+        accounts: accounts.to_account_metas(None),
+    };
+
+    (
+        accounts,
+        Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&seller.pubkey()),
+            &[seller],
+            context.last_blockhash,
+        ),
+    )
+}
+
+pub fn close_escrow_account(
+    context: &mut ProgramTestContext,
+    auction_house: &Pubkey,
+    auction_house_data: &AuctionHouse,
+    buyer: &Keypair,
+) -> (auction_house::accounts::CloseEscrowAccount, Transaction) {
+    let (escrow_payment_account, escrow_payment_account_bump) =
+        find_escrow_payment_account_address(auction_house, &buyer.pubkey());
+
+    let (escrow_ledger, _) = find_escrow_ledger_address(auction_house, &buyer.pubkey());
+
+    let data = auction_house::instruction::CloseEscrowAccount {
+        escrow_payment_bump: escrow_payment_account_bump,
+    };
+
+    let accounts = auction_house::accounts::CloseEscrowAccount {
+        wallet: buyer.pubkey(),
+        escrow_payment_account,
+        escrow_ledger,
+        receipt_account: buyer.pubkey(),
+        treasury_mint: auction_house_data.treasury_mint,
+        auction_house: *auction_house,
+        token_program: spl_token::id(),
+        system_program: system_program::id(),
+        associated_token_program: associated_token::ID,
+        rent: sysvar::rent::id(),
+    };
+
+    let ix = Instruction {
+        program_id: auction_house::id(),
+        data: data.data(),
+        accounts: accounts.to_account_metas(None),
+    };
+
+    (
+        accounts,
+        Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&buyer.pubkey()),
+            &[buyer],
+            context.last_blockhash,
+        ),
+    )
+}
+
+pub fn withdraw_from_fee(
+    context: &mut ProgramTestContext,
+    authority: &Keypair,
+    auction_house: &Pubkey,
+    auction_house_data: &AuctionHouse,
+    amount: u64,
+) -> (auction_house::accounts::WithdrawFromFee, Transaction) {
+    let data = auction_house::instruction::WithdrawFromFee { amount };
+
+    let accounts = auction_house::accounts::WithdrawFromFee {
+        authority: auction_house_data.authority,
+        fee_withdrawal_destination: auction_house_data.fee_withdrawal_destination,
+        auction_house_fee_account: auction_house_data.auction_house_fee_account,
+        auction_house: *auction_house,
+        system_program: system_program::id(),
+    };
+
+    let ix = Instruction {
+        program_id: auction_house::id(),
+        data: data.data(),
+        accounts: accounts.to_account_metas(None),
+    };
+
+    (
+        accounts,
+        Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&authority.pubkey()),
+            &[authority],
+            context.last_blockhash,
+        ),
+    )
+}
+
+pub fn withdraw_from_treasury(
+    context: &mut ProgramTestContext,
+    authority: &Keypair,
+    auction_house: &Pubkey,
+    auction_house_data: &AuctionHouse,
+    amount: u64,
+) -> (auction_house::accounts::WithdrawFromTreasury, Transaction) {
+    let data = auction_house::instruction::WithdrawFromTreasury { amount };
+
+    let accounts = auction_house::accounts::WithdrawFromTreasury {
+        treasury_mint: auction_house_data.treasury_mint,
+        treasury_withdrawal_destination: auction_house_data.treasury_withdrawal_destination,
+        auction_house_treasury: auction_house_data.auction_house_treasury,
+        authority: auction_house_data.authority,
+        auction_house: *auction_house,
+        token_program: spl_token::id(),
+        system_program: system_program::id(),
+    };
+
+    let ix = Instruction {
+        program_id: auction_house::id(),
+        data: data.data(),
+        accounts: accounts.to_account_metas(None),
+    };
+
+    (
+        accounts,
+        Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&authority.pubkey()),
+            &[authority],
+            context.last_blockhash,
+        ),
+    )
+}
+
+pub fn batch_sell(
+    context: &mut ProgramTestContext,
+    auction_house: &Pubkey,
+    auction_house_data: &AuctionHouse,
+    token: &NFT,
+    token_size: u64,
+    start_time: i64,
+    end_time: i64,
+    reserve_price: u64,
+) -> (auctioneer::accounts::AuctioneerBatchSell, Transaction) {
+    let (seller_trade_state, seller_trade_state_bump) = find_auctioneer_trade_state_address(
+        &token.owner.pubkey(),
+        auction_house,
+        &token.ata,
+        &auction_house_data.treasury_mint,
+        &token.mint.pubkey(),
+        token_size,
+    );
+
+    let (free_seller_trade_state, free_seller_trade_state_bump) = find_trade_state_address(
+        &token.owner.pubkey(),
+        auction_house,
+        &token.ata,
+        &auction_house_data.treasury_mint,
+        &token.mint.pubkey(),
+        0,
+        token_size,
+    );
+
+    let (batch_listing_config, _) = find_batch_listing_config_address(
+        &token.owner.pubkey(),
+        auction_house,
+        &token.ata,
+        &auction_house_data.treasury_mint,
+        &token.mint.pubkey(),
+        token_size,
+    );
+    let (bid_book, _) = find_bid_book_address(&batch_listing_config);
+
+    let (program_as_signer, program_as_signer_bump) = find_program_as_signer_address();
+
+    let (auctioneer_authority, auctioneer_authority_bump) =
+        find_auctioneer_authority_address(auction_house);
+
+    let (auctioneer, _) = find_auctioneer_address(auction_house, &auctioneer_authority);
+
+    let data = auctioneer::instruction::BatchSell {
+        trade_state_bump: seller_trade_state_bump,
+        free_trade_state_bump: free_seller_trade_state_bump,
+        program_as_signer_bump,
+        auctioneer_authority_bump,
+        token_size,
+        start_time,
+        end_time,
+        reserve_price,
+    };
+
+    let accounts = auctioneer::accounts::AuctioneerBatchSell {
+        auction_house_program: auction_house::id(),
+        batch_listing_config,
+        bid_book,
+        wallet: token.owner.pubkey(),
+        token_account: token.ata,
+        metadata: token.metadata,
+        authority: auction_house_data.authority,
+        auction_house: *auction_house,
+        auction_house_fee_account: auction_house_data.auction_house_fee_account,
+        seller_trade_state,
+        free_seller_trade_state,
+        token_program: spl_token::id(),
+        system_program: system_program::id(),
+        program_as_signer,
+        rent: sysvar::rent::id(),
+        auctioneer_authority,
+        auctioneer,
+    };
+
+    let ix = Instruction {
+        program_id: auctioneer::id(),
+        data: data.data(),
+        accounts: accounts.to_account_metas(None),
+    };
+
+    (
+        accounts,
+        Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&token.owner.pubkey()),
+            &[&token.owner],
+            context.last_blockhash,
+        ),
+    )
+}
+
+pub fn batch_bid(
+    context: &mut ProgramTestContext,
+    auction_house: &Pubkey,
+    auction_house_data: &AuctionHouse,
+    token: &NFT,
+    token_size: u64,
+    buyer: &Keypair,
+    price: u64, // Price per unit
+    quantity: u64,
+) -> (auctioneer::accounts::AuctioneerBatchBid, Transaction) {
+    let (batch_listing_config, _) = find_batch_listing_config_address(
+        &token.owner.pubkey(),
+        auction_house,
+        &token.ata,
+        &auction_house_data.treasury_mint,
+        &token.mint.pubkey(),
+        token_size,
+    );
+    let (bid_book, _) = find_bid_book_address(&batch_listing_config);
+    let (escrow_payment_account, escrow_payment_account_bump) =
+        find_escrow_payment_account_address(auction_house, &buyer.pubkey());
+    let (escrow_ledger, _) = find_escrow_ledger_address(auction_house, &buyer.pubkey());
+    let (auctioneer_authority, _) = find_auctioneer_authority_address(auction_house);
+    let (auctioneer, _) = find_auctioneer_address(auction_house, &auctioneer_authority);
+
+    let data = auctioneer::instruction::BatchBid {
+        escrow_payment_bump: escrow_payment_account_bump,
+        token_size,
+        price,
+        quantity,
+    };
+
+    let accounts = auctioneer::accounts::AuctioneerBatchBid {
+        auction_house_program: auction_house::id(),
+        batch_listing_config,
+        bid_book,
+        seller: token.owner.pubkey(),
+        wallet: buyer.pubkey(),
+        token_account: token.ata,
+        escrow_payment_account,
+        escrow_ledger,
+        treasury_mint: auction_house_data.treasury_mint,
         auction_house: *auction_house,
+        auctioneer_authority,
+        auctioneer,
         system_program: system_program::id(),
+        rent: sysvar::rent::id(),
     };
 
     let ix = Instruction {
-        program_id: auction_house::id(),
+        program_id: auctioneer::id(),
         data: data.data(),
         accounts: accounts.to_account_metas(None),
     };
@@ -772,25 +2120,108 @@ pub fn close_escrow_account(
     )
 }
 
-pub fn withdraw_from_fee(
+pub fn clear_batch_auction(
+    context: &mut ProgramTestContext,
+    batch_listing_config: &Pubkey,
+    payer: &Keypair,
+) -> (
+    auctioneer::accounts::AuctioneerClearBatchAuction,
+    Transaction,
+) {
+    let (bid_book, _) = find_bid_book_address(batch_listing_config);
+
+    let data = auctioneer::instruction::ClearBatchAuction {};
+
+    let accounts = auctioneer::accounts::AuctioneerClearBatchAuction {
+        batch_listing_config: *batch_listing_config,
+        bid_book,
+    };
+
+    let ix = Instruction {
+        program_id: auctioneer::id(),
+        data: data.data(),
+        accounts: accounts.to_account_metas(None),
+    };
+
+    (
+        accounts,
+        Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&payer.pubkey()),
+            &[payer],
+            context.last_blockhash,
+        ),
+    )
+}
+
+pub fn settle_batch_bid(
     context: &mut ProgramTestContext,
-    authority: &Keypair,
     auction_house: &Pubkey,
     auction_house_data: &AuctionHouse,
-    amount: u64,
-) -> (auction_house::accounts::WithdrawFromFee, Transaction) {
-    let data = auction_house::instruction::WithdrawFromFee { amount };
+    token: &NFT,
+    token_size: u64,
+    buyer: &Keypair,
+    buyer_price: u64, // Clearing price times the filled units
+    filled: u64,
+) -> (auctioneer::accounts::AuctioneerSettleBatchBid, Transaction) {
+    let (batch_listing_config, _) = find_batch_listing_config_address(
+        &token.owner.pubkey(),
+        auction_house,
+        &token.ata,
+        &auction_house_data.treasury_mint,
+        &token.mint.pubkey(),
+        token_size,
+    );
+    let (bid_book, _) = find_bid_book_address(&batch_listing_config);
+    let (escrow_payment_account, escrow_payment_account_bump) =
+        find_escrow_payment_account_address(auction_house, &buyer.pubkey());
+    let (escrow_ledger, _) = find_escrow_ledger_address(auction_house, &buyer.pubkey());
+    let (auctioneer_authority, auctioneer_authority_bump) =
+        find_auctioneer_authority_address(auction_house);
+    let (auctioneer, _) = find_auctioneer_address(auction_house, &auctioneer_authority);
+    let (buyer_trade_state, buyer_trade_state_bump) = find_trade_state_address(
+        &buyer.pubkey(),
+        auction_house,
+        &token.ata,
+        &auction_house_data.treasury_mint,
+        &token.mint.pubkey(),
+        buyer_price,
+        filled,
+    );
 
-    let accounts = auction_house::accounts::WithdrawFromFee {
+    let data = auctioneer::instruction::SettleBatchBid {
+        trade_state_bump: buyer_trade_state_bump,
+        escrow_payment_bump: escrow_payment_account_bump,
+        auctioneer_authority_bump,
+        token_size,
+    };
+
+    let accounts = auctioneer::accounts::AuctioneerSettleBatchBid {
+        auction_house_program: auction_house::id(),
+        batch_listing_config,
+        bid_book,
+        seller: token.owner.pubkey(),
+        wallet: buyer.pubkey(),
+        payment_account: buyer.pubkey(),
+        transfer_authority: buyer.pubkey(),
+        treasury_mint: auction_house_data.treasury_mint,
+        token_account: token.ata,
+        metadata: token.metadata,
+        escrow_payment_account,
+        escrow_ledger,
         authority: auction_house_data.authority,
-        fee_withdrawal_destination: auction_house_data.fee_withdrawal_destination,
-        auction_house_fee_account: auction_house_data.auction_house_fee_account,
         auction_house: *auction_house,
+        auction_house_fee_account: auction_house_data.auction_house_fee_account,
+        buyer_trade_state,
+        auctioneer_authority,
+        auctioneer,
+        token_program: spl_token::id(),
         system_program: system_program::id(),
+        rent: sysvar::rent::id(),
     };
 
     let ix = Instruction {
-        program_id: auction_house::id(),
+        program_id: auctioneer::id(),
         data: data.data(),
         accounts: accounts.to_account_metas(None),
     };
@@ -799,34 +2230,92 @@ pub fn withdraw_from_fee(
         accounts,
         Transaction::new_signed_with_payer(
             &[ix],
-            Some(&authority.pubkey()),
-            &[authority],
+            Some(&buyer.pubkey()),
+            &[buyer],
             context.last_blockhash,
         ),
     )
 }
 
-pub fn withdraw_from_treasury(
+pub fn execute_batch_sale(
     context: &mut ProgramTestContext,
-    authority: &Keypair,
     auction_house: &Pubkey,
     auction_house_data: &AuctionHouse,
-    amount: u64,
-) -> (auction_house::accounts::WithdrawFromTreasury, Transaction) {
-    let data = auction_house::instruction::WithdrawFromTreasury { amount };
+    token: &NFT,
+    sell_accounts: &auctioneer::accounts::AuctioneerBatchSell,
+    settle_accounts: &auctioneer::accounts::AuctioneerSettleBatchBid,
+    token_size: u64,
+    signer_payer: &Keypair,
+) -> (
+    auctioneer::accounts::AuctioneerExecuteBatchSale,
+    Transaction,
+) {
+    let (auctioneer_authority, auctioneer_authority_bump) =
+        find_auctioneer_authority_address(auction_house);
+    let (auctioneer, _) = find_auctioneer_address(auction_house, &auctioneer_authority);
+    let buyer_receipt_token_account =
+        get_associated_token_address(&settle_accounts.wallet, &token.mint.pubkey());
+    let (_, escrow_payment_account_bump) =
+        find_escrow_payment_account_address(auction_house, &settle_accounts.wallet);
+    let (_, program_as_signer_bump) = find_program_as_signer_address();
 
-    let accounts = auction_house::accounts::WithdrawFromTreasury {
-        treasury_mint: auction_house_data.treasury_mint,
-        treasury_withdrawal_destination: auction_house_data.treasury_withdrawal_destination,
-        auction_house_treasury: auction_house_data.auction_house_treasury,
+    let (_, free_seller_trade_state_bump) = find_trade_state_address(
+        &token.owner.pubkey(),
+        auction_house,
+        &sell_accounts.token_account,
+        &auction_house_data.treasury_mint,
+        &token.mint.pubkey(),
+        0,
+        token_size,
+    );
+
+    let data = auctioneer::instruction::ExecuteBatchSale {
+        escrow_payment_bump: escrow_payment_account_bump,
+        free_trade_state_bump: free_seller_trade_state_bump,
+        program_as_signer_bump,
+        auctioneer_authority_bump,
+        token_size,
+    };
+
+    let accounts = auctioneer::accounts::AuctioneerExecuteBatchSale {
+        auction_house_program: auction_house::id(),
+        batch_listing_config: sell_accounts.batch_listing_config,
+        bid_book: sell_accounts.bid_book,
+        buyer: settle_accounts.wallet,
+        seller: sell_accounts.wallet,
         authority: auction_house_data.authority,
         auction_house: *auction_house,
+        metadata: token.metadata,
+        token_account: sell_accounts.token_account,
+        seller_trade_state: sell_accounts.seller_trade_state,
+        buyer_trade_state: settle_accounts.buyer_trade_state,
         token_program: spl_token::id(),
+        treasury_token_program: spl_token::id(),
+        free_trade_state: sell_accounts.free_seller_trade_state,
+        proceeds_split: find_proceeds_split_address(&sell_accounts.seller_trade_state).0,
+        referral: find_referral_address(&sell_accounts.seller_trade_state).0,
+        seller_payment_receipt_account: token.owner.pubkey(),
+        buyer_receipt_token_account,
+        escrow_payment_account: settle_accounts.escrow_payment_account,
+        escrow_ledger: settle_accounts.escrow_ledger,
+        token_mint: token.mint.pubkey(),
+        auction_house_fee_account: auction_house_data.auction_house_fee_account,
+        auction_house_treasury: auction_house_data.auction_house_treasury,
+        treasury_mint: auction_house_data.treasury_mint,
+        program_as_signer: sell_accounts.program_as_signer,
         system_program: system_program::id(),
+        associated_token_program: anchor_spl::associated_token::ID,
+        rent: sysvar::rent::id(),
+        auctioneer_authority,
+        auctioneer,
+        referrer: None,
+        referrer_token_account: None,
+        auctioneer_config: find_auctioneer_config_address(auction_house).0,
+        auctioneer_fee_destination: auction_house_data.authority,
     };
 
     let ix = Instruction {
-        program_id: auction_house::id(),
+        program_id: auctioneer::id(),
         data: data.data(),
         accounts: accounts.to_account_metas(None),
     };
@@ -835,8 +2324,114 @@ pub fn withdraw_from_treasury(
         accounts,
         Transaction::new_signed_with_payer(
             &[ix],
-            Some(&authority.pubkey()),
-            &[authority],
+            Some(&signer_payer.pubkey()),
+            &[signer_payer],
+            context.last_blockhash,
+        ),
+    )
+}
+
+pub fn release_batch_bid(
+    context: &mut ProgramTestContext,
+    auction_house: &Pubkey,
+    token: &NFT,
+    token_size: u64,
+    bidder: &Pubkey,
+    sell_accounts: &auctioneer::accounts::AuctioneerBatchSell,
+    payer: &Keypair,
+) -> (auctioneer::accounts::AuctioneerReleaseBatchBid, Transaction) {
+    let (escrow_ledger, _) = find_escrow_ledger_address(auction_house, bidder);
+    let (auctioneer_authority, auctioneer_authority_bump) =
+        find_auctioneer_authority_address(auction_house);
+    let (auctioneer, _) = find_auctioneer_address(auction_house, &auctioneer_authority);
+
+    let data = auctioneer::instruction::ReleaseBatchBid {
+        auctioneer_authority_bump,
+        token_size,
+    };
+
+    let accounts = auctioneer::accounts::AuctioneerReleaseBatchBid {
+        auction_house_program: auction_house::id(),
+        batch_listing_config: sell_accounts.batch_listing_config,
+        bid_book: sell_accounts.bid_book,
+        seller: token.owner.pubkey(),
+        bidder: *bidder,
+        token_account: token.ata,
+        escrow_ledger,
+        auction_house: *auction_house,
+        auctioneer_authority,
+        auctioneer,
+    };
+
+    let ix = Instruction {
+        program_id: auctioneer::id(),
+        data: data.data(),
+        accounts: accounts.to_account_metas(None),
+    };
+
+    (
+        accounts,
+        Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&payer.pubkey()),
+            &[payer],
+            context.last_blockhash,
+        ),
+    )
+}
+
+pub fn cancel_batch_listing(
+    context: &mut ProgramTestContext,
+    auction_house: &Pubkey,
+    auction_house_data: &AuctionHouse,
+    token: &NFT,
+    token_size: u64,
+    sell_accounts: &auctioneer::accounts::AuctioneerBatchSell,
+) -> (
+    auctioneer::accounts::AuctioneerCancelBatchListing,
+    Transaction,
+) {
+    let (escrow_ledger, _) = find_escrow_ledger_address(auction_house, &token.owner.pubkey());
+    let (auctioneer_authority, auctioneer_authority_bump) =
+        find_auctioneer_authority_address(auction_house);
+    let (auctioneer, _) = find_auctioneer_address(auction_house, &auctioneer_authority);
+
+    let data = auctioneer::instruction::CancelBatchListing {
+        auctioneer_authority_bump,
+        token_size,
+    };
+
+    let accounts = auctioneer::accounts::AuctioneerCancelBatchListing {
+        auction_house_program: auction_house::id(),
+        batch_listing_config: sell_accounts.batch_listing_config,
+        bid_book: sell_accounts.bid_book,
+        wallet: token.owner.pubkey(),
+        token_account: token.ata,
+        token_mint: token.mint.pubkey(),
+        authority: auction_house_data.authority,
+        auction_house: *auction_house,
+        auction_house_fee_account: auction_house_data.auction_house_fee_account,
+        trade_state: sell_accounts.seller_trade_state,
+        proceeds_split: find_proceeds_split_address(&sell_accounts.seller_trade_state).0,
+        referral: find_referral_address(&sell_accounts.seller_trade_state).0,
+        escrow_ledger,
+        auctioneer_authority,
+        auctioneer,
+        token_program: spl_token::id(),
+    };
+
+    let ix = Instruction {
+        program_id: auctioneer::id(),
+        data: data.data(),
+        accounts: accounts.to_account_metas(None),
+    };
+
+    (
+        accounts,
+        Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&token.owner.pubkey()),
+            &[&token.owner],
             context.last_blockhash,
         ),
     )
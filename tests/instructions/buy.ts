@@ -47,6 +47,18 @@ export async function buy({
       wallet: buyerKeypair.publicKey,
       auctionHouseAddress: auctionHouse.address,
     });
+  const [escrowLedgerAddress] = pda.findEscrowLedgerAddress({
+    wallet: buyerKeypair.publicKey,
+    auctionHouseAddress: auctionHouse.address,
+  });
+  const listingConfig =
+    await auctioneerProgram.account.listingConfig.fetchNullable(
+      listingConfigAddress
+    );
+  const [highestBidderEscrowLedgerAddress] = pda.findEscrowLedgerAddress({
+    wallet: listingConfig?.highestBidder ?? anchor.web3.PublicKey.default,
+    auctionHouseAddress: auctionHouse.address,
+  });
   const [auctioneerAuthorityAddress, auctioneerAuthorityBump] =
     pda.findAuctioneerAuthorityAddress({
       auctionHouseAddress: auctionHouse.address,
@@ -67,6 +79,8 @@ export async function buy({
     tokenAccount: token.ata, // seller token account
     metadata: token.metadata,
     escrowPaymentAccount: escrowPaymentAccountAddress,
+    escrowLedger: escrowLedgerAddress,
+    highestBidderEscrowLedger: highestBidderEscrowLedgerAddress,
     authority: auctionHouse.authority,
     auctionHouse: auctionHouse.address,
     auctionHouseFeeAccount: auctionHouse.auctionHouseFeeAccount,
@@ -30,6 +30,17 @@ export async function cancel({
     tokenMint: token.mint.publicKey,
     tokenSize,
   });
+  const [escrowLedgerAddress] = pda.findEscrowLedgerAddress({
+    wallet: walletKeypair.publicKey,
+    auctionHouseAddress: auctionHouse.address,
+  });
+  const listingConfig = await auctioneerProgram.account.listingConfig.fetch(
+    listingConfigAddress
+  );
+  const [highestBidderEscrowLedgerAddress] = pda.findEscrowLedgerAddress({
+    wallet: listingConfig.highestBidder,
+    auctionHouseAddress: auctionHouse.address,
+  });
   const [auctioneerAuthorityAddress, auctioneerAuthorityBump] =
     pda.findAuctioneerAuthorityAddress({
       auctionHouseAddress: auctionHouse.address,
@@ -52,6 +63,8 @@ export async function cancel({
       auctionHouse: auctionHouse.address,
       auctionHouseFeeAccount: auctionHouse.auctionHouseFeeAccount,
       tradeState: tradeStateAddress,
+      escrowLedger: escrowLedgerAddress,
+      highestBidderEscrowLedger: highestBidderEscrowLedgerAddress,
       auctioneerAuthority: auctioneerAuthorityAddress,
       auctioneer: auctioneerAddress,
     })
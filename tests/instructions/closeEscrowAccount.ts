@@ -18,12 +18,19 @@ export async function closeEscrowAccount({
       wallet: walletKeypair.publicKey,
       auctionHouseAddress: auctionHouse.address,
     });
+  const [escrowLedgerAddress] = pda.findEscrowLedgerAddress({
+    wallet: walletKeypair.publicKey,
+    auctionHouseAddress: auctionHouse.address,
+  });
 
   const closeEscrowAccountTx = await auctionHouseProgram.methods
     .closeEscrowAccount(escrowBump)
     .accounts({
       wallet: walletKeypair.publicKey,
       escrowPaymentAccount: escrowPaymentAccountAddress,
+      escrowLedger: escrowLedgerAddress,
+      receiptAccount: walletKeypair.publicKey,
+      treasuryMint: auctionHouse.treasuryMint,
       auctionHouse: auctionHouse.address,
     })
     .signers([walletKeypair])
@@ -88,6 +88,20 @@ export async function createAuctionHouse({
     .rpc();
   console.log("Transaction [Authorize Auctioneer]", authorizeAuctioneerTx);
 
+  const initAuctioneerConfigTx = await auctioneerProgram.methods
+    .initAuctioneerConfig(0)
+    .accounts({
+      wallet: authorityKeypair.publicKey, // Signer
+      auctionHouse: auctionHouseAddress,
+      auctioneerConfig: pda.findAuctioneerConfigAddress({
+        auctionHouseAddress,
+      })[0],
+      feeDestination: authorityKeypair.publicKey,
+    })
+    .signers([authorityKeypair])
+    .rpc();
+  console.log("Transaction [Init Auctioneer Config]", initAuctioneerConfigTx);
+
   const ah = await auctionHouseProgram.account.auctionHouse.fetch(
     auctionHouseAddress
   );
@@ -94,6 +94,11 @@ export async function executeSale({
     auctionHouseAddress: auctionHouse.address,
   });
 
+  const [auctionResult] = pda.findAuctionResultAddress({
+    listingConfig: sellAccounts.listingConfig,
+    buyerTradeState: buyAccounts.buyerTradeState,
+  });
+
   const remainingAccounts: anchor.web3.AccountMeta[] = []; // NFT creators
 
   if (creators) {
@@ -118,6 +123,7 @@ export async function executeSale({
     .accounts({
       auctionHouseProgram: AUCTION_HOUSE_PROGRAM_ID,
       listingConfig: sellAccounts.listingConfig,
+      auctionResult,
       buyer,
       seller: token.owner.publicKey,
       tokenAccount: sellAccounts.tokenAccount,
@@ -125,6 +131,7 @@ export async function executeSale({
       metadata: sellAccounts.metadata,
       treasuryMint: auctionHouse.treasuryMint,
       escrowPaymentAccount: buyAccounts.escrowPaymentAccount,
+      escrowLedger: buyAccounts.escrowLedger,
       sellerPaymentReceiptAccount: token.owner.publicKey,
       buyerReceiptTokenAccount,
       authority: auctionHouse.authority,
@@ -134,9 +141,17 @@ export async function executeSale({
       buyerTradeState: buyAccounts.buyerTradeState,
       sellerTradeState: sellAccounts.sellerTradeState,
       freeTradeState: sellAccounts.freeSellerTradeState,
+      proceedsSplit: pda.findProceedsSplitAddress({
+        sellerTradeState: sellAccounts.sellerTradeState,
+      })[0],
       auctioneerAuthority: auctioneerAuthorityAddress,
       auctioneer: auctioneerAddress,
       programAsSigner: sellAccounts.programAsSigner,
+      cranker: signerKeypair.publicKey,
+      auctioneerConfig: pda.findAuctioneerConfigAddress({
+        auctionHouseAddress: auctionHouse.address,
+      })[0],
+      auctioneerFeeDestination: auctionHouse.authority,
     })
     .remainingAccounts(remainingAccounts)
     .instruction();
@@ -1,6 +1,13 @@
 import { Program, BN } from "@coral-xyz/anchor";
 import { Auctioneer } from "../../target/types/auctioneer";
-import { AuctionHouseData, NFT, SellAccounts } from "../interfaces";
+import {
+  AuctionHouseData,
+  BidIncrementParams,
+  DutchAuctionParams,
+  NFT,
+  SealedBidParams,
+  SellAccounts,
+} from "../interfaces";
 import * as pda from "../pda";
 import { AUCTION_HOUSE_PROGRAM_ID } from "../generated";
 
@@ -14,6 +21,10 @@ export async function sell({
   minBidIncrement,
   timeExtPeriod,
   timeExtDelta,
+  dutchAuction,
+  buyNowPrice,
+  sealedBid,
+  bidIncrement,
   tokenSize,
 }: {
   auctioneerProgram: Program<Auctioneer>;
@@ -25,6 +36,10 @@ export async function sell({
   minBidIncrement?: number;
   timeExtPeriod?: number;
   timeExtDelta?: number;
+  dutchAuction?: DutchAuctionParams;
+  buyNowPrice?: number;
+  sealedBid?: SealedBidParams;
+  bidIncrement?: BidIncrementParams;
   tokenSize: number;
 }) {
   const [sellerTradeStateAddress, sellerTradeStateBump] =
@@ -96,7 +111,32 @@ export async function sell({
       new BN(reservePrice ?? 0),
       new BN(minBidIncrement ?? 0),
       timeExtPeriod ?? 0,
-      timeExtDelta ?? 0
+      timeExtDelta ?? 0,
+      dutchAuction
+        ? {
+            startPrice: new BN(dutchAuction.startPrice),
+            floorPrice: new BN(dutchAuction.floorPrice),
+            priceDecayInterval: dutchAuction.priceDecayInterval,
+            priceDecayAmount: new BN(dutchAuction.priceDecayAmount),
+          }
+        : null,
+      buyNowPrice ? new BN(buyNowPrice) : null,
+      sealedBid
+        ? {
+            revealEndTime: new BN(sealedBid.revealEndTime),
+            secondPrice: sealedBid.secondPrice,
+          }
+        : null,
+      bidIncrement
+        ? {
+            mode: { [bidIncrement.mode]: {} },
+            basisPoints: bidIncrement.basisPoints ?? 0,
+            tiers: (bidIncrement.tiers ?? []).map((tier) => ({
+              minPrice: new BN(tier.minPrice),
+              increment: new BN(tier.increment),
+            })),
+          }
+        : null
     )
     .accounts(sellAccounts)
     .signers([token.owner])
@@ -21,6 +21,10 @@ export async function withdraw({
       wallet: buyerKeypair.publicKey,
       auctionHouseAddress: auctionHouse.address,
     });
+  const [escrowLedgerAddress] = pda.findEscrowLedgerAddress({
+    wallet: buyerKeypair.publicKey,
+    auctionHouseAddress: auctionHouse.address,
+  });
   const [auctioneerAuthorityAddress, auctioneerAuthorityBump] =
     pda.findAuctioneerAuthorityAddress({
       auctionHouseAddress: auctionHouse.address,
@@ -37,6 +41,7 @@ export async function withdraw({
       wallet: buyerKeypair.publicKey, // Signer
       receiptAccount: buyerKeypair.publicKey,
       escrowPaymentAccount: escrowPaymentAccountAddress,
+      escrowLedger: escrowLedgerAddress,
       treasuryMint: auctionHouse.treasuryMint,
       authority: auctionHouse.authority,
       auctionHouse: auctionHouse.address,
@@ -58,6 +58,8 @@ export interface BuyAccounts {
   tokenAccount: anchor.web3.PublicKey;
   metadata: anchor.web3.PublicKey;
   escrowPaymentAccount: anchor.web3.PublicKey;
+  escrowLedger: anchor.web3.PublicKey;
+  highestBidderEscrowLedger: anchor.web3.PublicKey;
   authority: anchor.web3.PublicKey;
   auctionHouse: anchor.web3.PublicKey;
   auctionHouseFeeAccount: anchor.web3.PublicKey;
@@ -71,3 +73,26 @@ export interface Creator {
   share: number;
   verified: boolean;
 }
+
+export interface DutchAuctionParams {
+  startPrice: number;
+  floorPrice: number;
+  priceDecayInterval: number;
+  priceDecayAmount: number;
+}
+
+export interface BidIncrementTier {
+  minPrice: number;
+  increment: number;
+}
+
+export interface BidIncrementParams {
+  mode: "absolute" | "basisPoints" | "tiered";
+  basisPoints?: number;
+  tiers?: BidIncrementTier[];
+}
+
+export interface SealedBidParams {
+  revealEndTime: number;
+  secondPrice: boolean;
+}
@@ -13,8 +13,12 @@ const PREFIX = "auction_house";
 const FEE_PAYER = "fee_payer";
 const TREASURY = "treasury";
 const AUCTIONEER = "auctioneer";
+const AUCTIONEER_CONFIG = "auctioneer_config";
 const LISTING_CONFIG = "listing_config";
+const AUCTION_RESULT = "auction_result";
 const SIGNER = "signer";
+const ESCROW_LEDGER = "escrow_ledger";
+const PROCEEDS_SPLIT = "proceeds_split";
 
 export const findMetadataAddress = ({
   mint,
@@ -95,6 +99,16 @@ export const findAuctioneerAuthorityAddress = ({
     AUCTIONEER_PROGRAM_ID
   );
 
+export const findAuctioneerConfigAddress = ({
+  auctionHouseAddress,
+}: {
+  auctionHouseAddress: anchor.web3.PublicKey;
+}): [anchor.web3.PublicKey, number] =>
+  anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from(AUCTIONEER_CONFIG), auctionHouseAddress.toBuffer()],
+    AUCTIONEER_PROGRAM_ID
+  );
+
 export const findAuctioneerAddress = ({
   auctionHouseAddress,
   auctioneerAuthorityAddress,
@@ -199,6 +213,22 @@ export const findListingConfigAddress = ({
     AUCTIONEER_PROGRAM_ID
   );
 
+export const findAuctionResultAddress = ({
+  listingConfig,
+  buyerTradeState,
+}: {
+  listingConfig: anchor.web3.PublicKey;
+  buyerTradeState: anchor.web3.PublicKey;
+}): [anchor.web3.PublicKey, number] =>
+  anchor.web3.PublicKey.findProgramAddressSync(
+    [
+      Buffer.from(AUCTION_RESULT),
+      listingConfig.toBuffer(),
+      buyerTradeState.toBuffer(),
+    ],
+    AUCTIONEER_PROGRAM_ID
+  );
+
 export const findProgramAsSignerAddress = (): [anchor.web3.PublicKey, number] =>
   anchor.web3.PublicKey.findProgramAddressSync(
     [Buffer.from(PREFIX), Buffer.from(SIGNER)],
@@ -216,3 +246,34 @@ export const findEscrowPaymentAccountAddress = ({
     [Buffer.from(PREFIX), auctionHouseAddress.toBuffer(), wallet.toBuffer()],
     AUCTION_HOUSE_PROGRAM_ID
   );
+
+export const findEscrowLedgerAddress = ({
+  wallet,
+  auctionHouseAddress,
+}: {
+  wallet: anchor.web3.PublicKey;
+  auctionHouseAddress: anchor.web3.PublicKey;
+}): [anchor.web3.PublicKey, number] =>
+  anchor.web3.PublicKey.findProgramAddressSync(
+    [
+      Buffer.from(PREFIX),
+      auctionHouseAddress.toBuffer(),
+      wallet.toBuffer(),
+      Buffer.from(ESCROW_LEDGER),
+    ],
+    AUCTION_HOUSE_PROGRAM_ID
+  );
+
+export const findProceedsSplitAddress = ({
+  sellerTradeState,
+}: {
+  sellerTradeState: anchor.web3.PublicKey;
+}): [anchor.web3.PublicKey, number] =>
+  anchor.web3.PublicKey.findProgramAddressSync(
+    [
+      Buffer.from(PREFIX),
+      sellerTradeState.toBuffer(),
+      Buffer.from(PROCEEDS_SPLIT),
+    ],
+    AUCTION_HOUSE_PROGRAM_ID
+  );